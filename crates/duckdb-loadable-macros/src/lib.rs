@@ -7,6 +7,10 @@ use proc_macro::TokenStream;
 use quote::quote_spanned;
 
 /// Wraps an entrypoint function to expose an unsafe extern "C" function of the same name.
+///
+/// The function is given a `duckdb::Connection` to the database loading the extension, and
+/// returns a `Result` whose error converts into `Box<dyn Error>`. Errors and panics are caught
+/// before they reach DuckDB and reported on stderr, as the entrypoint has no way to return them.
 #[proc_macro_attribute]
 pub fn duckdb_entrypoint(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(item as syn::Item);
@@ -30,20 +34,27 @@ pub fn duckdb_entrypoint(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 ///
                 /// Will be called by duckdb
                 #[no_mangle]
-                pub unsafe extern "C" fn #c_entrypoint(db: *mut c_void) {
-                    let connection = Connection::open_from_raw(db.cast()).expect("can't open db connection");
-                    #prefixed_original_function(connection).expect("init failed");
+                #[allow(clippy::useless_conversion)]
+                pub unsafe extern "C" fn #c_entrypoint(db: *mut ::std::ffi::c_void) {
+                    let init = ::std::panic::catch_unwind(|| -> ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> {
+                        let connection = ::duckdb::Connection::open_from_raw(db.cast())?;
+                        #prefixed_original_function(connection).map_err(::std::convert::Into::into)
+                    });
+                    match init {
+                        Ok(Ok(())) => {}
+                        Ok(Err(err)) => eprintln!("{} failed: {}", #original_funcname, err),
+                        // The panic hook has already printed the panic.
+                        Err(_) => eprintln!("{} panicked", #original_funcname),
+                    }
                 }
 
                 /// # Safety
                 ///
                 /// Predefined function, don't need to change unless you are sure
                 #[no_mangle]
-                pub unsafe extern "C" fn #c_entrypoint_version() -> *const c_char {
-                    ffi::duckdb_library_version()
+                pub unsafe extern "C" fn #c_entrypoint_version() -> *const ::std::os::raw::c_char {
+                    ::duckdb::ffi::duckdb_library_version()
                 }
-
-
             }
             .into()
         }
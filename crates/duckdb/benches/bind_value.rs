@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use duckdb::{params, types::Value, Connection, Result, Statement};
+
+const ITERATIONS: i64 = 1_000_000;
+
+const QUERY: &str = "SELECT ?, ?";
+
+fn bind_params(stmt: &mut Statement<'_>) -> Result<usize> {
+    let mut changed = 0;
+    for i in 0..ITERATIONS {
+        changed += stmt.execute(params![i, "duck"])?;
+    }
+    Ok(changed)
+}
+
+fn bind_values(stmt: &mut Statement<'_>) -> Result<usize> {
+    let mut values = [Value::BigInt(0), Value::Text("duck".to_owned())];
+    let mut changed = 0;
+    for i in 0..ITERATIONS {
+        values[0] = Value::BigInt(i);
+        stmt.bind_values(&values)?;
+        changed += stmt.raw_execute()?;
+    }
+    Ok(changed)
+}
+
+fn bench_bind_value(c: &mut Criterion) {
+    let conn = Connection::open_in_memory().unwrap();
+    let mut stmt = conn.prepare(QUERY).unwrap();
+
+    let mut group = c.benchmark_group("bind_value");
+    group.sample_size(10);
+    group.bench_function("params", |b| b.iter(|| bind_params(&mut stmt).unwrap()));
+    group.bench_function("bind_values", |b| b.iter(|| bind_values(&mut stmt).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_bind_value);
+criterion_main!(benches);
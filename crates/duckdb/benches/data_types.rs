@@ -0,0 +1,90 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    borrow::Cow,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use duckdb::{Connection, Result};
+
+/// Counts allocations, to check that borrowed scans don't allocate per row.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ROWS: usize = 100_000;
+
+fn scan_str(conn: &Connection) -> Result<usize> {
+    let mut stmt = conn.prepare_cached("SELECT s FROM strings")?;
+    let mut rows = stmt.query([])?;
+    let mut len = 0;
+    while let Some(row) = rows.next()? {
+        len += row.get_ref(0)?.as_str()?.len();
+    }
+    Ok(len)
+}
+
+fn scan_cow(conn: &Connection) -> Result<usize> {
+    let mut stmt = conn.prepare_cached("SELECT s FROM strings")?;
+    let mut rows = stmt.query([])?;
+    let mut len = 0;
+    while let Some(row) = rows.next()? {
+        len += row.get::<_, Cow<str>>(0)?.len();
+    }
+    Ok(len)
+}
+
+fn scan_string(conn: &Connection) -> Result<usize> {
+    let mut stmt = conn.prepare_cached("SELECT s FROM strings")?;
+    let mut rows = stmt.query([])?;
+    let mut len = 0;
+    while let Some(row) = rows.next()? {
+        len += row.get::<_, String>(0)?.len();
+    }
+    Ok(len)
+}
+
+fn allocations(scan: fn(&Connection) -> Result<usize>, conn: &Connection) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    scan(conn).unwrap();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn bench_varchar(c: &mut Criterion) {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(&format!(
+        "CREATE TABLE strings AS SELECT 'string number ' || range AS s FROM range({ROWS})"
+    ))
+    .unwrap();
+
+    // Only fetching each result chunk allocates, not reading its rows.
+    let borrowed = allocations(scan_str, &conn);
+    let cow = allocations(scan_cow, &conn);
+    let owned = allocations(scan_string, &conn);
+    assert!(borrowed < ROWS / 100, "{borrowed} allocations scanning {ROWS} rows");
+    assert!(cow < ROWS / 100, "{cow} allocations scanning {ROWS} rows");
+    assert!(owned >= ROWS, "{owned} allocations scanning {ROWS} rows");
+
+    let mut group = c.benchmark_group("varchar");
+    group.bench_function("get_ref_as_str", |b| b.iter(|| black_box(scan_str(&conn).unwrap())));
+    group.bench_function("get_cow_str", |b| b.iter(|| black_box(scan_cow(&conn).unwrap())));
+    group.bench_function("get_string", |b| b.iter(|| black_box(scan_string(&conn).unwrap())));
+    group.finish();
+}
+
+criterion_group!(benches, bench_varchar);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use duckdb::{Connection, Result};
+
+const ROWS: i64 = 10_000;
+
+const INSERT: &str = "INSERT INTO t VALUES (?, ?)";
+
+fn setup() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch("CREATE TABLE t (id BIGINT, doubled BIGINT)")
+        .unwrap();
+    conn
+}
+
+fn insert_loop(conn: &Connection) -> Result<usize> {
+    let mut stmt = conn.prepare(INSERT)?;
+    let mut changed = 0;
+    for i in 0..ROWS {
+        changed += stmt.execute([i, i * 2])?;
+    }
+    Ok(changed)
+}
+
+fn insert_batch(conn: &Connection) -> Result<usize> {
+    let mut stmt = conn.prepare(INSERT)?;
+    stmt.execute_batch_params((0..ROWS).map(|i| [i, i * 2]))
+}
+
+fn bench_execute_batch_params(c: &mut Criterion) {
+    assert_eq!(insert_loop(&setup()).unwrap(), insert_batch(&setup()).unwrap());
+
+    let mut group = c.benchmark_group("execute_batch_params");
+    group.sample_size(10);
+    group.bench_function("loop", |b| {
+        b.iter_batched(setup, |conn| insert_loop(&conn).unwrap(), BatchSize::PerIteration)
+    });
+    group.bench_function("batch", |b| {
+        b.iter_batched(setup, |conn| insert_batch(&conn).unwrap(), BatchSize::PerIteration)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_execute_batch_params);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use duckdb::{Connection, Result};
+
+const ROWS: i64 = 10_000;
+const LOOKUPS: i64 = 100;
+
+const LOOKUP: &str = "SELECT name FROM users WHERE id = ?";
+
+fn lookup_prepare(conn: &Connection) -> Result<usize> {
+    let mut len = 0;
+    for id in 0..LOOKUPS {
+        let mut stmt = conn.prepare(LOOKUP)?;
+        len += stmt.query_row([id], |r| r.get::<_, String>(0))?.len();
+    }
+    Ok(len)
+}
+
+fn lookup_prepare_cached(conn: &Connection) -> Result<usize> {
+    let mut len = 0;
+    for id in 0..LOOKUPS {
+        let mut stmt = conn.prepare_cached(LOOKUP)?;
+        len += stmt.query_row([id], |r| r.get::<_, String>(0))?.len();
+    }
+    Ok(len)
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(&format!(
+        "CREATE TABLE users (id BIGINT PRIMARY KEY, name VARCHAR);
+        INSERT INTO users SELECT range, 'user ' || range FROM range({ROWS});"
+    ))
+    .unwrap();
+    assert_eq!(lookup_prepare(&conn).unwrap(), lookup_prepare_cached(&conn).unwrap());
+
+    let mut group = c.benchmark_group("single_row_lookup");
+    group.bench_function("prepare", |b| b.iter(|| black_box(lookup_prepare(&conn).unwrap())));
+    group.bench_function("prepare_cached", |b| {
+        b.iter(|| black_box(lookup_prepare_cached(&conn).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_lookup);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use duckdb::{Connection, Result};
+
+const ROWS: i64 = 1_000_000;
+
+const SCAN: &str = "SELECT range AS id, range * 2 AS doubled, range % 7 AS weekday, 'x' AS tag FROM range(?)";
+
+fn sum_by_index(conn: &Connection) -> Result<i64> {
+    let mut stmt = conn.prepare(SCAN)?;
+    let mut rows = stmt.query([ROWS])?;
+    let mut sum = 0;
+    while let Some(row) = rows.next()? {
+        sum += row.get::<_, i64>(0)? + row.get::<_, i64>(2)?;
+    }
+    Ok(sum)
+}
+
+fn sum_by_name(conn: &Connection) -> Result<i64> {
+    let mut stmt = conn.prepare(SCAN)?;
+    let mut rows = stmt.query([ROWS])?;
+    let mut sum = 0;
+    while let Some(row) = rows.next()? {
+        sum += row.get::<_, i64>("id")? + row.get::<_, i64>("weekday")?;
+    }
+    Ok(sum)
+}
+
+fn bench_row_access(c: &mut Criterion) {
+    let conn = Connection::open_in_memory().unwrap();
+    assert_eq!(sum_by_index(&conn).unwrap(), sum_by_name(&conn).unwrap());
+
+    let mut group = c.benchmark_group("row_access");
+    group.sample_size(10);
+    group.bench_function("by_index", |b| b.iter(|| black_box(sum_by_index(&conn).unwrap())));
+    group.bench_function("by_name", |b| b.iter(|| black_box(sum_by_name(&conn).unwrap())));
+    group.finish();
+}
+
+criterion_group!(benches, bench_row_access);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+#!/bin/bash
+
+## Turn a library built with `#[duckdb_entrypoint]` into a loadable DuckDB
+## extension, by appending the metadata DuckDB checks when it loads one.
+## DuckDB only loads it unsigned, e.g. with `duckdb -unsigned`.
+##
+## How to run
+##   `./append_metadata.sh <library> <name>.duckdb_extension <duckdb version> <platform>`
+##   e.g. `./append_metadata.sh target/debug/examples/libhello_ext.so libhello_ext.duckdb_extension v0.10.2 linux_amd64`
+##
+## The version is `SELECT version()` and the platform `PRAGMA platform` of the
+## DuckDB that loads the extension.
+
+set -e
+
+if [ $# -ne 4 ]; then
+    sed -n 's/^## \?//p' "$0"
+    exit 1
+fi
+
+library=$1
+extension=$2
+duckdb_version=$3
+platform=$4
+
+# A 32 byte field, padded with NULs.
+field() {
+    printf '%s' "$1" | head -c 32
+    head -c $((32 - ${#1})) /dev/zero
+}
+
+cp "$library" "$extension"
+{
+    # Unused fields
+    head -c 128 /dev/zero
+    # Extension version
+    field ""
+    field "$duckdb_version"
+    field "$platform"
+    # Metadata format
+    field 4
+    # Signature
+    head -c 256 /dev/zero
+} >>"$extension"
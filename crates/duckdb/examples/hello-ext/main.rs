@@ -1,16 +1,13 @@
 extern crate duckdb;
-extern crate duckdb_loadable_macros;
-extern crate libduckdb_sys;
 
 use duckdb::{
+    duckdb_entrypoint,
     vtab::{BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab},
     Connection, Result,
 };
-use duckdb_loadable_macros::duckdb_entrypoint;
-use libduckdb_sys as ffi;
 use std::{
     error::Error,
-    ffi::{c_char, c_void, CString},
+    ffi::{c_char, CString},
 };
 
 #[repr(C)]
@@ -86,6 +83,10 @@ impl VTab for HelloVTab {
 
 // Exposes a extern C function named "libhello_ext_init" in the compiled dynamic library,
 // the "entrypoint" that duckdb will use to load the extension.
+//
+// Build it with `cargo build --example hello-ext --features extension`, then turn the library
+// into `libhello_ext.duckdb_extension` with `append_metadata.sh` next to this file, and
+// `LOAD` it in a DuckDB started with `-unsigned`.
 #[duckdb_entrypoint]
 pub fn libhello_ext_init(conn: Connection) -> Result<(), Box<dyn Error>> {
     conn.register_table_function::<HelloVTab>("hello")?;
@@ -47,8 +47,8 @@ impl Appender<'_> {
 mod test {
     use crate::{Connection, Result};
     use arrow::{
-        array::{Int8Array, StringArray},
-        datatypes::{DataType, Field, Schema},
+        array::{AsArray, Float64Array, Int8Array, StringArray},
+        datatypes::{DataType, Field, Float64Type, Schema},
         record_batch::RecordBatch,
     };
     use std::sync::Arc;
@@ -79,4 +79,29 @@ mod test {
         assert_eq!(rbs.iter().map(|op| op.num_rows()).sum::<usize>(), 5);
         Ok(())
     }
+
+    #[test]
+    fn test_append_record_batch_special_floats() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE floats (d DOUBLE)")?;
+        let doubles = [
+            0x7ff8_0000_0000_0001,
+            0xfff8_0000_0000_0000,
+            0xfff0_0000_0000_0000,
+            0x8000_0000_0000_0000,
+            1,
+        ]
+        .map(f64::from_bits);
+        {
+            let schema = Schema::new(vec![Field::new("d", DataType::Float64, false)]);
+            let record_batch =
+                RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Float64Array::from(doubles.to_vec()))]).unwrap();
+            let mut app = db.appender("floats")?;
+            app.append_record_batch(record_batch)?;
+        }
+        let rbs: Vec<RecordBatch> = db.prepare("SELECT d FROM floats")?.query_arrow([])?.collect();
+        let d = rbs[0].column(0).as_primitive::<Float64Type>().values();
+        assert_eq!(doubles.map(f64::to_bits), [0, 1, 2, 3, 4].map(|i| d[i].to_bits()));
+        Ok(())
+    }
 }
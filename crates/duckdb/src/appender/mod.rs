@@ -3,7 +3,7 @@ use std::{ffi::c_void, fmt, os::raw::c_char};
 
 use crate::{
     error::result_from_duckdb_appender,
-    types::{ToSql, ToSqlOutput},
+    types::{decimal_text, list_literal, timestamptz_to_string, ToSql, ToSqlOutput, Value},
     Error,
 };
 
@@ -11,6 +11,8 @@ use crate::{
 pub struct Appender<'conn> {
     conn: &'conn Connection,
     app: ffi::duckdb_appender,
+    // The connection the appender was created on, if it has one of its own.
+    _owner: Option<Connection>,
 }
 
 #[cfg(feature = "appender-arrow")]
@@ -86,6 +88,22 @@ impl Appender<'_> {
         let value = param.to_sql()?;
 
         let ptr = self.app;
+        let value = match value {
+            ToSqlOutput::Borrowed(v @ (ValueRef::List(..) | ValueRef::Array(..))) => ToSqlOutput::Owned(v.to_owned()),
+            value => value,
+        };
+        // The appender has no way to take a nested value, so lists and arrays
+        // are appended as text and cast by DuckDB.
+        let value = match value {
+            ToSqlOutput::Owned(Value::List(ref items)) => ToSqlOutput::Owned(Value::Text(list_literal(items, "LIST")?)),
+            ToSqlOutput::Owned(Value::Array(ref items)) => {
+                ToSqlOutput::Owned(Value::Text(list_literal(items, "ARRAY")?))
+            }
+            ToSqlOutput::Owned(Value::Struct(_)) | ToSqlOutput::Borrowed(ValueRef::Struct(..)) => {
+                return Err(Error::ToSqlConversionFailure("STRUCT values can't be appended".into()))
+            }
+            value => value,
+        };
         let value = match value {
             ToSqlOutput::Borrowed(v) => v,
             ToSqlOutput::Owned(ref v) => ValueRef::from(v),
@@ -111,15 +129,32 @@ impl Appender<'_> {
                 };
                 ffi::duckdb_append_hugeint(ptr, hi)
             },
+            ValueRef::UHugeInt(i) => unsafe {
+                let hi = ffi::duckdb_uhugeint {
+                    lower: i as u64,
+                    upper: (i >> 64) as u64,
+                };
+                ffi::duckdb_append_uhugeint(ptr, hi)
+            },
 
             ValueRef::Float(r) => unsafe { ffi::duckdb_append_float(ptr, r) },
             ValueRef::Double(r) => unsafe { ffi::duckdb_append_double(ptr, r) },
             ValueRef::Text(s) => unsafe {
                 ffi::duckdb_append_varchar_length(ptr, s.as_ptr() as *const c_char, s.len() as u64)
             },
+            // there is no decimal appender in the C API, let DuckDB cast the
+            // string representation into the column type
+            ValueRef::Decimal { value, scale, .. } => unsafe {
+                let s = decimal_text(value, scale);
+                ffi::duckdb_append_varchar_length(ptr, s.as_ptr() as *const c_char, s.len() as u64)
+            },
             ValueRef::Timestamp(u, i) => unsafe {
                 ffi::duckdb_append_timestamp(ptr, ffi::duckdb_timestamp { micros: u.to_micros(i) })
             },
+            ValueRef::TimestampTz(u, i) => unsafe {
+                let s = timestamptz_to_string(u, i);
+                ffi::duckdb_append_varchar_length(ptr, s.as_ptr() as *const c_char, s.len() as u64)
+            },
             ValueRef::Blob(b) => unsafe { ffi::duckdb_append_blob(ptr, b.as_ptr() as *const c_void, b.len() as u64) },
             ValueRef::Date32(d) => unsafe { ffi::duckdb_append_date(ptr, ffi::duckdb_date { days: d }) },
             ValueRef::Time64(u, v) => unsafe {
@@ -145,7 +180,29 @@ impl Appender<'_> {
 
     #[inline]
     pub(super) fn new(conn: &Connection, app: ffi::duckdb_appender) -> Appender<'_> {
-        Appender { conn, app }
+        Appender {
+            conn,
+            app,
+            _owner: None,
+        }
+    }
+
+    /// An appender created on `owner`, a clone of `conn`, which it keeps
+    /// open until it is closed.
+    #[inline]
+    pub(super) fn with_owner(conn: &Connection, app: ffi::duckdb_appender, owner: Connection) -> Appender<'_> {
+        Appender {
+            conn,
+            app,
+            _owner: Some(owner),
+        }
+    }
+
+    /// Append a chunk of values as DuckDB holds them, whose columns are of
+    /// the types of the table's columns.
+    pub(crate) unsafe fn append_data_chunk(&mut self, chunk: ffi::duckdb_data_chunk) -> Result<()> {
+        let rc = ffi::duckdb_append_data_chunk(self.app, chunk);
+        result_from_duckdb_appender(rc, &mut self.app)
     }
 
     /// Flush data into DB
@@ -156,16 +213,46 @@ impl Appender<'_> {
             result_from_duckdb_appender(res, &mut self.app)
         }
     }
+
+    /// Flush the rows appended so far and close the appender.
+    ///
+    /// Functionally equivalent to the `Drop` implementation, but allows
+    /// callers to see the error of the last flush, which dropping the
+    /// appender only writes to stderr.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if flushing fails, e.g. because a row violates a
+    /// constraint of the table. None of the rows since the last successful
+    /// flush are written then.
+    #[inline]
+    pub fn close(mut self) -> Result<()> {
+        self.close_()
+    }
+
+    fn close_(&mut self) -> Result<()> {
+        if self.app.is_null() {
+            // A failed flush already destroyed the appender.
+            return Ok(());
+        }
+        unsafe {
+            let res = ffi::duckdb_appender_close(self.app);
+            // Destroys the appender if closing failed.
+            let res = result_from_duckdb_appender(res, &mut self.app);
+            if !self.app.is_null() {
+                ffi::duckdb_appender_destroy(&mut self.app);
+            }
+            res
+        }
+    }
 }
 
 impl Drop for Appender<'_> {
     fn drop(&mut self) {
-        if !self.app.is_null() {
-            let _ = self.flush(); // can't safely handle failures here
-            unsafe {
-                ffi::duckdb_appender_close(self.app);
-                ffi::duckdb_appender_destroy(&mut self.app);
-            }
+        // This may run while unwinding from a panic, so report failures
+        // rather than panic again.
+        if let Err(e) = self.close_() {
+            eprintln!("Error while closing DuckDB appender: {e}");
         }
     }
 }
@@ -210,10 +297,43 @@ mod test {
         Ok(())
     }
 
-    // Waiting https://github.com/duckdb/duckdb/pull/3405
+    #[test]
+    fn test_append_uhugeint() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE foo(x UHUGEINT)")?;
+
+        {
+            let mut app = db.appender("foo")?;
+            app.append_rows([[u128::MAX], [1]])?;
+        }
+
+        // UHUGEINT isn't supported by the arrow export of DuckDB, so cast it
+        let val = db.query_row("SELECT max(x)::VARCHAR FROM foo", [], |row| <(u128,)>::try_from(row))?;
+        assert_eq!(val, (u128::MAX,));
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_decimal() -> Result<()> {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE foo(x DECIMAL(18, 3))")?;
+
+        let d = Decimal::from_str("-12345.678").unwrap();
+        {
+            let mut app = db.appender("foo")?;
+            app.append_row([d])?;
+        }
+
+        let val = db.query_row("SELECT x FROM foo", [], |row| <(Decimal,)>::try_from(row))?;
+        assert_eq!(val, (d,));
+        Ok(())
+    }
+
     #[cfg(feature = "uuid")]
     #[test]
-    #[ignore = "not supported for now"]
     fn test_append_uuid() -> Result<()> {
         use uuid::Uuid;
 
@@ -248,17 +368,19 @@ mod test {
 
     #[test]
     fn test_append_timestamp() -> Result<()> {
-        use std::time::Duration;
+        use std::time::{Duration, UNIX_EPOCH};
         let db = Connection::open_in_memory()?;
         db.execute_batch("CREATE TABLE foo(x TIMESTAMP)")?;
 
         let d = Duration::from_secs(1);
         {
             let mut app = db.appender("foo")?;
-            app.append_row([d])?;
+            app.append_row([UNIX_EPOCH + d])?;
         }
 
-        let val = db.query_row("SELECT x FROM foo where x=?", [d], |row| <(i32,)>::try_from(row))?;
+        let val = db.query_row("SELECT x FROM foo where x=?", [UNIX_EPOCH + d], |row| {
+            <(i32,)>::try_from(row)
+        })?;
         assert_eq!(val, (d.as_micros() as i32,));
         Ok(())
     }
@@ -286,6 +408,111 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_appender_constraint_violation() -> Result<()> {
+        use crate::{appender_params_from_iter, ConstraintKind, Error, ErrorKind};
+
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            "CREATE TABLE parent (id INTEGER PRIMARY KEY);
+             CREATE TABLE child (
+                 id INTEGER NOT NULL,
+                 parent_id INTEGER REFERENCES parent (id),
+                 qty INTEGER CHECK (qty > 0)
+             );
+             INSERT INTO parent VALUES (1);",
+        )?;
+        let violation = |table: &str, row: &[Option<i32>]| -> Result<_> {
+            let mut appender = conn.appender(table)?;
+            appender.append_row(appender_params_from_iter(row))?;
+            match appender.flush().unwrap_err() {
+                Error::ConstraintViolation {
+                    kind,
+                    table,
+                    constraint_or_column,
+                    ..
+                } => Ok((kind, table, constraint_or_column)),
+                err => panic!("expected a constraint violation, got {err}"),
+            }
+        };
+        let owned = |s: &str| Some(s.to_owned());
+
+        assert_eq!(
+            violation("child", &[None, Some(1), Some(1)])?,
+            (ConstraintKind::NotNull, owned("child"), owned("id"))
+        );
+        assert_eq!(
+            violation("parent", &[Some(1)])?,
+            (ConstraintKind::Unique, None, owned("id"))
+        );
+        assert_eq!(
+            violation("child", &[Some(1), Some(1), Some(0)])?,
+            (ConstraintKind::Check, owned("child"), None)
+        );
+        assert_eq!(
+            violation("child", &[Some(1), Some(2), Some(1)])?,
+            (ConstraintKind::ForeignKey, None, owned("parent_id"))
+        );
+
+        let mut appender = conn.appender("child")?;
+        appender.append_row([None, Some(1), Some(1)])?;
+        assert_eq!(appender.flush().unwrap_err().kind(), ErrorKind::Constraint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_appender_close() -> Result<()> {
+        use crate::ErrorKind;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE foo (x INTEGER NOT NULL)")?;
+        let mut app = db.appender("foo")?;
+        app.append_row([1])?;
+        app.close()?;
+
+        let mut app = db.appender("foo")?;
+        app.append_row([None::<i32>])?;
+        assert_eq!(app.close().unwrap_err().kind(), ErrorKind::Constraint);
+
+        // Closing after a failed flush has nothing left to report.
+        let mut app = db.appender("foo")?;
+        app.append_row([None::<i32>])?;
+        assert!(app.flush().is_err());
+        app.close()?;
+
+        let count: i64 = db.query_row("SELECT count(*) FROM foo", [], |r| r.get(0))?;
+        assert_eq!(count, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_appender_drop_while_unwinding() -> Result<()> {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE foo (x INTEGER NOT NULL)")?;
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut app = db.appender("foo").unwrap();
+            app.append_row([None::<i32>]).unwrap();
+            // Dropping `app` while unwinding flushes the row, which fails.
+            panic!("unrelated failure");
+        }));
+        assert_eq!(res.unwrap_err().downcast_ref::<&str>(), Some(&"unrelated failure"));
+
+        // So does dropping it after a failed flush.
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut app = db.appender("foo").unwrap();
+            app.append_row([None::<i32>]).unwrap();
+            app.flush().unwrap();
+        }));
+        assert!(res.is_err());
+
+        // The connection survives, without the rows.
+        let count: i64 = db.query_row("SELECT count(*) FROM foo", [], |r| r.get(0))?;
+        assert_eq!(count, 0);
+        Ok(())
+    }
+
     #[test]
     fn test_appender_error() -> Result<(), crate::Error> {
         let conn = Connection::open_in_memory()?;
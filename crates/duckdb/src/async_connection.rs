@@ -0,0 +1,304 @@
+//! An `async` interface to connections, for the `tokio` feature
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+};
+
+use arrow::record_batch::RecordBatch;
+use tokio::sync::oneshot;
+
+use crate::{ffi, Connection, Error, InterruptHandle, Params, Result};
+
+type Job = Box<dyn FnOnce(&Connection) + Send>;
+
+// The states of a job sent to the thread of an `AsyncConnection`. A job that
+// is cancelled while queued goes straight to `DONE`, and is skipped.
+const QUEUED: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+
+/// A connection for `async` code, which runs its queries on a thread of its
+/// own so they don't block the async runtime.
+///
+/// A connection runs one query at a time, queuing the others. Use
+/// [`try_clone`](AsyncConnection::try_clone) to run queries concurrently, on
+/// connections sharing a database.
+///
+/// Dropping the future of a query before it completes cancels the query: it
+/// doesn't run if it is still queued, and is interrupted if it is running.
+/// The connection itself closes once the queries queued before it was dropped
+/// have run.
+///
+/// Parameters are sent to the connection's thread, so they must be `Send`,
+/// which `[]` isn't; pass [`params_from_iter`](crate::params_from_iter) of
+/// an empty array, such as `params_from_iter::<[i32; 0]>([])`, to queries
+/// without parameters.
+///
+/// ## Example
+///
+/// ```rust
+/// # use duckdb::{AsyncConnection, Result};
+/// async fn count(conn: &AsyncConnection) -> Result<usize> {
+///     conn.execute_batch("CREATE TABLE t AS SELECT * FROM range(10)").await?;
+///     let batches = conn.query_arrow("SELECT * FROM t WHERE range < ?", [5]).await?;
+///     Ok(batches.iter().map(|batch| batch.num_rows()).sum())
+/// }
+/// # let runtime = tokio::runtime::Runtime::new().unwrap();
+/// # let conn = AsyncConnection::open_in_memory().unwrap();
+/// # assert_eq!(runtime.block_on(count(&conn)).unwrap(), 5);
+/// ```
+pub struct AsyncConnection {
+    jobs: mpsc::Sender<Job>,
+    interrupt: Arc<InterruptHandle>,
+    // Held while interrupting a job, and by the thread while marking the job
+    // it ran as done, so an interrupt never reaches the job after it.
+    finishing: Arc<Mutex<()>>,
+}
+
+impl AsyncConnection {
+    /// Moves `conn` to a thread of its own, to run queries on from `async`
+    /// code.
+    pub fn new(conn: Connection) -> AsyncConnection {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let interrupt = Arc::new(conn.interrupt_handle());
+        thread::spawn(move || {
+            for job in queue {
+                job(&conn);
+            }
+        });
+        AsyncConnection {
+            jobs,
+            interrupt,
+            finishing: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Open a new connection to a DuckDB database, see [`Connection::open`].
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<AsyncConnection> {
+        Connection::open(path).map(AsyncConnection::new)
+    }
+
+    /// Open a new connection to an in-memory DuckDB database, see
+    /// [`Connection::open_in_memory`].
+    pub fn open_in_memory() -> Result<AsyncConnection> {
+        Connection::open_in_memory().map(AsyncConnection::new)
+    }
+
+    /// Like [`Connection::try_clone`], a new connection to the same
+    /// database, with a thread of its own.
+    pub async fn try_clone(&self) -> Result<AsyncConnection> {
+        self.call(|conn| conn.try_clone()).await.map(AsyncConnection::new)
+    }
+
+    /// Run `f` with the connection, on its thread.
+    ///
+    /// If `f` panics, the panic resumes in the caller, and the connection can
+    /// still be used.
+    ///
+    /// # Failure
+    ///
+    /// Will return the error of `f`.
+    pub async fn call<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&Connection) -> Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let state = Arc::new(AtomicU8::new(QUEUED));
+        let job_state = Arc::clone(&state);
+        let finishing = Arc::clone(&self.finishing);
+        let job: Job = Box::new(move |conn| {
+            if job_state
+                .compare_exchange(QUEUED, RUNNING, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                return;
+            }
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(conn)));
+            {
+                let _finishing = finishing.lock().unwrap();
+                job_state.store(DONE, Ordering::Release);
+            }
+            let _ = sender.send(result);
+        });
+        self.jobs.send(job).map_err(|_| closed())?;
+
+        let _cancel = CancelOnDrop { conn: self, state };
+        match receiver.await {
+            Ok(Ok(result)) => result,
+            Ok(Err(payload)) => panic::resume_unwind(payload),
+            Err(_) => Err(closed()),
+        }
+    }
+
+    /// Convenience method to run multiple SQL statements (that cannot take
+    /// any parameters), see [`Connection::execute_batch`].
+    pub async fn execute_batch(&self, sql: &str) -> Result<()> {
+        let sql = sql.to_owned();
+        self.call(move |conn| conn.execute_batch(&sql)).await
+    }
+
+    /// Convenience method to prepare and execute a single SQL statement, see
+    /// [`Connection::execute`]. Returns the number of rows changed.
+    pub async fn execute<P: Params + Send + 'static>(&self, sql: &str, params: P) -> Result<usize> {
+        let sql = sql.to_owned();
+        self.call(move |conn| conn.execute(&sql, params)).await
+    }
+
+    /// Run a query, and collect its result as Arrow record batches.
+    pub async fn query_arrow<P: Params + Send + 'static>(&self, sql: &str, params: P) -> Result<Vec<RecordBatch>> {
+        let sql = sql.to_owned();
+        self.call(move |conn| Ok(conn.prepare(&sql)?.query_arrow(params)?.collect()))
+            .await
+    }
+
+    /// Prepare a statement, to run it any number of times. It is kept in the
+    /// connection's statement cache, see [`Connection::prepare_cached`].
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `sql` cannot be prepared.
+    pub async fn prepare(&self, sql: &str) -> Result<AsyncStatement<'_>> {
+        let sql = sql.to_owned();
+        let prepared = sql.clone();
+        self.call(move |conn| conn.prepare_cached(&prepared).map(drop)).await?;
+        Ok(AsyncStatement { conn: self, sql })
+    }
+}
+
+/// A statement prepared on an [`AsyncConnection`], see
+/// [`AsyncConnection::prepare`].
+pub struct AsyncStatement<'conn> {
+    conn: &'conn AsyncConnection,
+    sql: String,
+}
+
+impl AsyncStatement<'_> {
+    /// Execute the statement, see [`Statement::execute`](crate::Statement::execute).
+    /// Returns the number of rows changed.
+    pub async fn execute<P: Params + Send + 'static>(&self, params: P) -> Result<usize> {
+        let sql = self.sql.clone();
+        self.conn
+            .call(move |conn| conn.prepare_cached(&sql)?.execute(params))
+            .await
+    }
+
+    /// Run the statement as a query, and collect its result as Arrow record
+    /// batches.
+    pub async fn query_arrow<P: Params + Send + 'static>(&self, params: P) -> Result<Vec<RecordBatch>> {
+        let sql = self.sql.clone();
+        self.conn
+            .call(move |conn| Ok(conn.prepare_cached(&sql)?.query_arrow(params)?.collect()))
+            .await
+    }
+}
+
+/// Cancels a job when the future waiting for it is dropped before it is done.
+struct CancelOnDrop<'a> {
+    conn: &'a AsyncConnection,
+    state: Arc<AtomicU8>,
+}
+
+impl Drop for CancelOnDrop<'_> {
+    fn drop(&mut self) {
+        let _finishing = self.conn.finishing.lock().unwrap();
+        if self.state.swap(DONE, Ordering::AcqRel) == RUNNING {
+            self.conn.interrupt.interrupt();
+        }
+    }
+}
+
+fn closed() -> Error {
+    Error::DuckDBFailure(
+        ffi::Error::new(ffi::DuckDBError),
+        Some("connection is closed".to_owned()),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use arrow::array::{Array, Int64Array};
+
+    use super::AsyncConnection;
+    use crate::{params_from_iter, Error, ParamsFromIter, Result};
+
+    fn no_params() -> ParamsFromIter<[i32; 0]> {
+        params_from_iter([])
+    }
+
+    fn first_i64(batches: &[arrow::record_batch::RecordBatch]) -> i64 {
+        let column = batches[0].column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(column.len(), 1);
+        column.value(0)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_queries() -> Result<()> {
+        let conn = AsyncConnection::open_in_memory()?;
+        conn.execute_batch("CREATE TABLE t AS SELECT range AS x FROM range(1000)")
+            .await?;
+        let clones = [
+            conn.try_clone().await?,
+            conn.try_clone().await?,
+            conn.try_clone().await?,
+        ];
+
+        let sql = "SELECT sum(x)::BIGINT FROM t WHERE x % 3 = ?";
+        let (a, b, c) = tokio::join!(
+            clones[0].query_arrow(sql, [0]),
+            clones[1].query_arrow(sql, [1]),
+            clones[2].query_arrow(sql, [2]),
+        );
+        assert_eq!(first_i64(&a?) + first_i64(&b?) + first_i64(&c?), 999 * 1000 / 2);
+
+        let stmt = conn.prepare("INSERT INTO t VALUES (?)").await?;
+        assert_eq!(stmt.execute([-1]).await?, 1);
+        assert_eq!(stmt.execute([-2]).await?, 1);
+        let count = clones[0].query_arrow("SELECT count(*) FROM t", no_params()).await?;
+        assert_eq!(first_i64(&count), 1002);
+
+        let err = conn
+            .execute("INSERT INTO missing VALUES (1)", no_params())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::DuckDBFailure(..)), "{err:?}");
+        assert!(conn.prepare("SELEC 1").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cancel_long_query() -> Result<()> {
+        let conn = AsyncConnection::open_in_memory()?;
+        let long = conn.query_arrow("SELECT sum(range) FROM range(1000000000000)", no_params());
+        assert!(tokio::time::timeout(Duration::from_millis(200), long).await.is_err());
+
+        // The interrupted query gives way to the next one right away.
+        let start = Instant::now();
+        let batches = conn.query_arrow("SELECT 42::BIGINT", no_params()).await?;
+        assert_eq!(first_i64(&batches), 42);
+        assert!(start.elapsed() < Duration::from_secs(30), "{:?}", start.elapsed());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_call_panic() -> Result<()> {
+        let conn = Arc::new(AsyncConnection::open_in_memory()?);
+        let job = Arc::clone(&conn);
+        let panicked = tokio::spawn(async move { job.call(|_| -> Result<()> { panic!("in job") }).await }).await;
+        assert!(panicked.unwrap_err().is_panic());
+        let batches = conn.query_arrow("SELECT 1::BIGINT", no_params()).await?;
+        assert_eq!(first_i64(&batches), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+//! Attaching other database files to a connection
+
+use std::path::{Path, PathBuf};
+
+use crate::{pragma::Sql, Connection, DatabaseName, Error, Result};
+
+/// Options for [`Connection::attach`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttachOptions {
+    /// Attach the database in read-only mode. The file must already exist.
+    pub read_only: bool,
+    /// The storage extension used to read the file, such as `sqlite`, or
+    /// `None` for a DuckDB database.
+    pub db_type: Option<String>,
+}
+
+/// A database attached to a connection, as listed by
+/// [`Connection::list_attached`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttachedDb {
+    /// The name the database is attached as, used to qualify its tables.
+    pub alias: String,
+    /// The database file, or `None` for an in-memory database.
+    pub path: Option<PathBuf>,
+    /// Whether the database was attached in read-only mode.
+    pub read_only: bool,
+}
+
+impl Connection {
+    /// Attach the database file at `path` as `alias`, making its tables
+    /// available as `alias.table_name`.
+    ///
+    /// A read-write attach creates the file if it doesn't exist. Pass
+    /// `":memory:"` to attach a new in-memory database.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{AttachOptions, Connection, Result};
+    /// fn copy_users(conn: &Connection) -> Result<()> {
+    ///     conn.attach("archive.db", "archive", AttachOptions { read_only: true, ..Default::default() })?;
+    ///     conn.execute_batch("INSERT INTO users SELECT * FROM archive.users")?;
+    ///     conn.detach("archive")
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::DatabaseAlreadyAttached`] if a database is
+    /// already attached as `alias`, [`Error::DatabaseNotFound`] if a
+    /// read-only database doesn't exist, and `Err` if `alias` is empty or the
+    /// underlying DuckDB call fails.
+    pub fn attach<P: AsRef<Path>>(&self, path: P, alias: &str, options: AttachOptions) -> Result<()> {
+        let path = path.as_ref();
+        let path_str = path.to_str().ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        if self
+            .database_names()?
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(alias))
+        {
+            return Err(Error::DatabaseAlreadyAttached(alias.to_owned()));
+        }
+        if options.read_only && path_str != ":memory:" && !path.exists() {
+            return Err(Error::DatabaseNotFound(path.to_owned()));
+        }
+
+        let mut sql = Sql::new();
+        sql.push_keyword("ATTACH")?;
+        sql.push_space();
+        sql.push_string_literal(path_str);
+        sql.push_space();
+        sql.push_keyword("AS")?;
+        sql.push_space();
+        sql.push_quoted_identifier(alias)?;
+        if options.read_only || options.db_type.is_some() {
+            sql.push_space();
+            sql.open_brace();
+            if options.read_only {
+                sql.push_keyword("READ_ONLY")?;
+            }
+            if let Some(db_type) = &options.db_type {
+                if options.read_only {
+                    sql.push_comma();
+                    sql.push_space();
+                }
+                sql.push_keyword("TYPE")?;
+                sql.push_space();
+                sql.push_identifier(db_type);
+            }
+            sql.close_brace();
+        }
+        self.execute_batch(&sql)
+    }
+
+    /// Detach the database attached as `alias`.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if no database is attached as `alias`, or if it is
+    /// the default database or empty.
+    pub fn detach(&self, alias: &str) -> Result<()> {
+        let mut sql = Sql::new();
+        sql.push_keyword("DETACH")?;
+        sql.push_space();
+        sql.push_quoted_identifier(alias)?;
+        self.execute_batch(&sql)
+    }
+
+    /// List the databases attached to the connection, starting with the one
+    /// it was opened with.
+    pub fn list_attached(&self) -> Result<Vec<AttachedDb>> {
+        let mut stmt = self.prepare(
+            "SELECT database_name, path, readonly FROM duckdb_databases() WHERE NOT internal ORDER BY database_oid",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(AttachedDb {
+                alias: row.get(0)?,
+                path: row.get::<_, Option<String>>(1)?.map(PathBuf::from),
+                read_only: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Test whether `table` exists in any schema of the database `db`, such
+    /// as `DatabaseName::Attached("archive")`. `DatabaseName::Main` is the
+    /// connection's default database.
+    pub fn table_exists(&self, db: DatabaseName<'_>, table: &str) -> Result<bool> {
+        self.query_row(
+            "SELECT count(*) > 0 FROM duckdb_tables() WHERE database_name = ? AND table_name = ?",
+            [self.catalog_name(db)?.as_str(), table],
+            |row| row.get(0),
+        )
+    }
+
+    /// The name DuckDB's catalog functions know the database `db` by, which
+    /// for `DatabaseName::Main` is that of the connection's default database.
+    pub(crate) fn catalog_name(&self, db: DatabaseName<'_>) -> Result<String> {
+        match db {
+            DatabaseName::Main => self.query_row("SELECT current_database()", [], |row| row.get(0)),
+            db => Ok(db.to_string()),
+        }
+    }
+
+    /// The names of all databases, including internal ones.
+    fn database_names(&self) -> Result<Vec<String>> {
+        let mut stmt = self.prepare("SELECT database_name FROM duckdb_databases()")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AttachOptions, AttachedDb};
+    use crate::{Connection, DatabaseName, Error, Result};
+
+    #[test]
+    fn test_attach_detach() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("it's other.db");
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE users (id INTEGER, name TEXT); INSERT INTO users VALUES (1, 'a'), (2, 'b');")?;
+
+        db.attach(&path, "other db", AttachOptions::default())?;
+        db.execute_batch(
+            r#"CREATE TABLE "other db".orders (user_id INTEGER, total INTEGER);
+            INSERT INTO "other db".orders VALUES (1, 10), (1, 5), (2, 7);"#,
+        )?;
+        assert!(db.table_exists(DatabaseName::Attached("other db"), "orders")?);
+        assert!(!db.table_exists(DatabaseName::Attached("other db"), "users")?);
+        assert!(db.table_exists(DatabaseName::Main, "users")?);
+        assert!(!db.table_exists(DatabaseName::Main, "orders")?);
+
+        let totals = db
+            .prepare(
+                r#"SELECT u.name, sum(o.total) FROM users u JOIN "other db".orders o ON o.user_id = u.id
+                GROUP BY u.name ORDER BY u.name"#,
+            )?
+            .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(totals, [("a".to_owned(), 15), ("b".to_owned(), 7)]);
+
+        {
+            let mut app = db.appender_to_catalog("orders", "other db", "main")?;
+            app.append_row([2, 1])?;
+        }
+        let n: i64 = db.query_row(r#"SELECT count(*) FROM "other db".orders"#, [], |r| r.get(0))?;
+        assert_eq!(n, 4);
+        assert_eq!(
+            db.query_row("SELECT current_database()", [], |r| r.get::<_, String>(0))?,
+            "memory"
+        );
+
+        db.execute_batch(r#"CREATE SCHEMA "other db".s; CREATE TABLE "other db".s.users (id INTEGER)"#)?;
+        db.appender_to_catalog("users", "other db", "s")?.append_row([3])?;
+        let n: i64 = db.query_row(r#"SELECT count(*) FROM "other db".s.users"#, [], |r| r.get(0))?;
+        assert_eq!(n, 1);
+        assert!(db.appender_to_catalog("users", "no such db", "main").is_err());
+
+        assert_eq!(
+            db.list_attached()?,
+            [
+                AttachedDb {
+                    alias: "memory".to_owned(),
+                    path: None,
+                    read_only: false
+                },
+                AttachedDb {
+                    alias: "other db".to_owned(),
+                    path: Some(path.clone()),
+                    read_only: false
+                },
+            ]
+        );
+        db.detach("other db")?;
+        assert_eq!(db.list_attached()?.len(), 1);
+        assert!(!db.table_exists(DatabaseName::Attached("other db"), "orders")?);
+        assert!(db.detach("other db").is_err());
+
+        let read_only = AttachOptions {
+            read_only: true,
+            ..Default::default()
+        };
+        db.attach(&path, "archive", read_only.clone())?;
+        assert!(db.list_attached()?[1].read_only);
+        let n: i64 = db.query_row("SELECT count(*) FROM archive.orders", [], |r| r.get(0))?;
+        assert_eq!(n, 4);
+        assert!(db.execute_batch("INSERT INTO archive.orders VALUES (3, 3)").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_attach_errors() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open_in_memory()?;
+        db.attach(":memory:", "scratch", AttachOptions::default())?;
+        for alias in ["scratch", "SCRATCH", "memory", "temp"] {
+            assert_eq!(
+                db.attach(dir.path().join("new.db"), alias, AttachOptions::default()),
+                Err(Error::DatabaseAlreadyAttached(alias.to_owned()))
+            );
+        }
+        assert!(!dir.path().join("new.db").exists());
+
+        let missing = dir.path().join("missing.db");
+        let read_only = AttachOptions {
+            read_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            db.attach(&missing, "missing", read_only),
+            Err(Error::DatabaseNotFound(missing.clone()))
+        );
+        let unknown_type = AttachOptions {
+            db_type: Some("no_such_type".to_owned()),
+            ..Default::default()
+        };
+        assert!(db.attach(&missing, "missing", unknown_type).is_err());
+        assert!(db.attach(":memory:", "", AttachOptions::default()).is_err());
+        assert!(db.detach("").is_err());
+        assert_eq!(db.list_attached()?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_attach_keyword_alias() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.attach(":memory:", "order", AttachOptions::default())?;
+        db.execute_batch(r#"CREATE TABLE "order".t (x INTEGER)"#)?;
+        assert!(db.table_exists(DatabaseName::Attached("order"), "t")?);
+        db.detach("order")?;
+        assert_eq!(db.list_attached()?.len(), 1);
+        Ok(())
+    }
+}
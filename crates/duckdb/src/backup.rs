@@ -0,0 +1,265 @@
+//! Copying tables from the database of one connection into another's
+
+use crate::{Connection, DatabaseName, Error, Result, TableInfo};
+
+/// The progress of [`Connection::backup_to_with_progress`], reported once
+/// each table is copied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackupProgress {
+    /// The schema of the table copied.
+    pub schema: String,
+    /// The name of the table copied.
+    pub table: String,
+    /// The number of rows copied.
+    pub rows: usize,
+    /// The number of tables copied so far, this one included.
+    pub tables_copied: usize,
+    /// The number of tables to copy.
+    pub tables_total: usize,
+}
+
+impl Connection {
+    /// Copy the tables `tables` of the `main` schema, or all tables of the
+    /// database if `None`, from this connection's database into `other`'s,
+    /// creating them with the same column types. Views, sequences and
+    /// constraints aren't copied.
+    ///
+    /// The rows are streamed a chunk at a time, as DuckDB holds them, into
+    /// an appender on `other`, so values of every type, nested ones
+    /// included, are copied as they are. The databases may be in-memory
+    /// ones, which another connection can't `ATTACH`, and nothing is written
+    /// to files. Each table is copied in a transaction of its own.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn snapshot(conn: &Connection) -> Result<Connection> {
+    ///     let copy = Connection::open_in_memory()?;
+    ///     conn.backup_to(&copy, Some(&["users", "orders"]))?;
+    ///     Ok(copy)
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::BackupTableFailed`] if a table can't be read or
+    /// created, such as when `other` already has a table of the same name,
+    /// and `Err` if the tables can't be listed.
+    pub fn backup_to(&self, other: &Connection, tables: Option<&[&str]>) -> Result<()> {
+        self.backup_to_with_progress(other, tables, |_| {})
+    }
+
+    /// Like [`backup_to`](Connection::backup_to), calling `progress` once
+    /// each table is copied.
+    pub fn backup_to_with_progress<F>(&self, other: &Connection, tables: Option<&[&str]>, mut progress: F) -> Result<()>
+    where
+        F: FnMut(&BackupProgress),
+    {
+        let tables = match tables {
+            Some(names) => names
+                .iter()
+                .map(|&name| TableInfo {
+                    schema: "main".to_owned(),
+                    name: name.to_owned(),
+                })
+                .collect(),
+            None => self.tables(DatabaseName::Main)?,
+        };
+        for (i, table) in tables.iter().enumerate() {
+            let rows = self.copy_table(other, table).map_err(|err| Error::BackupTableFailed {
+                table: format!("{}.{}", table.schema, table.name),
+                error: Box::new(err),
+            })?;
+            progress(&BackupProgress {
+                schema: table.schema.clone(),
+                table: table.name.clone(),
+                rows,
+                tables_copied: i + 1,
+                tables_total: tables.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Copies `table` into `other`, returning the number of rows copied.
+    fn copy_table(&self, other: &Connection, table: &TableInfo) -> Result<usize> {
+        let (schema, name) = (identifier(&table.schema), identifier(&table.name));
+        // Fails the way DuckDB does if there is no such table.
+        self.prepare(&format!("SELECT * FROM {schema}.{name}"))?;
+        let mut stmt = self.prepare(
+            "SELECT column_name, data_type FROM duckdb_columns()
+            WHERE database_name = current_database() AND schema_name = ? AND table_name = ?
+            ORDER BY column_index",
+        )?;
+        let columns = stmt
+            .query_map([&table.schema, &table.name], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(String, String)>>>()?;
+        let definitions: Vec<_> = columns
+            .iter()
+            .map(|(column, ty)| format!("{} {ty}", identifier(column)))
+            .collect();
+        // The appender only takes chunks whose types are exactly its table's,
+        // so the columns are read as the types the copy is created with.
+        let select: Vec<_> = columns
+            .iter()
+            .map(|(column, ty)| format!("CAST({} AS {ty})", identifier(column)))
+            .collect();
+
+        let tx = other.unchecked_transaction()?;
+        if table.schema != "main" {
+            other.execute_batch(&format!("CREATE SCHEMA IF NOT EXISTS {schema}"))?;
+        }
+        other.execute_batch(&format!("CREATE TABLE {schema}.{name} ({})", definitions.join(", ")))?;
+        let mut source = self.prepare(&format!("SELECT {} FROM {schema}.{name}", select.join(", ")))?;
+        let rows = {
+            let mut appender = other.appender_to_db(&table.name, &table.schema)?;
+            let rows = source
+                .stmt
+                .for_each_data_chunk(|chunk| unsafe { appender.append_data_chunk(chunk) })?;
+            appender.flush()?;
+            rows
+        };
+        tx.commit()?;
+        Ok(rows)
+    }
+}
+
+/// `name` quoted, as it may be a keyword such as `order`.
+fn identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod test {
+    use super::BackupProgress;
+    use crate::{Connection, Error, Result};
+
+    const TABLES: &str = r#"
+        CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy');
+        CREATE TABLE scalars AS SELECT
+            range::INTEGER AS id,
+            range * 1.5 AS d,
+            (range / 3)::DECIMAL(10, 2) AS price,
+            range % 2 = 0 AS even,
+            CASE WHEN range % 3 > 0 THEN 'it''s, ' || range END AS s,
+            DATE '2024-01-01' + range::INTEGER AS day,
+            TIMESTAMP '2024-01-01 12:00:00' + to_seconds(range) AS at,
+            ('\xAA\x00'::BLOB || range::VARCHAR::BLOB) AS b
+        FROM range(3000);
+        CREATE TABLE nested AS SELECT
+            range AS id,
+            [range, NULL, range + 1] AS l,
+            CASE WHEN range % 7 > 0 THEN {'a': range::INTEGER, 'b': ['x, y', NULL, 'z]']} END AS st,
+            MAP {'k' || range: [range::DOUBLE, NULL]} AS m,
+            [[range::SMALLINT, 2::SMALLINT], NULL]::SMALLINT[2][] AS arr,
+            [[range], [], NULL] AS ll,
+            (CASE WHEN range % 2 = 0 THEN range::INTEGER END)::UNION(n INTEGER, s VARCHAR) AS un
+        FROM range(2500);
+        CREATE SCHEMA other;
+        CREATE TABLE other.special AS SELECT
+            range AS id,
+            ['sad', 'ok', 'happy'][range % 3 + 1]::mood AS mood,
+            uuid() AS u,
+            range::HUGEINT * 10000000000000000000 AS h,
+            TIMESTAMPTZ '2024-01-01 00:00:00+00' + INTERVAL (range) MINUTE AS tz,
+            INTERVAL (range) DAY AS iv,
+            [CASE WHEN range % 2 = 0 THEN 'ok'::mood END] AS moods,
+            TIME '10:00:00' AS t,
+            TIMETZ '10:00:00+02' AS ttz
+        FROM range(100);
+    "#;
+
+    fn checksum(db: &Connection, table: &str) -> Result<(i64, String)> {
+        db.query_row(
+            &format!("SELECT count(*), md5(string_agg(t::VARCHAR, '|' ORDER BY t.id)) FROM {table} t"),
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+
+    fn column_types(db: &Connection, table: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = db.prepare(
+            "SELECT column_name, data_type FROM duckdb_columns()
+            WHERE schema_name || '.' || table_name = ? ORDER BY column_index",
+        )?;
+        let rows = stmt.query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    #[test]
+    fn test_backup_file_to_memory() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let source = Connection::open(dir.path().join("source.db"))?;
+        source.execute_batch(TABLES)?;
+        let copy = Connection::open_in_memory()?;
+        let mut reported = Vec::new();
+        source.backup_to_with_progress(&copy, None, |progress| reported.push(progress.clone()))?;
+
+        let progress = |schema: &str, table: &str, rows, tables_copied| BackupProgress {
+            schema: schema.to_owned(),
+            table: table.to_owned(),
+            rows,
+            tables_copied,
+            tables_total: 3,
+        };
+        assert_eq!(
+            reported,
+            [
+                progress("main", "nested", 2500, 1),
+                progress("main", "scalars", 3000, 2),
+                progress("other", "special", 100, 3),
+            ]
+        );
+        for table in ["main.scalars", "main.nested", "other.special"] {
+            assert_eq!(column_types(&copy, table)?, column_types(&source, table)?, "{table}");
+            assert_eq!(checksum(&copy, table)?, checksum(&source, table)?, "{table}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_memory_to_file() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let source = Connection::open_in_memory()?;
+        source.execute_batch(TABLES)?;
+        let path = dir.path().join("copy.db");
+        {
+            let copy = Connection::open(&path)?;
+            source.backup_to(&copy, Some(&["scalars"]))?;
+        }
+        let copy = Connection::open(&path)?;
+        assert_eq!(checksum(&copy, "scalars")?, checksum(&source, "scalars")?);
+        assert!(copy.prepare("SELECT * FROM nested").is_err());
+
+        // The table exists now.
+        match source.backup_to(&copy, Some(&["scalars"])).unwrap_err() {
+            Error::BackupTableFailed { table, .. } => assert_eq!(table, "main.scalars"),
+            err => panic!("unexpected error {err:?}"),
+        }
+        match source.backup_to(&copy, Some(&["missing"])).unwrap_err() {
+            Error::BackupTableFailed { table, .. } => assert_eq!(table, "main.missing"),
+            err => panic!("unexpected error {err:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_keyword_names() -> Result<()> {
+        let source = Connection::open_in_memory()?;
+        source.execute_batch(
+            r#"CREATE SCHEMA "select";
+            CREATE TABLE "select"."order" ("group" INTEGER, "say ""hi""" TEXT);
+            INSERT INTO "select"."order" VALUES (1, 'a'), (2, 'b');"#,
+        )?;
+        let copy = Connection::open_in_memory()?;
+        source.backup_to(&copy, None)?;
+        let rows: i64 = copy.query_row(
+            r#"SELECT sum("group") FROM "select"."order" WHERE "say ""hi""" IS NOT NULL"#,
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(rows, 3);
+        Ok(())
+    }
+}
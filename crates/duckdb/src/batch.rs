@@ -0,0 +1,577 @@
+//! Running a batch of statements and reporting on each of them
+
+use std::ops::Range;
+
+use crate::{types::Value, Connection, Error, Result, Statement};
+
+/// What one statement of a batch did, as returned by
+/// [`Connection::execute_batch_verbose`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchResult<'a> {
+    /// The text of the statement within the batch, without the semicolon
+    /// ending it and the whitespace and comments before it.
+    ///
+    /// Empty if the statements couldn't be matched up with the text of the
+    /// batch.
+    pub sql: &'a str,
+    /// The number of rows inserted, updated or deleted by the statement.
+    pub rows_changed: usize,
+    /// The names of the columns of `rows`, empty if there are no rows.
+    pub columns: Vec<String>,
+    /// The rows returned by the statement, or `None` if it isn't a query,
+    /// such as `CREATE TABLE` or an `INSERT` without `RETURNING`.
+    pub rows: Option<Vec<Vec<Value>>>,
+}
+
+impl Connection {
+    /// Like [`execute_batch`](Connection::execute_batch), but returns what
+    /// each statement did, along with the rows of the statements returning
+    /// any.
+    ///
+    /// Each statement is prepared just before it runs, so it may refer to
+    /// tables created earlier in the batch.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use duckdb::{Connection, Result};
+    /// fn run(conn: &Connection) -> Result<()> {
+    ///     let results = conn.execute_batch_verbose(
+    ///         "CREATE TABLE t (x INTEGER);
+    ///          INSERT INTO t VALUES (1), (2);
+    ///          SELECT sum(x) AS total FROM t;",
+    ///     )?;
+    ///     assert_eq!(results[1].rows_changed, 2);
+    ///     assert_eq!(results[2].columns, ["total"]);
+    ///     assert_eq!(results[2].rows.as_ref().unwrap().len(), 1);
+    ///     Ok(())
+    /// }
+    /// # run(&Connection::open_in_memory().unwrap()).unwrap();
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `sql` can't be parsed, in which case no statement
+    /// has run, and [`Error::BatchStatementFailed`] with the index of the
+    /// first statement that fails to prepare or run. The statements after it
+    /// don't run.
+    pub fn execute_batch_verbose<'a>(&self, sql: &'a str) -> Result<Vec<BatchResult<'a>>> {
+        let extracted = self.db.borrow_mut().extract_statements(sql)?;
+        let mut texts = split_statements(sql);
+        if texts.len() != extracted.len() {
+            texts = vec![""; extracted.len()];
+        }
+        let mut results = Vec::with_capacity(texts.len());
+        for (index, text) in texts.into_iter().enumerate() {
+            let stmt = self.db.borrow_mut().prepare_extracted(self, &extracted, index);
+            let result = stmt.and_then(|mut stmt| run(&mut stmt, text));
+            results.push(result.map_err(|err| Error::BatchStatementFailed(index, Box::new(err)))?);
+        }
+        Ok(results)
+    }
+
+    /// Prepare each statement of `sql`, which may hold several separated by
+    /// semicolons, unlike [`prepare`](Connection::prepare).
+    ///
+    /// The statements are all prepared before any of them runs, so one
+    /// can't refer to a table an earlier one creates; run such scripts with
+    /// [`execute_batch_verbose`](Connection::execute_batch_verbose) instead.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn describe(conn: &Connection, script: &str) -> Result<()> {
+    ///     for stmt in conn.prepare_multi(script)? {
+    ///         println!("{} parameters, {} columns", stmt.parameter_count(), stmt.column_count());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `sql` can't be parsed, or if any of its
+    /// statements fails to prepare.
+    pub fn prepare_multi(&self, sql: &str) -> Result<Vec<Statement<'_>>> {
+        let extracted = self.db.borrow_mut().extract_statements(sql)?;
+        let texts = split_statements(sql);
+        (0..extracted.len())
+            .map(|index| {
+                let mut stmt = self.db.borrow_mut().prepare_extracted(self, &extracted, index)?;
+                if texts.len() == extracted.len() {
+                    stmt.stmt.set_sql(texts[index]);
+                }
+                Ok(stmt)
+            })
+            .collect()
+    }
+}
+
+/// Runs a statement of a batch, collecting its rows if it returns any.
+fn run<'a>(stmt: &mut Statement<'_>, sql: &'a str) -> Result<BatchResult<'a>> {
+    stmt.stmt.set_sql(sql);
+    let rows_changed = stmt.raw_execute()?;
+    let columns = stmt.column_names();
+    if !stmt.returns_rows() {
+        return Ok(BatchResult {
+            sql,
+            rows_changed,
+            columns: Vec::new(),
+            rows: None,
+        });
+    }
+    let mut rows = Vec::new();
+    let mut query = stmt.raw_query();
+    while let Some(row) = query.next()? {
+        rows.push((0..columns.len()).map(|i| row.get(i)).collect::<Result<_>>()?);
+    }
+    Ok(BatchResult {
+        sql,
+        rows_changed: 0,
+        columns,
+        rows: Some(rows),
+    })
+}
+
+/// Splits `sql` at the semicolons DuckDB's parser splits it at, those outside
+/// of strings, quoted identifiers and comments. Pieces holding nothing but
+/// whitespace and comments are left out, as DuckDB does.
+pub(crate) fn split_statements(sql: &str) -> Vec<&str> {
+    statement_spans(sql).into_iter().map(|(span, _)| &sql[span]).collect()
+}
+
+/// Where each statement [`split_statements`] finds is in `sql`, along with
+/// where the rest of `sql` after it starts: past the semicolon ending it, or
+/// at the end of `sql`.
+pub(crate) fn statement_spans(sql: &str) -> Vec<(Range<usize>, usize)> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    // Where the current statement starts, once a token of it has been seen.
+    let mut start = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b';' => {
+                if let Some(start) = start.take() {
+                    statements.push((start..start + sql[start..i].trim_end().len(), i + 1));
+                }
+                i += 1;
+                continue;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |n| i + n + 1);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_block_comment(bytes, i);
+                continue;
+            }
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+        i = match bytes[i] {
+            b'\'' => skip_string(bytes, i),
+            b'"' => skip_quoted(bytes, i, b'"', false),
+            b'$' if i == 0 || !is_identifier_byte(bytes[i - 1]) => skip_dollar_quoted(bytes, i).unwrap_or(i + 1),
+            _ => i + 1,
+        };
+    }
+    if let Some(start) = start {
+        statements.push((start..start + sql[start..].trim_end().len(), sql.len()));
+    }
+    statements
+}
+
+/// Turns the error DuckDB reports when preparing `sql` with several
+/// statements into [`Error::MultipleStatements`].
+pub(crate) fn multiple_statements(err: Error, sql: &str) -> Error {
+    match err {
+        Error::DuckDBFailure(_, Some(ref message))
+            if message.contains("Cannot prepare multiple statements at once") =>
+        {
+            match statement_spans(sql).first() {
+                Some(&(_, first_tail_offset)) if first_tail_offset < sql.len() => {
+                    Error::MultipleStatements { first_tail_offset }
+                }
+                _ => err,
+            }
+        }
+        err => err,
+    }
+}
+
+/// A parameter of a statement, as written in its text.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Placeholder<'a> {
+    /// `?`, which DuckDB numbers after the highest parameter before it.
+    Next,
+    /// `?NNN` or `$NNN`.
+    Numbered(usize),
+    /// `$name`.
+    Named(&'a str),
+}
+
+/// The parameters in `sql` and where they are, skipping strings, quoted
+/// identifiers and comments the way [`split_statements`] does.
+pub(crate) fn placeholders(sql: &str) -> Vec<(Range<usize>, Placeholder<'_>)> {
+    let bytes = sql.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        i = match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => bytes[i..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(bytes.len(), |n| i + n + 1),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => skip_block_comment(bytes, i),
+            b'\'' => skip_string(bytes, i),
+            b'"' => skip_quoted(bytes, i, b'"', false),
+            b'?' => {
+                let end = i + 1 + bytes[i + 1..].iter().take_while(|b| b.is_ascii_digit()).count();
+                let placeholder = if end == i + 1 {
+                    Some(Placeholder::Next)
+                } else {
+                    sql[i + 1..end].parse().ok().map(Placeholder::Numbered)
+                };
+                found.extend(placeholder.map(|p| (i..end, p)));
+                end
+            }
+            b'$' if i == 0 || !is_identifier_byte(bytes[i - 1]) => match skip_dollar_quoted(bytes, i) {
+                Some(end) => end,
+                None => {
+                    let rest = &bytes[i + 1..];
+                    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+                    let len = match digits {
+                        0 => rest.iter().take_while(|&&b| b != b'$' && is_identifier_byte(b)).count(),
+                        _ => digits,
+                    };
+                    let name = &sql[i + 1..i + 1 + len];
+                    let placeholder = match (digits, len) {
+                        (_, 0) => None,
+                        (0, _) => Some(Placeholder::Named(name)),
+                        _ => name.parse().ok().map(Placeholder::Numbered),
+                    };
+                    found.extend(placeholder.map(|p| (i..i + 1 + len, p)));
+                    i + 1 + len
+                }
+            },
+            _ => i + 1,
+        };
+    }
+    found
+}
+
+/// The tokens of `sql`, skipping whitespace and comments the way
+/// [`split_statements`] does: words, strings and quoted identifiers along
+/// with their quotes, and each other character on its own.
+pub(crate) fn tokens(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let end = match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |n| i + n + 1);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_block_comment(bytes, i);
+                continue;
+            }
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'\'' => skip_string(bytes, i),
+            b'"' => skip_quoted(bytes, i, b'"', false),
+            b'$' if i == 0 || !is_identifier_byte(bytes[i - 1]) => skip_dollar_quoted(bytes, i).unwrap_or(i + 1),
+            b if is_identifier_byte(b) => i + bytes[i..].iter().take_while(|&&b| is_identifier_byte(b)).count(),
+            _ => i + 1,
+        };
+        found.push(&sql[i..end]);
+        i = end;
+    }
+    found
+}
+
+#[inline]
+fn is_identifier_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || !b.is_ascii()
+}
+
+/// The end of the string opened at `i`, which takes backslash escapes if
+/// it's written `E'...'`.
+fn skip_string(bytes: &[u8], i: usize) -> usize {
+    let escapes = i > 0 && matches!(bytes[i - 1], b'e' | b'E') && (i < 2 || !is_identifier_byte(bytes[i - 2]));
+    skip_quoted(bytes, i, b'\'', escapes)
+}
+
+/// The end of the string or quoted identifier opened by `quote` at `i`, where
+/// a doubled `quote` doesn't close it, nor with `escapes` one after a
+/// backslash.
+fn skip_quoted(bytes: &[u8], i: usize, quote: u8, escapes: bool) -> usize {
+    let mut j = i + 1;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' if escapes => j += 2,
+            b if b == quote && bytes.get(j + 1) == Some(&quote) => j += 2,
+            b if b == quote => return j + 1,
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// The end of the `/* */` comment opened at `i`, which may nest.
+fn skip_block_comment(bytes: &[u8], i: usize) -> usize {
+    let mut depth = 0;
+    let mut j = i;
+    while j + 1 < bytes.len() {
+        match &bytes[j..j + 2] {
+            b"/*" => {
+                depth += 1;
+                j += 2;
+            }
+            b"*/" => {
+                depth -= 1;
+                j += 2;
+                if depth == 0 {
+                    return j;
+                }
+            }
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// The end of the `$tag$ ... $tag$` string opened at `i`, or `None` if there
+/// is no such string at `i`, like for the parameter `$1`.
+fn skip_dollar_quoted(bytes: &[u8], i: usize) -> Option<usize> {
+    let tag_len = bytes[i + 1..].iter().position(|&b| b == b'$')?;
+    let tag = &bytes[i + 1..i + 1 + tag_len];
+    if tag.first().is_some_and(|b| b.is_ascii_digit()) || !tag.iter().all(|&b| is_identifier_byte(b)) {
+        return None;
+    }
+    let delimiter = &bytes[i..i + tag_len + 2];
+    let body = i + delimiter.len();
+    Some(
+        bytes[body..]
+            .windows(delimiter.len())
+            .position(|w| w == delimiter)
+            .map_or(bytes.len(), |n| body + n + delimiter.len()),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{placeholders, split_statements, statement_spans, tokens, BatchResult, Placeholder};
+    use crate::{types::Value, Connection, Error, Result};
+
+    #[test]
+    fn test_execute_batch_verbose() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let results = db.execute_batch_verbose(
+            "-- set up
+            CREATE TABLE t (x INTEGER, s TEXT);
+            INSERT INTO t VALUES (1, 'a;b'), (2, NULL);
+            SELECT x, s FROM t ORDER BY x;
+            UPDATE t SET x = x + 10 RETURNING x;
+            DELETE FROM t WHERE x = 11",
+        )?;
+        assert_eq!(
+            results,
+            [
+                BatchResult {
+                    sql: "CREATE TABLE t (x INTEGER, s TEXT)",
+                    rows_changed: 0,
+                    columns: vec![],
+                    rows: None,
+                },
+                BatchResult {
+                    sql: "INSERT INTO t VALUES (1, 'a;b'), (2, NULL)",
+                    rows_changed: 2,
+                    columns: vec![],
+                    rows: None,
+                },
+                BatchResult {
+                    sql: "SELECT x, s FROM t ORDER BY x",
+                    rows_changed: 0,
+                    columns: vec!["x".to_owned(), "s".to_owned()],
+                    rows: Some(vec![
+                        vec![Value::Int(1), Value::Text("a;b".to_owned())],
+                        vec![Value::Int(2), Value::Null],
+                    ]),
+                },
+                BatchResult {
+                    sql: "UPDATE t SET x = x + 10 RETURNING x",
+                    rows_changed: 0,
+                    columns: vec!["x".to_owned()],
+                    rows: Some(vec![vec![Value::Int(11)], vec![Value::Int(12)]]),
+                },
+                BatchResult {
+                    sql: "DELETE FROM t WHERE x = 11",
+                    rows_changed: 1,
+                    columns: vec![],
+                    rows: None,
+                },
+            ]
+        );
+        assert!(db.execute_batch_verbose(" ; -- nothing")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_batch_verbose_failure() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .execute_batch_verbose(
+                "CREATE TABLE t (x INTEGER);
+                INSERT INTO t VALUES (1);
+                SELECT x FROM t;
+                INSERT INTO missing VALUES (2);
+                INSERT INTO t VALUES (3);",
+            )
+            .unwrap_err();
+        match err {
+            Error::BatchStatementFailed(3, ref inner) => {
+                assert!(matches!(**inner, Error::DuckDBFailure(..)), "{inner:?}");
+                assert!(
+                    err.to_string()
+                        .starts_with("Statement 3 of the batch failed: Catalog Error"),
+                    "{err}"
+                );
+            }
+            err => panic!("unexpected {err:?}"),
+        }
+        // The statements before the failing one ran, the ones after it didn't.
+        let count: i64 = db.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 1);
+
+        // Nothing runs if the batch doesn't parse.
+        let err = db
+            .execute_batch_verbose("INSERT INTO t VALUES (4); SELEC 1")
+            .unwrap_err();
+        assert!(matches!(err, Error::DuckDBFailure(..)), "{err:?}");
+        let count: i64 = db.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepare_multi() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let script = "-- setup; not a statement
+            SELECT 'a;b' AS x; /* ; */ SELECT ?::INTEGER + 1 AS y;
+            SELECT \"c;d\" FROM (SELECT 2 AS \"c;d\");";
+        let mut stmts = db.prepare_multi(script)?;
+        assert_eq!(stmts.len(), 3);
+        let counts: Vec<_> = stmts.iter().map(|stmt| stmt.parameter_count()).collect();
+        assert_eq!(counts, [0, 1, 0]);
+        assert_eq!(stmts[0].query_row([], |r| r.get::<_, String>(0))?, "a;b");
+        assert_eq!(stmts[1].query_row([2], |r| r.get::<_, i32>(0))?, 3);
+        assert_eq!(stmts[2].query_row([], |r| r.get::<_, i32>(0))?, 2);
+        assert_eq!(stmts[1].expanded_sql().as_deref(), Some("SELECT 2::INTEGER + 1 AS y"));
+        assert!(db.prepare_multi(" -- nothing\n;")?.is_empty());
+
+        // Nothing is prepared if a statement can't be parsed.
+        let script = "SELECT 1; SELECT ';'; SELEC 3";
+        let err = db.prepare_multi(script).unwrap_err();
+        assert!(
+            err.sql_error_position().unwrap() >= script.find("SELEC 3").unwrap(),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepare_multiple_statements() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let err = db.prepare("SELECT ';' AS x; SELECT 2").unwrap_err();
+        assert_eq!(err, Error::MultipleStatements { first_tail_offset: 16 });
+        let err = db.prepare("SELECT 1 /* ; */ ; -- ;\n SELECT 2;").unwrap_err();
+        assert_eq!(err, Error::MultipleStatements { first_tail_offset: 18 });
+        // A trailing semicolon or comment is no statement.
+        db.prepare("SELECT 1; -- done")?;
+        assert_eq!(statement_spans("SELECT 1; -- done"), [(0..8, 9)]);
+        assert_eq!(statement_spans(" SELECT 1 ; SELECT 2 "), [(1..9, 11), (12..20, 21)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(split_statements(""), Vec::<&str>::new());
+        assert_eq!(split_statements(" ;; -- a; b\n /* c; */ "), Vec::<&str>::new());
+        assert_eq!(split_statements("SELECT 1;SELECT 2 ; "), ["SELECT 1", "SELECT 2"]);
+        assert_eq!(
+            split_statements("SELECT 'a;''b'; SELECT \"c;\"\"d\"; SELECT E'e\\';f'"),
+            ["SELECT 'a;''b'", "SELECT \"c;\"\"d\"", "SELECT E'e\\';f'"]
+        );
+        assert_eq!(
+            split_statements("/* x /* y; */ ; */ SELECT 1 -- z;\n; SELECT $$;$$, $t$ $$; $t$; SELECT $1, a$b"),
+            ["SELECT 1 -- z;", "SELECT $$;$$, $t$ $$; $t$", "SELECT $1, a$b"]
+        );
+        assert_eq!(
+            split_statements("SELECT 'unterminated; SELECT 2"),
+            ["SELECT 'unterminated; SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let sql = "SELECT ?, $1, ?3, $name, '?', \"$x\", $$?$$, a$b -- ?\n /* $y */ FROM t WHERE x = ?";
+        let found: Vec<_> = placeholders(sql)
+            .into_iter()
+            .map(|(range, p)| (&sql[range], p))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("?", Placeholder::Next),
+                ("$1", Placeholder::Numbered(1)),
+                ("?3", Placeholder::Numbered(3)),
+                ("$name", Placeholder::Named("name")),
+                ("?", Placeholder::Next),
+            ]
+        );
+        assert!(placeholders("SELECT 1").is_empty());
+    }
+
+    #[test]
+    fn test_tokens() {
+        let sql = "FROM read_csv('it''s.csv', x := 1) -- a comment\n/* b */ WHERE \"a b\" >= E'\\''";
+        assert_eq!(
+            tokens(sql),
+            [
+                "FROM",
+                "read_csv",
+                "(",
+                "'it''s.csv'",
+                ",",
+                "x",
+                ":",
+                "=",
+                "1",
+                ")",
+                "WHERE",
+                "\"a b\"",
+                ">",
+                "=",
+                "E",
+                "'\\''",
+            ]
+        );
+        assert!(tokens(" -- nothing").is_empty());
+    }
+}
@@ -1,9 +1,9 @@
 //! Prepared statements cache for faster execution.
 
-use crate::{raw_statement::RawStatement, Connection, Result, Statement};
+use crate::{raw_statement::RawStatement, Connection, Error, ErrorKind, Result, Statement};
 use hashlink::LruCache;
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     ops::{Deref, DerefMut},
     sync::Arc,
 };
@@ -31,6 +31,21 @@ impl Connection {
     /// }
     /// ```
     ///
+    /// Bindings are cleared when the statement returns to the cache, so each
+    /// use starts with none.
+    ///
+    /// DuckDB rebinds a prepared statement itself when the tables it uses
+    /// change, but some changes, such as detaching the database that was the
+    /// default one when it was prepared, make a statement without parameters
+    /// fail where preparing the SQL again would succeed. Such a cached
+    /// statement that fails to execute with a
+    /// [binder](crate::ErrorKind::Binder) or
+    /// [catalog](crate::ErrorKind::Catalog) error is therefore prepared again
+    /// from its SQL and executed once more, and only the error of that
+    /// execution is returned. See
+    /// [`prepared_statement_reprepares`](Connection::prepared_statement_reprepares)
+    /// for how often that happened.
+    ///
     /// # Failure
     ///
     /// Will return `Err` if `sql` cannot be converted to a C-compatible string
@@ -55,11 +70,19 @@ impl Connection {
     pub fn flush_prepared_statement_cache(&self) {
         self.cache.flush();
     }
+
+    /// The number of times a cached statement was prepared again on this
+    /// connection, because it failed after a change to the catalog, see
+    /// [`prepare_cached`](Connection::prepare_cached).
+    #[inline]
+    pub fn prepared_statement_reprepares(&self) -> u64 {
+        self.cache.1.get()
+    }
 }
 
 /// Prepared statements LRU cache.
 #[derive(Debug)]
-pub struct StatementCache(RefCell<LruCache<Arc<str>, RawStatement>>);
+pub struct StatementCache(RefCell<LruCache<Arc<str>, RawStatement>>, Cell<u64>);
 
 #[allow(clippy::non_send_fields_in_send_ty)]
 unsafe impl Send for StatementCache {}
@@ -121,7 +144,7 @@ impl StatementCache {
     /// Create a statement cache.
     #[inline]
     pub fn with_capacity(capacity: usize) -> StatementCache {
-        StatementCache(RefCell::new(LruCache::new(capacity)))
+        StatementCache(RefCell::new(LruCache::new(capacity)), Cell::new(0))
     }
 
     #[inline]
@@ -150,19 +173,21 @@ impl StatementCache {
     }
 
     // Return a statement to the cache.
-    fn cache_stmt(&self, stmt: RawStatement) {
+    fn cache_stmt(&self, mut stmt: RawStatement) {
         if stmt.is_null() {
             return;
         }
-        let mut cache = self.0.borrow_mut();
+        // This runs when a `CachedStatement` is dropped, so it mustn't
+        // panic: a statement that can't go back into the cache is finalized
+        // instead.
+        let Ok(mut cache) = self.0.try_borrow_mut() else {
+            return;
+        };
         stmt.clear_bindings();
+        // The tables may change before the statement is used again.
+        stmt.forget_columns();
         if let Some(sql) = stmt.statement_cache_key() {
             cache.insert(sql, stmt);
-        } else {
-            debug_assert!(
-                false,
-                "bug in statement cache code, statement returned to cache that without key"
-            );
         }
     }
 
@@ -173,10 +198,33 @@ impl StatementCache {
     }
 }
 
+impl Statement<'_> {
+    // Replace a statement from the cache with a newly prepared one for the
+    // same SQL.
+    pub(crate) fn reprepare(&mut self) -> Result<()> {
+        let key = self.stmt.statement_cache_key().expect("statement from the cache");
+        let mut stmt = unsafe { self.conn.prepare(&key)?.into_raw() };
+        stmt.set_statement_cache_key(key);
+        // It is still the same statement to the caller.
+        stmt.set_execution_times(self.stmt.execution_times());
+        self.stmt = stmt;
+        let reprepares = &self.conn.cache.1;
+        reprepares.set(reprepares.get() + 1);
+        Ok(())
+    }
+}
+
+/// Whether `err` may come from executing a statement prepared before a change
+/// to the catalog. DuckDB raises these when binding the statement, before it
+/// has any effect.
+pub(crate) fn is_catalog_change(err: &Error) -> bool {
+    matches!(err.kind(), ErrorKind::Binder | ErrorKind::Catalog)
+}
+
 #[cfg(test)]
 mod test {
     use super::StatementCache;
-    use crate::{Connection, Result};
+    use crate::{Connection, Error, ErrorKind, Result};
     use fallible_iterator::FallibleIterator;
 
     impl StatementCache {
@@ -191,6 +239,10 @@ mod test {
         fn capacity(&self) -> usize {
             self.0.borrow().capacity()
         }
+
+        fn contains(&self, sql: &str) -> bool {
+            self.0.borrow().peek(sql).is_some()
+        }
     }
 
     #[test]
@@ -255,6 +307,117 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_capacity_eviction() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let cache = &db.cache;
+        db.set_prepared_statement_cache_capacity(2);
+
+        let [a, b, c] = ["SELECT 1", "SELECT 2", "SELECT 3"];
+        for sql in [a, b, a, c] {
+            db.prepare_cached(sql)?.execute([])?;
+        }
+        // `b` was the least recently used when `c` was cached.
+        assert_eq!(2, cache.len());
+        assert!(cache.contains(a) && cache.contains(c));
+        assert!(!cache.contains(b));
+
+        db.prepare_cached(b)?.execute([])?;
+        assert!(!cache.contains(a));
+        db.set_prepared_statement_cache_capacity(1);
+        assert_eq!(1, cache.len());
+        assert!(cache.contains(b));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bindings_reset() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let sql = "SELECT ?::INTEGER + 1";
+        {
+            let mut stmt = db.prepare_cached(sql)?;
+            stmt.raw_bind_parameter(1, 41)?;
+            stmt.raw_execute()?;
+            assert_eq!(42, stmt.raw_query().next()?.unwrap().get::<_, i32>(0)?);
+        }
+        let mut stmt = db.prepare_cached(sql)?;
+        assert!(stmt.raw_execute().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reprepare_after_catalog_change() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            r#"
+            ATTACH ':memory:' AS other;
+            CREATE TABLE other.t AS SELECT 1 AS id, 'other' AS name;
+            CREATE TABLE t AS SELECT 1 AS id, 'memory' AS name;
+            USE other;
+        "#,
+        )?;
+        let sql = "SELECT name FROM t";
+        let mut stale = db.prepare(sql)?;
+        assert_eq!(
+            "other",
+            db.prepare_cached(sql)?.query_row([], |r| r.get::<_, String>(0))?
+        );
+        assert_eq!("other", stale.query_row([], |r| r.get::<_, String>(0))?);
+        assert_eq!(db.prepared_statement_reprepares(), 0);
+
+        // Both statements now refer to a database that is gone.
+        db.execute_batch("USE memory; DETACH other")?;
+        assert!(stale.query_row([], |r| r.get::<_, String>(0)).is_err());
+        {
+            let mut stmt = db.prepare_cached(sql)?;
+            assert_eq!("memory", stmt.query_row([], |r| r.get::<_, String>(0))?);
+            assert_eq!("memory", stmt.query_row([], |r| r.get::<_, String>(0))?);
+        }
+        assert_eq!(db.prepared_statement_reprepares(), 1);
+        assert_eq!(1, db.cache.len());
+        assert_eq!(
+            "memory",
+            db.prepare_cached(sql)?.query_row([], |r| r.get::<_, String>(0))?
+        );
+
+        // Errors that preparing again doesn't fix are returned as they are.
+        db.execute_batch("DROP TABLE t")?;
+        match db.prepare_cached(sql)?.query_row([], |r| r.get::<_, String>(0)) {
+            Err(Error::DuckDBFailure(_, Some(msg))) => assert!(msg.starts_with("Catalog Error: "), "{msg}"),
+            res => panic!("unexpected {res:?}"),
+        }
+        assert_eq!(db.prepared_statement_reprepares(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cached_statement_after_alter() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (id INTEGER); INSERT INTO t VALUES (1)")?;
+        let sql = "SELECT * FROM t";
+        let columns = |db: &Connection| -> Result<Vec<String>> {
+            let mut stmt = db.prepare_cached(sql)?;
+            let mut rows = stmt.query([])?;
+            rows.next()?;
+            Ok(rows.as_ref().unwrap().column_names())
+        };
+        assert_eq!(columns(&db)?, ["id"]);
+
+        assert_eq!(db.prepare_cached(sql)?.column_count(), 1);
+        db.execute_batch("ALTER TABLE t ADD COLUMN name TEXT DEFAULT 'x'")?;
+        assert_eq!(db.prepare_cached(sql)?.column_count(), 2);
+        assert_eq!(columns(&db)?, ["id", "name"]);
+        let name: String = db.prepare_cached(sql)?.query_row([], |r| r.get("name"))?;
+        assert_eq!(name, "x");
+
+        db.execute_batch("DROP TABLE t")?;
+        let reprepares = db.prepared_statement_reprepares();
+        let err = columns(&db).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Catalog, "{err}");
+        assert_eq!(db.prepared_statement_reprepares(), reprepares);
+        Ok(())
+    }
+
     #[test]
     fn test_discard() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -0,0 +1,194 @@
+//! Cancelling one statement rather than whatever the connection runs
+
+use std::sync::{Arc, Mutex};
+
+use crate::{Error, InterruptHandle, Result, Statement};
+
+/// Cancels the executions of the statement it was taken from, with
+/// [`Statement::cancellation_token`], from any thread.
+///
+/// Unlike an [`InterruptHandle`], it never interrupts another statement of
+/// the connection.
+#[derive(Clone)]
+pub struct CancellationToken {
+    executions: Arc<Mutex<Executions>>,
+    interrupt: InterruptHandle,
+}
+
+/// The executions of a statement, as its cancellation tokens see them.
+#[derive(Default)]
+pub(crate) struct Executions {
+    // The generation of the execution running, if any.
+    running: Option<u64>,
+    next: u64,
+    // The generation of the execution that was interrupted by a cancel.
+    cancelled: Option<u64>,
+    // Whether the next execution is cancelled before it starts.
+    cancel_next: bool,
+}
+
+impl Executions {
+    /// Runs `f`, an execution of the statement, unless it was cancelled
+    /// before, replacing the error of an execution interrupted by a cancel
+    /// with [`Error::Cancelled`].
+    pub(crate) fn run<T>(executions: &Mutex<Executions>, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let generation = {
+            let mut executions = executions.lock().unwrap();
+            if std::mem::take(&mut executions.cancel_next) {
+                return Err(Error::Cancelled);
+            }
+            let generation = executions.next;
+            executions.next += 1;
+            executions.running = Some(generation);
+            generation
+        };
+        let result = f();
+        // A cancel holds the lock while it interrupts, so once `running` is
+        // cleared no interrupt can reach a later query.
+        let cancelled = {
+            let mut executions = executions.lock().unwrap();
+            executions.running = None;
+            executions.cancelled.take() == Some(generation)
+        };
+        match result {
+            // The query can still fail on its own.
+            Err(err) if cancelled => match err.without_context() {
+                Error::DuckDBFailure(_, Some(msg)) if msg.starts_with("INTERRUPT Error") => Err(Error::Cancelled),
+                _ => Err(err),
+            },
+            result => result,
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Cancel the statement: interrupt it if it is executing, which makes
+    /// it return [`Error::Cancelled`], or else make its next execution
+    /// return [`Error::Cancelled`] without running.
+    pub fn cancel(&self) {
+        let mut executions = self.executions.lock().unwrap();
+        match executions.running {
+            Some(generation) => {
+                executions.cancelled = Some(generation);
+                self.interrupt.interrupt();
+            }
+            None => executions.cancel_next = true,
+        }
+    }
+
+    /// Whether the statement is executing.
+    pub fn is_executing(&self) -> bool {
+        self.executions.lock().unwrap().running.is_some()
+    }
+}
+
+impl Statement<'_> {
+    /// Return a token that cancels this statement, and no other, from any
+    /// thread. See [`CancellationToken::cancel`].
+    ///
+    /// The statement is executing until the function that runs it, such as
+    /// [`execute`](Statement::execute) or [`query`](Statement::query),
+    /// returns. Reading the rows of a streamed query isn't cancelled.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Error, Result};
+    /// # use std::{thread, time::Duration};
+    /// fn refresh(conn: &Connection) -> Result<bool> {
+    ///     let mut stmt = conn.prepare("CREATE OR REPLACE TABLE summary AS SELECT * FROM slow_view")?;
+    ///     let token = stmt.cancellation_token();
+    ///     thread::spawn(move || {
+    ///         thread::sleep(Duration::from_secs(10));
+    ///         token.cancel();
+    ///     });
+    ///     match stmt.execute([]) {
+    ///         Ok(_) => Ok(true),
+    ///         Err(Error::Cancelled) => Ok(false),
+    ///         Err(err) => Err(err),
+    ///     }
+    /// }
+    /// ```
+    pub fn cancellation_token(&self) -> CancellationToken {
+        CancellationToken {
+            executions: Arc::clone(self.executions.get_or_init(Default::default)),
+            interrupt: self.conn.interrupt_handle(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use crate::{Connection, Error, ErrorKind, Result};
+
+    const SLOW: &str = "SELECT sum(a.range * b.range) FROM range(1000000) a, range(1000000) b";
+
+    #[test]
+    fn test_cancel_executing() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare(SLOW)?;
+        let token = stmt.cancellation_token();
+        let canceller = thread::spawn(move || {
+            while !token.is_executing() {
+                thread::sleep(Duration::from_millis(10));
+            }
+            token.cancel();
+        });
+        let err = stmt.query_row([], |r| r.get::<_, i128>(0)).unwrap_err();
+        canceller.join().unwrap();
+        assert_eq!(err, Error::Cancelled);
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+        // Only the cancelled execution is affected.
+        let mut quick = db.prepare("SELECT 42")?;
+        assert_eq!(quick.query_row([], |r| r.get::<_, i32>(0))?, 42);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel_before_execution() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (x INTEGER)")?;
+        let mut stmt = db.prepare("INSERT INTO t VALUES (?)")?;
+        let token = stmt.cancellation_token();
+        token.cancel();
+        assert_eq!(stmt.execute([1]).unwrap_err(), Error::Cancelled);
+        // The cancel only applies to one execution.
+        assert_eq!(stmt.execute([2])?, 1);
+        let rows: Vec<i32> = db
+            .prepare("SELECT x FROM t")?
+            .query_map([], |r| r.get(0))?
+            .collect::<Result<_>>()?;
+        assert_eq!(rows, [2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel_other_statement() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let mut finished = db.prepare("SELECT 1")?;
+        let token = finished.cancellation_token();
+        assert_eq!(finished.query_row([], |r| r.get::<_, i32>(0))?, 1);
+
+        // Cancels aimed at a finished statement leave other statements
+        // alone, even while they run.
+        let mut other = db.prepare("SELECT count(*) FROM range(100000000)")?;
+        let canceller = thread::spawn(move || {
+            for _ in 0..20 {
+                token.cancel();
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        assert_eq!(other.query_row([], |r| r.get::<_, i64>(0))?, 100_000_000);
+        canceller.join().unwrap();
+        // They are kept for the next execution of their statement.
+        assert_eq!(
+            finished.query_row([], |r| r.get::<_, i32>(0)).unwrap_err(),
+            Error::Cancelled
+        );
+        assert_eq!(finished.query_row([], |r| r.get::<_, i32>(0))?, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+//! Listing the schemas, tables and columns of a database
+
+use crate::{
+    types::{LogicalType, Type},
+    Connection, DatabaseName, Error, Result, Row,
+};
+
+/// A column of a table or view, as listed by [`Connection::table_info`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnInfo {
+    /// The name of the column.
+    pub name: String,
+    /// The type of the column.
+    pub logical_type: LogicalType,
+    /// Whether the column may hold `NULL`s.
+    pub nullable: bool,
+    /// The SQL expression of the column's default value, if it has one, such
+    /// as `'x'` or `nextval('seq')`.
+    pub default: Option<String>,
+}
+
+/// A table, as listed by [`Connection::tables`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableInfo {
+    /// The schema of the table.
+    pub schema: String,
+    /// The name of the table.
+    pub name: String,
+}
+
+impl Connection {
+    /// The columns of the table or view `table`, in the schema `schema` of
+    /// the database `db`, in order. `DatabaseName::Main` is the connection's
+    /// default database.
+    ///
+    /// Names are matched exactly, as they are spelled once unquoted: the
+    /// table created by `CREATE TABLE "my ""table"""` is `my "table"`.
+    /// Returns an empty `Vec` if there is no such table or view.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use duckdb::{types::LogicalType, Connection, Result, MAIN_DB};
+    /// fn describe(conn: &Connection) -> Result<()> {
+    ///     conn.execute_batch("CREATE TABLE \"my table\" (id INTEGER NOT NULL, tags VARCHAR[] DEFAULT [])")?;
+    ///     let columns = conn.table_info(MAIN_DB, "main", "my table")?;
+    ///     assert_eq!(columns[1].name, "tags");
+    ///     assert_eq!(columns[1].logical_type, LogicalType::List(Box::new(LogicalType::Text)));
+    ///     assert!(!columns[0].nullable);
+    ///     Ok(())
+    /// }
+    /// # describe(&Connection::open_in_memory().unwrap()).unwrap();
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if a column has a type this crate can't read, such
+    /// as `TIME WITH TIME ZONE`, or if the underlying DuckDB call fails.
+    pub fn table_info(&self, db: DatabaseName<'_>, schema: &str, table: &str) -> Result<Vec<ColumnInfo>> {
+        let mut stmt = self.prepare(
+            "SELECT column_name, data_type, is_nullable, column_default FROM duckdb_columns()
+            WHERE database_name = ? AND schema_name = ? AND table_name = ? ORDER BY column_index",
+        )?;
+        let rows = stmt.query_map([self.catalog_name(db)?.as_str(), schema, table], |row| {
+            Ok(ColumnInfo {
+                name: row.get(0)?,
+                logical_type: column_type(row, 1)?,
+                nullable: row.get(2)?,
+                default: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// The tables of the database `db`, ordered by schema and name. Views
+    /// aren't included.
+    pub fn tables(&self, db: DatabaseName<'_>) -> Result<Vec<TableInfo>> {
+        let mut stmt = self.prepare(
+            "SELECT schema_name, table_name FROM duckdb_tables()
+            WHERE database_name = ? AND NOT internal ORDER BY schema_name, table_name",
+        )?;
+        let rows = stmt.query_map([self.catalog_name(db)?], |row| {
+            Ok(TableInfo {
+                schema: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// The schemas of the database `db` holding user objects, `main` and
+    /// those created with `CREATE SCHEMA`, ordered by name.
+    pub fn schemas(&self, db: DatabaseName<'_>) -> Result<Vec<String>> {
+        // DuckDB counts `main` as internal, along with `information_schema`
+        // and `pg_catalog`.
+        let mut stmt = self.prepare(
+            "SELECT schema_name FROM duckdb_schemas()
+            WHERE database_name = ? AND (NOT internal OR schema_name = 'main') ORDER BY schema_name",
+        )?;
+        let rows = stmt.query_map([self.catalog_name(db)?], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+/// Reads the type spelled in column `idx` of `row`, the way DuckDB spells
+/// types in its catalog.
+pub(crate) fn column_type(row: &Row<'_>, idx: usize) -> Result<LogicalType> {
+    let data_type: String = row.get(idx)?;
+    LogicalType::parse(&data_type)
+        .ok_or_else(|| Error::FromSqlConversionFailure(idx, Type::Text, format!("unsupported type {data_type}").into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ColumnInfo, TableInfo};
+    use crate::{
+        types::{LogicalType, TimeUnit},
+        Connection, DatabaseName, Error, Result,
+    };
+
+    #[test]
+    fn test_table_info() -> Result<()> {
+        use LogicalType::*;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            r#"CREATE TYPE mood AS ENUM ('sad', 'it''s ok');
+            CREATE SCHEMA "we ird";
+            CREATE TABLE "we ird"."my ""table""" (
+                id INTEGER PRIMARY KEY,
+                "ü ""x""" mood,
+                s STRUCT("a b" INTEGER, c VARCHAR[]) DEFAULT NULL,
+                m MAP(VARCHAR, DECIMAL(10, 2)[3]),
+                u UNION(n INTEGER, t TEXT),
+                at TIMESTAMPTZ DEFAULT now(),
+                v VARCHAR NOT NULL DEFAULT 'it''s'
+            );"#,
+        )?;
+        let column = |name: &str, logical_type, nullable, default: Option<&str>| ColumnInfo {
+            name: name.to_owned(),
+            logical_type,
+            nullable,
+            default: default.map(str::to_owned),
+        };
+        assert_eq!(
+            db.table_info(DatabaseName::Main, "we ird", "my \"table\"")?,
+            [
+                column("id", Int, false, None),
+                column(
+                    "ü \"x\"",
+                    Enum(vec!["sad".to_owned(), "it's ok".to_owned()]),
+                    true,
+                    None
+                ),
+                column(
+                    "s",
+                    Struct(vec![("a b".to_owned(), Int), ("c".to_owned(), List(Box::new(Text)))]),
+                    true,
+                    Some("NULL")
+                ),
+                column(
+                    "m",
+                    Map(
+                        Box::new(Text),
+                        Box::new(Array(Box::new(Decimal { width: 10, scale: 2 }), 3))
+                    ),
+                    true,
+                    None
+                ),
+                column(
+                    "u",
+                    Union(vec![("n".to_owned(), Int), ("t".to_owned(), Text)]),
+                    true,
+                    None
+                ),
+                column("at", TimestampTz(TimeUnit::Microsecond), true, Some("now()")),
+                column("v", Text, false, Some("'it''s'")),
+            ]
+        );
+        assert!(db.table_info(DatabaseName::Main, "main", "my \"table\"")?.is_empty());
+
+        db.execute_batch("CREATE TEMP VIEW v AS SELECT 1::TINYINT AS x")?;
+        assert_eq!(
+            db.table_info(DatabaseName::Temp, "main", "v")?,
+            [column("x", TinyInt, true, None)]
+        );
+
+        db.execute_batch("CREATE TABLE tz (t TIMETZ)")?;
+        let err = db.table_info(DatabaseName::Main, "main", "tz").unwrap_err();
+        assert!(matches!(err, Error::FromSqlConversionFailure(1, ..)), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_tables_and_schemas() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        assert_eq!(db.schemas(DatabaseName::Main)?, ["main"]);
+        assert!(db.tables(DatabaseName::Main)?.is_empty());
+
+        db.execute_batch(
+            r#"CREATE SCHEMA "s ""1""";
+            CREATE TABLE "s ""1""".t (x INTEGER);
+            CREATE TABLE "🦆" (x INTEGER);
+            CREATE VIEW v AS SELECT 1;
+            CREATE TEMP TABLE scratch (x INTEGER);"#,
+        )?;
+        let table = |schema: &str, name: &str| TableInfo {
+            schema: schema.to_owned(),
+            name: name.to_owned(),
+        };
+        assert_eq!(db.schemas(DatabaseName::Main)?, ["main", "s \"1\""]);
+        assert_eq!(
+            db.tables(DatabaseName::Main)?,
+            [table("main", "🦆"), table("s \"1\"", "t")]
+        );
+        assert_eq!(db.tables(DatabaseName::Temp)?, [table("main", "scratch")]);
+        assert!(db.table_exists(DatabaseName::Main, "🦆")?);
+        assert!(db.table_exists(DatabaseName::Main, "t")?);
+        assert!(!db.table_exists(DatabaseName::Main, "v")?);
+        Ok(())
+    }
+}
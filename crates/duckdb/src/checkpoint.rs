@@ -0,0 +1,228 @@
+//! Checkpoint control and storage statistics
+
+use crate::{types::Type, Connection, DatabaseName, Error, Result};
+
+/// The storage used by a database, as reported by `pragma_database_size`.
+///
+/// In-memory databases report zero blocks and no WAL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatabaseSize {
+    /// The name of the database.
+    pub database_name: String,
+    /// The size of the database file in bytes, `block_size * total_blocks`.
+    pub database_size: u64,
+    /// The size of a block in bytes.
+    pub block_size: u64,
+    /// The number of blocks in the database file.
+    pub total_blocks: u64,
+    /// The number of blocks holding data.
+    pub used_blocks: u64,
+    /// The number of blocks free for reuse.
+    pub free_blocks: u64,
+    /// The size of the write-ahead log in bytes, `None` if there is none.
+    ///
+    /// DuckDB reports this rounded down to a tenth of its unit, such as
+    /// `783.7 KiB`.
+    pub wal_size: Option<u64>,
+    /// The memory used by the buffer manager in bytes, rounded like
+    /// `wal_size`.
+    pub memory_usage: u64,
+    /// The memory limit in bytes, rounded like `wal_size`, or `None` if
+    /// there is no limit.
+    pub memory_limit: Option<u64>,
+}
+
+impl Connection {
+    /// Write the changes in the write-ahead log of the default database to
+    /// its file, and truncate the log.
+    ///
+    /// Does nothing for an in-memory database.
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::CheckpointConflict`] if another connection has a
+    /// transaction open, or if this connection has uncommitted changes, and
+    /// `Err` if the underlying DuckDB call fails otherwise.
+    pub fn checkpoint(&self) -> Result<()> {
+        self.execute_batch("CHECKPOINT").map_err(checkpoint_error)
+    }
+
+    /// Like [`checkpoint`](Connection::checkpoint), but rolls back the open
+    /// transactions of other connections instead of failing (`FORCE
+    /// CHECKPOINT`).
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::CheckpointConflict`] if this connection has
+    /// uncommitted changes, and `Err` if the underlying DuckDB call fails
+    /// otherwise.
+    pub fn checkpoint_full(&self) -> Result<()> {
+        self.execute_batch("FORCE CHECKPOINT").map_err(checkpoint_error)
+    }
+
+    /// The storage used by the database `db`. `DatabaseName::Main` is the
+    /// connection's default database.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `db` isn't attached, or if the underlying DuckDB
+    /// call fails.
+    pub fn database_size(&self, db: DatabaseName<'_>) -> Result<DatabaseSize> {
+        self.query_row(
+            "SELECT database_name, block_size, total_blocks, used_blocks, free_blocks, wal_size, memory_usage,
+            memory_limit FROM pragma_database_size() WHERE database_name = ?",
+            [self.catalog_name(db)?],
+            |row| {
+                let block_size: u64 = row.get(1)?;
+                let total_blocks: u64 = row.get(2)?;
+                let size = |idx: usize, s: &str| {
+                    parse_size(s).ok_or_else(|| {
+                        Error::FromSqlConversionFailure(idx, Type::Text, format!("invalid size {s:?}").into())
+                    })
+                };
+                let wal_size: Option<String> = row.get(5)?;
+                let memory_limit: String = row.get(7)?;
+                Ok(DatabaseSize {
+                    database_name: row.get(0)?,
+                    database_size: block_size * total_blocks,
+                    block_size,
+                    total_blocks,
+                    used_blocks: row.get(3)?,
+                    free_blocks: row.get(4)?,
+                    wal_size: wal_size.map(|s| size(5, &s)).transpose()?,
+                    memory_usage: size(6, &row.get::<_, String>(6)?)?,
+                    memory_limit: match memory_limit.as_str() {
+                        "Unlimited" => None,
+                        limit => Some(size(7, limit)?),
+                    },
+                })
+            },
+        )
+    }
+}
+
+/// Reads back a size formatted by DuckDB, such as `1 byte`, `12 bytes`,
+/// `783.7 KiB` or `1.5 GB`.
+fn parse_size(s: &str) -> Option<u64> {
+    let (number, unit) = s.split_once(' ')?;
+    let multiplier: u64 = match unit {
+        "byte" | "bytes" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        "PiB" => 1 << 50,
+        "kB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        "PB" => 1_000_000_000_000_000,
+        _ => return None,
+    };
+    let (whole, tenths) = match number.split_once('.') {
+        Some((whole, tenths)) if tenths.len() == 1 => (whole, tenths),
+        None => (number, "0"),
+        _ => return None,
+    };
+    let whole: u64 = whole.parse().ok()?;
+    let tenths: u64 = tenths.parse().ok()?;
+    Some(whole * multiplier + tenths * multiplier / 10)
+}
+
+/// Turns DuckDB's refusal to checkpoint into [`Error::CheckpointConflict`],
+/// leaving other errors as they are.
+fn checkpoint_error(err: Error) -> Error {
+    match err.without_context() {
+        Error::DuckDBFailure(_, Some(msg)) if msg.contains("Cannot CHECKPOINT") => {
+            Error::CheckpointConflict(msg.clone())
+        }
+        _ => err,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_size;
+    use crate::{Connection, DatabaseName, Error, Result};
+
+    #[test]
+    fn test_checkpoint_reopen() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.db");
+        {
+            let db = Connection::open(&path)?;
+            db.execute_batch("CREATE TABLE t AS SELECT range AS x FROM range(100000)")?;
+            let before = db.database_size(DatabaseName::Main)?;
+            assert_eq!(before.database_name, "checkpoint");
+            assert!(before.wal_size.unwrap() > 0, "{before:?}");
+
+            db.checkpoint()?;
+            let after = db.database_size(DatabaseName::Main)?;
+            assert_eq!(after.wal_size, Some(0));
+            assert!(after.total_blocks > 0, "{after:?}");
+            assert_eq!(after.database_size, after.block_size * after.total_blocks);
+            assert_eq!(after.used_blocks + after.free_blocks, after.total_blocks);
+            assert!(after.memory_limit.is_some());
+
+            db.execute_batch("INSERT INTO t VALUES (-1)")?;
+            db.checkpoint_full()?;
+        }
+        assert_eq!(
+            std::fs::metadata(dir.path().join("checkpoint.db.wal"))
+                .map(|m| m.len())
+                .unwrap_or(0),
+            0
+        );
+
+        let db = Connection::open(&path)?;
+        let (count, min): (i64, i64) =
+            db.query_row("SELECT count(*), min(x) FROM t", [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        assert_eq!((count, min), (100001, -1));
+
+        let memory = Connection::open_in_memory()?;
+        memory.checkpoint()?;
+        let size = memory.database_size(DatabaseName::Main)?;
+        assert_eq!((size.database_name.as_str(), size.total_blocks), ("memory", 0));
+        assert!(memory.database_size(DatabaseName::Attached("nope")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_checkpoint_conflict() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open(dir.path().join("conflict.db"))?;
+        db.execute_batch("CREATE TABLE t (x INTEGER)")?;
+        let other = db.try_clone()?;
+        other.execute_batch("BEGIN; INSERT INTO t VALUES (1);")?;
+        db.execute_batch("INSERT INTO t VALUES (2)")?;
+
+        match db.checkpoint() {
+            Err(Error::CheckpointConflict(msg)) => assert!(msg.contains("other transactions"), "{msg}"),
+            res => panic!("unexpected {res:?}"),
+        }
+        // Forcing it rolls back the other transaction.
+        db.checkpoint_full()?;
+        let xs = db
+            .prepare("SELECT x FROM t")?
+            .query_map([], |r| r.get::<_, i32>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(xs, [2]);
+
+        db.execute_batch("BEGIN; INSERT INTO t VALUES (3);")?;
+        assert!(matches!(db.checkpoint_full(), Err(Error::CheckpointConflict(_))));
+        db.execute_batch("ROLLBACK")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("0 bytes"), Some(0));
+        assert_eq!(parse_size("1 byte"), Some(1));
+        assert_eq!(parse_size("512.0 KiB"), Some(512 * 1024));
+        assert_eq!(parse_size("1.5 MiB"), Some(3 << 19));
+        assert_eq!(parse_size("4.6 GB"), Some(4_600_000_000));
+        for s in ["", "1", "1.25 KiB", "x KiB", "1 XB"] {
+            assert_eq!(parse_size(s), None, "{s}");
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::str;
 
-use crate::{Error, Result, Statement};
+use crate::{ffi, types::LogicalType, Error, Result, Statement};
 
 /// Information about a column of a DuckDB query.
 #[derive(Debug)]
@@ -30,23 +30,85 @@ impl Statement<'_> {
     /// sure that current statement has already been stepped once before
     /// calling this method.
     pub fn column_names(&self) -> Vec<String> {
-        self.stmt
-            .schema()
-            .fields()
-            .iter()
-            .map(|f| f.name().to_owned())
+        (0..self.column_count())
+            .filter_map(|i| self.column_name(i).ok().cloned())
             .collect()
     }
 
     /// Return the number of columns in the result set returned by the prepared
     /// statement.
     ///
+    /// The columns of a query are known before it is executed: DuckDB
+    /// describes them by running it with `LIMIT 0` and every parameter
+    /// `NULL`, which takes another query, or two if some of the column names
+    /// are repeated. If that fails, such as for a query that needs a
+    /// parameter not to be `NULL`, this is `0` until the statement runs, and
+    /// [`column_name`](Statement::column_name) returns the error.
+    /// Other statements have no columns until they are executed, after which
+    /// `INSERT`, `UPDATE` and `DELETE` without `RETURNING`, and DDL, have a
+    /// `Count` column. See [`returns_rows`](Statement::returns_rows).
+    ///
     /// If associated DB schema can be altered concurrently, you should make
     /// sure that current statement has already been stepped once before
     /// calling this method.
     #[inline]
     pub fn column_count(&self) -> usize {
-        self.stmt.column_count()
+        if self.stmt.has_result() {
+            self.stmt.column_count()
+        } else {
+            self.described_columns().map_or(0, <[_]>::len)
+        }
+    }
+
+    /// Returns the type of a column in the result set returned by the
+    /// prepared statement, with the details of `DECIMAL`, `ENUM` and nested
+    /// types, such as `STRUCT(a INTEGER)[]`. Queries are described before
+    /// they run; for other statements that return rows, such as an `INSERT`
+    /// with `RETURNING`, the type is known once the statement was executed.
+    ///
+    /// Returns `None` if `col` is out of range, for the type of `NULL` and for
+    /// types this crate can't read, as well as for `ENUM`s returned by
+    /// statements other than queries, whose entries aren't known. Before the
+    /// statement runs, it's also `None` if describing the query failed, see
+    /// [`column_count`](Statement::column_count).
+    pub fn column_logical_type(&self, col: usize) -> Option<LogicalType> {
+        if let Some((_, ty)) = self.described_columns().ok().and_then(|columns| columns.get(col)) {
+            return ty.clone();
+        }
+        if !self.stmt.has_result() {
+            return None;
+        }
+        LogicalType::of_data_type(self.stmt.schema().fields().get(col)?.data_type())
+    }
+
+    /// Returns whether the statement returns rows, rather than the number of
+    /// rows it changed or nothing: `true` for queries, `EXPLAIN` and `CALL`,
+    /// and for `INSERT`, `UPDATE` and `DELETE` with `RETURNING` once they
+    /// were executed.
+    pub fn returns_rows(&self) -> bool {
+        match self.stmt.statement_type() {
+            ffi::duckdb_statement_type_DUCKDB_STATEMENT_TYPE_SELECT
+            | ffi::duckdb_statement_type_DUCKDB_STATEMENT_TYPE_EXPLAIN
+            | ffi::duckdb_statement_type_DUCKDB_STATEMENT_TYPE_CALL => true,
+            // With `RETURNING`, these return their rows instead of a count.
+            ffi::duckdb_statement_type_DUCKDB_STATEMENT_TYPE_INSERT
+            | ffi::duckdb_statement_type_DUCKDB_STATEMENT_TYPE_UPDATE
+            | ffi::duckdb_statement_type_DUCKDB_STATEMENT_TYPE_DELETE => {
+                self.stmt.has_result() && self.column_names() != ["Count"]
+            }
+            _ => false,
+        }
+    }
+
+    /// The columns of a query as described before it runs, empty for other
+    /// statements.
+    fn described_columns(&self) -> Result<&[(String, Option<LogicalType>)]> {
+        self.stmt.columns_or_else(|| match self.stmt.sql() {
+            Some(sql) if self.stmt.statement_type() == ffi::duckdb_statement_type_DUCKDB_STATEMENT_TYPE_SELECT => {
+                self.conn.db.borrow_mut().describe(&sql)
+            }
+            _ => Ok(Vec::new()),
+        })
     }
 
     /// Check that column name reference lifetime is limited:
@@ -85,12 +147,18 @@ impl Statement<'_> {
     /// ## Failure
     ///
     /// Returns an `Error::InvalidColumnIndex` if `idx` is outside the valid
-    /// column range for this row.
+    /// column range for this row, and the error describing the columns of a
+    /// query that hasn't run yet if that failed.
     ///
     /// Panics when column name is not valid UTF-8.
     #[inline]
     pub fn column_name(&self, col: usize) -> Result<&String> {
-        self.stmt.column_name(col).ok_or(Error::InvalidColumnIndex(col))
+        let name = if self.stmt.has_result() {
+            self.stmt.column_name(col)
+        } else {
+            self.described_columns()?.get(col).map(|(name, _)| name)
+        };
+        name.ok_or(Error::InvalidColumnIndex(col))
     }
 
     /// Returns the column index in the result set for a given column name.
@@ -108,11 +176,14 @@ impl Statement<'_> {
     /// the specified `name`.
     #[inline]
     pub fn column_index(&self, name: &str) -> Result<usize> {
-        let n = self.column_count();
-        for i in 0..n {
-            // Note: `column_name` is only fallible if `i` is out of bounds,
-            // which we've already checked.
-            if name.eq_ignore_ascii_case(self.stmt.column_name(i).unwrap()) {
+        if self.stmt.has_result() {
+            return self
+                .stmt
+                .column_index(name)
+                .ok_or_else(|| Error::InvalidColumnName(String::from(name)));
+        }
+        for (i, (column, _)) in self.described_columns()?.iter().enumerate() {
+            if name.eq_ignore_ascii_case(column) {
                 return Ok(i);
             }
         }
@@ -164,9 +235,105 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_columns_before_execution() -> Result<()> {
+        use crate::types::LogicalType;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TYPE mood AS ENUM ('sad', 'happy');
+             CREATE TABLE t (id INTEGER, price DECIMAL(9, 2), m mood, tags VARCHAR[], s STRUCT(a INTEGER, b VARCHAR));
+             INSERT INTO t VALUES (1, 1.5, 'happy', ['x'], {'a': 1, 'b': 'y'});",
+        )?;
+        let mut stmt = db.prepare("SELECT id, price AS cost, m, tags, s FROM t WHERE id = ? -- trailing comment")?;
+        assert!(stmt.returns_rows());
+        assert_eq!(stmt.column_count(), 5);
+        assert_eq!(stmt.column_names(), ["id", "cost", "m", "tags", "s"]);
+        assert_eq!(stmt.column_index("COST")?, 1);
+        let types: Vec<_> = (0..5).map(|i| stmt.column_logical_type(i)).collect();
+        assert_eq!(
+            types,
+            [
+                Some(LogicalType::Int),
+                Some(LogicalType::Decimal { width: 9, scale: 2 }),
+                Some(LogicalType::Enum(vec!["sad".to_owned(), "happy".to_owned()])),
+                Some(LogicalType::List(Box::new(LogicalType::Text))),
+                Some(LogicalType::Struct(vec![
+                    ("a".to_owned(), LogicalType::Int),
+                    ("b".to_owned(), LogicalType::Text)
+                ])),
+            ]
+        );
+        assert_eq!(stmt.column_logical_type(5), None);
+
+        // Executing the statement reports the same columns.
+        let mut rows = stmt.query([1])?;
+        let row = rows.next()?.unwrap();
+        for (i, ty) in types.iter().enumerate() {
+            assert_eq!(row.get_ref(i)?.logical_type(), *ty);
+        }
+        drop(rows);
+        assert_eq!(stmt.column_count(), 5);
+        assert_eq!(stmt.column_names(), ["id", "cost", "m", "tags", "s"]);
+
+        // A statement that never runs.
+        let stmt = db.prepare("SELECT 42::BIGINT AS answer, [1, 2]::INTEGER[2] AS pair;")?;
+        assert_eq!(stmt.column_names(), ["answer", "pair"]);
+        assert_eq!(
+            stmt.column_logical_type(1),
+            Some(LogicalType::Array(Box::new(LogicalType::Int), 2))
+        );
+
+        // DDL has no columns, and nothing ran to describe it.
+        let stmt = db.prepare("CREATE TABLE u (x INTEGER)")?;
+        assert!(!stmt.returns_rows());
+        assert_eq!(stmt.column_count(), 0);
+        assert!(stmt.column_names().is_empty());
+        assert!(stmt.column_name(0).is_err());
+        assert_eq!(stmt.column_logical_type(0), None);
+        assert!(db.prepare("SELECT * FROM u").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_repeated_columns_before_execution() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare("SELECT 1 AS a, 2 AS A, 3 AS a_1, 4 AS b")?;
+        assert_eq!(stmt.column_names(), ["a", "A", "a_1", "b"]);
+        assert_eq!(stmt.column_index("a_1")?, 2);
+        stmt.query([])?.next()?;
+        assert_eq!(stmt.column_names(), ["a", "A", "a_1", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_columns_not_described() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        // The query can't run with `NULL` parameters.
+        let mut stmt = db.prepare("SELECT * FROM range(?::TIMESTAMP, ?::TIMESTAMP, INTERVAL 1 DAY)")?;
+        assert_eq!(stmt.column_count(), 0);
+        assert!(stmt.column_names().is_empty());
+        let err = stmt.column_name(0).unwrap_err();
+        assert!(err.to_string().contains("NULL"), "{err}");
+        assert!(stmt.column_index("range").is_err());
+        assert_eq!(stmt.column_logical_type(0), None);
+
+        // Once it ran, the columns of its result are known.
+        let mut rows = stmt.query(["2024-01-01", "2024-01-03"])?;
+        assert!(rows.next()?.is_some());
+        drop(rows);
+        assert_eq!(stmt.column_names(), ["range"]);
+        assert_eq!(stmt.column_index("range")?, 0);
+        assert!(stmt.column_logical_type(0).is_some());
+        Ok(())
+    }
+
     #[test]
     fn test_column_name_in_error() -> Result<()> {
-        use crate::{types::Type, Error};
+        use crate::{
+            types::{FromSqlError, Type},
+            Error,
+        };
         let db = Connection::open_in_memory()?;
         db.execute_batch(
             "BEGIN;
@@ -178,20 +345,34 @@ mod test {
         let mut rows = stmt.query([])?;
         let row = rows.next()?.unwrap();
         match row.get::<_, String>(0).unwrap_err() {
-            Error::InvalidColumnType(idx, name, ty) => {
-                assert_eq!(idx, 0);
+            Error::ColumnConversionFailure {
+                index,
+                name,
+                column_type,
+                rust_type,
+                source,
+            } => {
+                assert_eq!(index, 0);
                 assert_eq!(name, "renamed");
-                assert_eq!(ty, Type::Int);
+                assert_eq!(column_type, Type::Int);
+                assert_eq!(rust_type, "alloc::string::String");
+                assert_eq!(source, FromSqlError::InvalidType);
             }
             e => {
                 panic!("Unexpected error type: {e:?}");
             }
         }
+        // A NULL is reported with the type of its column.
         match row.get::<_, String>("y").unwrap_err() {
-            Error::InvalidColumnType(idx, name, ty) => {
-                assert_eq!(idx, 1);
+            Error::ColumnConversionFailure {
+                index,
+                name,
+                column_type,
+                ..
+            } => {
+                assert_eq!(index, 1);
                 assert_eq!(name, "y");
-                assert_eq!(ty, Type::Null);
+                assert_eq!(column_type, Type::Text);
             }
             e => {
                 panic!("Unexpected error type: {e:?}");
@@ -199,4 +380,85 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_column_logical_types() -> Result<()> {
+        use arrow::datatypes::DataType;
+
+        use crate::types::{LogicalType, TimeUnit};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TYPE mood AS ENUM ('sad', 'happy');
+             CREATE TABLE t (
+                 price DECIMAL(9, 2), m mood, items STRUCT(a INTEGER)[], attrs MAP(VARCHAR, INTEGER),
+                 at TIMESTAMPTZ, tags VARCHAR[2]
+             );
+             INSERT INTO t VALUES (1.5, 'happy', [{'a': 1}], MAP {'x': 1}, '2024-01-01 00:00:00+00', ['a', 'b']);",
+        )?;
+        let mut stmt = db.prepare("SELECT * FROM t")?;
+        let types: Vec<_> = (0..stmt.column_count())
+            .map(|i| stmt.column_logical_type(i).unwrap())
+            .collect();
+        let items = LogicalType::List(Box::new(LogicalType::Struct(vec![("a".to_owned(), LogicalType::Int)])));
+        assert_eq!(
+            types,
+            [
+                LogicalType::Decimal { width: 9, scale: 2 },
+                LogicalType::Enum(vec!["sad".to_owned(), "happy".to_owned()]),
+                items.clone(),
+                LogicalType::Map(Box::new(LogicalType::Text), Box::new(LogicalType::Int)),
+                LogicalType::TimestampTz(TimeUnit::Microsecond),
+                LogicalType::Array(Box::new(LogicalType::Text), 2),
+            ]
+        );
+        let names: Vec<_> = types.iter().map(|ty| ty.to_string()).collect();
+        assert_eq!(
+            names,
+            [
+                "DECIMAL(9,2)",
+                "ENUM('sad', 'happy')",
+                "STRUCT(a INTEGER)[]",
+                "MAP(VARCHAR, INTEGER)",
+                "TIMESTAMP WITH TIME ZONE",
+                "VARCHAR[2]",
+            ]
+        );
+        // The names are the ones DuckDB gives the types.
+        let typeofs: Vec<String> = db.query_row(
+            "SELECT [typeof(price), typeof(m), typeof(items), typeof(attrs), typeof(at), typeof(tags)] FROM t",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(typeofs[1], "mood");
+        for i in [0, 2, 3, 4, 5] {
+            assert_eq!(typeofs[i], names[i]);
+        }
+
+        // They convert to Arrow as DuckDB converts them.
+        stmt.query([])?.next()?;
+        for (i, ty) in types.iter().enumerate() {
+            match ty.arrow_data_type().unwrap() {
+                // In the time zone of the connection.
+                DataType::Timestamp(unit, Some(_)) => {
+                    assert!(matches!(stmt.column_type(i), DataType::Timestamp(u, Some(_)) if u == unit))
+                }
+                data_type => assert_eq!(stmt.column_type(i), data_type, "{ty}"),
+            }
+        }
+        assert_eq!(LogicalType::UHugeInt.arrow_data_type(), None);
+
+        // Other statements report their types once they ran, except for the
+        // entries of enums.
+        let mut stmt = db.prepare("INSERT INTO t SELECT * FROM t RETURNING items, m, price")?;
+        assert_eq!(stmt.column_logical_type(0), None);
+        stmt.query([])?.next()?;
+        assert_eq!(stmt.column_logical_type(0), Some(items));
+        assert_eq!(stmt.column_logical_type(1), None);
+        assert_eq!(
+            stmt.column_logical_type(2),
+            Some(LogicalType::Decimal { width: 9, scale: 2 })
+        );
+        Ok(())
+    }
 }
@@ -1,6 +1,12 @@
 use super::{ffi, Result};
 use crate::error::Error;
-use std::{default::Default, ffi::CString, os::raw::c_char, ptr};
+use std::{
+    default::Default,
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    path::Path,
+    ptr,
+};
 
 use strum::{Display, EnumString};
 
@@ -42,21 +48,52 @@ pub enum DefaultNullOrder {
 
 /// duckdb configuration
 /// Refer to <https://github.com/duckdb/duckdb/blob/master/src/main/config.cpp>
+///
+/// Options are checked against the database when it is opened: an invalid
+/// value makes [`Connection::open_with_flags`](crate::Connection::open_with_flags)
+/// fail with an error naming the option.
 #[derive(Default)]
 pub struct Config {
-    config: Option<ffi::duckdb_config>,
+    options: Vec<(String, String)>,
 }
 
 impl Config {
-    pub(crate) fn duckdb_config(&self) -> ffi::duckdb_config {
-        self.config.unwrap_or(std::ptr::null_mut() as ffi::duckdb_config)
+    /// Builds the `duckdb_config` holding every option.
+    pub(crate) fn duckdb_config(&self) -> Result<RawConfig> {
+        let mut raw = RawConfig(ptr::null_mut());
+        let state = unsafe { ffi::duckdb_create_config(&mut raw.0) };
+        if state != ffi::DuckDBSuccess {
+            return Err(Error::DuckDBFailure(
+                ffi::Error::new(state),
+                Some("create config error".to_owned()),
+            ));
+        }
+        // Identifies this crate in the user agent, unless the caller set it.
+        let api = ("duckdb_api".to_owned(), DUCKDB_API.to_owned());
+        for (key, value) in std::iter::once(&api).chain(&self.options) {
+            let c_key = CString::new(key.as_str())?;
+            let c_value = CString::new(value.as_str())?;
+            let state = unsafe {
+                ffi::duckdb_set_config(
+                    raw.0,
+                    c_key.as_ptr() as *const c_char,
+                    c_value.as_ptr() as *const c_char,
+                )
+            };
+            if state != ffi::DuckDBSuccess {
+                return Err(Error::DuckDBFailure(
+                    ffi::Error::new(state),
+                    Some(format!("Invalid value {value:?} for configuration option {key:?}")),
+                ));
+            }
+        }
+        Ok(raw)
     }
 
     /// enable autoload extensions
-    pub fn enable_autoload_extension(mut self, enabled: bool) -> Result<Config> {
-        self.set("autoinstall_known_extensions", &(enabled as i32).to_string())?;
-        self.set("autoload_known_extensions", &(enabled as i32).to_string())?;
-        Ok(self)
+    pub fn enable_autoload_extension(self, enabled: bool) -> Result<Config> {
+        self.autoinstall_known_extensions(enabled)?
+            .autoload_known_extensions(enabled)
     }
 
     /// Access mode of the database ([AUTOMATIC], READ_ONLY or READ_WRITE)
@@ -90,8 +127,20 @@ impl Config {
     }
 
     /// Allow to load third-party duckdb extensions.
-    pub fn allow_unsigned_extensions(mut self) -> Result<Config> {
-        self.set("allow_unsigned_extensions", "true")?;
+    pub fn allow_unsigned_extensions(mut self, enabled: bool) -> Result<Config> {
+        self.set("allow_unsigned_extensions", &enabled.to_string())?;
+        Ok(self)
+    }
+
+    /// Whether known extensions are automatically loaded when a query needs them
+    pub fn autoload_known_extensions(mut self, enabled: bool) -> Result<Config> {
+        self.set("autoload_known_extensions", &enabled.to_string())?;
+        Ok(self)
+    }
+
+    /// Whether known extensions are automatically installed when a query needs them
+    pub fn autoinstall_known_extensions(mut self, enabled: bool) -> Result<Config> {
+        self.set("autoinstall_known_extensions", &enabled.to_string())?;
         Ok(self)
     }
 
@@ -102,48 +151,100 @@ impl Config {
     }
 
     /// The number of total threads used by the system
-    pub fn threads(mut self, thread_num: i64) -> Result<Config> {
+    pub fn threads(mut self, thread_num: u32) -> Result<Config> {
         self.set("threads", &thread_num.to_string())?;
         Ok(self)
     }
 
-    /// Add any setting to the config. DuckDB will return an error if the setting is unknown or
-    /// otherwise invalid.
-    pub fn with(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Result<Config> {
-        self.set(key.as_ref(), value.as_ref())?;
+    /// The directory to which to write temporary files when spilling to disk
+    pub fn temp_directory(mut self, path: impl AsRef<Path>) -> Result<Config> {
+        let path = path.as_ref();
+        let dir = path.to_str().ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        self.set("temp_directory", dir)?;
         Ok(self)
     }
 
-    fn set(&mut self, key: &str, value: &str) -> Result<()> {
-        if self.config.is_none() {
-            let mut config: ffi::duckdb_config = ptr::null_mut();
-            let state = unsafe { ffi::duckdb_create_config(&mut config) };
-            assert_eq!(state, ffi::DuckDBSuccess);
-            self.config = Some(config);
-        }
-        let c_key = CString::new(key).unwrap();
-        let c_value = CString::new(value).unwrap();
-        let state = unsafe {
-            ffi::duckdb_set_config(
-                self.config.unwrap(),
-                c_key.as_ptr() as *const c_char,
-                c_value.as_ptr() as *const c_char,
-            )
-        };
-        if state != ffi::DuckDBSuccess {
+    /// The directory extensions are installed to and loaded from
+    pub fn extension_directory(mut self, path: impl AsRef<Path>) -> Result<Config> {
+        let path = path.as_ref();
+        let dir = path.to_str().ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        self.set("extension_directory", dir)?;
+        Ok(self)
+    }
+
+    /// Whether or not to preserve insertion order; turning it off lets some
+    /// operations use less memory
+    pub fn preserve_insertion_order(mut self, enabled: bool) -> Result<Config> {
+        self.set("preserve_insertion_order", &enabled.to_string())?;
+        Ok(self)
+    }
+
+    /// A string appended to the user agent DuckDB sends when it installs
+    /// extensions, to identify the application. Calling this again appends
+    /// another string. See [`Connection::user_agent`](crate::Connection::user_agent).
+    pub fn custom_user_agent(mut self, user_agent: &str) -> Result<Config> {
+        self.set("custom_user_agent", user_agent)?;
+        Ok(self)
+    }
+
+    /// Add any setting to the config.
+    ///
+    /// The key must be one of the options DuckDB reports through
+    /// `duckdb_get_config_flag`, in any case, otherwise this returns an
+    /// error. Options
+    /// added by extensions aren't known before the database is open, so set
+    /// those with `SET` once the extension is loaded. The value is checked
+    /// when the database is opened.
+    pub fn with(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Result<Config> {
+        let key = key.as_ref();
+        let Some(flag) = config_flags().find(|flag| flag.eq_ignore_ascii_case(key)) else {
             return Err(Error::DuckDBFailure(
-                ffi::Error::new(state),
-                Some(format!("set {key}:{value} error")),
+                ffi::Error::new(ffi::DuckDBError),
+                Some(format!("Unrecognized configuration property {key:?}")),
             ));
-        }
+        };
+        self.set(&flag, value.as_ref())?;
+        Ok(self)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        // Catch NUL bytes now rather than when opening.
+        CString::new(value)?;
+        self.options.push((key.to_owned(), value.to_owned()));
         Ok(())
     }
 }
 
-impl Drop for Config {
+/// The API name this crate reports in the user agent.
+const DUCKDB_API: &str = "duckdb-rs";
+
+/// The names of all options DuckDB accepts in a `duckdb_config`.
+fn config_flags() -> impl Iterator<Item = String> {
+    (0..unsafe { ffi::duckdb_config_count() }).filter_map(|i| {
+        let mut name: *const c_char = ptr::null();
+        let mut description: *const c_char = ptr::null();
+        let state = unsafe { ffi::duckdb_get_config_flag(i, &mut name, &mut description) };
+        if state != ffi::DuckDBSuccess || name.is_null() {
+            return None;
+        }
+        Some(unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned())
+    })
+}
+
+/// An owned `duckdb_config`, destroyed on drop.
+pub(crate) struct RawConfig(ffi::duckdb_config);
+
+impl RawConfig {
+    #[inline]
+    pub(crate) fn as_ptr(&self) -> ffi::duckdb_config {
+        self.0
+    }
+}
+
+impl Drop for RawConfig {
     fn drop(&mut self) {
-        if self.config.is_some() {
-            unsafe { ffi::duckdb_destroy_config(&mut self.config.unwrap()) };
+        if !self.0.is_null() {
+            unsafe { ffi::duckdb_destroy_config(&mut self.0) };
         }
     }
 }
@@ -189,7 +290,7 @@ mod test {
             .enable_external_access(true)?
             .enable_object_cache(false)?
             .enable_autoload_extension(true)?
-            .allow_unsigned_extensions()?
+            .allow_unsigned_extensions(true)?
             .max_memory("2GB")?
             .threads(4)?
             .with("preserve_insertion_order", "true")?;
@@ -218,13 +319,84 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_current_settings() -> Result<()> {
+        let tmp = tempfile::tempdir().unwrap();
+        let spill = tmp.path().join("spill");
+        let config = Config::default()
+            .threads(3)?
+            .max_memory("1GB")?
+            .temp_directory(&spill)?
+            .default_order(crate::DefaultOrder::Desc)?
+            .preserve_insertion_order(false)?
+            .allow_unsigned_extensions(true)?
+            .autoload_known_extensions(false)?
+            .autoinstall_known_extensions(false)?
+            .with("Enable_Object_Cache", "true")?;
+        let db = Connection::open_in_memory_with_flags(config)?;
+        let setting = |name: &str| -> Result<Value> { db.query_row("SELECT current_setting(?)", [name], |r| r.get(0)) };
+        assert_eq!(setting("threads")?, Value::BigInt(3));
+        let max_memory = setting("max_memory")?;
+        assert!(
+            matches!(&max_memory, Value::Text(s) if s.starts_with("953.6") && s.ends_with("MiB")),
+            "{max_memory:?}"
+        );
+        assert_eq!(
+            setting("temp_directory")?,
+            Value::Text(spill.to_str().unwrap().to_owned())
+        );
+        assert_eq!(setting("default_order")?, Value::Text("desc".to_owned()));
+        assert_eq!(setting("preserve_insertion_order")?, Value::Boolean(false));
+        assert_eq!(setting("allow_unsigned_extensions")?, Value::Boolean(true));
+        assert_eq!(setting("autoload_known_extensions")?, Value::Boolean(false));
+        assert_eq!(setting("autoinstall_known_extensions")?, Value::Boolean(false));
+        assert_eq!(setting("enable_object_cache")?, Value::Boolean(true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_user_agent() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let user_agent = db.user_agent()?;
+        assert!(user_agent.starts_with("duckdb/"), "{user_agent}");
+        assert!(user_agent.ends_with(") duckdb-rs"), "{user_agent}");
+
+        let config = Config::default()
+            .custom_user_agent("my-service/1.0")?
+            .custom_user_agent("(batch)")?;
+        let db = Connection::open_in_memory_with_flags(config)?;
+        let user_agent = db.user_agent()?;
+        assert!(
+            user_agent.ends_with(") duckdb-rs my-service/1.0 (batch)"),
+            "{user_agent}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_value() -> Result<()> {
+        let config = Config::default().threads(2)?.max_memory("lots")?;
+        let err = Connection::open_in_memory_with_flags(config).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value \"lots\" for configuration option \"max_memory\""
+        );
+        let config = Config::default().with("default_order", "sideways")?;
+        let err = Connection::open_in_memory_with_flags(config).unwrap_err();
+        assert!(err.to_string().contains("\"default_order\""), "{err}");
+        assert!(matches!(
+            Config::default().with("threads", "1\0"),
+            Err(crate::Error::NulError(_))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_invalid_setting() -> Result<()> {
-        let config = Config::default().with("some-invalid-setting", "true")?;
-        let res = Connection::open_in_memory_with_flags(config);
+        let err = Config::default().with("some-invalid-setting", "true").err().unwrap();
         assert_eq!(
-            res.unwrap_err().to_string(),
-            "Invalid Input Error: Unrecognized configuration property \"some-invalid-setting\""
+            err.to_string(),
+            "Unrecognized configuration property \"some-invalid-setting\""
         );
         Ok(())
     }
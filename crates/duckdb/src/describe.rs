@@ -0,0 +1,233 @@
+//! Describing the columns of queries and summarizing the values of tables
+
+use crate::{
+    catalog::column_type,
+    params_from_iter,
+    pragma::Sql,
+    types::{LogicalType, Null},
+    Connection, Result,
+};
+
+/// A column of a query's result, as returned by [`Connection::describe`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnDescription {
+    /// The name of the column.
+    pub name: String,
+    /// The type of the column.
+    pub logical_type: LogicalType,
+    /// Whether the column may hold `NULL`s. DuckDB doesn't track this
+    /// through queries, so it is `true` for every column of one.
+    pub nullable: bool,
+}
+
+/// The statistics of a column of a table, as returned by
+/// [`Connection::summarize`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSummary {
+    /// The name of the column.
+    pub name: String,
+    /// The type of the column.
+    pub logical_type: LogicalType,
+    /// The smallest value of the column, spelled as DuckDB casts it to
+    /// `VARCHAR`, or `None` if the column only holds `NULL`s.
+    pub min: Option<String>,
+    /// The largest value of the column, spelled as DuckDB casts it to
+    /// `VARCHAR`, or `None` if the column only holds `NULL`s.
+    pub max: Option<String>,
+    /// The approximate number of distinct values of the column.
+    pub approx_unique: u64,
+    /// The mean of the column, for numeric columns with values.
+    pub avg: Option<f64>,
+    /// The standard deviation of the column, for numeric columns with more
+    /// than one value.
+    pub std: Option<f64>,
+    /// The approximate 25th percentile of the column, for numeric columns
+    /// with values.
+    pub q25: Option<f64>,
+    /// The approximate median of the column, for numeric columns with
+    /// values.
+    pub q50: Option<f64>,
+    /// The approximate 75th percentile of the column, for numeric columns
+    /// with values.
+    pub q75: Option<f64>,
+    /// The number of rows of the table.
+    pub count: u64,
+    /// The percentage of the rows whose value is `NULL`, from 0 to 100, or
+    /// `None` if the table is empty.
+    pub null_percentage: Option<f64>,
+}
+
+impl Connection {
+    /// The columns the query `sql` returns, in order. The query is planned
+    /// but not run, and may have parameters, which need no values.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use duckdb::{types::LogicalType, Connection, Result};
+    /// fn columns(conn: &Connection) -> Result<()> {
+    ///     conn.execute_batch("CREATE TABLE t (id INTEGER, tags VARCHAR[])")?;
+    ///     let columns = conn.describe("SELECT id, tags FROM t WHERE id > ?")?;
+    ///     assert_eq!(columns[1].name, "tags");
+    ///     assert_eq!(columns[1].logical_type, LogicalType::List(Box::new(LogicalType::Text)));
+    ///     Ok(())
+    /// }
+    /// # columns(&Connection::open_in_memory().unwrap()).unwrap();
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `sql` isn't a valid query, a column has a type
+    /// this crate can't read, such as `TIME WITH TIME ZONE`, or the
+    /// underlying DuckDB call fails.
+    pub fn describe(&self, sql: &str) -> Result<Vec<ColumnDescription>> {
+        let mut stmt = self.prepare(&format!("DESCRIBE {sql}"))?;
+        // DESCRIBE answers from the plan alone, but its parameters must still
+        // be bound. NULLs leave the types DuckDB inferred for them alone.
+        let nulls = vec![Null; stmt.parameter_count()];
+        let rows = stmt.query_map(params_from_iter(nulls), |row| {
+            Ok(ColumnDescription {
+                name: row.get(0)?,
+                logical_type: column_type(row, 1)?,
+                nullable: row.get::<_, String>(2)? == "YES",
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// The statistics of each column of the table or view `table`, in order,
+    /// computed by DuckDB's `SUMMARIZE`, which reads the whole table.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn sparse_columns(conn: &Connection) -> Result<Vec<String>> {
+    ///     let columns = conn.summarize("events")?;
+    ///     Ok(columns
+    ///         .into_iter()
+    ///         .filter(|column| column.null_percentage > Some(90.0))
+    ///         .map(|column| column.name)
+    ///         .collect())
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if there is no such table, a column has a type this
+    /// crate can't read, or the underlying DuckDB call fails.
+    pub fn summarize(&self, table: &str) -> Result<Vec<ColumnSummary>> {
+        let mut summarize = Sql::new();
+        summarize.push_keyword("SUMMARIZE")?;
+        summarize.push_space();
+        summarize.push_identifier(table);
+        // The statistics are text, and the null percentage a decimal, which
+        // only read as `f64` without strict types.
+        let sql = format!(
+            "SELECT column_name, column_type, min, max, approx_unique, TRY_CAST(avg AS DOUBLE), \
+             TRY_CAST(std AS DOUBLE), TRY_CAST(q25 AS DOUBLE), TRY_CAST(q50 AS DOUBLE), TRY_CAST(q75 AS DOUBLE), \
+             count, CAST(null_percentage AS DOUBLE) FROM ({})",
+            &*summarize
+        );
+        let mut stmt = self.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ColumnSummary {
+                name: row.get(0)?,
+                logical_type: column_type(row, 1)?,
+                min: row.get(2)?,
+                max: row.get(3)?,
+                approx_unique: row.get(4)?,
+                avg: row.get(5)?,
+                std: row.get(6)?,
+                q25: row.get(7)?,
+                q50: row.get(8)?,
+                q75: row.get(9)?,
+                count: row.get(10)?,
+                null_percentage: row.get(11)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ColumnDescription;
+    use crate::{types::LogicalType, Connection, Result};
+
+    #[test]
+    fn test_describe_with_parameters() -> Result<()> {
+        use LogicalType::*;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TABLE t (id INTEGER NOT NULL, s STRUCT(a INTEGER, b VARCHAR[]), m MAP(VARCHAR, DOUBLE))",
+        )?;
+        let column = |name: &str, logical_type| ColumnDescription {
+            name: name.to_owned(),
+            logical_type,
+            nullable: true,
+        };
+        assert_eq!(
+            db.describe("SELECT id, s, m, s.b[1] || $1 AS label FROM t WHERE id > $2 AND s.a = $3")?,
+            [
+                column("id", Int),
+                column(
+                    "s",
+                    Struct(vec![("a".to_owned(), Int), ("b".to_owned(), List(Box::new(Text)))])
+                ),
+                column("m", Map(Box::new(Text), Box::new(Double))),
+                column("label", Text),
+            ]
+        );
+        // The query isn't run, so its cast can't fail.
+        assert_eq!(db.describe("SELECT CAST('x' AS INTEGER) AS x")?, [column("x", Int)]);
+        assert!(db.describe("SELECT * FROM missing").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            r#"CREATE TABLE "my t" AS SELECT
+                range::INTEGER AS id,
+                CASE WHEN range % 4 = 0 THEN 'x' || range END AS sparse,
+                {'a': range, 'b': [range]} AS nested,
+                NULL::VARCHAR AS empty
+            FROM range(8)"#,
+        )?;
+        let summary = db.summarize("my t")?;
+        let names: Vec<_> = summary.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "sparse", "nested", "empty"]);
+
+        let id = &summary[0];
+        assert_eq!(id.logical_type, LogicalType::Int);
+        assert_eq!((id.min.as_deref(), id.max.as_deref()), (Some("0"), Some("7")));
+        assert_eq!(id.avg, Some(3.5));
+        assert!(id.std.unwrap() > 2.0, "{id:?}");
+        assert!(id.q25 <= id.q50 && id.q50 <= id.q75, "{id:?}");
+        assert_eq!((id.count, id.null_percentage), (8, Some(0.0)));
+
+        let sparse = &summary[1];
+        assert_eq!(sparse.logical_type, LogicalType::Text);
+        assert_eq!((sparse.min.as_deref(), sparse.max.as_deref()), (Some("x0"), Some("x4")));
+        assert_eq!((sparse.avg, sparse.q50), (None, None));
+        assert_eq!(sparse.null_percentage, Some(75.0));
+
+        let nested = &summary[2];
+        assert!(matches!(nested.logical_type, LogicalType::Struct(_)));
+        assert!(nested.min.is_some() && nested.max.is_some(), "{nested:?}");
+
+        let empty = &summary[3];
+        assert_eq!((empty.min.as_deref(), empty.approx_unique), (None, 0));
+        assert_eq!(empty.null_percentage, Some(100.0));
+
+        assert!(db.summarize("missing").is_err());
+
+        // The statistics are read the same with strict types.
+        db.set_strict_types(true);
+        assert_eq!(db.summarize("my t")?, summary);
+        Ok(())
+    }
+}
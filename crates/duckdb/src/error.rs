@@ -5,7 +5,7 @@ use crate::{
     ffi,
     types::{FromSqlError, Type},
 };
-use std::{error, ffi::CStr, fmt, path::PathBuf, str};
+use std::{error, ffi::CStr, fmt, io, path::PathBuf, str, time::Duration};
 
 /// Enum listing possible errors from duckdb.
 #[derive(Debug)]
@@ -47,6 +47,11 @@ pub enum Error {
     /// for [`query_row`](crate::Connection::query_row)) did not return any.
     QueryReturnedNoRows,
 
+    /// Error when a query that was expected to return at most one row (e.g.,
+    /// for [`query_one`](crate::Connection::query_one)) returned more, with
+    /// how many it returned.
+    QueryReturnedMoreThanOneRow(usize),
+
     /// Error when the value of a particular column is requested, but the index
     /// is out of range for the statement.
     InvalidColumnIndex(usize),
@@ -74,15 +79,633 @@ pub enum Error {
     /// Error when the SQL is not a `SELECT`, is not read-only.
     InvalidQuery,
 
-    /// Error when the SQL contains multiple statements.
-    MultipleStatement,
+    /// Error when preparing SQL that holds more than one statement, see
+    /// [`Connection::prepare_multi`](crate::Connection::prepare_multi).
+    /// Holds the byte offset in the SQL just past the semicolon ending the
+    /// first statement, where the rest of the statements start.
+    MultipleStatements {
+        /// Where the SQL after the first statement starts.
+        first_tail_offset: usize,
+    },
     /// Error when the number of bound parameters does not match the number of
     /// parameters in the query. The first `usize` is how many parameters were
     /// given, the 2nd is how many were expected.
     InvalidParameterCount(usize, usize),
 
+    /// Error when binding parameters by name, or fewer parameters by position
+    /// than the query has, leaves some parameters of the query without a
+    /// value. Holds their names, without the `$` prefix, which are their
+    /// positions for `?` parameters.
+    MissingParameters(Vec<String>),
+
+    /// Error when a query returns a different number of columns than the
+    /// caller expects. The first `usize` is how many columns were returned,
+    /// the 2nd is how many were expected.
+    InvalidColumnCount(usize, usize),
+
     /// Append Error
     AppendError,
+
+    /// Error when attaching a database under an alias that is already in
+    /// use.
+    DatabaseAlreadyAttached(String),
+
+    /// Error when attaching a database file that doesn't exist in read-only
+    /// mode, or importing a database from a directory it wasn't exported to.
+    DatabaseNotFound(PathBuf),
+
+    /// Error when an extension to install or load can't be found, either in
+    /// the repository or in the extension directory. Holds DuckDB's message.
+    ExtensionNotFound(String),
+
+    /// Error when loading an extension whose signature is missing or invalid
+    /// while `allow_unsigned_extensions` is off. Holds DuckDB's message.
+    ExtensionSignatureRejected(String),
+
+    /// Error when the extension repository can't be reached to download an
+    /// extension. Holds DuckDB's message.
+    ExtensionNetworkFailure(String),
+
+    /// Error when DuckDB refuses to checkpoint, because other transactions
+    /// are open or this one has uncommitted changes. Holds DuckDB's message.
+    CheckpointConflict(String),
+
+    /// Error when beginning a transaction while another one is active on the
+    /// same connection.
+    NestedTransaction,
+
+    /// Error when beginning a transaction while a failed one is open on the
+    /// same connection, which has to be rolled back first.
+    FailedTransaction,
+
+    /// Error when a statement of a batch run with
+    /// [`execute_batch_verbose`](crate::Connection::execute_batch_verbose)
+    /// fails. Holds the zero-based index of the statement, and its error. The
+    /// statements before it have run, the ones after it haven't.
+    BatchStatementFailed(usize, Box<Error>),
+
+    /// Error when executing a statement with a set of parameters passed to
+    /// [`execute_batch_params`](crate::Statement::execute_batch_params)
+    /// fails. Holds the zero-based index of the set of parameters, and its
+    /// error.
+    ParamSetFailed(usize, Box<Error>),
+
+    /// Error when a query run with
+    /// [`query_with_timeout`](crate::Statement::query_with_timeout) is
+    /// interrupted, because it didn't finish in time. Holds the timeout.
+    Timeout(Duration),
+
+    /// Error when a statement is cancelled through its
+    /// [`CancellationToken`](crate::CancellationToken), either while it was
+    /// executing or before it started.
+    Cancelled,
+
+    /// Error when exporting a database into a directory that holds files,
+    /// without [`overwrite`](crate::ExportOptions::overwrite) set.
+    ExportDirectoryNotEmpty(PathBuf),
+
+    /// Error when copying a query result with
+    /// [`copy_to`](crate::Connection::copy_to) to a file that exists, or to
+    /// a partition directory that holds files, without
+    /// [`overwrite`](crate::CopyOptions::overwrite) set.
+    CopyTargetExists(PathBuf),
+
+    /// Error when a table fails to load while importing a database with
+    /// [`import_database`](crate::Connection::import_database). Holds the
+    /// table as it is spelled in SQL, and the error.
+    ImportTableFailed(String, Box<Error>),
+
+    /// Error when a line of a CSV file read with
+    /// [`read_csv`](crate::Connection::read_csv) can't be read, without
+    /// [`ignore_errors`](crate::CsvReadBuilder::ignore_errors) set.
+    CsvReadFailed {
+        /// The file, as DuckDB spells its path.
+        file: String,
+        /// The line of the file, counting from 1, when DuckDB reports it.
+        line: Option<u64>,
+        /// The error DuckDB reported.
+        error: Box<Error>,
+    },
+
+    /// Error when DuckDB fails on a file read with
+    /// [`read_parquet`](crate::Connection::read_parquet) or written with
+    /// [`write_parquet`](crate::Connection::write_parquet), such as one that
+    /// isn't Parquet, or a glob that matches no files.
+    ParquetFileFailed {
+        /// The file or glob, as DuckDB spells its path.
+        file: String,
+        /// The error DuckDB reported.
+        error: Box<Error>,
+    },
+
+    /// Error when a table fails to be copied by
+    /// [`backup_to`](crate::Connection::backup_to). The tables copied before
+    /// it are kept.
+    BackupTableFailed {
+        /// The table, qualified with its schema.
+        table: String,
+        /// The error copying it.
+        error: Box<Error>,
+    },
+
+    /// Error when the SQL of a statement can't be parsed, with where in the
+    /// SQL DuckDB found the error, see [`Error::sql_error_position`].
+    ///
+    /// Only syntax errors carry a position, other errors in SQL are reported
+    /// as [`Error::DuckDBFailure`].
+    SqlInputError {
+        /// The DuckDB error code.
+        error: ffi::Error,
+        /// The message of the error, without the SQL DuckDB appends to it.
+        msg: String,
+        /// The SQL that failed to parse.
+        sql: String,
+        /// The byte offset of the error in `sql`.
+        offset: usize,
+    },
+
+    /// Error when [`Row::get`](crate::Row::get) can't read the value of a
+    /// column as the requested Rust type.
+    ColumnConversionFailure {
+        /// The index of the column.
+        index: usize,
+        /// The name of the column.
+        name: String,
+        /// The type of the column in the result.
+        column_type: Type,
+        /// The name of the requested Rust type.
+        rust_type: &'static str,
+        /// Why the value couldn't be converted.
+        source: FromSqlError,
+    },
+
+    /// Error of a statement, with its SQL and, if they were bound, its
+    /// parameters, when the connection's
+    /// [error verbosity](crate::Connection::set_error_verbosity) asks for
+    /// them. [`Error::kind`] and the other accessors look through it to
+    /// `error`.
+    StatementFailed {
+        /// The error of the statement.
+        error: Box<Error>,
+        /// The SQL of the statement.
+        sql: String,
+        /// The name of each parameter with its value, written as a SQL
+        /// literal or as the redaction hook replaced it. `None` unless
+        /// [`ErrorVerbosity::WithSqlAndParams`] is set.
+        params: Option<Vec<(String, String)>>,
+    },
+
+    /// Error when a statement or an [`Appender`](crate::Appender) violates a
+    /// `NOT NULL`, `UNIQUE`, `PRIMARY KEY`, `CHECK` or `FOREIGN KEY`
+    /// constraint.
+    ConstraintViolation {
+        /// The kind of constraint violated.
+        kind: ConstraintKind,
+        /// The table of the constraint, when DuckDB names it: for `NOT
+        /// NULL` and `CHECK` constraints.
+        table: Option<String>,
+        /// The column of a `NOT NULL` constraint, or the columns of the key
+        /// of a `UNIQUE`, `PRIMARY KEY` or `FOREIGN KEY` constraint,
+        /// separated by `, `, when DuckDB names them.
+        constraint_or_column: Option<String>,
+        /// DuckDB's message.
+        message: String,
+    },
+}
+
+/// The kind of constraint an [`Error::ConstraintViolation`] violated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConstraintKind {
+    /// A `NOT NULL` constraint, or a `PRIMARY KEY` column set to `NULL`.
+    NotNull,
+    /// A `UNIQUE` or `PRIMARY KEY` constraint, by a duplicate key.
+    Unique,
+    /// A `CHECK` constraint.
+    Check,
+    /// A `FOREIGN KEY` constraint, by a key missing from the referenced
+    /// table, or by deleting a key still referenced.
+    ForeignKey,
+}
+
+/// How much of the failing statement an [`Error`] shows, as set with
+/// [`Connection::set_error_verbosity`](crate::Connection::set_error_verbosity).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorVerbosity {
+    /// Only the error itself. This is the default.
+    #[default]
+    Message,
+    /// The error, followed by the SQL of the statement.
+    WithSql,
+    /// The error, followed by the SQL of the statement and the values of the
+    /// parameters bound to it.
+    WithSqlAndParams,
+}
+
+/// The class of an [`Error`], as returned by [`Error::kind`], to tell
+/// failures apart without matching on their messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A table, view, schema, function or other catalog entry doesn't
+    /// exist, or already does, such as when querying a missing table or
+    /// creating one twice.
+    Catalog,
+    /// The SQL isn't valid syntax. Reported when preparing a statement.
+    Parser,
+    /// The SQL is valid syntax but doesn't make sense, such as a column that
+    /// doesn't exist or a function called with the wrong arguments.
+    /// Reported when preparing a statement.
+    Binder,
+    /// A `PRIMARY KEY`, `UNIQUE`, `NOT NULL`, `CHECK` or `FOREIGN KEY`
+    /// constraint was violated, by an insert, update or appender.
+    Constraint,
+    /// A value couldn't be converted to another type, by a cast in SQL or
+    /// when reading it into a Rust type, see
+    /// [`Error::FromSqlConversionFailure`] and [`Error::InvalidColumnType`].
+    Conversion,
+    /// A value doesn't fit its type, such as an overflowing sum or a division
+    /// by zero, or when reading it into a Rust type, see
+    /// [`Error::IntegralValueOutOfRange`].
+    OutOfRange,
+    /// DuckDB ran out of memory, within its `memory_limit`.
+    OutOfMemory,
+    /// The query was interrupted, through an
+    /// [`InterruptHandle`](crate::InterruptHandle), because of a timeout,
+    /// see [`Error::Timeout`], or by cancelling it, see [`Error::Cancelled`].
+    Interrupted,
+    /// Reading or writing a file failed, such as a database, a CSV or
+    /// Parquet file, or a directory to export to.
+    IO,
+    /// A transaction couldn't begin, commit or checkpoint, such as a write
+    /// conflict with another transaction. See [`Error::NestedTransaction`],
+    /// [`Error::FailedTransaction`] and [`Error::CheckpointConflict`].
+    Transaction,
+    /// An argument, option or setting is invalid, such as an unknown
+    /// configuration value.
+    InvalidInput,
+    /// An extension couldn't be installed, loaded or autoloaded, see
+    /// [`Error::ExtensionNotFound`] and the other extension errors.
+    Extension,
+    /// The database is read-only, or the operation isn't allowed by the
+    /// configuration, such as file access with `enable_external_access` off.
+    Permission,
+    /// DuckDB doesn't support the operation.
+    NotImplemented,
+    /// An internal error in DuckDB, which may have left the database
+    /// unusable.
+    Internal,
+    /// Any other error, including those raised by this crate for misuse,
+    /// such as [`Error::InvalidColumnIndex`].
+    Other,
+}
+
+impl ErrorKind {
+    /// The kind of error DuckDB reports with `message`, which starts with
+    /// the name of the exception type, such as `Catalog Error: `.
+    fn from_message(message: &str) -> ErrorKind {
+        let Some((exception, _)) = message.split_once(" Error: ") else {
+            return ErrorKind::Other;
+        };
+        match exception {
+            "Catalog" | "Dependency" | "Sequence" => ErrorKind::Catalog,
+            "Parser" => ErrorKind::Parser,
+            "Binder" | "Parameter Not Resolved" | "Parameter Not Allowed" => ErrorKind::Binder,
+            "Constraint" => ErrorKind::Constraint,
+            "Conversion" | "Invalid type" | "Mismatch Type" | "Unknown Type" => ErrorKind::Conversion,
+            "Out of Range" | "Divide by Zero" | "Decimal" => ErrorKind::OutOfRange,
+            "Out of Memory" => ErrorKind::OutOfMemory,
+            "INTERRUPT" => ErrorKind::Interrupted,
+            "IO" | "HTTP" | "Serialization" => ErrorKind::IO,
+            "TransactionContext" => ErrorKind::Transaction,
+            // DuckDB raises syntax errors for invalid settings.
+            "Invalid Input" | "Settings" | "Syntax" => ErrorKind::InvalidInput,
+            "Missing Extension" | "Extension Autoloading" => ErrorKind::Extension,
+            "Permission" => ErrorKind::Permission,
+            "Not implemented" => ErrorKind::NotImplemented,
+            "INTERNAL" | "FATAL" => ErrorKind::Internal,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// The reasons the operating system gives for failed file operations, as
+/// `strerror` words them and DuckDB appends them to its IO errors.
+const OS_ERRORS: &[(&str, io::ErrorKind)] = &[
+    ("Permission denied", io::ErrorKind::PermissionDenied),
+    ("Operation not permitted", io::ErrorKind::PermissionDenied),
+    ("No such file or directory", io::ErrorKind::NotFound),
+    ("File exists", io::ErrorKind::AlreadyExists),
+    ("No space left on device", io::ErrorKind::StorageFull),
+    ("Disk quota exceeded", io::ErrorKind::QuotaExceeded),
+    ("Read-only file system", io::ErrorKind::ReadOnlyFilesystem),
+    ("Is a directory", io::ErrorKind::IsADirectory),
+    ("Not a directory", io::ErrorKind::NotADirectory),
+    ("Directory not empty", io::ErrorKind::DirectoryNotEmpty),
+    ("File too large", io::ErrorKind::FileTooLarge),
+    ("Resource temporarily unavailable", io::ErrorKind::WouldBlock),
+    ("Interrupted system call", io::ErrorKind::Interrupted),
+    ("Too many open files", io::ErrorKind::Other),
+    ("Input/output error", io::ErrorKind::Other),
+];
+
+impl Error {
+    /// The class of this error. The kind of a DuckDB failure comes from its
+    /// message, which is left as it is.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, ErrorKind, Result};
+    /// fn count(conn: &Connection) -> Result<i64> {
+    ///     match conn.query_row("SELECT count(*) FROM events", [], |r| r.get(0)) {
+    ///         Err(err) if err.kind() == ErrorKind::Catalog => Ok(0),
+    ///         res => res,
+    ///     }
+    /// }
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::DuckDBFailure(_, Some(message)) | Error::SqlInputError { msg: message, .. } => {
+                ErrorKind::from_message(message)
+            }
+            Error::BatchStatementFailed(_, err)
+            | Error::ParamSetFailed(_, err)
+            | Error::ImportTableFailed(_, err)
+            | Error::CsvReadFailed { error: err, .. }
+            | Error::ParquetFileFailed { error: err, .. }
+            | Error::BackupTableFailed { error: err, .. }
+            | Error::StatementFailed { error: err, .. } => err.kind(),
+            Error::ConstraintViolation { .. } => ErrorKind::Constraint,
+            Error::FromSqlConversionFailure(_, _, err)
+                if matches!(err.downcast_ref(), Some(FromSqlError::OutOfRange { .. })) =>
+            {
+                ErrorKind::OutOfRange
+            }
+            Error::FromSqlConversionFailure(..)
+            | Error::InvalidColumnType(..)
+            | Error::ToSqlConversionFailure(_)
+            | Error::Utf8Error(_)
+            | Error::ArrowTypeToDuckdbType(..) => ErrorKind::Conversion,
+            Error::IntegralValueOutOfRange(..)
+            | Error::ColumnConversionFailure {
+                source: FromSqlError::OutOfRange { .. },
+                ..
+            } => ErrorKind::OutOfRange,
+            Error::ColumnConversionFailure { .. } => ErrorKind::Conversion,
+            Error::Timeout(_) | Error::Cancelled => ErrorKind::Interrupted,
+            Error::NestedTransaction | Error::FailedTransaction | Error::CheckpointConflict(_) => {
+                ErrorKind::Transaction
+            }
+            Error::ExtensionNotFound(_) | Error::ExtensionSignatureRejected(_) | Error::ExtensionNetworkFailure(_) => {
+                ErrorKind::Extension
+            }
+            Error::DatabaseNotFound(_) | Error::ExportDirectoryNotEmpty(_) | Error::CopyTargetExists(_) => {
+                ErrorKind::IO
+            }
+            Error::DatabaseAlreadyAttached(_) => ErrorKind::Catalog,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl Error {
+    /// Whether the operation that failed may succeed if it is run again,
+    /// once other connections are done: when a transaction conflicts with
+    /// another, DuckDB refuses to checkpoint while other transactions are
+    /// open or another thread is checkpointing, or another process holds the
+    /// lock on the database file.
+    ///
+    /// A transaction that failed with a retryable error has to be rolled back
+    /// and run again from the start, see [`retry`](crate::retry).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::DuckDBFailure(_, Some(message)) => {
+                let Some((exception, msg)) = message.split_once(" Error: ") else {
+                    return false;
+                };
+                match exception {
+                    "TransactionContext" => {
+                        msg.contains("conflict")
+                            || msg.starts_with("Conflict on ")
+                            || msg.starts_with("Failed to lock clients")
+                    }
+                    "IO" => msg.starts_with("Could not set lock on file"),
+                    _ => false,
+                }
+            }
+            Error::CheckpointConflict(_) => true,
+            Error::BatchStatementFailed(_, err)
+            | Error::ParamSetFailed(_, err)
+            | Error::StatementFailed { error: err, .. } => err.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// The file-system error behind this error, when DuckDB failed to open,
+    /// read, write or remove a file: a database file, its write-ahead log,
+    /// or a temporary file it spilled to. Its kind comes from the reason the
+    /// operating system gave, and its message is DuckDB's whole message.
+    ///
+    /// Returns `None` for other errors, including IO errors DuckDB raises
+    /// itself, such as a glob that matches no files.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use std::io;
+    /// # use duckdb::{Connection, Result};
+    /// fn open_or_in_memory(path: &str) -> Result<Connection> {
+    ///     match Connection::open(path) {
+    ///         Err(err) if err.as_io_error().is_some_and(|e| e.kind() == io::ErrorKind::PermissionDenied) => {
+    ///             Connection::open_in_memory()
+    ///         }
+    ///         res => res,
+    ///     }
+    /// }
+    /// ```
+    pub fn as_io_error(&self) -> Option<io::Error> {
+        match self {
+            Error::DuckDBFailure(_, Some(message)) => {
+                // A fatal error, such as a failed write to the write-ahead
+                // log, holds the IO error that caused it.
+                let (exception, _) = message.split_once(" Error: ")?;
+                if exception != "IO" && exception != "FATAL" {
+                    return None;
+                }
+                let kind = OS_ERRORS
+                    .iter()
+                    .find(|(reason, _)| message.contains(&format!(": {reason}")))
+                    .map(|(_, kind)| *kind)?;
+                Some(io::Error::new(kind, message.clone()))
+            }
+            Error::DatabaseNotFound(_) => Some(io::Error::new(io::ErrorKind::NotFound, self.to_string())),
+            Error::CopyTargetExists(_) => Some(io::Error::new(io::ErrorKind::AlreadyExists, self.to_string())),
+            Error::BatchStatementFailed(_, err)
+            | Error::ParamSetFailed(_, err)
+            | Error::ImportTableFailed(_, err)
+            | Error::CsvReadFailed { error: err, .. }
+            | Error::ParquetFileFailed { error: err, .. }
+            | Error::BackupTableFailed { error: err, .. }
+            | Error::StatementFailed { error: err, .. } => err.as_io_error(),
+            _ => None,
+        }
+    }
+
+    /// The byte offset of a syntax error in the SQL that failed to parse,
+    /// for an [`Error::SqlInputError`].
+    ///
+    /// Displaying the error with the alternate flag, as in `{err:#}`, adds
+    /// the line of the SQL holding the error, with a caret under it.
+    pub fn sql_error_position(&self) -> Option<usize> {
+        match self.without_context() {
+            Error::SqlInputError { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// The token a syntax error was found at, for an
+    /// [`Error::SqlInputError`], unless the SQL ended unexpectedly.
+    pub fn sql_error_token(&self) -> Option<&str> {
+        let Error::SqlInputError { msg, .. } = self.without_context() else {
+            return None;
+        };
+        let (_, near) = msg.split_once(" at or near \"")?;
+        near.rsplit_once('"').map(|(token, _)| token)
+    }
+
+    /// The error of an [`Error::StatementFailed`], or this error.
+    pub(crate) fn without_context(&self) -> &Error {
+        match self {
+            Error::StatementFailed { error, .. } => error,
+            err => err,
+        }
+    }
+
+    /// Turns a syntax error DuckDB reported with its position in `sql` into
+    /// an [`Error::SqlInputError`].
+    pub(crate) fn with_sql(self, sql: &str) -> Error {
+        match self {
+            Error::DuckDBFailure(error, Some(message)) if message.starts_with("Parser Error: ") => {
+                match locate_error(&message, sql) {
+                    Some((msg, offset)) => Error::SqlInputError {
+                        error,
+                        msg: msg.to_owned(),
+                        sql: sql.to_owned(),
+                        offset,
+                    },
+                    None => Error::DuckDBFailure(error, Some(message)),
+                }
+            }
+            err => err,
+        }
+    }
+}
+
+/// Parses the constraint violation DuckDB reports with `message`. Statements
+/// report it with the `Constraint Error: ` prefix, appenders without.
+fn constraint_violation(message: &str) -> Option<Error> {
+    let body = message.strip_prefix("Constraint Error: ").unwrap_or(message);
+    let (kind, table, constraint_or_column) = if let Some(rest) = body.strip_prefix("NOT NULL constraint failed: ") {
+        let (table, column) = rest.split_once('.')?;
+        (ConstraintKind::NotNull, Some(table), Some(column.to_owned()))
+    } else if let Some(rest) = body.strip_prefix("CHECK constraint failed: ") {
+        // DuckDB doesn't name CHECK constraints, and may follow the table
+        // with the error evaluating the check.
+        let table = rest.split_once(" (").map_or(rest, |(table, _)| table);
+        (ConstraintKind::Check, Some(table), None)
+    } else if body.starts_with("Duplicate key \"") {
+        (ConstraintKind::Unique, None, key_columns(body))
+    } else if body.starts_with("PRIMARY KEY or UNIQUE constraint violated: ") {
+        // Duplicates within a transaction are reported by value only.
+        (ConstraintKind::Unique, None, None)
+    } else if body.starts_with("Violates foreign key constraint because key \"") {
+        (ConstraintKind::ForeignKey, None, key_columns(body))
+    } else {
+        return None;
+    };
+    Some(Error::ConstraintViolation {
+        kind,
+        table: table.map(str::to_owned),
+        constraint_or_column,
+        message: message.to_owned(),
+    })
+}
+
+/// The columns of the key DuckDB quotes in `message` as `"a: 1, b: 2"`.
+fn key_columns(message: &str) -> Option<String> {
+    let (_, key) = message.split_once(" key \"")?;
+    let (key, _) = key.split_once("\" ")?;
+    // A value holding `, ` splits into parts without a column.
+    let columns: Vec<_> = key
+        .split(", ")
+        .filter_map(|part| part.split_once(": ").map(|(column, _)| column))
+        .collect();
+    (!columns.is_empty()).then(|| columns.join(", "))
+}
+
+/// Finds the byte offset in `sql` of the error DuckDB points at in
+/// `message`, which it follows with the line of `sql` holding the error,
+/// at most 40 characters either side of it, and a line with a caret under
+/// it:
+///
+/// ```text
+/// Parser Error: syntax error at or near "SELEC"
+/// LINE 2: SELEC x FROM t
+///         ^
+/// ```
+///
+/// Returns the message without the lines pointing at the error, and the
+/// offset.
+fn locate_error<'m>(message: &'m str, sql: &str) -> Option<(&'m str, usize)> {
+    let (msg, context) = message.split_once("\nLINE ")?;
+    let (line_number, rest) = context.split_once(": ")?;
+    let (snippet, caret) = rest.split_once('\n')?;
+    let caret = caret.strip_suffix('^')?;
+    // The caret is indented by the line indicator, then by one space per
+    // character before the error.
+    let indicator = "LINE ".len() + line_number.len() + ": ".len();
+    let mut column = caret.len().checked_sub(indicator)?;
+    let line = sql_lines(sql).nth(line_number.parse::<usize>().ok()?.checked_sub(1)?)?;
+    let start = match snippet.strip_prefix("...") {
+        Some(snippet) => {
+            column = column.checked_sub(3)?;
+            let snippet = snippet.strip_suffix("...").unwrap_or(snippet);
+            line.1.find(snippet)?
+        }
+        None => 0,
+    };
+    let (offset, _) = line.1[start..]
+        .char_indices()
+        .chain([(line.1.len() - start, ' ')])
+        .nth(column)?;
+    Some((msg, line.0 + start + offset))
+}
+
+/// The lines of `sql` with their byte offsets, split where DuckDB counts
+/// lines: at `\n`, `\r\n` and `\r`.
+fn sql_lines(sql: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = 0;
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let rest = &sql[start..];
+        let line_start = start;
+        match rest.find(['\n', '\r']) {
+            Some(end) => {
+                let newline = if rest[end..].starts_with("\r\n") { 2 } else { 1 };
+                start += end + newline;
+                Some((line_start, &rest[..end]))
+            }
+            None => {
+                done = true;
+                Some((line_start, rest))
+            }
+        }
+    })
 }
 
 impl PartialEq for Error {
@@ -96,13 +719,109 @@ impl PartialEq for Error {
             (Error::InvalidPath(p1), Error::InvalidPath(p2)) => p1 == p2,
             (Error::ExecuteReturnedResults, Error::ExecuteReturnedResults) => true,
             (Error::QueryReturnedNoRows, Error::QueryReturnedNoRows) => true,
+            (Error::QueryReturnedMoreThanOneRow(n1), Error::QueryReturnedMoreThanOneRow(n2)) => n1 == n2,
             (Error::InvalidColumnIndex(i1), Error::InvalidColumnIndex(i2)) => i1 == i2,
             (Error::InvalidColumnName(n1), Error::InvalidColumnName(n2)) => n1 == n2,
             (Error::InvalidColumnType(i1, n1, t1), Error::InvalidColumnType(i2, n2, t2)) => {
                 i1 == i2 && t1 == t2 && n1 == n2
             }
             (Error::StatementChangedRows(n1), Error::StatementChangedRows(n2)) => n1 == n2,
+            (
+                Error::MultipleStatements { first_tail_offset: o1 },
+                Error::MultipleStatements { first_tail_offset: o2 },
+            ) => o1 == o2,
             (Error::InvalidParameterCount(i1, n1), Error::InvalidParameterCount(i2, n2)) => i1 == i2 && n1 == n2,
+            (Error::MissingParameters(n1), Error::MissingParameters(n2)) => n1 == n2,
+            (Error::InvalidColumnCount(i1, n1), Error::InvalidColumnCount(i2, n2)) => i1 == i2 && n1 == n2,
+            (Error::DatabaseAlreadyAttached(a1), Error::DatabaseAlreadyAttached(a2)) => a1 == a2,
+            (Error::DatabaseNotFound(p1), Error::DatabaseNotFound(p2)) => p1 == p2,
+            (Error::ExtensionNotFound(m1), Error::ExtensionNotFound(m2)) => m1 == m2,
+            (Error::ExtensionSignatureRejected(m1), Error::ExtensionSignatureRejected(m2)) => m1 == m2,
+            (Error::ExtensionNetworkFailure(m1), Error::ExtensionNetworkFailure(m2)) => m1 == m2,
+            (Error::CheckpointConflict(m1), Error::CheckpointConflict(m2)) => m1 == m2,
+            (Error::NestedTransaction, Error::NestedTransaction) => true,
+            (Error::FailedTransaction, Error::FailedTransaction) => true,
+            (Error::BatchStatementFailed(i1, e1), Error::BatchStatementFailed(i2, e2)) => i1 == i2 && e1 == e2,
+            (Error::ParamSetFailed(i1, e1), Error::ParamSetFailed(i2, e2)) => i1 == i2 && e1 == e2,
+            (Error::ExportDirectoryNotEmpty(p1), Error::ExportDirectoryNotEmpty(p2)) => p1 == p2,
+            (Error::CopyTargetExists(p1), Error::CopyTargetExists(p2)) => p1 == p2,
+            (Error::ImportTableFailed(t1, e1), Error::ImportTableFailed(t2, e2)) => t1 == t2 && e1 == e2,
+            (
+                Error::CsvReadFailed {
+                    file: f1,
+                    line: l1,
+                    error: e1,
+                },
+                Error::CsvReadFailed {
+                    file: f2,
+                    line: l2,
+                    error: e2,
+                },
+            ) => f1 == f2 && l1 == l2 && e1 == e2,
+            (Error::ParquetFileFailed { file: f1, error: e1 }, Error::ParquetFileFailed { file: f2, error: e2 }) => {
+                f1 == f2 && e1 == e2
+            }
+            (Error::BackupTableFailed { table: t1, error: e1 }, Error::BackupTableFailed { table: t2, error: e2 }) => {
+                t1 == t2 && e1 == e2
+            }
+            (
+                Error::SqlInputError {
+                    error: e1,
+                    msg: m1,
+                    sql: s1,
+                    offset: o1,
+                },
+                Error::SqlInputError {
+                    error: e2,
+                    msg: m2,
+                    sql: s2,
+                    offset: o2,
+                },
+            ) => e1 == e2 && m1 == m2 && s1 == s2 && o1 == o2,
+            (
+                Error::ConstraintViolation {
+                    kind: k1,
+                    table: t1,
+                    constraint_or_column: c1,
+                    message: m1,
+                },
+                Error::ConstraintViolation {
+                    kind: k2,
+                    table: t2,
+                    constraint_or_column: c2,
+                    message: m2,
+                },
+            ) => k1 == k2 && t1 == t2 && c1 == c2 && m1 == m2,
+            (
+                Error::ColumnConversionFailure {
+                    index: i1,
+                    name: n1,
+                    column_type: t1,
+                    rust_type: r1,
+                    source: s1,
+                },
+                Error::ColumnConversionFailure {
+                    index: i2,
+                    name: n2,
+                    column_type: t2,
+                    rust_type: r2,
+                    source: s2,
+                },
+            ) => i1 == i2 && n1 == n2 && t1 == t2 && r1 == r2 && s1 == s2,
+            (
+                Error::StatementFailed {
+                    error: e1,
+                    sql: s1,
+                    params: p1,
+                },
+                Error::StatementFailed {
+                    error: e2,
+                    sql: s2,
+                    params: p2,
+                },
+            ) => e1 == e2 && s1 == s2 && p1 == p2,
+            (Error::Timeout(d1), Error::Timeout(d2)) => d1 == d2,
+            (Error::Cancelled, Error::Cancelled) => true,
             (..) => false,
         }
     }
@@ -122,7 +841,7 @@ impl From<::std::ffi::NulError> for Error {
     }
 }
 
-const UNKNOWN_COLUMN: usize = std::usize::MAX;
+pub(crate) const UNKNOWN_COLUMN: usize = usize::MAX;
 
 /// The conversion isn't precise, but it's convenient to have it
 /// to allow use of `get_raw(…).as_…()?` in callbacks that take `Error`.
@@ -132,11 +851,18 @@ impl From<FromSqlError> for Error {
         // The error type requires index and type fields, but they aren't known in this
         // context.
         match err {
-            FromSqlError::OutOfRange(val) => Error::IntegralValueOutOfRange(UNKNOWN_COLUMN, val),
+            FromSqlError::OutOfRange { ref value, .. } => match value.parse() {
+                Ok(val) => Error::IntegralValueOutOfRange(UNKNOWN_COLUMN, val),
+                Err(_) => Error::FromSqlConversionFailure(UNKNOWN_COLUMN, Type::Null, Box::new(err)),
+            },
             #[cfg(feature = "uuid")]
             FromSqlError::InvalidUuidSize(_) => {
                 Error::FromSqlConversionFailure(UNKNOWN_COLUMN, Type::Blob, Box::new(err))
             }
+            FromSqlError::InvalidUtf8(err) => Error::Utf8Error(err),
+            FromSqlError::InvalidCharLength(_) => {
+                Error::FromSqlConversionFailure(UNKNOWN_COLUMN, Type::Text, Box::new(err))
+            }
             FromSqlError::Other(source) => Error::FromSqlConversionFailure(UNKNOWN_COLUMN, Type::Null, source),
             _ => Error::FromSqlConversionFailure(UNKNOWN_COLUMN, Type::Null, Box::new(err)),
         }
@@ -170,22 +896,120 @@ impl fmt::Display for Error {
                 write!(f, "Execute returned results - did you mean to call query?")
             }
             Error::QueryReturnedNoRows => write!(f, "Query returned no rows"),
+            Error::QueryReturnedMoreThanOneRow(n) => write!(f, "Query returned more than one row: {n} rows"),
             Error::InvalidColumnIndex(i) => write!(f, "Invalid column index: {i}"),
             Error::InvalidColumnName(ref name) => write!(f, "Invalid column name: {name}"),
             Error::InvalidColumnType(i, ref name, ref t) => {
                 write!(f, "Invalid column type {t} at index: {i}, name: {name}")
             }
+            Error::StatementFailed {
+                ref error,
+                ref sql,
+                ref params,
+            } => {
+                if f.alternate() {
+                    write!(f, "{error:#}")?;
+                } else {
+                    write!(f, "{error}")?;
+                }
+                write!(f, "\nSQL: {sql}")?;
+                if let Some(params) = params {
+                    f.write_str("\nParameters:")?;
+                    for (i, (name, value)) in params.iter().enumerate() {
+                        let sep = if i == 0 { " " } else { ", " };
+                        write!(f, "{sep}${name} = {value}")?;
+                    }
+                }
+                Ok(())
+            }
+            Error::ColumnConversionFailure {
+                index,
+                ref name,
+                ref column_type,
+                rust_type,
+                ref source,
+            } => write!(
+                f,
+                "Cannot read column {index} ({name}) of type {column_type} as {rust_type}: {source}"
+            ),
             Error::ArrowTypeToDuckdbType(ref name, ref t) => {
                 write!(f, "Invalid column type {t} , name: {name}")
             }
             Error::InvalidParameterCount(i1, n1) => {
                 write!(f, "Wrong number of parameters passed to query. Got {i1}, needed {n1}")
             }
+            Error::MissingParameters(ref names) => {
+                f.write_str("No value bound for parameters:")?;
+                for (i, name) in names.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    write!(f, "{sep}${name}")?;
+                }
+                Ok(())
+            }
+            Error::InvalidColumnCount(i1, n1) => {
+                write!(f, "Wrong number of columns returned by query. Got {i1}, expected {n1}")
+            }
             Error::StatementChangedRows(i) => write!(f, "Query changed {i} rows"),
             Error::ToSqlConversionFailure(ref err) => err.fmt(f),
             Error::InvalidQuery => write!(f, "Query is not read-only"),
-            Error::MultipleStatement => write!(f, "Multiple statements provided"),
+            Error::MultipleStatements { first_tail_offset } => write!(
+                f,
+                "Cannot prepare multiple statements at once, the second starts after byte {first_tail_offset}"
+            ),
             Error::AppendError => write!(f, "Append error"),
+            Error::DatabaseAlreadyAttached(ref alias) => write!(f, "A database is already attached as {alias}"),
+            Error::DatabaseNotFound(ref p) => write!(f, "Database not found: {}", p.to_string_lossy()),
+            Error::ExtensionNotFound(ref msg)
+            | Error::ExtensionSignatureRejected(ref msg)
+            | Error::ExtensionNetworkFailure(ref msg)
+            | Error::CheckpointConflict(ref msg)
+            | Error::ConstraintViolation { message: ref msg, .. } => write!(f, "{msg}"),
+            Error::NestedTransaction => write!(f, "A transaction is already active on this connection"),
+            Error::FailedTransaction => {
+                write!(
+                    f,
+                    "The transaction open on this connection failed and must be rolled back"
+                )
+            }
+            Error::BatchStatementFailed(i, ref err) => write!(f, "Statement {i} of the batch failed: {err}"),
+            Error::ParamSetFailed(i, ref err) => write!(f, "Parameter set {i} of the batch failed: {err}"),
+            Error::Timeout(timeout) => write!(f, "Query timed out after {timeout:?}"),
+            Error::Cancelled => write!(f, "Statement was cancelled"),
+            Error::ExportDirectoryNotEmpty(ref p) => {
+                write!(f, "Export directory is not empty: {}", p.to_string_lossy())
+            }
+            Error::CopyTargetExists(ref p) => write!(f, "Copy target already exists: {}", p.to_string_lossy()),
+            Error::ImportTableFailed(ref table, ref err) => write!(f, "Importing table {table} failed: {err}"),
+            Error::CsvReadFailed {
+                ref file,
+                line: Some(line),
+                ref error,
+            } => write!(f, "Reading line {line} of CSV file {file} failed: {error}"),
+            Error::CsvReadFailed {
+                ref file,
+                line: None,
+                ref error,
+            } => write!(f, "Reading CSV file {file} failed: {error}"),
+            Error::ParquetFileFailed { ref file, ref error } => write!(f, "Parquet file {file} failed: {error}"),
+            Error::BackupTableFailed { ref table, ref error } => write!(f, "Copying table {table} failed: {error}"),
+            Error::SqlInputError {
+                ref msg,
+                ref sql,
+                offset,
+                ..
+            } => {
+                write!(f, "{msg}")?;
+                let line = sql_lines(sql)
+                    .enumerate()
+                    .take_while(|(_, (start, _))| *start <= offset)
+                    .last();
+                if let (true, Some((i, (start, line)))) = (f.alternate(), line) {
+                    let indicator = format!("LINE {}: ", i + 1);
+                    let width = indicator.len() + sql[start..offset].chars().count();
+                    write!(f, "\n{indicator}{line}\n{:width$}^", "")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -193,25 +1017,49 @@ impl fmt::Display for Error {
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
-            Error::DuckDBFailure(ref err, _) => Some(err),
+            Error::DuckDBFailure(ref err, _) | Error::SqlInputError { error: ref err, .. } => Some(err),
             Error::Utf8Error(ref err) => Some(err),
             Error::NulError(ref err) => Some(err),
+            Error::BatchStatementFailed(_, ref err)
+            | Error::ParamSetFailed(_, ref err)
+            | Error::ImportTableFailed(_, ref err)
+            | Error::CsvReadFailed { error: ref err, .. }
+            | Error::ParquetFileFailed { error: ref err, .. }
+            | Error::BackupTableFailed { error: ref err, .. }
+            | Error::StatementFailed { error: ref err, .. } => Some(&**err),
 
             Error::IntegralValueOutOfRange(..)
             | Error::InvalidParameterName(_)
             | Error::ExecuteReturnedResults
             | Error::QueryReturnedNoRows
+            | Error::QueryReturnedMoreThanOneRow(_)
             | Error::InvalidColumnIndex(_)
             | Error::InvalidColumnName(_)
             | Error::InvalidColumnType(..)
             | Error::InvalidPath(_)
             | Error::InvalidParameterCount(..)
+            | Error::MissingParameters(_)
+            | Error::InvalidColumnCount(..)
             | Error::StatementChangedRows(_)
             | Error::InvalidQuery
             | Error::AppendError
+            | Error::DatabaseAlreadyAttached(_)
+            | Error::DatabaseNotFound(_)
+            | Error::ExtensionNotFound(_)
+            | Error::ExtensionSignatureRejected(_)
+            | Error::ExtensionNetworkFailure(_)
+            | Error::CheckpointConflict(_)
+            | Error::ConstraintViolation { .. }
+            | Error::NestedTransaction
+            | Error::FailedTransaction
+            | Error::Timeout(_)
+            | Error::Cancelled
+            | Error::ExportDirectoryNotEmpty(_)
+            | Error::CopyTargetExists(_)
             | Error::ArrowTypeToDuckdbType(..)
-            | Error::MultipleStatement => None,
+            | Error::MultipleStatements { .. } => None,
             Error::FromSqlConversionFailure(_, _, ref err) | Error::ToSqlConversionFailure(ref err) => Some(&**err),
+            Error::ColumnConversionFailure { ref source, .. } => Some(source),
         }
     }
 }
@@ -220,6 +1068,9 @@ impl error::Error for Error {
 
 #[inline]
 fn error_from_duckdb_code(code: ffi::duckdb_state, message: Option<String>) -> Result<()> {
+    if let Some(err) = message.as_deref().and_then(constraint_violation) {
+        return Err(err);
+    }
     Err(Error::DuckDBFailure(ffi::Error::new(code), message))
 }
 
@@ -279,3 +1130,309 @@ pub fn result_from_duckdb_arrow(code: ffi::duckdb_state, mut out: ffi::duckdb_ar
         error_from_duckdb_code(code, message)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{constraint_violation, locate_error, ConstraintKind, ErrorKind};
+    use crate::{Connection, Error, Result};
+
+    #[test]
+    fn test_error_kind() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY, x TINYINT); INSERT INTO t VALUES (1, 1)")?;
+        let kind = |sql: &str| db.execute_batch(sql).unwrap_err().kind();
+        assert_eq!(kind("SELECT * FROM missing"), ErrorKind::Catalog);
+        assert_eq!(kind("CREATE TABLE t (id INTEGER)"), ErrorKind::Catalog);
+        assert_eq!(kind("SELEC 1"), ErrorKind::Parser);
+        assert_eq!(kind("SELECT missing FROM t"), ErrorKind::Binder);
+        assert_eq!(kind("INSERT INTO t VALUES (1, 2)"), ErrorKind::Constraint);
+        assert_eq!(kind("SELECT 'abc'::INTEGER"), ErrorKind::Conversion);
+        assert_eq!(
+            kind("SELECT 1 // 0::HUGEINT - 170141183460469231731687303715884105727::HUGEINT - 2"),
+            ErrorKind::OutOfRange
+        );
+        assert_eq!(kind("SELECT * FROM read_csv('/nonexistent/file.csv')"), ErrorKind::IO);
+        assert_eq!(kind("SELECT hamming('a', 'ab')"), ErrorKind::InvalidInput);
+        assert_eq!(kind("SET threads = 0"), ErrorKind::InvalidInput);
+
+        // Prepared statements classify their errors the same way.
+        let err = db.prepare("SELECT missing FROM t").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Binder);
+        let err = db.execute("INSERT INTO t VALUES (?, ?)", [1, 1]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Constraint);
+        // The message is left intact.
+        assert!(err.to_string().starts_with("Constraint Error: "), "{err}");
+
+        let err = db.query_row("SELECT 300", [], |r| r.get::<_, u8>(0)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OutOfRange);
+        let err = db.query_row("SELECT 'abc'", [], |r| r.get::<_, i32>(0)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Conversion);
+        assert_eq!(Error::InvalidColumnIndex(3).kind(), ErrorKind::Other);
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraint_violation() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TABLE parent (id INTEGER PRIMARY KEY, code TEXT UNIQUE);
+             CREATE TABLE child (
+                 id INTEGER NOT NULL,
+                 parent_id INTEGER REFERENCES parent (id),
+                 qty INTEGER CHECK (qty > 0)
+             );
+             INSERT INTO parent VALUES (1, 'a');
+             INSERT INTO child VALUES (1, 1, 1);",
+        )?;
+        let violation = |err: Error| match err {
+            Error::ConstraintViolation {
+                kind,
+                table,
+                constraint_or_column,
+                ..
+            } => (kind, table, constraint_or_column),
+            err => panic!("expected a constraint violation, got {err}"),
+        };
+        let owned = |s: &str| Some(s.to_owned());
+
+        let err = db.execute("INSERT INTO child VALUES (NULL, 1, 1)", []).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Constraint);
+        assert!(err.to_string().starts_with("Constraint Error: "), "{err}");
+        assert_eq!(violation(err), (ConstraintKind::NotNull, owned("child"), owned("id")));
+
+        let err = db.execute("INSERT INTO parent VALUES (?, 'b')", [1]).unwrap_err();
+        assert_eq!(violation(err), (ConstraintKind::Unique, None, owned("id")));
+        let err = db.execute_batch("INSERT INTO parent VALUES (2, 'a')").unwrap_err();
+        assert_eq!(violation(err), (ConstraintKind::Unique, None, owned("code")));
+
+        let mut stmt = db.prepare("INSERT INTO child VALUES (2, 1, ?)")?;
+        let err = stmt.execute([0]).unwrap_err();
+        assert_eq!(violation(err), (ConstraintKind::Check, owned("child"), None));
+        stmt.execute([2])?;
+
+        let err = db.execute("INSERT INTO child VALUES (3, ?, 1)", [5]).unwrap_err();
+        assert_eq!(violation(err), (ConstraintKind::ForeignKey, None, owned("parent_id")));
+        let err = db.execute("DELETE FROM parent WHERE id = 1", []).unwrap_err();
+        assert_eq!(violation(err).0, ConstraintKind::ForeignKey);
+
+        // Other constraint errors are left as they are.
+        assert!(constraint_violation("Constraint Error: Data contains duplicates on indexed column(s)").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraint_violation_from_message() {
+        assert_eq!(
+            constraint_violation("CHECK constraint failed: t (Error: Conversion Error: x)"),
+            Some(Error::ConstraintViolation {
+                kind: ConstraintKind::Check,
+                table: Some("t".to_owned()),
+                constraint_or_column: None,
+                message: "CHECK constraint failed: t (Error: Conversion Error: x)".to_owned(),
+            })
+        );
+        let err = constraint_violation(
+            "Constraint Error: Duplicate key \"a: x, y, b: 2\" violates unique constraint. If this is ...",
+        );
+        assert!(
+            matches!(&err, Some(Error::ConstraintViolation { constraint_or_column: Some(c), .. }) if c == "a, b"),
+            "{err:?}"
+        );
+        assert!(matches!(
+            constraint_violation("PRIMARY KEY or UNIQUE constraint violated: duplicate key \"1\""),
+            Some(Error::ConstraintViolation {
+                kind: ConstraintKind::Unique,
+                constraint_or_column: None,
+                ..
+            })
+        ));
+        assert_eq!(
+            constraint_violation("Catalog Error: Table with name t does not exist!"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let failure =
+            |msg: &str| Error::DuckDBFailure(crate::ffi::Error::new(crate::ffi::DuckDBError), Some(msg.into()));
+        for msg in [
+            "TransactionContext Error: Conflict on update!",
+            "TransactionContext Error: Conflict on tuple deletion!",
+            "TransactionContext Error: Catalog write-write conflict on create with \"t\"",
+            "TransactionContext Error: Transaction conflict: adding entries to a table that has been altered!",
+            "TransactionContext Error: Failed to lock clients - another thread is running FORCE CHECKPOINT",
+            "IO Error: Could not set lock on file \"db.duckdb\": Conflicting lock is held",
+        ] {
+            assert!(failure(msg).is_retryable(), "{msg}");
+        }
+        for msg in [
+            "TransactionContext Error: cannot start a transaction within a transaction",
+            "TransactionContext Error: Current transaction is aborted (please ROLLBACK)",
+            "IO Error: No files found that match the pattern \"x.csv\"",
+            "Catalog Error: Table with name t does not exist!",
+        ] {
+            assert!(!failure(msg).is_retryable(), "{msg}");
+        }
+        assert!(Error::CheckpointConflict("busy".into()).is_retryable());
+        assert!(!Error::NestedTransaction.is_retryable());
+    }
+
+    #[test]
+    fn test_as_io_error() {
+        use std::io::ErrorKind as IoKind;
+
+        let failure =
+            |msg: &str| Error::DuckDBFailure(crate::ffi::Error::new(crate::ffi::DuckDBError), Some(msg.into()));
+        for (msg, kind) in [
+            (
+                "IO Error: Cannot open file \"/ro/db.duckdb\": Read-only file system",
+                IoKind::ReadOnlyFilesystem,
+            ),
+            (
+                "IO Error: Could not write file \"db.duckdb.wal\": No space left on device",
+                IoKind::StorageFull,
+            ),
+            (
+                "IO Error: Could not write file \"/tmp/.tmp/duckdb_temp_storage-0.tmp\": Disk quota exceeded",
+                IoKind::QuotaExceeded,
+            ),
+            (
+                "FATAL Error: Failed: database has been invalidated because of a previous fatal error. The database \
+                 must be restarted prior to being used again.\nOriginal error: \"Could not write file \"db.wal\": \
+                 No space left on device\"",
+                IoKind::StorageFull,
+            ),
+            (
+                "IO Error: Could not remove file \"x\": Is a directory",
+                IoKind::IsADirectory,
+            ),
+        ] {
+            let err = failure(msg).as_io_error().unwrap_or_else(|| panic!("{msg}"));
+            assert_eq!(err.kind(), kind, "{msg}");
+            assert_eq!(err.to_string(), msg);
+        }
+        for msg in [
+            "IO Error: No files found that match the pattern \"x.csv\"",
+            "IO Error: Could not set lock on file \"db.duckdb\": Conflicting lock is held",
+            "Binder Error: Referenced column \"Permission denied\" not found: No such file or directory",
+        ] {
+            assert!(failure(msg).as_io_error().is_none(), "{msg}");
+        }
+        let not_found = Error::DatabaseNotFound("missing.db".into()).as_io_error().unwrap();
+        assert_eq!(not_found.kind(), IoKind::NotFound);
+        assert!(Error::NestedTransaction.as_io_error().is_none());
+    }
+
+    #[test]
+    fn test_open_io_error() -> Result<()> {
+        use std::io::ErrorKind as IoKind;
+
+        let dir = tempfile::tempdir().unwrap();
+        let err = Connection::open(dir.path().join("missing").join("db.duckdb")).unwrap_err();
+        assert_eq!(err.as_io_error().map(|e| e.kind()), Some(IoKind::NotFound), "{err}");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_permission_denied() -> Result<()> {
+        use std::{
+            fs,
+            io::ErrorKind as IoKind,
+            os::unix::fs::{MetadataExt, PermissionsExt},
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        // Permissions don't hold back root, who owns the files it creates.
+        if fs::metadata(dir.path()).unwrap().uid() == 0 {
+            return Ok(());
+        }
+        let read_only = dir.path().join("read_only");
+        fs::create_dir(&read_only).unwrap();
+        fs::set_permissions(&read_only, fs::Permissions::from_mode(0o500)).unwrap();
+        let res = Connection::open(read_only.join("db.duckdb"));
+        fs::set_permissions(&read_only, fs::Permissions::from_mode(0o700)).unwrap();
+        let err = res.unwrap_err();
+        let io = err.as_io_error().unwrap_or_else(|| panic!("not an IO error: {err}"));
+        assert_eq!(io.kind(), IoKind::PermissionDenied, "{err}");
+        assert_eq!(io.to_string(), err.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_kind_from_message() {
+        assert_eq!(
+            ErrorKind::from_message("Out of Memory Error: failed to allocate data of size 1.0 MiB"),
+            ErrorKind::OutOfMemory
+        );
+        assert_eq!(
+            ErrorKind::from_message("INTERRUPT Error: Interrupted!"),
+            ErrorKind::Interrupted
+        );
+        assert_eq!(
+            ErrorKind::from_message("TransactionContext Error: Catalog write-write conflict"),
+            ErrorKind::Transaction
+        );
+        assert_eq!(
+            ErrorKind::from_message("Permission Error: File system LocalFileSystem has been disabled"),
+            ErrorKind::Permission
+        );
+        assert_eq!(ErrorKind::from_message("appender is null"), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_sql_error_position() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let sql = "SELECT id,\n       name\nFROM users\nWHERE id IN (1, 2,, 3)";
+        let err = db.prepare(sql).unwrap_err();
+        let typo = sql.find(",,").unwrap() + 1;
+        assert_eq!(err.sql_error_position(), Some(typo));
+        assert_eq!(err.sql_error_token(), Some(","));
+        assert_eq!(err.kind(), ErrorKind::Parser);
+        assert_eq!(err.to_string(), "Parser Error: syntax error at or near \",\"");
+        assert_eq!(
+            format!("{err:#}"),
+            "Parser Error: syntax error at or near \",\"\nLINE 4: WHERE id IN (1, 2,, 3)\n                          ^"
+        );
+
+        let err = db
+            .execute_batch("CREATE TABLE t (x INTEGER);\r\nSELEC x FROM t")
+            .unwrap_err();
+        assert_eq!(err.sql_error_position(), Some(29));
+        assert_eq!(err.sql_error_token(), Some("SELEC"));
+
+        // Other errors have no position.
+        let err = db.prepare("SELECT * FROM missing").unwrap_err();
+        assert_eq!(err.sql_error_position(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_locate_error() {
+        // DuckDB cuts the line to 39 characters either side of the error.
+        let sql = format!(
+            "SELECT {}1 FROM t WHERE x = = 1{}",
+            "a, ".repeat(20),
+            " AND y = 1".repeat(5)
+        );
+        let error = sql.find("= =").unwrap() + 2;
+        let message = format!(
+            "Parser Error: syntax error at or near \"=\"\nLINE 1: ...{}...\n{}^",
+            &sql[error - 39..error + 39],
+            " ".repeat("LINE 1: ...".len() + 39)
+        );
+        assert_eq!(
+            locate_error(&message, &sql),
+            Some(("Parser Error: syntax error at or near \"=\"", error))
+        );
+        assert_eq!(
+            locate_error("Parser Error: syntax error at end of input", "SELECT"),
+            None
+        );
+        assert_eq!(
+            locate_error("Parser Error: x\nLINE 3: SELECT\n        ^", "SELECT"),
+            None
+        );
+    }
+}
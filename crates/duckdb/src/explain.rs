@@ -0,0 +1,392 @@
+//! Query plans as trees of operators, read from DuckDB's JSON profile
+
+#[cfg(feature = "serde_json")]
+use std::{collections::BTreeMap, time::Duration};
+
+#[cfg(feature = "serde_json")]
+use serde_json::Value;
+
+#[cfg(feature = "serde_json")]
+use crate::{ffi, pragma::Sql, profile::parse_json, Error};
+use crate::{Connection, Params, Result};
+
+/// `feature = "serde_json"` A physical operator of a query plan and the
+/// operators whose output it consumes, as returned by
+/// [`Connection::explain_analyze`].
+#[cfg(feature = "serde_json")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    /// The name of the operator, such as `HASH_JOIN` or `SEQ_SCAN`.
+    pub name: String,
+    /// The number of rows the optimizer expected the operator to produce,
+    /// if DuckDB reports it for the operator.
+    pub estimated_cardinality: Option<u64>,
+    /// The number of rows the operator produced.
+    pub actual_cardinality: u64,
+    /// The time the operator spent, not counting its children.
+    pub timing: Duration,
+    /// The labeled details of the operator, such as `Filters`, in the order
+    /// DuckDB lists them.
+    pub extra_info: Vec<(String, String)>,
+    /// The other details of the operator, such as the join type, its
+    /// conditions or the columns it reads, one per line.
+    pub details: Vec<String>,
+    /// The fields of the operator this version doesn't know, as JSON text.
+    pub other: BTreeMap<String, String>,
+    /// The operators whose output this one consumes.
+    pub children: Vec<Plan>,
+}
+
+#[cfg(feature = "serde_json")]
+impl Plan {
+    /// The first operator called `name` in the tree, depth first, starting
+    /// with this one.
+    pub fn find(&self, name: &str) -> Option<&Plan> {
+        self.iter().find(|plan| plan.name == name)
+    }
+
+    /// The operators of the tree, depth first, starting with this one.
+    pub fn iter(&self) -> impl Iterator<Item = &Plan> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let plan = stack.pop()?;
+            stack.extend(plan.children.iter().rev());
+            Some(plan)
+        })
+    }
+
+    /// The value of the labeled detail `key`, if the operator has it.
+    pub fn extra_info(&self, key: &str) -> Option<&str> {
+        self.extra_info
+            .iter()
+            .find_map(|(k, v)| (k == key).then_some(v.as_str()))
+    }
+}
+
+impl Connection {
+    /// The plan DuckDB would run `sql` with, with `params` bound, as the
+    /// text `EXPLAIN` returns: a tree of the physical operators, drawn in
+    /// ASCII art. The query isn't run.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn uses_index(conn: &Connection) -> Result<bool> {
+    ///     let plan = conn.explain("SELECT * FROM orders WHERE id = ?", [42])?;
+    ///     Ok(plan.contains("INDEX_SCAN"))
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `sql` isn't a valid statement or the underlying
+    /// DuckDB call fails.
+    pub fn explain<P: Params>(&self, sql: &str, params: P) -> Result<String> {
+        let mut stmt = self.prepare(&format!("EXPLAIN {sql}"))?;
+        // With `explain_output` set to `all`, there's a row for each plan.
+        let rows = stmt.query_map(params, |row| row.get::<_, String>(1))?;
+        Ok(rows.collect::<Result<Vec<_>>>()?.join("\n"))
+    }
+
+    /// `feature = "serde_json"` Run `sql` with `params` under
+    /// `EXPLAIN ANALYZE` and return its plan,
+    /// with the rows each operator produced and the time it took. The query
+    /// runs, so its changes are made, but its rows are discarded.
+    ///
+    /// DuckDB only writes the plan of a query that isn't run as ASCII art, so
+    /// there is no structured plan without running it; see
+    /// [`explain`](Connection::explain) for that.
+    ///
+    /// The plan is only JSON while profiling is, so profiling is switched to
+    /// JSON for the query and back to how it was after, whether it was
+    /// enabled with [`enable_profiling`](Connection::enable_profiling) or a
+    /// `PRAGMA`. When a `PRAGMA` enabled it, the query reading how it is set
+    /// is profiled too.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn full_scans(conn: &Connection) -> Result<Vec<String>> {
+    ///     let plan = conn.explain_analyze("SELECT * FROM orders JOIN customers USING (id)", [])?;
+    ///     Ok(plan
+    ///         .iter()
+    ///         .filter(|op| op.name == "SEQ_SCAN" && op.extra_info("Filters").is_none())
+    ///         .map(|op| format!("{} rows", op.actual_cardinality))
+    ///         .collect())
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the query fails or the plan DuckDB returns can't
+    /// be read.
+    #[cfg(feature = "serde_json")]
+    pub fn explain_analyze<P: Params>(&self, sql: &str, params: P) -> Result<Plan> {
+        // EXPLAIN ANALYZE writes the plan in the format profiling is enabled
+        // with. Statements such as SET are never profiled, so switching the
+        // format prints no profile of its own.
+        let format: Option<String> = self.query_row("SELECT current_setting('enable_profiling')", [], |r| r.get(0))?;
+        let switch = format.as_deref() != Some("json");
+        if switch {
+            self.execute_batch("SET enable_profiling = 'json'")?;
+        }
+        let plan = self.query_row(&format!("EXPLAIN ANALYZE {sql}"), params, |row| row.get::<_, String>(1));
+        if switch {
+            let mut restore = Sql::new();
+            restore.push_keyword(if format.is_some() { "SET" } else { "RESET" })?;
+            restore.push_space();
+            restore.push_keyword("enable_profiling")?;
+            if let Some(format) = format {
+                restore.push_equal_sign();
+                restore.push_string_literal(&format);
+            }
+            self.execute_batch(&restore)?;
+        }
+        parse_plan(&plan?)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn parse_plan(plan: &str) -> Result<Plan> {
+    let invalid = || {
+        Error::DuckDBFailure(
+            ffi::Error::new(ffi::DuckDBError),
+            Some(format!("invalid query plan: {plan}")),
+        )
+    };
+    let mut json = parse_json(plan).ok_or_else(invalid)?;
+    // The plan is under the query and the EXPLAIN ANALYZE operator.
+    while matches!(
+        json.get("name").and_then(Value::as_str),
+        Some("Query" | "EXPLAIN_ANALYZE")
+    ) {
+        json = only_child(json).ok_or_else(invalid)?;
+    }
+    to_plan(json).ok_or_else(invalid)
+}
+
+#[cfg(feature = "serde_json")]
+fn only_child(mut json: Value) -> Option<Value> {
+    match json.get_mut("children")?.take() {
+        Value::Array(mut children) if children.len() == 1 => children.pop(),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn to_plan(json: Value) -> Option<Plan> {
+    let Value::Object(members) = json else {
+        return None;
+    };
+    let (mut name, mut timing, mut cardinality) = (None, None, None);
+    let mut plan = Plan {
+        name: String::new(),
+        estimated_cardinality: None,
+        actual_cardinality: 0,
+        timing: Duration::ZERO,
+        extra_info: Vec::new(),
+        details: Vec::new(),
+        other: BTreeMap::new(),
+        children: Vec::new(),
+    };
+    for (key, value) in members {
+        match (key.as_str(), value) {
+            // Scans are named with their function's extra info, which is
+            // usually empty.
+            ("name", Value::String(s)) => name = Some(s.trim().to_owned()),
+            ("timing", Value::Number(n)) => timing = Some(Duration::try_from_secs_f64(n.as_f64()?).ok()?),
+            ("cardinality", Value::Number(n)) => cardinality = Some(n.as_f64()? as u64),
+            ("extra_info", Value::String(s)) => parse_extra_info(&s, &mut plan),
+            ("children", Value::Array(children)) => {
+                plan.children = children.into_iter().map(to_plan).collect::<Option<_>>()?;
+            }
+            (_, value) => {
+                plan.other.insert(key, value.to_string());
+            }
+        }
+    }
+    plan.name = name?;
+    plan.timing = timing?;
+    plan.actual_cardinality = cardinality?;
+    Some(plan)
+}
+
+/// Splits the extra info of an operator into its lines, which DuckDB
+/// separates into sections with `[INFOSEPARATOR]` lines.
+#[cfg(feature = "serde_json")]
+fn parse_extra_info(extra_info: &str, plan: &mut Plan) {
+    for line in extra_info.lines().map(str::trim) {
+        if line.is_empty() || line == "[INFOSEPARATOR]" {
+            continue;
+        }
+        match line.split_once(": ") {
+            Some(("EC", n)) if n.parse::<u64>().is_ok() => plan.estimated_cardinality = n.parse().ok(),
+            // Labels are words, unlike the expressions on the other lines.
+            Some((key, value))
+                if key.starts_with(|c: char| c.is_ascii_uppercase())
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ') =>
+            {
+                plan.extra_info.push((key.to_owned(), value.to_owned()));
+            }
+            _ => plan.details.push(line.to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "serde_json")]
+    use std::{collections::BTreeMap, time::Duration};
+
+    #[cfg(feature = "serde_json")]
+    use super::{parse_plan, Plan};
+    use crate::{Connection, Result};
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn test_explain_analyze_join() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TABLE customers AS SELECT range AS id, 'c' || range AS name FROM range(100);
+             CREATE TABLE orders AS SELECT range AS id, range % 10 AS customer FROM range(1000);",
+        )?;
+        let plan = db.explain_analyze(
+            "SELECT name, count(*) FROM orders JOIN customers ON orders.customer = customers.id
+             WHERE customers.id < ? GROUP BY name",
+            [5],
+        )?;
+        let names: Vec<&str> = plan.iter().map(|op| op.name.as_str()).collect();
+        assert!(names.contains(&"HASH_GROUP_BY"), "{names:?}");
+        assert!(names.contains(&"HASH_JOIN"), "{names:?}");
+        assert_eq!(names.iter().filter(|&&name| name == "SEQ_SCAN").count(), 2, "{names:?}");
+
+        let join = plan.find("HASH_JOIN").unwrap();
+        assert_eq!(join.actual_cardinality, 500);
+        assert!(join.estimated_cardinality.is_some(), "{join:?}");
+        assert!(join.details.contains(&"INNER".to_owned()), "{join:?}");
+        assert_eq!(join.children.len(), 2);
+        assert_eq!(plan.find("HASH_GROUP_BY").unwrap().actual_cardinality, 5);
+        // The filter is read off the customers as they are scanned.
+        assert!(
+            plan.iter()
+                .any(|op| op.name == "SEQ_SCAN" && op.extra_info("Filters").is_some() && op.actual_cardinality == 5),
+            "{plan:?}"
+        );
+
+        // Profiling is left as it was.
+        assert!(db.profile_output.borrow().is_none());
+        let format: Option<String> = db.query_row("SELECT current_setting('enable_profiling')", [], |r| r.get(0))?;
+        assert_eq!(format, None);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn test_explain_analyze_keeps_profiling() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let setting = || -> Result<Option<String>> {
+            db.query_row("SELECT current_setting('enable_profiling')", [], |r| r.get(0))
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("profile.txt");
+        db.execute_batch(&format!(
+            "PRAGMA profiling_output = '{}'; PRAGMA enable_profiling = 'query_tree'",
+            output.display()
+        ))?;
+        let plan = db.explain_analyze("SELECT count(*) FROM range(10)", [])?;
+        assert!(plan.find("UNGROUPED_AGGREGATE").is_some(), "{plan:?}");
+        assert_eq!(setting()?.as_deref(), Some("query_tree"));
+        let output_setting: String = db.query_row("SELECT current_setting('profiling_output')", [], |r| r.get(0))?;
+        assert_eq!(output_setting, output.display().to_string());
+
+        db.execute_batch("PRAGMA disable_profiling")?;
+        db.enable_profiling(crate::ProfilingFormat::Json)?;
+        db.explain_analyze("SELECT 1", [])?;
+        assert_eq!(setting()?.as_deref(), Some("json"));
+        assert!(db.profile_output.borrow().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (x INTEGER)")?;
+        let plan = db.explain("SELECT x FROM t WHERE x > ?", [1])?;
+        assert!(plan.contains("SEQ_SCAN"), "{plan}");
+        // Nothing ran.
+        let plan = db.explain("INSERT INTO t VALUES (1)", [])?;
+        assert!(plan.contains("INSERT"), "{plan}");
+        let count: i64 = db.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 0);
+        assert!(db.explain("SELECT * FROM missing", []).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn test_explain_analyze_changes() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (x INTEGER)")?;
+        let plan = db.explain_analyze("INSERT INTO t SELECT * FROM range(10)", [])?;
+        assert_eq!(plan.name, "INSERT");
+        let count: i64 = db.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 10);
+        assert!(db.explain_analyze("SELECT * FROM missing", []).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn test_parse_plan() -> Result<()> {
+        let plan = parse_plan(
+            r#"{
+   "name":  "Query",
+   "result": 0.5,
+   "timing": 0.5,
+   "cardinality": 1,
+   "extra-info": "EXPLAIN ANALYZE SELECT 1",
+   "timings": [],
+   "children": [
+    {
+       "name": "EXPLAIN_ANALYZE",
+       "timing":0.000000,
+       "cardinality":0,
+       "extra_info": "",
+       "children": [
+        {
+           "name": "SEQ_SCAN ",
+           "timing":0.250000,
+           "cardinality":3,
+           "extra_info": "x\ny\n\n[INFOSEPARATOR]\nFilters: x>=1 AND x IS NOT NULL\n\n[INFOSEPARATOR]\nEC: 42",
+           "memory": {"peak": 1024, "spilled": false, "note": null},
+           "children": [
+           ]
+        }
+       ]
+    }
+   ]
+}"#,
+        )?;
+        assert_eq!(
+            plan,
+            Plan {
+                name: "SEQ_SCAN".to_owned(),
+                estimated_cardinality: Some(42),
+                actual_cardinality: 3,
+                timing: Duration::from_millis(250),
+                extra_info: vec![("Filters".to_owned(), "x>=1 AND x IS NOT NULL".to_owned())],
+                details: vec!["x".to_owned(), "y".to_owned()],
+                other: BTreeMap::from([(
+                    "memory".to_owned(),
+                    r#"{"note":null,"peak":1024,"spilled":false}"#.to_owned()
+                )]),
+                children: vec![],
+            }
+        );
+        assert!(parse_plan(r#"{ "result": "disabled" }"#).is_err());
+        assert!(parse_plan("┌───────────────┐").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,538 @@
+//! Exporting a database to a directory of files, and importing it back, and
+//! copying query results to files
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{batch::split_statements, ffi, pragma::Sql, Connection, Error, Params, Result};
+
+/// The format [`Connection::export_database`] writes tables in, and
+/// [`Connection::copy_to`] writes query results in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// CSV, with a header. Nested values are written as text.
+    #[default]
+    Csv,
+    /// Parquet. Needs the `parquet` extension.
+    Parquet,
+}
+
+/// Options for [`Connection::export_database`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// The format to write tables in.
+    pub format: ExportFormat,
+    /// Export into the directory even if it isn't empty, replacing the files
+    /// of a previous export. Other files are left alone.
+    pub overwrite: bool,
+}
+
+/// Options for [`Connection::copy_to`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CopyOptions {
+    /// The format to write the result in.
+    pub format: ExportFormat,
+    /// The file to write, or the directory to write the partitions into if
+    /// `partition_by` is set.
+    pub path: PathBuf,
+    /// The compression to write with, such as `gzip` for CSV or `zstd` for
+    /// Parquet, instead of the format's default.
+    pub compression: Option<String>,
+    /// The columns to partition the result by, each value of them written to
+    /// files of its own under a `column=value` directory.
+    pub partition_by: Vec<String>,
+    /// Replace the file at `path`, or write the partitions into a directory
+    /// that holds files, replacing files of the same name.
+    pub overwrite: bool,
+}
+
+impl Connection {
+    /// Export the schemas, tables, views and sequences of the connection's
+    /// database into the directory `dir`, to be read back with
+    /// [`import_database`](Connection::import_database). The directory is
+    /// created if it doesn't exist, but its parent must.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, ExportFormat, ExportOptions, Result};
+    /// fn back_up(conn: &Connection) -> Result<()> {
+    ///     let options = ExportOptions { format: ExportFormat::Parquet, overwrite: true };
+    ///     conn.export_database("backup", options)
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::ExportDirectoryNotEmpty`] if `dir` holds files
+    /// and `options.overwrite` isn't set, and `Err` if `dir` isn't valid
+    /// UTF-8, or if the underlying DuckDB call fails.
+    pub fn export_database<P: AsRef<Path>>(&self, dir: P, options: ExportOptions) -> Result<()> {
+        let dir = dir.as_ref();
+        let dir_str = dir.to_str().ok_or_else(|| Error::InvalidPath(dir.to_owned()))?;
+        let has_files = fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some());
+        if has_files && !options.overwrite {
+            return Err(Error::ExportDirectoryNotEmpty(dir.to_owned()));
+        }
+
+        let mut sql = Sql::new();
+        sql.push_keyword("EXPORT")?;
+        sql.push_space();
+        sql.push_keyword("DATABASE")?;
+        sql.push_space();
+        sql.push_string_literal(dir_str);
+        sql.push_space();
+        sql.open_brace();
+        sql.push_keyword("FORMAT")?;
+        sql.push_space();
+        sql.push_keyword(match options.format {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Parquet => "PARQUET",
+        })?;
+        sql.close_brace();
+        self.execute_batch(&sql)
+    }
+
+    /// Import a database exported with
+    /// [`export_database`](Connection::export_database) from the directory
+    /// `dir`, creating its objects and loading its tables into the
+    /// connection's database. The directory may have been moved since.
+    ///
+    /// Tables are loaded one at a time. If one fails to load, those loaded
+    /// before it are kept.
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::DatabaseNotFound`] if `dir` doesn't hold an
+    /// exported database, [`Error::ImportTableFailed`] if a table fails to
+    /// load, and `Err` if the schema can't be created, such as when a table
+    /// of the same name already exists.
+    pub fn import_database<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        let dir = dir.as_ref();
+        let read = |file: &str| match fs::read_to_string(dir.join(file)) {
+            Ok(sql) => Ok(sql),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(Error::DatabaseNotFound(dir.to_owned())),
+            Err(err) => Err(Error::DuckDBFailure(
+                ffi::Error::new(ffi::DuckDBError),
+                Some(format!("Failed to read {file}: {err}")),
+            )),
+        };
+        let schema = read("schema.sql")?;
+        let load = read("load.sql")?;
+
+        self.execute_batch(&schema)?;
+        for statement in split_statements(&load) {
+            match parse_copy(statement) {
+                Some((table, file_name, options)) => {
+                    // Like IMPORT DATABASE, read the file from `dir` rather
+                    // than from where it was exported to.
+                    let path = dir.join(file_name);
+                    let path = path.to_str().ok_or_else(|| Error::InvalidPath(path.clone()))?;
+                    let mut file = Sql::new();
+                    file.push_string_literal(path);
+                    self.execute_batch(&format!("COPY {table} FROM {}{options}", &*file))
+                        .map_err(|err| Error::ImportTableFailed(table.to_owned(), Box::new(err)))?;
+                }
+                None => self.execute_batch(statement)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the result of `query`, with `params` bound to its parameters,
+    /// to files as `options` say, returning the number of rows written.
+    ///
+    /// The path and options are quoted into a `COPY (query) TO` statement,
+    /// so they may hold any characters.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, CopyOptions, ExportFormat, Result};
+    /// fn export_year(conn: &Connection, year: i32) -> Result<usize> {
+    ///     let options = CopyOptions {
+    ///         format: ExportFormat::Parquet,
+    ///         path: "sales".into(),
+    ///         partition_by: vec!["region".to_owned()],
+    ///         overwrite: true,
+    ///         ..CopyOptions::default()
+    ///     };
+    ///     conn.copy_to("SELECT * FROM sales WHERE year = ?", [year], options)
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::CopyTargetExists`] if `options.overwrite` isn't
+    /// set and the file at `path` exists, or the partitions would be written
+    /// into a directory that holds files. Will return `Err` if `path` isn't
+    /// valid UTF-8, a partition column isn't in the result, or the
+    /// underlying DuckDB call fails.
+    pub fn copy_to<P: Params>(&self, query: &str, params: P, options: CopyOptions) -> Result<usize> {
+        self.copy_to_with_row_groups(query, params, options, None)
+    }
+
+    /// Like [`copy_to`](Connection::copy_to), writing Parquet row groups of
+    /// `row_group_size` rows if it is set.
+    pub(crate) fn copy_to_with_row_groups<P: Params>(
+        &self,
+        query: &str,
+        params: P,
+        options: CopyOptions,
+        row_group_size: Option<usize>,
+    ) -> Result<usize> {
+        let path = options.path.as_path();
+        let path_str = path.to_str().ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        let partitioned = !options.partition_by.is_empty();
+        if !options.overwrite {
+            let exists = if partitioned {
+                path.is_file() || fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_some())
+            } else {
+                path.exists()
+            };
+            if exists {
+                return Err(Error::CopyTargetExists(path.to_owned()));
+            }
+        }
+
+        let mut target = Sql::new();
+        target.push_string_literal(path_str);
+        target.push_space();
+        target.open_brace();
+        target.push_keyword("FORMAT")?;
+        target.push_space();
+        target.push_keyword(match options.format {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Parquet => "PARQUET",
+        })?;
+        if options.format == ExportFormat::Csv {
+            target.push_comma();
+            target.push_space();
+            target.push_keyword("HEADER")?;
+        }
+        if let Some(compression) = &options.compression {
+            target.push_comma();
+            target.push_space();
+            target.push_keyword("COMPRESSION")?;
+            target.push_space();
+            target.push_string_literal(compression);
+        }
+        if let Some(rows) = row_group_size {
+            target.push_comma();
+            target.push_space();
+            target.push_keyword("ROW_GROUP_SIZE")?;
+            target.push_space();
+            target.push_int(rows as i64);
+        }
+        if partitioned {
+            target.push_comma();
+            target.push_space();
+            target.push_keyword("PARTITION_BY")?;
+            target.push_space();
+            target.open_brace();
+            for (i, column) in options.partition_by.iter().enumerate() {
+                if i > 0 {
+                    target.push_comma();
+                    target.push_space();
+                }
+                target.push_identifier(column);
+            }
+            target.close_brace();
+            // A single file is always replaced.
+            if options.overwrite {
+                target.push_comma();
+                target.push_space();
+                target.push_keyword("OVERWRITE_OR_IGNORE")?;
+            }
+        }
+        target.close_brace();
+
+        let query = query.trim().trim_end_matches(';');
+        self.prepare(&format!("COPY ({query}) TO {}", &*target))?
+            .execute(params)
+    }
+}
+
+/// Splits a statement of `load.sql`, `COPY table FROM 'path' (options)`,
+/// into the table as it is spelled there, the file name of the path, and
+/// what follows the path.
+fn parse_copy(statement: &str) -> Option<(&str, &str, &str)> {
+    let rest = statement.trim_start().strip_prefix("COPY ")?;
+    // The table is a possibly qualified identifier, which ends at the first
+    // ` FROM ` outside of double quotes. Doubled quotes toggle twice.
+    let mut quoted = false;
+    let mut table_end = None;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ' ' if !quoted && rest[i..].starts_with(" FROM '") => {
+                table_end = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let table_end = table_end?;
+    let literal = &rest[table_end + " FROM '".len()..];
+    // The path ends at the first quote that isn't doubled.
+    let mut i = 0;
+    let path_end = loop {
+        let offset = literal[i..].find('\'')?;
+        i += offset + 1;
+        if literal[i..].starts_with('\'') {
+            i += 1;
+        } else {
+            break i - 1;
+        }
+    };
+    let path = &literal[..path_end];
+    let file_name = path.rsplit(['/', '\\']).next()?;
+    // A file name with a quote in it would need unescaping, and DuckDB never
+    // writes one.
+    if file_name.is_empty() || file_name.contains('\'') {
+        return None;
+    }
+    Some((&rest[..table_end], file_name, &literal[path_end + 1..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_copy, CopyOptions, ExportFormat, ExportOptions};
+    use crate::{Connection, Error, Result};
+
+    const SETUP: &str = r#"
+        CREATE SCHEMA "my ""s""";
+        CREATE SEQUENCE seq;
+        CREATE TABLE "my ""s""".people (
+            id INTEGER PRIMARY KEY DEFAULT nextval('seq'),
+            name VARCHAR NOT NULL,
+            born DATE,
+            score DECIMAL(9, 2),
+            ratio DOUBLE
+        );
+        INSERT INTO "my ""s""".people (name, born, score, ratio) VALUES
+            ('Ann', '1990-01-31', 12.5, 0.1),
+            ('O''Brien, "Bo"', NULL, -3.25, NULL),
+            ('multi
+line', '2000-02-29', NULL, 1e100);
+        CREATE TABLE "nested" (id INTEGER, tags VARCHAR[], point STRUCT(x INTEGER, label VARCHAR), attrs MAP(VARCHAR, INTEGER));
+        INSERT INTO "nested" VALUES
+            (1, ['a', 'b, c'], {'x': 1, 'label': 'it''s'}, MAP {'k': 1}),
+            (2, [], NULL, MAP {}),
+            (3, NULL, {'x': NULL, 'label': NULL}, NULL);
+        CREATE VIEW named AS SELECT name FROM "my ""s""".people;
+    "#;
+
+    // The row counts of the tables, and a checksum of their contents.
+    const CHECK: &str = r#"
+        SELECT
+            (SELECT count(*) FROM "my ""s""".people),
+            (SELECT count(*) FROM "nested"),
+            (SELECT count(*) FROM named),
+            (SELECT md5(string_agg(p::VARCHAR, '|' ORDER BY id)) FROM "my ""s""".people p),
+            (SELECT md5(string_agg(n::VARCHAR, '|' ORDER BY id)) FROM "nested" n)
+    "#;
+
+    fn check(conn: &Connection) -> Result<(i64, i64, i64, String, String)> {
+        conn.query_row(CHECK, [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })
+    }
+
+    fn round_trip(format: ExportFormat) -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let export_dir = dir.path().join("it's exported");
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(SETUP)?;
+        db.export_database(
+            &export_dir,
+            ExportOptions {
+                format,
+                overwrite: false,
+            },
+        )?;
+
+        // The export can be moved before it is imported.
+        let moved = dir.path().join("moved");
+        std::fs::rename(&export_dir, &moved).unwrap();
+        let imported = Connection::open_in_memory()?;
+        imported.import_database(&moved)?;
+
+        let expected = check(&db)?;
+        assert_eq!((expected.0, expected.1, expected.2), (3, 3, 3));
+        assert_eq!(check(&imported)?, expected);
+        // The sequence carries on where it was.
+        let id: i32 = imported.query_row(
+            r#"INSERT INTO "my ""s""".people (name) VALUES ('new') RETURNING id"#,
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(id, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_import_csv() -> Result<()> {
+        round_trip(ExportFormat::Csv)
+    }
+
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_export_import_parquet() -> Result<()> {
+        round_trip(ExportFormat::Parquet)
+    }
+
+    #[test]
+    fn test_export_overwrite() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t AS SELECT 1 AS x")?;
+        db.export_database(dir.path(), ExportOptions::default())?;
+
+        db.execute_batch("INSERT INTO t VALUES (2)")?;
+        let err = db.export_database(dir.path(), ExportOptions::default()).unwrap_err();
+        assert_eq!(err, Error::ExportDirectoryNotEmpty(dir.path().to_owned()));
+        let overwrite = ExportOptions {
+            overwrite: true,
+            ..Default::default()
+        };
+        db.export_database(dir.path(), overwrite.clone())?;
+
+        let imported = Connection::open_in_memory()?;
+        imported.import_database(dir.path())?;
+        let count: i64 = imported.query_row("SELECT count(*) FROM t", [], |row| row.get(0))?;
+        assert_eq!(count, 2);
+
+        let file = dir.path().join("t.csv");
+        assert!(db.export_database(&file, overwrite).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_copy_to_parquet() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open_in_memory()?;
+        let path = dir.path().join("it's.parquet");
+        let options = CopyOptions {
+            format: ExportFormat::Parquet,
+            path: path.clone(),
+            compression: Some("zstd".to_owned()),
+            ..CopyOptions::default()
+        };
+        let query = "SELECT range AS x FROM range(?) WHERE range % 2 = 0;";
+        assert_eq!(db.copy_to(query, [10], options.clone())?, 5);
+        let read = format!(
+            "SELECT count(*), sum(x) FROM read_parquet('{}')",
+            path.to_str().unwrap().replace('\'', "''")
+        );
+        let counts: (i64, i64) = db.query_row(&read, [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        assert_eq!(counts, (5, 20));
+
+        let err = db.copy_to(query, [4], options.clone()).unwrap_err();
+        assert_eq!(err, Error::CopyTargetExists(path.clone()));
+        let overwrite = CopyOptions {
+            overwrite: true,
+            ..options
+        };
+        assert_eq!(db.copy_to(query, [4], overwrite)?, 2);
+        let counts: (i64, i64) = db.query_row(&read, [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        assert_eq!(counts, (2, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_to_partitioned_csv() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            r#"CREATE TABLE sales AS
+               SELECT i, CASE WHEN i % 2 = 0 THEN 'north' ELSE 'south' END AS "the region" FROM range(10) t(i)"#,
+        )?;
+        let path = dir.path().join("sales");
+        let options = CopyOptions {
+            path: path.clone(),
+            partition_by: vec!["the region".to_owned()],
+            ..CopyOptions::default()
+        };
+        let query = "SELECT * FROM sales WHERE i < ?";
+        assert_eq!(db.copy_to(query, [6], options.clone())?, 6);
+        let mut partitions: Vec<_> = std::fs::read_dir(&path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        partitions.sort();
+        assert_eq!(partitions, ["the region=north", "the region=south"]);
+        let read = format!(
+            "SELECT count(*), sum(i) FROM read_csv('{}/*/*.csv', hive_partitioning = true) WHERE \"the region\" = 'north'",
+            path.to_str().unwrap()
+        );
+        let counts: (i64, i64) = db.query_row(&read, [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        assert_eq!(counts, (3, 6));
+
+        let err = db.copy_to(query, [6], options.clone()).unwrap_err();
+        assert_eq!(err, Error::CopyTargetExists(path.clone()));
+        let overwrite = CopyOptions {
+            overwrite: true,
+            ..options.clone()
+        };
+        assert_eq!(db.copy_to(query, [6], overwrite)?, 6);
+
+        let missing = CopyOptions {
+            path: dir.path().join("other"),
+            partition_by: vec!["region".to_owned()],
+            ..options
+        };
+        assert!(db.copy_to(query, [6], missing).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_errors() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open_in_memory()?;
+        let missing = dir.path().join("missing");
+        assert_eq!(
+            db.import_database(&missing).unwrap_err(),
+            Error::DatabaseNotFound(missing)
+        );
+
+        db.execute_batch(
+            r#"CREATE TABLE good AS SELECT 1 AS x;
+            CREATE TABLE "bad ""one""" AS SELECT 1 AS x;"#,
+        )?;
+        db.export_database(dir.path(), ExportOptions::default())?;
+        std::fs::write(dir.path().join("bad__one_.csv"), "x\nnot a number\n").unwrap();
+
+        let imported = Connection::open_in_memory()?;
+        match imported.import_database(dir.path()).unwrap_err() {
+            Error::ImportTableFailed(table, err) => {
+                assert_eq!(table, r#""bad ""one""""#);
+                assert!(matches!(*err, Error::DuckDBFailure(..)), "{err:?}");
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+        let count: i64 = imported.query_row("SELECT count(*) FROM good", [], |row| row.get(0))?;
+        assert_eq!(count, 1);
+
+        // Importing again fails to create the tables.
+        assert!(imported.import_database(dir.path()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_copy() {
+        assert_eq!(
+            parse_copy("\nCOPY s.\"a \"\" FROM 'x\" FROM '/tmp/it''s/s_a.csv' (FORMAT 'csv', header 1)"),
+            Some(("s.\"a \"\" FROM 'x\"", "s_a.csv", " (FORMAT 'csv', header 1)"))
+        );
+        assert_eq!(
+            parse_copy("COPY t FROM 'C:\\dir\\t.parquet' (FORMAT 'parquet')"),
+            Some(("t", "t.parquet", " (FORMAT 'parquet')"))
+        );
+        assert_eq!(parse_copy("COPY t FROM 'unterminated"), None);
+        assert_eq!(parse_copy("CREATE TABLE t (x INTEGER)"), None);
+    }
+}
@@ -1,8 +1,154 @@
+//! Installing, loading and listing DuckDB extensions
+
+use std::path::PathBuf;
+
+use crate::{pragma::Sql, Connection, Error, Result};
+
+/// An extension known to DuckDB, as listed by
+/// [`Connection::extensions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtensionInfo {
+    /// The name to install and load the extension by.
+    pub name: String,
+    /// Whether the extension is loaded into the database.
+    pub loaded: bool,
+    /// Whether the extension is installed, or built into DuckDB.
+    pub installed: bool,
+    /// Whether the extension is built into DuckDB, so it needs no install.
+    pub built_in: bool,
+    /// Where the extension was installed to, `None` if it isn't installed or
+    /// is built in.
+    pub install_path: Option<PathBuf>,
+    /// What the extension adds.
+    pub description: String,
+    /// Other names the extension is known by.
+    pub aliases: Vec<String>,
+    /// The version of the extension, `None` if unknown.
+    pub version: Option<String>,
+}
+
+/// DuckDB's `install_path` for extensions linked into the library.
+const BUILT_IN: &str = "(BUILT-IN)";
+
+impl Connection {
+    /// Install the extension `name`, downloading it from `repository`, or
+    /// from the default repository if that is `None`, into the extension
+    /// directory. Built-in extensions need no install, so this does nothing
+    /// for them.
+    ///
+    /// `name` may also be the path of an extension file to copy.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn read_remote(conn: &Connection) -> Result<()> {
+    ///     conn.install_extension("httpfs", None)?;
+    ///     conn.load_extension("httpfs")?;
+    ///     conn.execute_batch("CREATE TABLE t AS SELECT * FROM 'https://example.com/data.parquet'")
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::ExtensionNotFound`] if the repository doesn't
+    /// have the extension, [`Error::ExtensionNetworkFailure`] if the
+    /// repository can't be reached, and `Err` if the underlying DuckDB call
+    /// fails otherwise.
+    pub fn install_extension(&self, name: &str, repository: Option<&str>) -> Result<()> {
+        if self.extensions()?.iter().any(|ext| ext.built_in && ext.name == name) {
+            return Ok(());
+        }
+        let mut sql = Sql::new();
+        sql.push_keyword("INSTALL")?;
+        sql.push_space();
+        sql.push_identifier(name);
+        if let Some(repository) = repository {
+            sql.push_space();
+            sql.push_keyword("FROM")?;
+            sql.push_space();
+            sql.push_string_literal(repository);
+        }
+        self.execute_batch(&sql).map_err(extension_error)
+    }
+
+    /// Load the extension `name`, which must be built in or installed, or the
+    /// extension file at that path.
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::ExtensionNotFound`] if the extension isn't
+    /// installed, [`Error::ExtensionSignatureRejected`] if its signature is
+    /// missing or invalid and unsigned extensions aren't allowed, and `Err`
+    /// if the underlying DuckDB call fails otherwise.
+    pub fn load_extension(&self, name: &str) -> Result<()> {
+        let mut sql = Sql::new();
+        sql.push_keyword("LOAD")?;
+        sql.push_space();
+        sql.push_identifier(name);
+        self.execute_batch(&sql).map_err(extension_error)
+    }
+
+    /// List the extensions loaded into the database.
+    pub fn loaded_extensions(&self) -> Result<Vec<ExtensionInfo>> {
+        let mut extensions = self.extensions()?;
+        extensions.retain(|ext| ext.loaded);
+        Ok(extensions)
+    }
+
+    /// List all extensions DuckDB knows of, whether or not they are
+    /// installed or loaded.
+    pub fn extensions(&self) -> Result<Vec<ExtensionInfo>> {
+        let mut stmt = self.prepare(
+            "SELECT extension_name, loaded, installed, install_path, description, aliases, extension_version
+            FROM duckdb_extensions() ORDER BY extension_name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let install_path: Option<String> = row.get(3)?;
+            let built_in = install_path.as_deref() == Some(BUILT_IN);
+            let version: Option<String> = row.get(6)?;
+            Ok(ExtensionInfo {
+                name: row.get(0)?,
+                loaded: row.get(1)?,
+                installed: row.get(2)?,
+                built_in,
+                install_path: install_path.filter(|p| !built_in && !p.is_empty()).map(PathBuf::from),
+                description: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                aliases: row.get::<_, Option<Vec<String>>>(5)?.unwrap_or_default(),
+                version: version.filter(|v| !v.is_empty()),
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+/// Sorts DuckDB's errors for `INSTALL` and `LOAD` into the typed extension
+/// errors, leaving other errors as they are.
+fn extension_error(err: Error) -> Error {
+    let Error::DuckDBFailure(_, Some(msg)) = err.without_context() else {
+        return err;
+    };
+    if msg.contains("could not be loaded because its signature is either missing or invalid") {
+        Error::ExtensionSignatureRejected(msg.clone())
+    } else if msg.starts_with("HTTP Error: Failed to download extension")
+        || msg.contains("Failed to copy local extension")
+        || (msg.starts_with("IO Error: Extension") && msg.contains("not found"))
+    {
+        // The repository answered, but has no such extension.
+        Error::ExtensionNotFound(msg.clone())
+    } else if msg.contains("Failed to download extension") {
+        Error::ExtensionNetworkFailure(msg.clone())
+    } else {
+        err
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{Connection, Result};
+    use crate::{Config, Connection, Error, Result};
 
     // https://duckdb.org/docs/extensions/json
+    #[cfg(feature = "extensions-full")]
     #[test]
     fn test_extension_json() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -18,6 +164,7 @@ mod test {
     }
 
     // https://duckdb.org/docs/data/parquet/overview.html
+    #[cfg(feature = "extensions-full")]
     #[test]
     fn test_extension_parquet() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -33,6 +180,7 @@ mod test {
     }
 
     // https://duckdb.org/docs/extensions/httpfs
+    #[cfg(feature = "extensions-full")]
     #[test]
     fn test_extension_httpfs() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -42,4 +190,90 @@ mod test {
         );
         Ok(())
     }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_built_in() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.install_extension("json", None)?;
+        db.load_extension("json")?;
+        let json = db
+            .loaded_extensions()?
+            .into_iter()
+            .find(|ext| ext.name == "json")
+            .unwrap();
+        assert!(json.loaded && json.installed && json.built_in, "{json:?}");
+        assert_eq!(json.install_path, None);
+        assert!(!json.description.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extension_list() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let extensions = db.extensions()?;
+        let httpfs = extensions.iter().find(|ext| ext.name == "httpfs").unwrap();
+        assert!(httpfs.aliases.iter().any(|alias| alias == "s3"), "{httpfs:?}");
+        let loaded = db.loaded_extensions()?;
+        assert!(loaded.iter().all(|ext| ext.loaded));
+        assert_eq!(loaded.len(), extensions.iter().filter(|ext| ext.loaded).count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extension_errors() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let ext_dir = dir.path().join("extensions");
+        let config = Config::default().extension_directory(&ext_dir)?;
+        let db = Connection::open_in_memory_with_flags(config)?;
+        let setting: String = db.query_row("SELECT current_setting('extension_directory')", [], |r| r.get(0))?;
+        assert_eq!(setting, ext_dir.to_str().unwrap());
+
+        match db.load_extension("no_such_extension") {
+            Err(Error::ExtensionNotFound(msg)) => assert!(msg.contains(ext_dir.to_str().unwrap()), "{msg}"),
+            res => panic!("unexpected {res:?}"),
+        }
+        let missing_repo = dir.path().join("no_repo");
+        assert!(matches!(
+            db.install_extension("no_such_extension", missing_repo.to_str()),
+            Err(Error::ExtensionNotFound(_))
+        ));
+        // Nothing listens on the discard port.
+        assert!(matches!(
+            db.install_extension("inet", Some("http://127.0.0.1:9")),
+            Err(Error::ExtensionNetworkFailure(_))
+        ));
+
+        let fake = dir.path().join("fake.duckdb_extension");
+        std::fs::write(&fake, [0u8; 1024]).unwrap();
+        assert!(matches!(
+            db.load_extension(fake.to_str().unwrap()),
+            Err(Error::ExtensionSignatureRejected(_))
+        ));
+        db.install_extension(fake.to_str().unwrap(), None)?;
+        assert!(std::fs::read_dir(&ext_dir).unwrap().next().is_some());
+        Ok(())
+    }
+
+    // Needs access to the default extension repository.
+    #[test]
+    #[ignore]
+    fn test_install_from_repository() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::default().extension_directory(dir.path())?;
+        let db = Connection::open_in_memory_with_flags(config)?;
+        db.install_extension("inet", None)?;
+        db.load_extension("inet")?;
+        let inet = db
+            .loaded_extensions()?
+            .into_iter()
+            .find(|ext| ext.name == "inet")
+            .unwrap();
+        assert!(inet.install_path.unwrap().starts_with(dir.path()));
+        assert!(matches!(
+            db.install_extension("no_such_extension", None),
+            Err(Error::ExtensionNotFound(_))
+        ));
+        Ok(())
+    }
 }
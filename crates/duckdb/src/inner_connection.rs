@@ -1,26 +1,95 @@
 use std::{
+    collections::{BTreeMap, HashSet},
     ffi::{c_void, CStr, CString},
     mem,
     os::raw::c_char,
     ptr, str,
+    sync::{Arc, Condvar, Mutex, PoisonError, Weak},
+    thread,
+    time::Duration,
 };
 
 use super::{ffi, Appender, Config, Connection, Result};
 use crate::{
+    batch::multiple_statements,
     error::{result_from_duckdb_appender, result_from_duckdb_arrow, result_from_duckdb_prepare, Error},
-    raw_statement::RawStatement,
+    raw_statement::{ArrowOptions, RawStatement},
     statement::Statement,
+    types::LogicalType,
 };
 
+/// A `duckdb_database` shared by a connection and all its clones, closed
+/// along with the last of them if it was opened by this crate.
+struct Database {
+    raw: ffi::duckdb_database,
+    owned: bool,
+    /// The name of an in-memory database opened as `:memory:name`.
+    name: Option<Vec<u8>>,
+    /// Run on every connection made to the database after it was set.
+    init_hook: Mutex<Option<Arc<InitHook>>>,
+}
+
+/// A hook set with [`Connection::set_init_hook`].
+pub(crate) type InitHook = dyn Fn(&Connection) -> Result<()> + Send + Sync;
+
+// A `duckdb_database` can be connected to from any thread.
+unsafe impl Send for Database {}
+unsafe impl Sync for Database {}
+
+impl Drop for Database {
+    fn drop(&mut self) {
+        if let Some(name) = &self.name {
+            let mut named = NAMED_DATABASES.lock().unwrap();
+            // The name may already belong to a database opened since.
+            if named.get(name).is_some_and(|database| database.strong_count() == 0) {
+                named.remove(name);
+            }
+        }
+        if self.owned {
+            unsafe { ffi::duckdb_close(&mut self.raw) };
+        }
+    }
+}
+
+/// The in-memory databases opened as `:memory:name`, so that connections
+/// opened with the same name share one.
+static NAMED_DATABASES: Mutex<BTreeMap<Vec<u8>, Weak<Database>>> = Mutex::new(BTreeMap::new());
+
+fn open_database(c_path: &CStr, config: Config) -> Result<ffi::duckdb_database> {
+    let config = config.duckdb_config()?;
+    unsafe {
+        let mut db: ffi::duckdb_database = ptr::null_mut();
+        let mut c_err = std::ptr::null_mut();
+        let r = ffi::duckdb_open_ext(c_path.as_ptr(), &mut db, config.as_ptr(), &mut c_err);
+        if r != ffi::DuckDBSuccess {
+            let msg = Some(CStr::from_ptr(c_err).to_string_lossy().to_string());
+            ffi::duckdb_free(c_err as *mut c_void);
+            return Err(Error::DuckDBFailure(ffi::Error::new(r), msg));
+        }
+        Ok(db)
+    }
+}
+
 pub struct InnerConnection {
     pub db: ffi::duckdb_database,
     pub con: ffi::duckdb_connection,
-    owned: bool,
+    interrupt_lock: Arc<Mutex<ffi::duckdb_connection>>,
+    database: Option<Arc<Database>>,
 }
 
 impl InnerConnection {
     #[inline]
     pub unsafe fn new(db: ffi::duckdb_database, owned: bool) -> Result<InnerConnection> {
+        InnerConnection::connect(Arc::new(Database {
+            raw: db,
+            owned,
+            name: None,
+            init_hook: Mutex::new(None),
+        }))
+    }
+
+    unsafe fn connect(database: Arc<Database>) -> Result<InnerConnection> {
+        let db = database.raw;
         let mut con: ffi::duckdb_connection = ptr::null_mut();
         let r = ffi::duckdb_connect(db, &mut con);
         if r != ffi::DuckDBSuccess {
@@ -30,23 +99,45 @@ impl InnerConnection {
                 Some("connect error".to_owned()),
             ));
         }
-        Ok(InnerConnection { db, con, owned })
+        #[allow(clippy::arc_with_non_send_sync)] // See unsafe impl Send / Sync for InterruptHandle
+        let interrupt_lock = Arc::new(Mutex::new(con));
+        Ok(InnerConnection {
+            db,
+            con,
+            interrupt_lock,
+            database: Some(database),
+        })
     }
 
     pub fn open_with_flags(c_path: &CStr, config: Config) -> Result<InnerConnection> {
-        unsafe {
-            let mut db: ffi::duckdb_database = ptr::null_mut();
-            let mut c_err = std::ptr::null_mut();
-            let r = ffi::duckdb_open_ext(c_path.as_ptr(), &mut db, config.duckdb_config(), &mut c_err);
-            if r != ffi::DuckDBSuccess {
-                let msg = Some(CStr::from_ptr(c_err).to_string_lossy().to_string());
-                ffi::duckdb_free(c_err as *mut c_void);
-                return Err(Error::DuckDBFailure(ffi::Error::new(r), msg));
-            }
-            InnerConnection::new(db, true)
+        match c_path.to_bytes().strip_prefix(b":memory:") {
+            Some(name) if !name.is_empty() => InnerConnection::open_named(name, config),
+            _ => unsafe { InnerConnection::new(open_database(c_path, config)?, true) },
         }
     }
 
+    /// Connects to the in-memory database called `name`, opening it with
+    /// `config` if no connection to it is open.
+    fn open_named(name: &[u8], config: Config) -> Result<InnerConnection> {
+        let mut named = NAMED_DATABASES.lock().unwrap();
+        let database = match named.get(name).and_then(Weak::upgrade) {
+            Some(database) => database,
+            None => {
+                let database = Arc::new(Database {
+                    raw: open_database(c":memory:", config)?,
+                    owned: true,
+                    name: Some(name.to_owned()),
+                    init_hook: Mutex::new(None),
+                });
+                named.insert(name.to_owned(), Arc::downgrade(&database));
+                database
+            }
+        };
+        // Dropping the database takes the lock, so release it first.
+        drop(named);
+        unsafe { InnerConnection::connect(database) }
+    }
+
     pub fn close(&mut self) -> Result<()> {
         if self.db.is_null() {
             return Ok(());
@@ -55,20 +146,60 @@ impl InnerConnection {
             return Ok(());
         }
         unsafe {
+            // Nothing panics while holding the lock, and closing must not
+            // panic when dropping.
+            let mut shared_handle = self.interrupt_lock.lock().unwrap_or_else(PoisonError::into_inner);
+            debug_assert!(
+                !shared_handle.is_null(),
+                "Bug: Somehow interrupt_lock was cleared before the DB was closed"
+            );
+            *shared_handle = ptr::null_mut();
+            drop(shared_handle);
+
             ffi::duckdb_disconnect(&mut self.con);
             self.con = ptr::null_mut();
-
-            if self.owned {
-                ffi::duckdb_close(&mut self.db);
-                self.db = ptr::null_mut();
-            }
         }
+        // Closes the database if no clone of this connection is left.
+        self.database = None;
+        self.db = ptr::null_mut();
         Ok(())
     }
 
     /// Creates a new connection to the already-opened database.
     pub fn try_clone(&self) -> Result<Self> {
-        unsafe { InnerConnection::new(self.db, false) }
+        match &self.database {
+            Some(database) => unsafe { InnerConnection::connect(Arc::clone(database)) },
+            None => Err(Error::DuckDBFailure(
+                ffi::Error::new(ffi::DuckDBError),
+                Some("connection is closed".to_owned()),
+            )),
+        }
+    }
+
+    /// Whether the database was opened by this crate, and closes along with
+    /// this connection.
+    pub fn is_last_connection(&self) -> bool {
+        self.database
+            .as_ref()
+            .is_some_and(|database| database.owned && Arc::strong_count(database) == 1)
+    }
+
+    /// The hook new connections to the database run, if one was set.
+    pub fn init_hook(&self) -> Option<Arc<InitHook>> {
+        self.database.as_ref()?.init_hook.lock().unwrap().clone()
+    }
+
+    pub fn set_init_hook(&self, hook: Arc<InitHook>) -> Result<()> {
+        match &self.database {
+            Some(database) => {
+                *database.init_hook.lock().unwrap() = Some(hook);
+                Ok(())
+            }
+            None => Err(Error::DuckDBFailure(
+                ffi::Error::new(ffi::DuckDBError),
+                Some("connection is closed".to_owned()),
+            )),
+        }
     }
 
     pub fn execute(&mut self, sql: &str) -> Result<()> {
@@ -76,21 +207,167 @@ impl InnerConnection {
         unsafe {
             let mut out = mem::zeroed();
             let r = ffi::duckdb_query_arrow(self.con, c_str.as_ptr() as *const c_char, &mut out);
-            result_from_duckdb_arrow(r, out)?;
+            result_from_duckdb_arrow(r, out).map_err(|err| err.with_sql(sql))?;
             ffi::duckdb_destroy_arrow(&mut out);
             Ok(())
         }
     }
 
+    /// How DuckDB converts results to Arrow on this connection, from its
+    /// settings.
+    pub fn arrow_options(&mut self) -> Result<ArrowOptions> {
+        let sql = "SELECT name, value FROM duckdb_settings() WHERE name IN ('arrow_large_buffer_size', 'TimeZone')";
+        let c_str = CString::new(sql).unwrap();
+        let mut options = ArrowOptions {
+            large_buffers: false,
+            time_zone: "UTC".to_owned(),
+        };
+        unsafe {
+            let mut result: ffi::duckdb_result = mem::zeroed();
+            let r = ffi::duckdb_query(self.con, c_str.as_ptr() as *const c_char, &mut result);
+            if r != ffi::DuckDBSuccess {
+                let c_err = ffi::duckdb_result_error(&mut result);
+                let message = (!c_err.is_null()).then(|| CStr::from_ptr(c_err).to_string_lossy().into_owned());
+                ffi::duckdb_destroy_result(&mut result);
+                return Err(Error::DuckDBFailure(ffi::Error::new(r), message));
+            }
+            for row in 0..ffi::duckdb_row_count(&mut result) {
+                let [name, value] = [0, 1].map(|col| {
+                    let c_str = ffi::duckdb_value_varchar(&mut result, col, row);
+                    let owned = CStr::from_ptr(c_str).to_string_lossy().into_owned();
+                    ffi::duckdb_free(c_str.cast());
+                    owned
+                });
+                match name.as_str() {
+                    "arrow_large_buffer_size" => options.large_buffers = value == "true",
+                    _ => options.time_zone = value,
+                }
+            }
+            ffi::duckdb_destroy_result(&mut result);
+        }
+        Ok(options)
+    }
+
+    /// The name and type of each column the query `sql` returns, found by
+    /// running it with no rows and every parameter `NULL`.
+    pub fn describe(&mut self, sql: &str) -> Result<Vec<(String, Option<LogicalType>)>> {
+        let mut query = sql.trim_end();
+        while let Some(rest) = query.strip_suffix(';') {
+            query = rest.trim_end();
+        }
+        // The newline ends a trailing line comment.
+        let wrapped = format!("SELECT * FROM (\n{query}\n) LIMIT 0");
+        let mut columns = self.query_with_null_parameters(&wrapped, |result| unsafe {
+            (0..ffi::duckdb_column_count(result))
+                .map(|col| {
+                    let name = CStr::from_ptr(ffi::duckdb_column_name(result, col));
+                    let mut ty = ffi::duckdb_column_logical_type(result, col);
+                    let logical_type = LogicalType::from_duckdb_logical_type(ty);
+                    ffi::duckdb_destroy_logical_type(&mut ty);
+                    (name.to_string_lossy().into_owned(), logical_type)
+                })
+                .collect::<Vec<_>>()
+        })?;
+        // The subquery numbers repeated names, `a` and `a_1`. `DESCRIBE`
+        // keeps them as the query returns them, but only has the types as
+        // text, so it's only asked for the names that may have been renamed.
+        if may_be_renamed(columns.iter().map(|(name, _)| name.as_str())) {
+            let names = self.query_with_null_parameters(&format!("DESCRIBE\n{query}\n"), |result| unsafe {
+                (0..ffi::duckdb_row_count(result))
+                    .map(|row| {
+                        let c_str = ffi::duckdb_value_varchar(result, 0, row);
+                        let name = CStr::from_ptr(c_str).to_string_lossy().into_owned();
+                        ffi::duckdb_free(c_str.cast());
+                        name
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            if names.len() == columns.len() {
+                for ((name, _), original) in columns.iter_mut().zip(names) {
+                    *name = original;
+                }
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Runs `sql` with every parameter `NULL`, and reads its result with
+    /// `read`.
+    fn query_with_null_parameters<T>(
+        &mut self,
+        sql: &str,
+        read: impl FnOnce(&mut ffi::duckdb_result) -> T,
+    ) -> Result<T> {
+        let c_str = CString::new(sql)?;
+        unsafe {
+            let mut c_stmt: ffi::duckdb_prepared_statement = ptr::null_mut();
+            let r = ffi::duckdb_prepare(self.con, c_str.as_ptr() as *const c_char, &mut c_stmt);
+            result_from_duckdb_prepare(r, c_stmt)?;
+            let stmt = RawStatement::new(c_stmt);
+            for index in 1..=stmt.bind_parameter_count() {
+                ffi::duckdb_bind_null(c_stmt, index as u64);
+            }
+            let mut result: ffi::duckdb_result = mem::zeroed();
+            let r = ffi::duckdb_execute_prepared(c_stmt, &mut result);
+            let value = (r == ffi::DuckDBSuccess).then(|| read(&mut result));
+            let c_err = ffi::duckdb_result_error(&mut result);
+            let message = (!c_err.is_null()).then(|| CStr::from_ptr(c_err).to_string_lossy().into_owned());
+            ffi::duckdb_destroy_result(&mut result);
+            value.ok_or_else(|| Error::DuckDBFailure(ffi::Error::new(r), message))
+        }
+    }
+
     pub fn prepare<'a>(&mut self, conn: &'a Connection, sql: &str) -> Result<Statement<'a>> {
         let mut c_stmt: ffi::duckdb_prepared_statement = ptr::null_mut();
         let c_str = CString::new(sql).unwrap();
         let r = unsafe { ffi::duckdb_prepare(self.con, c_str.as_ptr() as *const c_char, &mut c_stmt) };
+        result_from_duckdb_prepare(r, c_stmt)
+            .map_err(|err| conn.error_context(multiple_statements(err, sql).with_sql(sql), sql, None))?;
+        let mut stmt = unsafe { RawStatement::new(c_stmt) };
+        stmt.set_sql(sql);
+        Ok(Statement::new(conn, stmt))
+    }
+
+    /// Splits `sql` into its statements without preparing any of them.
+    pub fn extract_statements(&mut self, sql: &str) -> Result<ExtractedStatements> {
+        let c_str = CString::new(sql)?;
+        let mut extracted = ExtractedStatements {
+            ptr: ptr::null_mut(),
+            count: 0,
+        };
+        extracted.count =
+            unsafe { ffi::duckdb_extract_statements(self.con, c_str.as_ptr() as *const c_char, &mut extracted.ptr) }
+                as usize;
+        if extracted.count == 0 {
+            let c_err = unsafe { ffi::duckdb_extract_statements_error(extracted.ptr) };
+            if !c_err.is_null() {
+                let message = unsafe { CStr::from_ptr(c_err) }.to_string_lossy().into_owned();
+                return Err(Error::DuckDBFailure(ffi::Error::new(ffi::DuckDBError), Some(message)).with_sql(sql));
+            }
+        }
+        Ok(extracted)
+    }
+
+    /// Prepares the statement `idx` of `extracted`.
+    pub fn prepare_extracted<'a>(
+        &mut self,
+        conn: &'a Connection,
+        extracted: &ExtractedStatements,
+        idx: usize,
+    ) -> Result<Statement<'a>> {
+        let mut c_stmt: ffi::duckdb_prepared_statement = ptr::null_mut();
+        let r =
+            unsafe { ffi::duckdb_prepare_extracted_statement(self.con, extracted.ptr, idx as ffi::idx_t, &mut c_stmt) };
         result_from_duckdb_prepare(r, c_stmt)?;
         Ok(Statement::new(conn, unsafe { RawStatement::new(c_stmt) }))
     }
 
     pub fn appender<'a>(&mut self, conn: &'a Connection, table: &str, schema: &str) -> Result<Appender<'a>> {
+        Ok(Appender::new(conn, self.raw_appender(table, schema)?))
+    }
+
+    /// Creates an appender to `schema.table`, which the caller destroys.
+    pub fn raw_appender(&mut self, table: &str, schema: &str) -> Result<ffi::duckdb_appender> {
         let mut c_app: ffi::duckdb_appender = ptr::null_mut();
         let c_table = CString::new(table).unwrap();
         let c_schema = CString::new(schema).unwrap();
@@ -103,26 +380,174 @@ impl InnerConnection {
             )
         };
         result_from_duckdb_appender(r, &mut c_app)?;
-        Ok(Appender::new(conn, c_app))
+        Ok(c_app)
     }
 
     #[inline]
-    pub fn is_autocommit(&self) -> bool {
-        true
+    pub fn get_interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle {
+            conn: Arc::clone(&self.interrupt_lock),
+        }
+    }
+}
+
+/// The statements of a query, split by DuckDB's parser but not yet prepared.
+pub struct ExtractedStatements {
+    ptr: ffi::duckdb_extracted_statements,
+    count: usize,
+}
+
+impl ExtractedStatements {
+    /// The number of statements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+}
+
+impl Drop for ExtractedStatements {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe { ffi::duckdb_destroy_extracted(&mut self.ptr) };
+        }
+    }
+}
+
+/// Allows interrupting a long-running computation, or following its progress,
+/// from another thread.
+#[derive(Clone)]
+pub struct InterruptHandle {
+    conn: Arc<Mutex<ffi::duckdb_connection>>,
+}
+
+unsafe impl Send for InterruptHandle {}
+unsafe impl Sync for InterruptHandle {}
+
+impl InterruptHandle {
+    /// Interrupt the query currently executing on another thread. The query
+    /// returns an error. This does nothing if no query is running or the
+    /// connection has been closed.
+    pub fn interrupt(&self) {
+        let conn = self.conn.lock().unwrap();
+        if !conn.is_null() {
+            unsafe { ffi::duckdb_interrupt(*conn) };
+        }
+    }
+
+    /// The progress of the query currently executing on another thread.
+    ///
+    /// DuckDB only tracks progress once it is enabled with
+    /// `SET enable_progress_bar = true`; also run
+    /// `SET enable_progress_bar_print = false` to keep it from drawing its own
+    /// progress bar on stdout. Returns `None` if no query is running, progress
+    /// isn't tracked or the connection has been closed.
+    pub fn query_progress(&self) -> Option<QueryProgress> {
+        let conn = self.conn.lock().unwrap();
+        if conn.is_null() {
+            return None;
+        }
+        QueryProgress::from_raw(unsafe { ffi::duckdb_query_progress(*conn) })
+    }
+
+    /// Runs `f`, interrupting its query from a watchdog thread if `f` hasn't
+    /// returned after `timeout`. The error of an interrupted query is replaced
+    /// by [`Error::Timeout`].
+    pub(crate) fn run_with_timeout<T>(&self, timeout: Duration, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        // Set once `f` has returned. The watchdog only interrupts while it
+        // holds the lock and sees it unset, and it is joined before returning,
+        // so an interrupt can't reach a later query.
+        let done = Arc::new((Mutex::new(false), Condvar::new()));
+        let watchdog = {
+            let done = Arc::clone(&done);
+            let handle = InterruptHandle {
+                conn: Arc::clone(&self.conn),
+            };
+            thread::spawn(move || {
+                let (lock, cvar) = &*done;
+                let (done, _) = cvar
+                    .wait_timeout_while(lock.lock().unwrap(), timeout, |done| !*done)
+                    .unwrap();
+                if !*done {
+                    handle.interrupt();
+                }
+                !*done
+            })
+        };
+        let result = f();
+        *done.0.lock().unwrap() = true;
+        done.1.notify_one();
+        let interrupted = watchdog.join().unwrap();
+        match result {
+            // A query can still fail on its own after the deadline.
+            Err(err) if interrupted => match err.without_context() {
+                Error::DuckDBFailure(_, Some(msg)) if msg.starts_with("INTERRUPT Error") => {
+                    Err(Error::Timeout(timeout))
+                }
+                _ => Err(err),
+            },
+            result => result,
+        }
+    }
+}
+
+/// The progress of a running query, as returned by
+/// [`InterruptHandle::query_progress`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueryProgress {
+    /// The estimated percentage of the query done, between 0 and 100.
+    pub percentage: f64,
+    /// The number of rows processed so far.
+    pub rows_processed: u64,
+    /// The estimated total number of rows to process.
+    pub total_rows: u64,
+}
+
+impl QueryProgress {
+    fn from_raw(progress: ffi::duckdb_query_progress_type) -> Option<QueryProgress> {
+        // DuckDB reports -1 when no query is being tracked.
+        if progress.percentage < 0.0 {
+            return None;
+        }
+        Some(QueryProgress {
+            percentage: progress.percentage,
+            rows_processed: progress.rows_processed,
+            total_rows: progress.total_rows_to_process,
+        })
     }
 }
 
 impl Drop for InnerConnection {
-    #[allow(unused_must_use)]
     #[inline]
     fn drop(&mut self) {
-        use std::thread::panicking;
+        // DuckDB checkpoints when the database closes, but ignores failures.
+        if !self.con.is_null() && self.is_last_connection() {
+            // Uncommitted changes would fail the checkpoint, and are lost
+            // anyway.
+            let _ = self.execute("ROLLBACK");
+            if let Err(e) = self.execute("CHECKPOINT") {
+                eprintln!("Error while checkpointing DuckDB database: {e}");
+            }
+        }
+        // Panicking here would abort the process if the connection is
+        // dropped while unwinding; `Connection::close` returns the error.
         if let Err(e) = self.close() {
-            if panicking() {
-                eprintln!("Error while closing DuckDB connection: {e:?}");
-            } else {
-                panic!("Error while closing DuckDB connection: {e:?}");
+            eprintln!("Error while closing DuckDB connection: {e:?}");
+        }
+    }
+}
+
+/// Whether a subquery may have renamed some of the columns `names`, which
+/// it does by numbering each repeated name, ignoring case, from `_1`.
+fn may_be_renamed<'a>(names: impl Iterator<Item = &'a str>) -> bool {
+    let mut seen = HashSet::new();
+    for name in names {
+        let name = name.to_lowercase();
+        if let Some((base, number)) = name.rsplit_once('_') {
+            if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) && seen.contains(base) {
+                return true;
             }
         }
+        seen.insert(name);
     }
+    false
 }
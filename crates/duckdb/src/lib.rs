@@ -58,33 +58,67 @@
 pub use libduckdb_sys as ffi;
 
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     convert,
     ffi::CString,
     fmt,
     path::{Path, PathBuf},
     result, str,
+    sync::Arc,
 };
 
-use crate::{cache::StatementCache, inner_connection::InnerConnection, raw_statement::RawStatement, types::ValueRef};
+use crate::{
+    cache::StatementCache,
+    inner_connection::InnerConnection,
+    pragma::Sql,
+    profile::ProfileOutput,
+    raw_statement::RawStatement,
+    trace::{ProfileFn, RedactFn},
+    types::ValueRef,
+    warning::Warnings,
+};
 
+#[cfg(feature = "tokio")]
+pub use crate::async_connection::{AsyncConnection, AsyncStatement};
+#[cfg(feature = "serde")]
+pub use crate::params::{to_params, NamedParams};
 #[cfg(feature = "r2d2")]
 pub use crate::r2d2::DuckdbConnectionManager;
 pub use crate::{
     appender::Appender,
     appender_params::{appender_params_from_iter, AppenderParams, AppenderParamsFromIter},
     arrow_batch::Arrow,
+    attach::{AttachOptions, AttachedDb},
+    backup::BackupProgress,
+    batch::BatchResult,
     cache::CachedStatement,
+    cancel::CancellationToken,
+    catalog::{ColumnInfo, TableInfo},
+    checkpoint::DatabaseSize,
     column::Column,
     config::{AccessMode, Config, DefaultNullOrder, DefaultOrder},
-    error::Error,
+    describe::{ColumnDescription, ColumnSummary},
+    error::{ConstraintKind, Error, ErrorKind, ErrorVerbosity},
+    export::{CopyOptions, ExportFormat, ExportOptions},
+    extension::ExtensionInfo,
     ffi::ErrorCode,
-    params::{params_from_iter, Params, ParamsFromIter},
-    row::{AndThenRows, Map, MappedRows, Row, RowIndex, Rows},
+    inner_connection::{InterruptHandle, QueryProgress},
+    params::{in_list, params_from_iter, InList, Params, ParamsFromIter},
+    parquet::{ParquetReadBuilder, ParquetWriteOptions},
+    pending::{PendingQuery, PendingState},
+    profile::ProfilingFormat,
+    read_csv::CsvReadBuilder,
+    retry::{retry, RetryPolicy},
+    row::{AndThenRows, Map, MappedRows, Row, RowIndex, RowIter, Rows},
+    secret::{SecretDef, SecretInfo},
     statement::Statement,
-    transaction::{DropBehavior, Savepoint, Transaction, TransactionBehavior},
+    transaction::{DropBehavior, Savepoint, Transaction, TransactionBehavior, TransactionState},
     types::ToSql,
+    version::{version, version_number},
+    warning::{Warning, WarningCategory},
 };
+#[cfg(feature = "serde_json")]
+pub use crate::{explain::Plan, profile::QueryProfile};
 #[cfg(feature = "polars")]
 pub use polars_dataframe::Polars;
 
@@ -93,28 +127,59 @@ pub use arrow;
 #[cfg(feature = "polars")]
 pub use polars::{self, export::arrow as arrow2};
 
+/// Exposes a function as the entrypoint of a loadable DuckDB extension,
+/// which DuckDB calls with a [`Connection`] to the database that loads it.
+///
+/// The function must be named after the file of the extension, so that
+/// `libmy_ext_init` is the entrypoint of `libmy_ext.duckdb_extension`. See
+/// the `hello-ext` example.
+#[cfg(feature = "vtab-loadable")]
+pub use duckdb_loadable_macros::duckdb_entrypoint;
+
+// The entrypoint macro refers to this crate as `::duckdb`.
+#[cfg(all(test, feature = "vtab-loadable"))]
+extern crate self as duckdb;
+
 #[macro_use]
 mod error;
 mod appender;
 mod appender_params;
 mod arrow_batch;
+#[cfg(feature = "tokio")]
+mod async_connection;
+mod attach;
+mod backup;
+mod batch;
 mod cache;
+mod cancel;
+mod catalog;
+mod checkpoint;
 mod column;
 mod config;
+mod describe;
+mod explain;
+mod export;
+mod extension;
 mod inner_connection;
 mod params;
+mod parquet;
+mod pending;
 #[cfg(feature = "polars")]
 mod polars_dataframe;
 mod pragma;
+mod profile;
 #[cfg(feature = "r2d2")]
 mod r2d2;
 mod raw_statement;
+mod read_csv;
+mod retry;
 mod row;
+mod secret;
 mod statement;
+mod trace;
 mod transaction;
-
-#[cfg(feature = "extensions-full")]
-mod extension;
+mod version;
+mod warning;
 
 pub mod types;
 /// The duckdb table function interface
@@ -160,6 +225,43 @@ macro_rules! params {
     };
 }
 
+/// A macro making it more convenient to pass lists of named parameters
+/// as a `&[(&str, &dyn ToSql)]`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use duckdb::{Result, Connection, named_params};
+///
+/// struct Person {
+///     name: String,
+///     age_in_years: u8,
+///     data: Option<Vec<u8>>,
+/// }
+///
+/// fn add_person(conn: &Connection, person: &Person) -> Result<()> {
+///     conn.execute(
+///         "INSERT INTO person (name, age_in_years, data)
+///          VALUES ($name, $age, $data)",
+///         named_params! {
+///             "$name": person.name,
+///             "$age": person.age_in_years,
+///             "$data": person.data,
+///         },
+///     )?;
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! named_params {
+    () => {
+        &[] as &[(&str, &dyn $crate::ToSql)]
+    };
+    ($($param_name:literal: $param_val:expr),+ $(,)?) => {
+        &[$(($param_name, &$param_val as &dyn $crate::ToSql)),+] as &[(&str, &dyn $crate::ToSql)]
+    };
+}
+
 /// A typedef of the result returned by many methods.
 pub type Result<T, E = Error> = result::Result<T, E>;
 
@@ -217,6 +319,15 @@ pub struct Connection {
     db: RefCell<InnerConnection>,
     cache: StatementCache,
     path: Option<PathBuf>,
+    lenient_timestamps: Cell<bool>,
+    lenient_bools: Cell<bool>,
+    strict_types: Cell<bool>,
+    profile_output: RefCell<Option<ProfileOutput>>,
+    trace_hook: Cell<Option<fn(&str)>>,
+    profile_hook: Cell<Option<ProfileFn>>,
+    error_verbosity: Cell<ErrorVerbosity>,
+    param_redactor: Cell<Option<RedactFn>>,
+    warnings: RefCell<Warnings>,
 }
 
 unsafe impl Send for Connection {}
@@ -228,12 +339,15 @@ impl Connection {
     /// `Connection::open_with_flags(path,
     /// Config::default())`.
     ///
+    /// A `path` of `:memory:name` opens the in-memory database called
+    /// `name`, see [`Connection::open_in_memory_named`].
+    ///
     /// ```rust,no_run
     /// # use duckdb::{Connection, Result};
     /// fn open_my_db() -> Result<()> {
     ///     let path = "./my_db.db3";
     ///     let db = Connection::open(&path)?;
-    ///     println!("{}", db.is_autocommit());
+    ///     println!("{}", db.is_autocommit()?);
     ///     Ok(())
     /// }
     /// ```
@@ -247,6 +361,23 @@ impl Connection {
         Connection::open_with_flags(path, Config::default())
     }
 
+    /// Open a new connection to an existing DuckDB database in read-only mode.
+    ///
+    /// Any statement that would write to the database returns an error. The
+    /// file is locked for reading only, so read-only connections from several
+    /// processes can use it at the same time, but it can't be opened for
+    /// writing until they are closed.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `path` cannot be converted to a C-compatible
+    /// string, if the database doesn't exist or if the underlying DuckDB open
+    /// call fails.
+    #[inline]
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Connection> {
+        Connection::open_with_flags(path, Config::default().access_mode(AccessMode::ReadOnly)?)
+    }
+
     /// Open a new connection to an in-memory DuckDB database.
     ///
     /// # Failure
@@ -257,6 +388,35 @@ impl Connection {
         Connection::open_in_memory_with_flags(Config::default())
     }
 
+    /// Open a new connection to the in-memory database called `name`, which
+    /// every connection opened with that name shares. The database is
+    /// dropped once the last of its connections is closed.
+    ///
+    /// `Connection::open_in_memory_named(name)` is equivalent to
+    /// `Connection::open(format!(":memory:{name}"))`. When opening with a
+    /// config, it is only applied if no connection to the database is open.
+    ///
+    /// ```rust
+    /// # use duckdb::{Connection, Result};
+    /// fn share() -> Result<()> {
+    ///     let writer = Connection::open_in_memory_named("shared")?;
+    ///     writer.execute_batch("CREATE TABLE t AS SELECT 42 AS x")?;
+    ///     let reader = Connection::open(":memory:shared")?;
+    ///     assert_eq!(reader.query_row("SELECT x FROM t", [], |row| row.get::<_, i32>(0))?, 42);
+    ///     Ok(())
+    /// }
+    /// # share().unwrap();
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `name` contains a nul byte or if the underlying
+    /// DuckDB open call fails.
+    #[inline]
+    pub fn open_in_memory_named(name: &str) -> Result<Connection> {
+        Connection::open(format!(":memory:{name}"))
+    }
+
     /// Open a new connection to an ffi database.
     ///
     /// # Failure
@@ -271,6 +431,15 @@ impl Connection {
             db: RefCell::new(db),
             cache: StatementCache::with_capacity(STATEMENT_CACHE_DEFAULT_CAPACITY),
             path: None, // Can we know the path from connection?
+            lenient_timestamps: Cell::new(false),
+            lenient_bools: Cell::new(false),
+            strict_types: Cell::new(false),
+            profile_output: RefCell::new(None),
+            trace_hook: Cell::new(None),
+            profile_hook: Cell::new(None),
+            error_verbosity: Cell::new(ErrorVerbosity::Message),
+            param_redactor: Cell::new(None),
+            warnings: RefCell::default(),
         })
     }
 
@@ -295,11 +464,23 @@ impl Connection {
         }
 
         let c_path = path_to_cstring(path.as_ref())?;
-        InnerConnection::open_with_flags(&c_path, config).map(|db| Connection {
-            db: RefCell::new(db),
-            cache: StatementCache::with_capacity(STATEMENT_CACHE_DEFAULT_CAPACITY),
-            path: Some(path.as_ref().to_path_buf()),
-        })
+        InnerConnection::open_with_flags(&c_path, config)
+            .map(|db| Connection {
+                db: RefCell::new(db),
+                cache: StatementCache::with_capacity(STATEMENT_CACHE_DEFAULT_CAPACITY),
+                path: Some(path.as_ref().to_path_buf()),
+                lenient_timestamps: Cell::new(false),
+                lenient_bools: Cell::new(false),
+                strict_types: Cell::new(false),
+                profile_output: RefCell::new(None),
+                trace_hook: Cell::new(None),
+                profile_hook: Cell::new(None),
+                error_verbosity: Cell::new(ErrorVerbosity::Message),
+                param_redactor: Cell::new(None),
+                warnings: RefCell::default(),
+            })?
+            // A named in-memory database may already have a hook.
+            .run_init_hook()
     }
 
     /// Open a new connection to an in-memory DuckDB database.
@@ -333,13 +514,18 @@ impl Connection {
     /// Will return `Err` if `sql` cannot be converted to a C-compatible string
     /// or if the underlying DuckDB call fails.
     pub fn execute_batch(&self, sql: &str) -> Result<()> {
-        self.db.borrow_mut().execute(sql)
+        self.traced(sql, || self.db.borrow_mut().execute(sql))
+            .map_err(|err| self.error_context(err, sql, None))?;
+        self.collect_warnings(sql);
+        Ok(())
     }
 
     /// Convenience method to prepare and execute a single SQL statement.
     ///
     /// On success, returns the number of rows that were changed or inserted or
-    /// deleted.
+    /// deleted, as [`Statement::execute`] does. `sql` must hold a single
+    /// statement; use [`execute_batch`](Connection::execute_batch) to run
+    /// several.
     ///
     /// ## Example
     ///
@@ -369,13 +555,36 @@ impl Connection {
     ///
     /// # Failure
     ///
-    /// Will return `Err` if `sql` cannot be converted to a C-compatible string
-    /// or if the underlying DuckDB call fails.
+    /// Will return `Err` if `sql` cannot be converted to a C-compatible string,
+    /// holds more than one statement, or if the underlying DuckDB call fails.
     #[inline]
     pub fn execute<P: Params>(&self, sql: &str, params: P) -> Result<usize> {
         self.prepare(sql).and_then(|mut stmt| stmt.execute(params))
     }
 
+    /// Convenience method to execute an `INSERT` that inserts a single row
+    /// and returns a single integer with `RETURNING`, such as a generated id.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn add_person(conn: &Connection, name: &str) -> Result<i64> {
+    ///     conn.insert("INSERT INTO people (name) VALUES (?) RETURNING id", [name])
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err(QueryReturnedNoRows)` if no row is returned,
+    /// `Err(QueryReturnedMoreThanOneRow)` if more than one is, and `Err` if
+    /// `sql` cannot be converted to a C-compatible string, the statement fails
+    /// or its first column isn't an integer.
+    #[inline]
+    pub fn insert<P: Params>(&self, sql: &str, params: P) -> Result<i64> {
+        self.query_one(sql, params, |row| row.get(0))
+    }
+
     /// Returns the path to the database file, if one exists and is known.
     #[inline]
     pub fn path(&self) -> Option<&Path> {
@@ -418,6 +627,88 @@ impl Connection {
         self.prepare(sql)?.query_row(params, f)
     }
 
+    /// Convenience method to execute a query that is expected to return
+    /// exactly one row, mapped with `f`.
+    ///
+    /// Unlike [`query_row`](Connection::query_row), which ignores all rows
+    /// but the first, this fails if the query returns more than one.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Result, Connection};
+    /// fn user_count(conn: &Connection) -> Result<i64> {
+    ///     conn.query_one("SELECT count(*) FROM users", [], |row| row.get(0))
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err(QueryReturnedNoRows)` if no rows are returned,
+    /// `Err(QueryReturnedMoreThanOneRow)` with their number if more than one
+    /// are, and `Err` if `sql` cannot be converted to a C-compatible string or
+    /// the query or `f` fails.
+    #[inline]
+    pub fn query_one<T, P, F>(&self, sql: &str, params: P, f: F) -> Result<T>
+    where
+        P: Params,
+        F: FnOnce(&Row<'_>) -> Result<T>,
+    {
+        self.prepare(sql)?.query_one(params, f)
+    }
+
+    /// Convenience method to execute a query that is expected to return at
+    /// most one row, mapped with `f`, or `None` if it returns none.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Result, Connection};
+    /// fn preferred_locale(conn: &Connection) -> Result<Option<String>> {
+    ///     conn.query_opt(
+    ///         "SELECT value FROM preferences WHERE name='locale'",
+    ///         [],
+    ///         |row| row.get(0),
+    ///     )
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err(QueryReturnedMoreThanOneRow)` with the number of rows
+    /// if more than one are returned, and `Err` if `sql` cannot be converted
+    /// to a C-compatible string or the query or `f` fails.
+    #[inline]
+    pub fn query_opt<T, P, F>(&self, sql: &str, params: P, f: F) -> Result<Option<T>>
+    where
+        P: Params,
+        F: FnOnce(&Row<'_>) -> Result<T>,
+    {
+        self.prepare(sql)?.query_opt(params, f)
+    }
+
+    /// Return `true` if the query `sql` returns one or more rows and `false`
+    /// if it returns none. DuckDB stops computing the result once the first
+    /// row is found.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Result, Connection};
+    /// fn has_admins(conn: &Connection) -> Result<bool> {
+    ///     conn.exists("SELECT 1 FROM users WHERE role = ?", ["admin"])
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `sql` cannot be converted to a C-compatible string
+    /// or if the underlying DuckDB call fails.
+    #[inline]
+    pub fn exists<P: Params>(&self, sql: &str, params: P) -> Result<bool> {
+        self.prepare(sql)?.exists(params)
+    }
+
     /// Convenience method to execute a query that is expected to return a
     /// single row, and execute a mapping via `f` on that returned row with
     /// the possibility of failure. The `Result` type of `f` must implement
@@ -457,6 +748,30 @@ impl Connection {
             .and_then(f)
     }
 
+    /// Convenience method to execute a query that returns a single column,
+    /// collecting it into a polars Series.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Result, Connection};
+    /// # use polars::prelude::Series;
+    /// fn get_ids(conn: &Connection) -> Result<Series> {
+    ///     conn.query_series("SELECT id FROM people WHERE age > ?", [30])
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `sql` cannot be converted to a C-compatible string,
+    /// if the query does not return exactly one column, or if the underlying
+    /// DuckDB call fails.
+    #[cfg(feature = "polars")]
+    #[inline]
+    pub fn query_series<P: Params>(&self, sql: &str, params: P) -> Result<polars::prelude::Series> {
+        self.prepare(sql)?.query_series(params)
+    }
+
     /// Prepare a SQL statement for execution.
     ///
     /// ## Example
@@ -474,7 +789,9 @@ impl Connection {
     /// # Failure
     ///
     /// Will return `Err` if `sql` cannot be converted to a C-compatible string
-    /// or if the underlying DuckDB call fails.
+    /// or if the underlying DuckDB call fails, and
+    /// [`Error::MultipleStatements`] if `sql` holds more than one statement;
+    /// see [`prepare_multi`](Connection::prepare_multi) for those.
     #[inline]
     pub fn prepare(&self, sql: &str) -> Result<Statement<'_>> {
         self.db.borrow_mut().prepare(self, sql)
@@ -503,6 +820,10 @@ impl Connection {
 
     /// Create an Appender for fast import data
     ///
+    /// `schema` is a schema of the default database, see
+    /// [`appender_to_catalog`](Connection::appender_to_catalog) for the
+    /// tables of [attached](Connection::attach) databases.
+    ///
     /// ## Example
     ///
     /// ```rust,no_run
@@ -521,39 +842,264 @@ impl Connection {
         self.db.borrow_mut().appender(self, table, schema)
     }
 
+    /// Create an Appender for fast import data into `schema.table` of the
+    /// database `catalog`, such as one [attached](Connection::attach) to this
+    /// connection.
+    ///
+    /// DuckDB's appenders only find tables of the default database, so the
+    /// appender runs on a [clone](Connection::try_clone) of this connection
+    /// that has `catalog` as its default. Its rows are committed on their own
+    /// when they are flushed, not as part of a transaction open on this
+    /// connection, and `catalog` can't be `temp`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{AttachOptions, Connection, Result};
+    /// fn archive_rows(conn: &Connection) -> Result<()> {
+    ///     conn.attach("archive.db", "archive", AttachOptions::default())?;
+    ///     let mut app = conn.appender_to_catalog("foo", "archive", "main")?;
+    ///     app.append_rows([[1, 2], [3, 4]])?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `catalog` isn't attached, or `table` not exists
+    pub fn appender_to_catalog(&self, table: &str, catalog: &str, schema: &str) -> Result<Appender<'_>> {
+        let owner = self.try_clone()?;
+        let mut sql = Sql::new();
+        sql.push_keyword("USE")?;
+        sql.push_space();
+        sql.push_quoted_identifier(catalog)?;
+        owner.execute_batch(&sql)?;
+        let app = owner.db.borrow_mut().raw_appender(table, schema)?;
+        Ok(Appender::with_owner(self, app, owner))
+    }
+
     /// Close the DuckDB connection.
     ///
     /// This is functionally equivalent to the `Drop` implementation for
     /// `Connection` except that on failure, it returns an error and the
-    /// connection itself (presumably so closing can be attempted again).
+    /// connection itself, so closing can be attempted again.
+    ///
+    /// If this is the last connection to a database file, the database is
+    /// checkpointed first, moving the write-ahead log into the file. DuckDB
+    /// does the same when the database closes, but can't report a failure
+    /// then; `Drop` prints it to stderr.
+    ///
+    /// Statements and appenders borrow the connection, so it can't be closed
+    /// while one is alive:
+    ///
+    /// ```rust,compile_fail
+    /// # use duckdb::Connection;
+    /// let conn = Connection::open_in_memory().unwrap();
+    /// let stmt = conn.prepare("SELECT 1").unwrap();
+    /// conn.close().unwrap();
+    /// drop(stmt);
+    /// ```
     ///
     /// # Failure
     ///
-    /// Will return `Err` if the underlying DuckDB call fails.
+    /// Will return [`Error::CheckpointConflict`] if the checkpoint fails
+    /// because this connection has uncommitted changes, which should be
+    /// committed or rolled back before trying again, and `Err` if the
+    /// underlying DuckDB call fails.
     #[inline]
+    #[allow(clippy::result_large_err)]
     pub fn close(self) -> Result<(), (Connection, Error)> {
+        self.flush_prepared_statement_cache();
+        let last = self.db.borrow().is_last_connection();
+        if last {
+            if let Err(err) = self.checkpoint() {
+                return Err((self, err));
+            }
+        }
         let r = self.db.borrow_mut().close();
         r.map_err(move |err| (self, err))
     }
 
-    /// Test for auto-commit mode.
-    /// Autocommit mode is on by default.
+    /// Get access to a handle that can be used to interrupt long running
+    /// queries from another thread.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// # use std::{thread, time::Duration};
+    /// fn with_timeout(conn: &Connection) -> Result<i64> {
+    ///     let handle = conn.interrupt_handle();
+    ///     thread::spawn(move || {
+    ///         thread::sleep(Duration::from_secs(10));
+    ///         handle.interrupt();
+    ///     });
+    ///     conn.query_row("SELECT count(*) FROM big_table", [], |row| row.get(0))
+    /// }
+    /// ```
+    #[inline]
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.db.borrow().get_interrupt_handle()
+    }
+
+    /// The progress of the running query, see
+    /// [`InterruptHandle::query_progress`]. Use an
+    /// [`interrupt_handle`](Connection::interrupt_handle) to poll it from
+    /// another thread.
     #[inline]
-    pub fn is_autocommit(&self) -> bool {
-        self.db.borrow().is_autocommit()
+    pub fn query_progress(&self) -> Option<QueryProgress> {
+        self.interrupt_handle().query_progress()
+    }
+
+    /// Test for auto-commit mode: whether statements commit on their own,
+    /// because no transaction is open on this connection, see
+    /// [`transaction_state`](Connection::transaction_state).
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the underlying DuckDB call fails.
+    #[inline]
+    pub fn is_autocommit(&self) -> Result<bool> {
+        Ok(self.transaction_state()? == TransactionState::None)
     }
 
     /// Creates a new connection to the already-opened database.
+    ///
+    /// The clone shares the database, including an in-memory one, without
+    /// opening it again: it can be moved to another thread to run queries
+    /// next to this connection, and the database stays open until the last
+    /// connection to it is closed. Table functions registered with
+    /// `register_table_function` are stored in the database, so they are visible from every clone,
+    /// whichever connection registered them. Settings changed with `SET`,
+    /// the statement cache and the modes set on this `Connection` apply to
+    /// one connection only; the modes are copied to the clone.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn insert_in_background(conn: &Connection) -> Result<()> {
+    ///     let clone = conn.try_clone()?;
+    ///     std::thread::spawn(move || clone.execute_batch("INSERT INTO foo VALUES (1)"))
+    ///         .join()
+    ///         .unwrap()
+    /// }
+    /// ```
     pub fn try_clone(&self) -> Result<Self> {
         let inner = self.db.borrow().try_clone()?;
-        Ok(Connection {
+        Connection {
             db: RefCell::new(inner),
             cache: StatementCache::with_capacity(STATEMENT_CACHE_DEFAULT_CAPACITY),
             path: self.path.clone(),
-        })
+            lenient_timestamps: self.lenient_timestamps.clone(),
+            lenient_bools: self.lenient_bools.clone(),
+            strict_types: self.strict_types.clone(),
+            profile_output: RefCell::new(None),
+            trace_hook: self.trace_hook.clone(),
+            profile_hook: self.profile_hook.clone(),
+            error_verbosity: self.error_verbosity.clone(),
+            param_redactor: self.param_redactor.clone(),
+            warnings: RefCell::default(),
+        }
+        .run_init_hook()
+    }
+
+    /// Sets up every connection made to this connection's database from now
+    /// on, such as its clones from [`try_clone`](Connection::try_clone) and
+    /// the connections of a pool, by running `hook` on it before it's
+    /// returned. A hook set before replaces it; existing connections,
+    /// including this one, aren't affected.
+    ///
+    /// Session state such as `SET` options, temporary objects and loaded
+    /// scalar functions is per connection, which the hook can restore.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn utc_clones(conn: &Connection) -> Result<Connection> {
+    ///     conn.set_init_hook(|conn| conn.execute_batch("SET TimeZone = 'UTC'"))?;
+    ///     conn.try_clone()
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the connection is closed. An error returned by
+    /// `hook` is returned by whatever made the connection, which is dropped.
+    pub fn set_init_hook<F>(&self, hook: F) -> Result<()>
+    where
+        F: Fn(&Connection) -> Result<()> + Send + Sync + 'static,
+    {
+        self.db.borrow().set_init_hook(Arc::new(hook))
     }
 
-    /// Returns the version of the DuckDB library
+    fn run_init_hook(self) -> Result<Connection> {
+        let hook = self.db.borrow().init_hook();
+        if let Some(hook) = hook {
+            hook(&self)?;
+        }
+        Ok(self)
+    }
+
+    /// Controls whether plain `TIMESTAMP` columns may be read as
+    /// timezone-aware values such as `chrono::DateTime<Utc>`.
+    ///
+    /// By default only `TIMESTAMPTZ` columns convert to timezone-aware types,
+    /// since a `TIMESTAMP` carries no zone information. When enabled, a
+    /// `TIMESTAMP` read as one of them is assumed to be in UTC; other types,
+    /// such as `String` or [`Value`](types::Value), read it as they always do.
+    /// A type is timezone-aware when its
+    /// [`FromSql::accepts`](types::FromSql::accepts) takes `TIMESTAMPTZ` but
+    /// not `TIMESTAMP`.
+    #[inline]
+    pub fn set_lenient_timestamps(&self, lenient: bool) {
+        self.lenient_timestamps.set(lenient);
+    }
+
+    #[inline]
+    pub(crate) fn lenient_timestamps(&self) -> bool {
+        self.lenient_timestamps.get()
+    }
+
+    /// Controls whether `bool` may be read from integer columns.
+    ///
+    /// By default `bool` only reads `BOOLEAN` columns. When enabled, an
+    /// integer column such as a `TINYINT` holding `0` or `1` reads as `false`
+    /// or `true`, and any other value fails with an
+    /// [`Error::ColumnConversionFailure`] holding
+    /// [`FromSqlError::OutOfRange`](types::FromSqlError::OutOfRange).
+    #[inline]
+    pub fn set_lenient_bools(&self, lenient: bool) {
+        self.lenient_bools.set(lenient);
+    }
+
+    #[inline]
+    pub(crate) fn lenient_bools(&self) -> bool {
+        self.lenient_bools.get()
+    }
+
+    /// Controls whether [`Row::get`] only converts columns whose type belongs
+    /// to the family the Rust type naturally reads from.
+    ///
+    /// By default conversions are lenient: a `VARCHAR` holding `"123"` reads
+    /// as an `i64`, and numbers and dates read as a `String`. In strict mode
+    /// those fail with [`Error::ColumnConversionFailure`], while conversions
+    /// within a family, such as a `SMALLINT` into an `i64` or a `FLOAT` into
+    /// an `f64`, still work. `NULL` is accepted by every type, and types decide
+    /// which families they accept through [`FromSql::accepts`](types::FromSql::accepts).
+    #[inline]
+    pub fn set_strict_types(&self, strict: bool) {
+        self.strict_types.set(strict);
+    }
+
+    #[inline]
+    pub(crate) fn strict_types(&self) -> bool {
+        self.strict_types.get()
+    }
+
+    /// Returns the version of the DuckDB library, as reported by
+    /// `PRAGMA version`. See also [`version()`].
     pub fn version(&self) -> Result<String> {
         self.query_row("PRAGMA version", [], |row| row.get(0))
     }
@@ -570,7 +1116,7 @@ doc_comment::doctest!("../../../README.md");
 
 #[cfg(test)]
 mod test {
-    use crate::types::Value;
+    use crate::types::{FromSqlError, Value};
 
     use super::*;
     use std::{error::Error as StdError, fmt};
@@ -708,6 +1254,130 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_open_read_only() -> Result<()> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("read_only.db");
+        assert!(Connection::open_read_only(&path).is_err());
+        Connection::open(&path)?.execute_batch("CREATE TABLE foo(x INTEGER); INSERT INTO foo VALUES (1), (2), (3);")?;
+
+        let db = Connection::open_read_only(&path)?;
+        for sql in [
+            "INSERT INTO foo VALUES (4)",
+            "UPDATE foo SET x = 0",
+            "DELETE FROM foo",
+            "CREATE TABLE bar(y INTEGER)",
+        ] {
+            let err = db.execute_batch(sql).unwrap_err();
+            assert!(err.to_string().contains("read-only"), "{sql}: {err}");
+        }
+        assert!(db.prepare("INSERT INTO foo VALUES (?)")?.execute([4]).is_err());
+
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || -> Result<i64> {
+                    let db = Connection::open_read_only(path)?;
+                    (0..20).try_fold(0, |_, _| db.query_row("SELECT sum(x) FROM foo", [], |r| r.get(0)))
+                })
+            })
+            .collect();
+        for reader in readers {
+            assert_eq!(reader.join().unwrap()?, 6);
+        }
+        let sum: i64 = db.query_row("SELECT sum(x) FROM foo", [], |r| r.get(0))?;
+        assert_eq!(sum, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_interrupt() -> Result<()> {
+        use std::{
+            sync::{
+                atomic::{AtomicBool, Ordering},
+                Arc,
+            },
+            thread,
+            time::{Duration, Instant},
+        };
+
+        let db = Connection::open_in_memory()?;
+        let handle = db.interrupt_handle();
+        // Nothing is running yet. Later queries are unaffected.
+        handle.interrupt();
+        assert_eq!(db.query_row("SELECT 1", [], |r| r.get::<_, i32>(0))?, 1);
+
+        let done = Arc::new(AtomicBool::new(false));
+        let interrupter = {
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                // Keep interrupting in case the query hasn't started yet.
+                while !done.load(Ordering::SeqCst) {
+                    thread::sleep(Duration::from_millis(50));
+                    handle.interrupt();
+                }
+                handle
+            })
+        };
+        let start = Instant::now();
+        let res = db.query_row(
+            "SELECT sum(a.range * b.range) FROM range(1000000) a, range(1000000) b",
+            [],
+            |r| r.get::<_, i128>(0),
+        );
+        done.store(true, Ordering::SeqCst);
+        let handle = interrupter.join().unwrap();
+        let err = res.unwrap_err();
+        assert!(err.to_string().contains("Interrupted"), "{err}");
+        assert!(start.elapsed() < Duration::from_secs(30), "{:?}", start.elapsed());
+
+        assert_eq!(db.query_row("SELECT 2", [], |r| r.get::<_, i32>(0))?, 2);
+        drop(db);
+        handle.interrupt();
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_progress() -> Result<()> {
+        use std::{
+            thread,
+            time::{Duration, Instant},
+        };
+
+        let db = Connection::open_in_memory()?;
+        assert_eq!(db.query_progress(), None);
+        db.execute_batch(
+            "SET enable_progress_bar = true; SET enable_progress_bar_print = false;
+            CREATE TABLE t AS SELECT range AS x FROM range(100000);",
+        )?;
+        assert_eq!(db.query_progress(), None);
+
+        let handle = db.interrupt_handle();
+        let query = thread::spawn(move || {
+            let res = db.query_row("SELECT sum(a.x * b.x) FROM t a, t b", [], |r| r.get::<_, i128>(0));
+            (db, res)
+        });
+        let start = Instant::now();
+        let progress = loop {
+            match handle.query_progress() {
+                Some(progress) if progress.percentage > 0.0 => break progress,
+                _ if start.elapsed() > Duration::from_secs(30) => panic!("no progress reported"),
+                _ => thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        assert!(progress.percentage <= 100.0, "{progress:?}");
+        assert!(progress.rows_processed > 0, "{progress:?}");
+        assert!(progress.rows_processed <= progress.total_rows, "{progress:?}");
+
+        handle.interrupt();
+        let (db, res) = query.join().unwrap();
+        assert!(res.is_err());
+        assert_eq!(handle.query_progress(), None);
+        drop(db);
+        assert_eq!(handle.query_progress(), None);
+        Ok(())
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_invalid_unicode_file_names() -> Result<()> {
@@ -739,10 +1409,47 @@ mod test {
     #[test]
     fn test_close_always_ok() -> Result<()> {
         let db = checked_memory_handle();
+        // Cached statements are finalized along with the connection.
+        db.prepare_cached("SELECT 1")?;
+        db.close().unwrap();
+        Ok(())
+    }
 
-        // TODO: prepare a query but not execute it
+    #[test]
+    fn test_close_checkpoints() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("close.db");
+        let wal = dir.path().join("close.db.wal");
+        let db = Connection::open(&path)?;
+        db.execute_batch("CREATE TABLE t AS SELECT 42 AS x")?;
+        assert!(wal.exists());
+        // A clone keeps the database open, so closing the first doesn't checkpoint.
+        let clone = db.try_clone()?;
+        db.close().unwrap();
+        assert!(wal.exists());
+        clone.close().unwrap();
+        assert!(!wal.exists());
 
+        let db = Connection::open(&path)?;
+        let x: i32 = db.query_row("SELECT x FROM t", [], |r| r.get(0))?;
+        assert_eq!(x, 42);
+        Ok(())
+    }
+
+    #[test]
+    fn test_close_retry() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("retry.db");
+        let db = Connection::open(&path)?;
+        db.execute_batch("CREATE TABLE t (x INTEGER); BEGIN; INSERT INTO t VALUES (1)")?;
+        let (db, err) = db.close().unwrap_err();
+        assert!(matches!(err, Error::CheckpointConflict(_)), "{err:?}");
+        db.execute_batch("COMMIT")?;
         db.close().unwrap();
+
+        let db = Connection::open(&path)?;
+        let count: i64 = db.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 1);
         Ok(())
     }
 
@@ -928,6 +1635,39 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_query_one_opt_exists() -> Result<()> {
+        let db = checked_memory_handle();
+        db.execute_batch("CREATE TABLE foo(x INTEGER); INSERT INTO foo VALUES (1), (2), (3)")?;
+
+        let x: i32 = db.query_one("SELECT x FROM foo WHERE x = ?", [2], |r| r.get(0))?;
+        assert_eq!(x, 2);
+        let err = db
+            .query_one("SELECT x FROM foo WHERE x > ?", [5], |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert_eq!(err, Error::QueryReturnedNoRows);
+        let err = db
+            .query_one("SELECT x FROM foo", [], |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert_eq!(err, Error::QueryReturnedMoreThanOneRow(3));
+        assert_eq!(err.to_string(), "Query returned more than one row: 3 rows");
+
+        let x: Option<i32> = db.query_opt("SELECT x FROM foo WHERE x = ?", [3], |r| r.get(0))?;
+        assert_eq!(x, Some(3));
+        let x: Option<i32> = db.query_opt("SELECT x FROM foo WHERE x > ?", [5], |r| r.get(0))?;
+        assert_eq!(x, None);
+        let err = db
+            .query_opt("SELECT x FROM foo WHERE x > ?", [1], |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert_eq!(err, Error::QueryReturnedMoreThanOneRow(2));
+
+        assert!(db.exists("SELECT 1 FROM foo WHERE x = ?", [1])?);
+        assert!(!db.exists("SELECT 1 FROM foo WHERE x = ?", [4])?);
+        assert!(db.exists("SELECT x FROM foo", [])?);
+        assert!(db.exists("NOT A PROPER QUERY", []).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_prepare_failures() -> Result<()> {
         let db = checked_memory_handle();
@@ -941,7 +1681,10 @@ mod test {
     #[test]
     fn test_is_autocommit() {
         let db = checked_memory_handle();
-        assert!(db.is_autocommit(), "autocommit expected to be active by default");
+        assert!(
+            db.is_autocommit().unwrap(),
+            "autocommit expected to be active by default"
+        );
     }
 
     #[test]
@@ -964,9 +1707,14 @@ mod test {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            Error::DuckDBFailure(err, _) => {
-                // TODO(wangfenjin): Update errorcode
-                assert_eq!(err.code, ErrorCode::Unknown);
+            Error::ConstraintViolation {
+                kind: ConstraintKind::NotNull,
+                table,
+                constraint_or_column,
+                ..
+            } => {
+                assert_eq!(table.as_deref(), Some("foo"));
+                assert_eq!(constraint_or_column.as_deref(), Some("x"));
             }
             err => panic!("Unexpected error {err}"),
         }
@@ -999,6 +1747,104 @@ mod test {
             cloned_con.execute_batch("create table test2 (c1 bigint)")?;
             cloned_con.close().unwrap();
         }
+
+        // 3. Clone a clone after the original is gone.
+        {
+            let clone = checked_memory_handle().try_clone()?;
+            clone.execute_batch("create table test (c1 bigint)")?;
+            let clone2 = clone.try_clone()?;
+            drop(clone);
+            clone2.execute_batch("insert into test values (1)")?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_in_memory_named() -> Result<()> {
+        let count = |conn: &Connection| -> Result<i64> { conn.query_row("SELECT count(*) FROM t", [], |r| r.get(0)) };
+
+        let a1 = Connection::open_in_memory_named("test_named_a")?;
+        let a2 = Connection::open(":memory:test_named_a")?;
+        let b = Connection::open_in_memory_named("test_named_b")?;
+        a1.execute_batch("CREATE TABLE t AS SELECT * FROM range(3)")?;
+        assert_eq!(count(&a2)?, 3);
+        std::thread::spawn(move || a2.execute_batch("INSERT INTO t VALUES (3)"))
+            .join()
+            .unwrap()?;
+        assert_eq!(count(&a1)?, 4);
+        assert!(count(&b).is_err());
+        assert!(count(&Connection::open_in_memory()?).is_err());
+        assert!(!Path::new(":memory:test_named_a").exists());
+
+        // The database goes with its last connection.
+        a1.close().unwrap();
+        assert!(count(&Connection::open_in_memory_named("test_named_a")?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_threads() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE events (thread INTEGER, n INTEGER)")?;
+        let threads = (0..4)
+            .map(|t| {
+                let conn = db.try_clone()?;
+                Ok(std::thread::spawn(move || -> Result<()> {
+                    for n in 0..100 {
+                        conn.execute("INSERT INTO events VALUES (?, ?)", [t, n])?;
+                    }
+                    let mut app = conn.appender("events")?;
+                    app.append_row([t, 100])?;
+                    Ok(())
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for thread in threads {
+            thread.join().unwrap()?;
+        }
+        let (count, threads): (i64, i64) =
+            db.query_row("SELECT count(*), count(DISTINCT thread) FROM events", [], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })?;
+        assert_eq!((count, threads), (404, 4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_init_hook() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let db = Connection::open_in_memory()?;
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        db.set_init_hook(move |conn| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            conn.execute_batch("CREATE TEMP MACRO add_one(x) AS x + 1")
+        })?;
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(db.query_row("SELECT add_one(1)", [], |r| r.get::<_, i32>(0)).is_err());
+
+        let clone = db.try_clone()?;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let two: i32 = clone.query_row("SELECT add_one(1)", [], |r| r.get(0))?;
+        assert_eq!(two, 2);
+        // Clones of clones share the database, and so the hook.
+        let _ = clone.try_clone()?;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        db.set_init_hook(|conn| conn.execute_batch("SELECT * FROM missing"))?;
+        let err = db.try_clone().unwrap_err();
+        assert!(err.to_string().contains("missing"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_init_hook_named() -> Result<()> {
+        let db = Connection::open_in_memory_named("init_hook")?;
+        db.set_init_hook(|conn| conn.execute_batch("SET TimeZone = 'UTC'"))?;
+        let other = Connection::open_in_memory_named("init_hook")?;
+        let tz: String = other.query_row("SELECT current_setting('TimeZone')", [], |r| r.get(0))?;
+        assert_eq!(tz, "UTC");
         Ok(())
     }
 
@@ -1076,7 +1922,10 @@ mod test {
             let bad_type: Result<Vec<f64>> = query.query_and_then([], |row| row.get(1))?.collect();
 
             match bad_type.unwrap_err() {
-                Error::InvalidColumnType(..) => (),
+                Error::ColumnConversionFailure {
+                    source: FromSqlError::InvalidType,
+                    ..
+                } => (),
                 err => panic!("Unexpected error {err}"),
             }
 
@@ -1128,7 +1977,10 @@ mod test {
                 .collect();
 
             match bad_type.unwrap_err() {
-                CustomError::Sqlite(Error::InvalidColumnType(..)) => (),
+                CustomError::Sqlite(Error::ColumnConversionFailure {
+                    source: FromSqlError::InvalidType,
+                    ..
+                }) => (),
                 err => panic!("Unexpected error {err}"),
             }
 
@@ -1182,7 +2034,10 @@ mod test {
                 db.query_row_and_then(query, [], |row| row.get(1).map_err(CustomError::Sqlite));
 
             match bad_type.unwrap_err() {
-                CustomError::Sqlite(Error::InvalidColumnType(..)) => (),
+                CustomError::Sqlite(Error::ColumnConversionFailure {
+                    source: FromSqlError::InvalidType,
+                    ..
+                }) => (),
                 err => panic!("Unexpected error {err}"),
             }
 
@@ -1298,6 +2153,47 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_query_arrow_with_params() -> Result<()> {
+        use arrow::array::{Array, Int64Array, StringArray};
+
+        let db = checked_memory_handle();
+        db.execute_batch(
+            "CREATE TABLE test(id INTEGER, name TEXT, score DOUBLE);
+             INSERT INTO test VALUES (1, 'a', 0.5), (2, NULL, NULL), (3, NULL, NULL), (4, 'd', 2.0)",
+        )?;
+        let mut stmt =
+            db.prepare("SELECT id, name, score, ?::BIGINT AS tag FROM test WHERE id BETWEEN ? AND ? ORDER BY id")?;
+        let mut run = |params: &[&dyn ToSql]| -> Result<_> {
+            let arrow = stmt.query_arrow(params)?;
+            Ok((arrow.get_schema(), arrow.collect::<Vec<RecordBatch>>()))
+        };
+
+        let (schema, all) = run(params![7, 1, 4])?;
+        let types: Vec<_> = schema.fields().iter().map(|f| f.data_type().clone()).collect();
+        assert_eq!(
+            types,
+            [DataType::Int32, DataType::Utf8, DataType::Float64, DataType::Int64]
+        );
+        assert_eq!(all.iter().map(RecordBatch::num_rows).sum::<usize>(), 4);
+        let tags = all[0].column(3).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(tags.value(0), 7);
+
+        // Only NULLs, including the parameter.
+        let (nulls_schema, nulls) = run(params![types::Null, 2, 3])?;
+        assert_eq!(nulls_schema, schema);
+        assert_eq!(nulls[0].schema(), schema);
+        assert_eq!(nulls[0].num_rows(), 2);
+        let names = nulls[0].column(1).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(names.null_count(), 2);
+        assert_eq!(nulls[0].column(3).null_count(), 2);
+
+        let (empty_schema, empty) = run(params![8, 10, 20])?;
+        assert_eq!(empty_schema, schema);
+        assert_eq!(empty.iter().map(RecordBatch::num_rows).sum::<usize>(), 0);
+        Ok(())
+    }
+
     #[test]
     fn round_trip_interval() -> Result<()> {
         let db = checked_memory_handle();
@@ -1306,15 +2202,15 @@ mod test {
         let d = Value::Interval {
             months: 1,
             days: 2,
-            nanos: 3,
+            nanos: 3_000,
         };
-        db.execute("INSERT INTO foo VALUES (?)", [d])?;
+        db.execute("INSERT INTO foo VALUES (?)", [&d])?;
 
         let mut stmt = db.prepare("SELECT t FROM foo")?;
         let mut rows = stmt.query([])?;
         let row = rows.next()?.unwrap();
-        let d: Value = row.get_unwrap(0);
-        assert_eq!(d, d);
+        let v: Value = row.get_unwrap(0);
+        assert_eq!(d, v);
         Ok(())
     }
 
@@ -1,4 +1,9 @@
-use crate::{Result, Statement, ToSql};
+#[cfg(feature = "serde")]
+use crate::Error;
+use crate::{
+    types::{owned_items, Value},
+    Result, Statement, ToSql,
+};
 
 mod sealed {
     /// This trait exists just to ensure that the only impls of `trait Params`
@@ -86,6 +91,30 @@ use sealed::Sealed;
 /// }
 /// ```
 ///
+/// ## Named parameters
+///
+/// Parameters named in the SQL, as `$name`, can be bound by name in any
+/// order, with a slice of `(name, value)` pairs, or with the
+/// [`duckdb::named_params!`](crate::named_params!) macro that builds one.
+/// The names may leave out the `$`, and positional parameters are named by
+/// their position. Binding fails with
+/// [`Error::InvalidParameterName`](crate::Error::InvalidParameterName) for a
+/// name the statement doesn't have, and
+/// [`Error::MissingParameters`](crate::Error::MissingParameters) if a
+/// parameter is left without a value. DuckDB doesn't allow named and
+/// positional parameters in the same statement.
+///
+/// ### Example (named)
+///
+/// ```rust,no_run
+/// # use duckdb::{named_params, Connection, Result};
+/// fn insert(conn: &Connection) -> Result<()> {
+///     let mut stmt = conn.prepare("INSERT INTO test (a, b) VALUES ($a, $b)")?;
+///     stmt.execute(named_params! { "$b": "blah", "$a": 1 })?;
+///     Ok(())
+/// }
+/// ```
+///
 /// ## No parameters
 ///
 /// You can just use an empty array literal for no params. The
@@ -149,6 +178,14 @@ impl Params for &[&dyn ToSql] {
     }
 }
 
+impl Sealed for &[(&str, &dyn ToSql)] {}
+impl Params for &[(&str, &dyn ToSql)] {
+    #[inline]
+    fn __bind_in(self, stmt: &mut Statement<'_>) -> Result<()> {
+        stmt.bind_parameters_named(self)
+    }
+}
+
 macro_rules! impl_for_array_ref {
     ($($N:literal)+) => {$(
         // These are already generic, and there's a shedload of them, so lets
@@ -268,6 +305,8 @@ impl_for_array_ref!(
 ///
 /// [limits]: crate::Connection::limit
 ///
+/// [`in_list`] does all of this for `IN` lists.
+///
 /// This complexity reflects the fact that `ParamsFromIter` is mainly intended
 /// for advanced use cases — most of the time you should know how many
 /// parameters you have statically (and if you don't, you're either doing
@@ -303,3 +342,201 @@ where
         stmt.bind_parameters(self.0)
     }
 }
+
+/// The most values [`InList`] binds as one parameter each.
+const MAX_IN_LIST_PLACEHOLDERS: usize = 1000;
+
+/// The values of a `col IN ({})` list, created by [`in_list`].
+///
+/// [`sql`](InList::sql) fills in the `{}` of a statement for the number of
+/// values, and the `InList` is then the parameters of the statement prepared
+/// from it:
+///
+/// - With no values, the `{}` becomes a subquery without rows, so `IN`
+///   matches nothing and `NOT IN` everything.
+/// - With up to 1000 values, it becomes a `?` for each value.
+/// - With more, it becomes `SELECT unnest(?)`, and the values are bound as a
+///   single `LIST`, which keeps the statement small. Like other lists, the
+///   values then can't be `NULL`.
+///
+/// The list must be the only parameter of the statement.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use duckdb::{in_list, Connection, Result};
+/// fn user_ids(conn: &Connection, names: &[String]) -> Result<Vec<i64>> {
+///     let names = in_list(names);
+///     let mut stmt = conn.prepare(&names.sql("SELECT id FROM users WHERE name IN ({})"))?;
+///     let ids = stmt.query_map(names, |row| row.get(0))?;
+///     ids.collect()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct InList<'a, T> {
+    values: &'a [T],
+}
+
+/// Constructor function for an [`InList`]. See its documentation for more.
+#[inline]
+pub fn in_list<T: ToSql>(values: &[T]) -> InList<'_, T> {
+    InList { values }
+}
+
+impl<T> InList<'_, T> {
+    /// Return `sql` with its first `{}` replaced by the placeholders of the
+    /// list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sql` has no `{}`.
+    pub fn sql(&self, sql: &str) -> String {
+        let list = match self.values.len() {
+            0 => "SELECT NULL WHERE false".to_owned(),
+            len if len <= MAX_IN_LIST_PLACEHOLDERS => {
+                let mut list = "?, ".repeat(len);
+                list.truncate(list.len() - 2);
+                list
+            }
+            _ => "SELECT unnest(?)".to_owned(),
+        };
+        assert!(sql.contains("{}"), "no {{}} to put the IN list in: {sql}");
+        sql.replacen("{}", &list, 1)
+    }
+}
+
+impl<T> Clone for InList<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for InList<'_, T> {}
+
+impl<T: ToSql> Sealed for InList<'_, T> {}
+
+impl<T: ToSql> Params for InList<'_, T> {
+    fn __bind_in(self, stmt: &mut Statement<'_>) -> Result<()> {
+        if self.values.len() <= MAX_IN_LIST_PLACEHOLDERS {
+            stmt.bind_parameters(self.values)
+        } else {
+            stmt.bind_parameters([Value::List(owned_items(self.values)?)])
+        }
+    }
+}
+
+/// `feature = "serde"` Named parameters read from the fields of a struct,
+/// created by [`to_params`].
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug)]
+pub struct NamedParams {
+    params: Vec<(String, Value)>,
+    strict: bool,
+}
+
+#[cfg(feature = "serde")]
+impl NamedParams {
+    /// Fail with `Error::InvalidParameterName` when a field has no matching
+    /// parameter in the statement, instead of ignoring it.
+    #[inline]
+    pub fn strict(mut self) -> NamedParams {
+        self.strict = true;
+        self
+    }
+}
+
+/// `feature = "serde"` Serialize `value`, a struct or a map with string
+/// keys, into parameters binding each field to the `$field` parameter of the
+/// same name.
+///
+/// Field values are serialized like [`Serialized`](crate::types::Serialized)
+/// parameters, with `None` bound as `NULL`. serde writes dates and times as
+/// ISO 8601 text, which DuckDB casts to the type of the parameter. Binding
+/// fails when a parameter has no field of its name, and fields without a
+/// parameter are ignored unless the parameters are made
+/// [`strict`](NamedParams::strict).
+///
+/// ```rust,no_run
+/// # use duckdb::{to_params, Connection, Result};
+/// #[derive(serde::Serialize)]
+/// struct Person {
+///     name: String,
+///     age: Option<u8>,
+/// }
+///
+/// fn insert(conn: &Connection, person: &Person) -> Result<usize> {
+///     let mut stmt = conn.prepare("INSERT INTO people (name, age) VALUES ($name, $age)")?;
+///     stmt.execute(to_params(person)?)
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn to_params<T: serde::Serialize + ?Sized>(value: &T) -> Result<NamedParams> {
+    match crate::types::to_value(value) {
+        Ok(Value::Struct(fields)) => Ok(NamedParams {
+            params: fields.into_iter().collect(),
+            strict: false,
+        }),
+        Ok(_) => Err(Error::ToSqlConversionFailure(
+            "parameters must serialize to a struct or a map".into(),
+        )),
+        Err(err) => Err(Error::ToSqlConversionFailure(Box::new(err))),
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Sealed for NamedParams {}
+
+#[cfg(feature = "serde")]
+impl Params for NamedParams {
+    #[inline]
+    fn __bind_in(self, stmt: &mut Statement<'_>) -> Result<()> {
+        stmt.bind_named_parameters(&self.params, self.strict)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::in_list;
+    use crate::{Connection, Result};
+
+    fn count_in(db: &Connection, ids: &[i64]) -> Result<(i64, i64)> {
+        let ids = in_list(ids);
+        let count = |sql: &str| {
+            let mut stmt = db.prepare(&ids.sql(sql))?;
+            stmt.query_row(ids, |r| r.get(0))
+        };
+        Ok((
+            count("SELECT count(*) FROM range(200000) t(id) WHERE id IN ({})")?,
+            count("SELECT count(*) FROM range(200000) t(id) WHERE id NOT IN ({})")?,
+        ))
+    }
+
+    #[test]
+    fn test_in_list() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        assert_eq!(count_in(&db, &[])?, (0, 200_000));
+        assert_eq!(count_in(&db, &[7])?, (1, 199_999));
+
+        let ids: Vec<i64> = (0..2000).step_by(2).collect();
+        assert_eq!(ids.len(), 1000);
+        assert_eq!(count_in(&db, &ids)?, (1000, 199_000));
+        assert_eq!(in_list(&ids).sql("{}").matches('?').count(), 1000);
+
+        // Longer lists are bound as a single list.
+        let ids: Vec<i64> = (0..100_000).map(|i| i * 3).collect();
+        assert_eq!(in_list(&ids).sql("x IN ({})"), "x IN (SELECT unnest(?))");
+        assert_eq!(count_in(&db, &ids)?, (66_667, 133_333));
+
+        let names = ["a".to_owned(), "b".to_owned()];
+        let names = in_list(&names);
+        assert_eq!(names.sql("name IN ({})"), "name IN (?, ?)");
+        let found: i64 = db.query_row(
+            &names.sql("SELECT count(*) FROM (VALUES ('a'), ('c')) t(name) WHERE name IN ({})"),
+            names,
+            |r| r.get(0),
+        )?;
+        assert_eq!(found, 1);
+        Ok(())
+    }
+}
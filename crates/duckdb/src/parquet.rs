@@ -0,0 +1,326 @@
+//! Reading and writing Parquet files, without writing their options as SQL
+
+use std::path::{Path, PathBuf};
+
+use arrow::record_batch::RecordBatch;
+
+use crate::{pragma::Sql, read_csv::literal, Connection, CopyOptions, Error, ExportFormat, Result};
+
+/// Reads Parquet files into a table, a view or Arrow record batches, built
+/// with [`Connection::read_parquet`].
+#[derive(Clone, Debug)]
+#[must_use = "a ParquetReadBuilder reads nothing until create_table, create_view or query_arrow is called"]
+pub struct ParquetReadBuilder<'conn> {
+    conn: &'conn Connection,
+    paths: Vec<PathBuf>,
+    hive_partitioning: Option<bool>,
+    union_by_name: bool,
+    filename: bool,
+}
+
+/// Options for [`Connection::write_parquet`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParquetWriteOptions {
+    /// The compression to write with, such as `zstd` or `uncompressed`,
+    /// instead of `snappy`.
+    pub compression: Option<String>,
+    /// How many rows to write in each row group, instead of DuckDB's default.
+    pub row_group_size: Option<usize>,
+    /// The columns to partition the result by, each value of them written to
+    /// files of its own under a `column=value` directory.
+    pub partition_by: Vec<String>,
+    /// Replace the file at the path, or write the partitions into a
+    /// directory that holds files, replacing files of the same name.
+    pub overwrite: bool,
+}
+
+impl Connection {
+    /// Start reading the Parquet file at `path`, which may be a glob such as
+    /// `data/**/*.parquet` matching several files.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn load(conn: &Connection) -> Result<()> {
+    ///     conn.read_parquet("sales/**/*.parquet")
+    ///         .hive_partitioning(true)
+    ///         .filename(true)
+    ///         .create_table("sales")
+    /// }
+    /// ```
+    pub fn read_parquet<P: AsRef<Path>>(&self, path: P) -> ParquetReadBuilder<'_> {
+        ParquetReadBuilder {
+            conn: self,
+            paths: vec![path.as_ref().to_owned()],
+            hive_partitioning: None,
+            union_by_name: false,
+            filename: false,
+        }
+    }
+
+    /// Write the result of `query` to the Parquet file at `path`, or to a
+    /// directory of them if `options.partition_by` is set, returning the
+    /// number of rows written. See [`copy_to`](Connection::copy_to) to bind
+    /// parameters of the query.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, ParquetWriteOptions, Result};
+    /// fn archive(conn: &Connection) -> Result<usize> {
+    ///     let options = ParquetWriteOptions {
+    ///         compression: Some("zstd".to_owned()),
+    ///         partition_by: vec!["year".to_owned()],
+    ///         ..ParquetWriteOptions::default()
+    ///     };
+    ///     conn.write_parquet("SELECT * FROM events", "archive", options)
+    /// }
+    /// ```
+    ///
+    /// ## Failure
+    ///
+    /// Will return [`Error::CopyTargetExists`] if `options.overwrite` isn't
+    /// set and the file at `path` exists, or the partitions would be written
+    /// into a directory that holds files, [`Error::ParquetFileFailed`] if
+    /// DuckDB fails on a file, and `Err` if `path` isn't valid UTF-8 or the
+    /// query fails.
+    pub fn write_parquet<P: AsRef<Path>>(&self, query: &str, path: P, options: ParquetWriteOptions) -> Result<usize> {
+        let copy = CopyOptions {
+            format: ExportFormat::Parquet,
+            path: path.as_ref().to_owned(),
+            compression: options.compression,
+            partition_by: options.partition_by,
+            overwrite: options.overwrite,
+        };
+        self.copy_to_with_row_groups(query, [], copy, options.row_group_size)
+            .map_err(with_parquet_file)
+    }
+}
+
+impl ParquetReadBuilder<'_> {
+    /// Read the Parquet file at `path` too, which may be a glob as well.
+    pub fn add_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.paths.push(path.as_ref().to_owned());
+        self
+    }
+
+    /// Whether to add a column for each `column=value` directory in the
+    /// paths of the files, as [`write_parquet`](Connection::write_parquet)
+    /// writes partitions. DuckDB detects it from the paths if it isn't set.
+    pub fn hive_partitioning(mut self, hive_partitioning: bool) -> Self {
+        self.hive_partitioning = Some(hive_partitioning);
+        self
+    }
+
+    /// Match the columns of the files by name rather than position, so that
+    /// files with different columns can be read together. A column missing
+    /// from a file is NULL in its rows.
+    pub fn union_by_name(mut self, union_by_name: bool) -> Self {
+        self.union_by_name = union_by_name;
+        self
+    }
+
+    /// Whether to add a `filename` column holding the path of the file each
+    /// row was read from.
+    pub fn filename(mut self, filename: bool) -> Self {
+        self.filename = filename;
+        self
+    }
+
+    /// Create the table `name` holding the rows of the files.
+    ///
+    /// ## Failure
+    ///
+    /// Will return [`Error::ParquetFileFailed`] if DuckDB fails on a file,
+    /// such as one that isn't Parquet or a glob that matches none, and `Err`
+    /// if a path isn't valid UTF-8 or the table can't be created.
+    pub fn create_table(self, name: &str) -> Result<()> {
+        self.create("TABLE", name)
+    }
+
+    /// Create the view `name`, which reads the files each time it is
+    /// queried.
+    ///
+    /// ## Failure
+    ///
+    /// Will return [`Error::ParquetFileFailed`] if DuckDB fails on a file,
+    /// and `Err` if a path isn't valid UTF-8 or the view can't be created.
+    pub fn create_view(self, name: &str) -> Result<()> {
+        self.create("VIEW", name)
+    }
+
+    /// Read the rows of the files as Arrow record batches.
+    ///
+    /// ## Failure
+    ///
+    /// Will return [`Error::ParquetFileFailed`] if DuckDB fails on a file,
+    /// and `Err` if a path isn't valid UTF-8.
+    pub fn query_arrow(self) -> Result<Vec<RecordBatch>> {
+        let sql = format!("SELECT * FROM {}", self.read_parquet_sql()?);
+        let mut stmt = self.conn.prepare(&sql).map_err(with_parquet_file)?;
+        let batches = stmt.query_arrow([]).map_err(with_parquet_file)?;
+        Ok(batches.collect())
+    }
+
+    fn create(self, kind: &str, name: &str) -> Result<()> {
+        let mut target = Sql::new();
+        target.push_identifier(name);
+        let sql = format!(
+            "CREATE {kind} {} AS SELECT * FROM {}",
+            &*target,
+            self.read_parquet_sql()?
+        );
+        self.conn.execute_batch(&sql).map_err(with_parquet_file)
+    }
+
+    /// The `read_parquet(...)` call reading the files with the options set.
+    fn read_parquet_sql(&self) -> Result<String> {
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| {
+                path.to_str()
+                    .map(literal)
+                    .ok_or_else(|| Error::InvalidPath(path.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut sql = format!("read_parquet([{}]", paths.join(", "));
+        if let Some(hive_partitioning) = self.hive_partitioning {
+            sql.push_str(&format!(", hive_partitioning = {hive_partitioning}"));
+        }
+        if self.union_by_name {
+            sql.push_str(", union_by_name = true");
+        }
+        if self.filename {
+            sql.push_str(", filename = true");
+        }
+        sql.push(')');
+        Ok(sql)
+    }
+}
+
+/// Turns an error DuckDB reported about a file into an
+/// [`Error::ParquetFileFailed`] naming the file.
+fn with_parquet_file(err: Error) -> Error {
+    let Error::DuckDBFailure(_, Some(msg)) = err.without_context() else {
+        return err;
+    };
+    match error_file(msg) {
+        Some(file) => Error::ParquetFileFailed {
+            file: file.to_owned(),
+            error: Box::new(err),
+        },
+        None => err,
+    }
+}
+
+/// The file a DuckDB error message is about. DuckDB quotes it without
+/// escaping, so it ends at the last quote of the line.
+fn error_file(msg: &str) -> Option<&str> {
+    let line = msg.lines().next()?;
+    ["file '", "File '", "file \"", "pattern \""].iter().find_map(|prefix| {
+        let quote = prefix.chars().last()?;
+        let start = line.find(prefix)? + prefix.len();
+        let end = start + line[start..].rfind(quote)?;
+        Some(&line[start..end])
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::error_file;
+    #[cfg(feature = "parquet")]
+    use super::ParquetWriteOptions;
+    #[cfg(feature = "parquet")]
+    use crate::{Connection, Error, Result};
+
+    #[test]
+    fn test_error_file() {
+        assert_eq!(
+            error_file("Invalid Input Error: File '/d/it's.parquet' too small to be a Parquet file"),
+            Some("/d/it's.parquet")
+        );
+        assert_eq!(
+            error_file("Invalid Input Error: No magic bytes found at end of file '/d/a.parquet'"),
+            Some("/d/a.parquet")
+        );
+        assert_eq!(
+            error_file("IO Error: No files found that match the pattern \"/d/*.parquet\""),
+            Some("/d/*.parquet")
+        );
+        assert_eq!(error_file("Binder Error: Referenced column \"x\" not found"), None);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("it's \"sales\"");
+        let db = Connection::open_in_memory()?;
+        let query = "SELECT range AS id, ['north', 'south'][range % 2 + 1] AS region FROM range(10)";
+        let options = ParquetWriteOptions {
+            compression: Some("zstd".to_owned()),
+            row_group_size: Some(2),
+            partition_by: vec!["region".to_owned()],
+            overwrite: false,
+        };
+        assert_eq!(db.write_parquet(query, &out, options.clone())?, 10);
+        assert_eq!(
+            db.write_parquet(query, &out, options).unwrap_err(),
+            Error::CopyTargetExists(out.clone())
+        );
+
+        db.read_parquet(out.join("*").join("*.parquet"))
+            .hive_partitioning(true)
+            .filename(true)
+            .create_table("sales")?;
+        let mut stmt =
+            db.prepare("SELECT region, count(*), min(filename) FROM sales GROUP BY region ORDER BY region")?;
+        let rows: Vec<(String, i64, String)> = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+            .collect::<Result<_>>()?;
+        assert_eq!(rows.len(), 2);
+        for (region, count, filename) in rows {
+            assert_eq!(count, 5);
+            let partition = out.join(format!("region={region}"));
+            assert!(filename.starts_with(partition.to_str().unwrap()), "{filename}");
+        }
+
+        // Files with other columns read together by name.
+        let extra = dir.path().join("extra.parquet");
+        db.write_parquet("SELECT 10 AS id, true AS flag", &extra, ParquetWriteOptions::default())?;
+        let batches = db
+            .read_parquet(out.join("region=north").join("*.parquet"))
+            .add_path(&extra)
+            .hive_partitioning(false)
+            .union_by_name(true)
+            .query_arrow()?;
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 6);
+        let schema = batches[0].schema();
+        let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, ["id", "flag"]);
+        Ok(())
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_read_parquet_bad_file() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let bad = dir.path().join("it's.parquet");
+        std::fs::write(&bad, "not parquet").unwrap();
+        let db = Connection::open_in_memory()?;
+        match db.read_parquet(&bad).query_arrow().unwrap_err() {
+            Error::ParquetFileFailed { file, .. } => assert_eq!(file, bad.to_str().unwrap()),
+            err => panic!("unexpected error {err:?}"),
+        }
+
+        let missing = dir.path().join("missing").join("*.parquet");
+        match db.read_parquet(&missing).create_view("missing").unwrap_err() {
+            Error::ParquetFileFailed { file, .. } => assert_eq!(file, missing.to_str().unwrap()),
+            err => panic!("unexpected error {err:?}"),
+        }
+        Ok(())
+    }
+}
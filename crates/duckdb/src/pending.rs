@@ -0,0 +1,170 @@
+//! Executing a statement a task at a time
+
+use crate::{ffi, Arrow, Params, Result, Rows, Statement};
+
+/// The state of a [`PendingQuery`] after [`poll`](PendingQuery::poll).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PendingState {
+    /// The query has finished, and [`finish`](PendingQuery::finish) returns
+    /// its result right away.
+    Ready,
+    /// The query has more tasks to run.
+    NotReady,
+    /// The query failed, and [`finish`](PendingQuery::finish) returns why.
+    Error,
+}
+
+/// A query started with [`Statement::start_pending`], which runs when it is
+/// [polled](PendingQuery::poll), a task at a time.
+///
+/// Dropping it before it finishes abandons the query.
+pub struct PendingQuery<'stmt, 'conn> {
+    stmt: &'stmt mut Statement<'conn>,
+    pending: PendingHandle,
+}
+
+/// A `duckdb_pending_result`, destroyed on drop.
+struct PendingHandle(ffi::duckdb_pending_result);
+
+impl Drop for PendingHandle {
+    fn drop(&mut self) {
+        unsafe { ffi::duckdb_destroy_pending(&mut self.0) };
+    }
+}
+
+impl<'conn> Statement<'conn> {
+    /// Start the prepared statement without running it, returning a
+    /// [`PendingQuery`] that runs a task of the query each time it is
+    /// [polled](PendingQuery::poll). This lets an application with a
+    /// scheduler of its own interleave a query with other work on the same
+    /// thread.
+    ///
+    /// DuckDB's own threads run tasks of the query too, unless the
+    /// connection is limited to one with `SET threads = 1`. Nothing else
+    /// should run on the connection until the query is finished or dropped.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, PendingState, Result};
+    /// fn count(conn: &Connection, mut other_work: impl FnMut()) -> Result<i64> {
+    ///     let mut stmt = conn.prepare("SELECT count(*) FROM big_table")?;
+    ///     let mut pending = stmt.start_pending([])?;
+    ///     while pending.poll() == PendingState::NotReady {
+    ///         other_work();
+    ///     }
+    ///     let mut rows = pending.finish()?;
+    ///     rows.next()?.unwrap().get(0)
+    /// }
+    /// ```
+    ///
+    /// ## Failure
+    ///
+    /// Will return `Err` if binding parameters fails or the statement can't
+    /// be started. Errors running it are returned by
+    /// [`finish`](PendingQuery::finish).
+    pub fn start_pending<P: Params>(&mut self, params: P) -> Result<PendingQuery<'_, 'conn>> {
+        params.__bind_in(self)?;
+        let pending = PendingHandle(self.stmt.pending(false)?);
+        Ok(PendingQuery { stmt: self, pending })
+    }
+}
+
+impl<'stmt> PendingQuery<'stmt, '_> {
+    /// Run a task of the query, returning whether it is finished.
+    pub fn poll(&mut self) -> PendingState {
+        let state = unsafe { ffi::duckdb_pending_execute_task(self.pending.0) };
+        match state {
+            ffi::duckdb_pending_state_DUCKDB_PENDING_RESULT_READY => PendingState::Ready,
+            ffi::duckdb_pending_state_DUCKDB_PENDING_ERROR => PendingState::Error,
+            // Other threads are running the remaining tasks.
+            _ => PendingState::NotReady,
+        }
+    }
+
+    /// Run the rest of the query, if any, and return its rows, as
+    /// [`Statement::query`] does.
+    ///
+    /// ## Failure
+    ///
+    /// Will return `Err` if the query fails.
+    pub fn finish(self) -> Result<Rows<'stmt>> {
+        let stmt = self.run()?;
+        Ok(Rows::new(stmt))
+    }
+
+    /// Run the rest of the query, if any, and return its result as Arrow
+    /// record batches, as [`Statement::query_arrow`] does.
+    ///
+    /// ## Failure
+    ///
+    /// Will return `Err` if the query fails.
+    pub fn finish_arrow(self) -> Result<Arrow<'stmt>> {
+        let stmt = self.run()?;
+        Ok(Arrow::new(stmt))
+    }
+
+    fn run(self) -> Result<&'stmt Statement<'stmt>> {
+        let PendingQuery { stmt, pending } = self;
+        let options = stmt.conn.db.borrow_mut().arrow_options()?;
+        unsafe { stmt.stmt.execute_pending(pending.0, &options)? };
+        Ok(stmt)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PendingState;
+    use crate::{Connection, Result};
+
+    #[test]
+    fn test_pending_query() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        // Only polling runs the query.
+        db.execute_batch("SET threads = 1")?;
+        let mut stmt = db.prepare("SELECT sum(i) FROM range(?) t(i)")?;
+        let mut pending = stmt.start_pending([10_000_000])?;
+        let mut not_ready = 0;
+        loop {
+            match pending.poll() {
+                PendingState::NotReady => not_ready += 1,
+                PendingState::Ready => break,
+                PendingState::Error => panic!("query failed"),
+            }
+        }
+        assert!(not_ready > 0);
+        let mut rows = pending.finish()?;
+        let sum: i128 = rows.next()?.unwrap().get(0)?;
+        assert_eq!(sum, 49_999_995_000_000);
+        drop(rows);
+
+        // Finishing runs what is left.
+        let batches: Vec<_> = stmt.start_pending([10])?.finish_arrow()?.collect();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+        // An abandoned query leaves the connection usable.
+        let mut pending = stmt.start_pending([10_000_000])?;
+        assert_eq!(pending.poll(), PendingState::NotReady);
+        drop(pending);
+        let n: i64 = db.query_row("SELECT 42", [], |r| r.get(0))?;
+        assert_eq!(n, 42);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pending_query_error() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("SET threads = 1")?;
+        let mut stmt =
+            db.prepare("SELECT sum(CASE WHEN i = 5000000 THEN error('boom') ELSE i END) FROM range(10000000) t(i)")?;
+        let mut pending = stmt.start_pending([])?;
+        let mut state = pending.poll();
+        while state == PendingState::NotReady {
+            state = pending.poll();
+        }
+        assert_eq!(state, PendingState::Error);
+        let err = pending.finish().map(|_| ()).unwrap_err();
+        assert!(err.to_string().contains("boom"), "{err}");
+        Ok(())
+    }
+}
@@ -83,4 +83,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_query_polars_with_params() -> Result<()> {
+        let db = checked_memory_handle();
+        db.execute_batch(
+            "CREATE TABLE test(t INTEGER, s TEXT); INSERT INTO test VALUES (1, 'a'), (2, NULL), (3, 'c')",
+        )?;
+        let mut stmt = db.prepare("SELECT t, s FROM test WHERE t BETWEEN ? AND ? ORDER BY t")?;
+
+        let df = accumulate_dataframes_vertical_unchecked(stmt.query_polars([1, 3])?);
+        assert_eq!(df.height(), 3);
+        let schema = df.schema();
+
+        // A result with only NULLs in `s` keeps its type.
+        let df = accumulate_dataframes_vertical_unchecked(stmt.query_polars([2, 2])?);
+        assert_eq!(df.schema(), schema);
+        assert_eq!(df.column("s").unwrap().null_count(), 1);
+
+        let df = accumulate_dataframes_vertical_unchecked(stmt.query_polars([3, 3])?);
+        assert_eq!(df.schema(), schema);
+        assert_eq!(df.column("s").unwrap().utf8().unwrap().get(0), Some("c"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_series_integer_with_nulls() -> Result<()> {
+        let db = checked_memory_handle();
+        db.execute_batch("CREATE TABLE test(t INTEGER); INSERT INTO test VALUES (1), (NULL), (3);")?;
+
+        let series = db.query_series("SELECT t FROM test ORDER BY t NULLS LAST", [])?;
+        assert_eq!(series.name(), "t");
+        assert_eq!(series.dtype(), &DataType::Int32);
+        assert_eq!(series.null_count(), 1);
+        assert_eq!(
+            series.i32().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![Some(1), Some(3), None]
+        );
+
+        let empty = db.prepare("SELECT t FROM test WHERE t > ?")?.query_series([10])?;
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.dtype(), &DataType::Int32);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_series_varchar() -> Result<()> {
+        let db = checked_memory_handle();
+        let series = db.query_series("SELECT * FROM (VALUES ('a'), ('b'), (NULL)) t(s)", [])?;
+        assert_eq!(series.name(), "s");
+        assert_eq!(
+            series.utf8().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![Some("a"), Some("b"), None]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_series_multiple_columns() -> Result<()> {
+        let db = checked_memory_handle();
+        let err = db.query_series("SELECT 1 AS a, 2 AS b", []).unwrap_err();
+        assert_eq!(err, crate::Error::InvalidColumnCount(2, 1));
+        Ok(())
+    }
 }
@@ -56,6 +56,19 @@ impl Sql {
         }
     }
 
+    /// Quotes `s` even if it is a valid identifier, as it may be a keyword
+    /// such as `order`.
+    pub fn push_quoted_identifier(&mut self, s: &str) -> Result<()> {
+        if s.is_empty() {
+            return Err(Error::DuckDBFailure(
+                ffi::Error::new(ffi::DuckDBError),
+                Some("Invalid identifier \"\"".to_owned()),
+            ));
+        }
+        self.wrap_and_escape(s, '"');
+        Ok(())
+    }
+
     pub fn push_value(&mut self, value: &dyn ToSql) -> Result<()> {
         let value = value.to_sql()?;
         let value = match value {
@@ -103,6 +116,10 @@ impl Sql {
         self.buf.push('.');
     }
 
+    pub fn push_comma(&mut self) {
+        self.buf.push(',');
+    }
+
     pub fn push_equal_sign(&mut self) {
         self.buf.push('=');
     }
@@ -355,6 +372,17 @@ mod test {
         assert_eq!("'value''; --'", sql.as_str());
     }
 
+    #[test]
+    fn push_quoted_identifier() -> Result<()> {
+        let mut sql = Sql::new();
+        sql.push_quoted_identifier("order")?;
+        sql.push_dot();
+        sql.push_quoted_identifier("a \"b\"")?;
+        assert_eq!(r#""order"."a ""b""""#, sql.as_str());
+        assert!(sql.push_quoted_identifier("").is_err());
+        Ok(())
+    }
+
     #[test]
     #[ignore]
     fn test_locking_mode() -> Result<()> {
@@ -0,0 +1,310 @@
+//! Query profiling: operator timings and cardinalities of executed statements
+
+#[cfg(feature = "serde_json")]
+use std::time::Duration;
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(feature = "serde_json")]
+use serde_json::Value;
+
+#[cfg(feature = "serde_json")]
+use crate::{ffi, Statement};
+use crate::{pragma::Sql, Connection, Error, Result};
+
+/// The format DuckDB writes query profiles in, see
+/// [`Connection::enable_profiling`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProfilingFormat {
+    /// DuckDB's JSON profile, read back as a `QueryProfile` with
+    /// `feature = "serde_json"`.
+    #[default]
+    Json,
+}
+
+/// `feature = "serde_json"` The profile of an executed query, a tree of the
+/// physical operators that ran it, as returned by
+/// [`Statement::last_profile`].
+///
+/// The root is the query itself, its `extra_info` the SQL and its `timing`
+/// the time the whole query took.
+#[cfg(feature = "serde_json")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryProfile {
+    /// The name of the operator, such as `HASH_GROUP_BY` or `TABLE_SCAN`.
+    /// `Query` for the root.
+    pub name: String,
+    /// The time the operator spent, not counting its children.
+    pub timing: Duration,
+    /// The number of rows the operator produced.
+    pub cardinality: u64,
+    /// Details of the operator, such as the expressions it computes, one per
+    /// line.
+    pub extra_info: String,
+    /// The operators whose output this one consumes.
+    pub children: Vec<QueryProfile>,
+}
+
+#[cfg(feature = "serde_json")]
+impl QueryProfile {
+    /// The first operator called `name` in the tree, depth first, starting
+    /// with this one.
+    pub fn find(&self, name: &str) -> Option<&QueryProfile> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+}
+
+/// The file DuckDB writes the profile of each query to while profiling is
+/// enabled, removed when profiling is disabled.
+#[derive(Debug)]
+pub(crate) struct ProfileOutput {
+    path: PathBuf,
+}
+
+impl ProfileOutput {
+    fn new() -> ProfileOutput {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "duckdb-rs-profile-{}-{}.json",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        );
+        ProfileOutput {
+            path: env::temp_dir().join(name),
+        }
+    }
+
+    /// The profile written since the last call, if any.
+    fn take(&self) -> Option<String> {
+        let profile = fs::read_to_string(&self.path).ok()?;
+        let _ = fs::remove_file(&self.path);
+        Some(profile)
+    }
+}
+
+impl Drop for ProfileOutput {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl Connection {
+    /// Profile the queries this connection runs from now on, so that
+    /// `Statement::last_profile` returns the profile of the statement's last
+    /// execution, with `feature = "serde_json"`. Profiling adds some overhead
+    /// to every query.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, ProfilingFormat, Result};
+    /// # #[cfg(feature = "serde_json")]
+    /// fn slowest(conn: &Connection) -> Result<()> {
+    ///     conn.enable_profiling(ProfilingFormat::Json)?;
+    ///     let mut stmt = conn.prepare("SELECT a, count(*) FROM t GROUP BY a")?;
+    ///     stmt.execute([])?;
+    ///     if let Some(profile) = stmt.last_profile()? {
+    ///         println!("{} took {:?}", profile.extra_info, profile.timing);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the underlying DuckDB call fails.
+    pub fn enable_profiling(&self, format: ProfilingFormat) -> Result<()> {
+        let output = ProfileOutput::new();
+        let path = output
+            .path
+            .to_str()
+            .ok_or_else(|| Error::InvalidPath(output.path.clone()))?;
+        // The output is set first, as the query setting it would be profiled
+        // and printed otherwise.
+        let mut sql = Sql::new();
+        sql.push_pragma(None, "profiling_output")?;
+        sql.push_equal_sign();
+        sql.push_string_literal(path);
+        self.execute_batch(&sql)?;
+        self.execute_batch(match format {
+            ProfilingFormat::Json => "PRAGMA enable_profiling = 'json'",
+        })?;
+        *self.profile_output.borrow_mut() = Some(output);
+        Ok(())
+    }
+
+    /// Stop profiling the queries this connection runs.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the underlying DuckDB call fails.
+    pub fn disable_profiling(&self) -> Result<()> {
+        self.execute_batch("PRAGMA disable_profiling")?;
+        *self.profile_output.borrow_mut() = None;
+        Ok(())
+    }
+
+    /// The profile of the query this connection finished last, if profiling
+    /// is enabled and it hasn't been taken yet.
+    pub(crate) fn take_profile(&self) -> Option<String> {
+        self.profile_output.borrow().as_ref().and_then(ProfileOutput::take)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl Statement<'_> {
+    /// `feature = "serde_json"` The profile of the last execution of this statement, or `None` if it
+    /// hasn't been executed since [`Connection::enable_profiling`] was
+    /// called, or its last execution failed.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the profile DuckDB wrote can't be read.
+    pub fn last_profile(&self) -> Result<Option<QueryProfile>> {
+        self.profile.as_deref().map(parse_profile).transpose()
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn parse_profile(profile: &str) -> Result<QueryProfile> {
+    let invalid = || {
+        Error::DuckDBFailure(
+            ffi::Error::new(ffi::DuckDBError),
+            Some(format!("invalid query profile: {profile}")),
+        )
+    };
+    let json = parse_json(profile).ok_or_else(invalid)?;
+    to_profile(&json).ok_or_else(invalid)
+}
+
+/// Parses a profile DuckDB wrote. It leaves control characters other than
+/// the common ones unescaped, which JSON doesn't allow in strings.
+#[cfg(feature = "serde_json")]
+pub(crate) fn parse_json(text: &str) -> Option<Value> {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c < ' ' => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    serde_json::from_str(&escaped).ok()
+}
+
+#[cfg(feature = "serde_json")]
+fn to_profile(json: &Value) -> Option<QueryProfile> {
+    let timing = json.get("timing")?.as_f64()?;
+    Some(QueryProfile {
+        name: json.get("name")?.as_str()?.to_owned(),
+        timing: Duration::try_from_secs_f64(timing).ok()?,
+        cardinality: json.get("cardinality")?.as_f64()? as u64,
+        // The root spells the key differently.
+        extra_info: json
+            .get("extra_info")
+            .or_else(|| json.get("extra-info"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned(),
+        children: match json.get("children") {
+            Some(Value::Array(children)) => children.iter().map(to_profile).collect::<Option<_>>()?,
+            _ => Vec::new(),
+        },
+    })
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod test {
+    use std::time::Duration;
+
+    use super::{parse_json, parse_profile, ProfilingFormat, QueryProfile};
+    use crate::{Connection, Result};
+
+    #[test]
+    fn test_profile_aggregation() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t AS SELECT range % 100 AS a, range AS b FROM range(1000000)")?;
+        let sql = "SELECT a, sum(b) FROM t GROUP BY a";
+        let mut stmt = db.prepare(sql)?;
+        stmt.execute([])?;
+        assert_eq!(stmt.last_profile()?, None);
+
+        db.enable_profiling(ProfilingFormat::Json)?;
+        stmt.execute([])?;
+        let profile = stmt.last_profile()?.unwrap();
+        assert_eq!(profile.name, "Query");
+        assert_eq!(profile.extra_info, sql);
+        let group_by = profile.find("HASH_GROUP_BY").unwrap();
+        assert!(group_by.timing > Duration::ZERO, "{group_by:?}");
+        assert_eq!(group_by.cardinality, 100);
+        assert!(!group_by.children.is_empty());
+
+        // Other queries leave the statement's profile alone.
+        db.execute_batch("SELECT 42")?;
+        let count: i64 = db.query_row("SELECT count(*) FROM t", [], |row| row.get(0))?;
+        assert_eq!(count, 1000000);
+        assert_eq!(stmt.last_profile()?.unwrap().extra_info, sql);
+
+        db.disable_profiling()?;
+        stmt.execute([])?;
+        assert_eq!(stmt.last_profile()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_profile() -> Result<()> {
+        let profile = parse_profile(
+            r#"{
+   "name":  "Query",
+   "result": 0.5,
+   "timing": 0.5,
+   "cardinality": 1,
+   "extra-info": "SELECT '\"\\\n\u0001é'",
+   "timings": [
+   {
+   "annotation": "optimizer",
+   "timing": 0.000100
+   }
+   ],
+   "children": [
+    {
+       "name": "PROJECTION",
+       "timing":0.250000,
+       "cardinality":1,
+       "extra_info": "x\n",
+       "children": [
+       ]
+    }
+   ]
+}"#,
+        )?;
+        assert_eq!(
+            profile,
+            QueryProfile {
+                name: "Query".to_owned(),
+                timing: Duration::from_millis(500),
+                cardinality: 1,
+                extra_info: "SELECT '\"\\\n\u{1}é'".to_owned(),
+                children: vec![QueryProfile {
+                    name: "PROJECTION".to_owned(),
+                    timing: Duration::from_millis(250),
+                    cardinality: 1,
+                    extra_info: "x\n".to_owned(),
+                    children: vec![],
+                }],
+            }
+        );
+        // Control characters DuckDB doesn't escape.
+        assert_eq!(parse_json("[\"a\u{1}b\"]"), Some(serde_json::json!(["a\u{1}b"])));
+        assert!(parse_profile(r#"{ "result": "disabled" }"#).is_err());
+        assert!(parse_profile(r#"{ "name": "Query" "#).is_err());
+        Ok(())
+    }
+}
@@ -40,13 +40,18 @@
 //!         .unwrap()
 //! }
 //! ```
-use crate::{Config, Connection, Error, Result};
+use crate::{Config, Connection, Error, Result, TransactionState};
 use std::{
     path::Path,
     sync::{Arc, Mutex},
 };
 
 /// An `r2d2::ManageConnection` for `duckdb::Connection`s.
+///
+/// The pooled connections are clones of one connection, made with
+/// [`Connection::try_clone`], so they share its database. In particular, an
+/// in-memory pool has a single database, whose tables every pooled connection
+/// sees.
 pub struct DuckdbConnectionManager {
     connection: Arc<Mutex<Connection>>,
 }
@@ -78,6 +83,16 @@ impl DuckdbConnectionManager {
             connection: Arc::new(Mutex::new(Connection::open_in_memory_with_flags(config)?)),
         })
     }
+
+    /// Runs `hook` on every connection the pool makes from now on, before
+    /// handing it out, see [`Connection::set_init_hook`]. An error from
+    /// `hook` is returned when getting a connection from the pool.
+    pub fn set_init_hook<F>(&self, hook: F) -> Result<()>
+    where
+        F: Fn(&Connection) -> Result<()> + Send + Sync + 'static,
+    {
+        self.connection.lock().unwrap().set_init_hook(hook)
+    }
 }
 
 impl r2d2::ManageConnection for DuckdbConnectionManager {
@@ -89,8 +104,14 @@ impl r2d2::ManageConnection for DuckdbConnectionManager {
         conn.try_clone()
     }
 
+    /// A connection is invalid if it was handed back with a transaction still
+    /// open, or one that failed, which the next user would otherwise inherit.
     fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
-        conn.execute_batch("").map_err(Into::into)
+        match conn.transaction_state()? {
+            TransactionState::None => Ok(()),
+            TransactionState::Active => Err(Error::NestedTransaction),
+            TransactionState::Failed => Err(Error::FailedTransaction),
+        }
     }
 
     fn has_broken(&self, _: &mut Self::Connection) -> bool {
@@ -169,6 +190,98 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_file_concurrent_writes() -> Result<()> {
+        let dir = TempDir::new("r2d2-duckdb").expect("Could not create temporary directory");
+        let manager = DuckdbConnectionManager::file(dir.path().join("pool.db"))?;
+        let pool = r2d2::Pool::builder().max_size(4).build(manager).unwrap();
+        pool.get()
+            .unwrap()
+            .execute_batch("CREATE TABLE t (worker INTEGER, i INTEGER)")?;
+
+        let workers = (0..4)
+            .map(|worker| {
+                let pool = pool.clone();
+                thread::spawn(move || -> Result<()> {
+                    let conn = pool.get().unwrap();
+                    for i in 0..50 {
+                        conn.execute("INSERT INTO t VALUES (?, ?)", [worker, i])?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            worker.join().unwrap()?;
+        }
+
+        let (rows, workers): (i64, i64) =
+            pool.get()
+                .unwrap()
+                .query_row("SELECT count(*), count(DISTINCT worker) FROM t", [], |r| {
+                    Ok((r.get(0)?, r.get(1)?))
+                })?;
+        assert_eq!((rows, workers), (200, 4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_shared() -> Result<()> {
+        let pool = r2d2::Pool::builder()
+            .max_size(2)
+            .build(DuckdbConnectionManager::memory()?)
+            .unwrap();
+        let first = pool.get().unwrap();
+        let second = pool.get().unwrap();
+        first.execute_batch("CREATE TABLE t AS SELECT 42 AS x")?;
+        let x: i32 = second.query_row("SELECT x FROM t", [], |r| r.get(0))?;
+        assert_eq!(x, 42);
+        second.execute_batch("CREATE TABLE u (y INTEGER)")?;
+        first.execute_batch("INSERT INTO u VALUES (1)")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_init_hook() -> Result<()> {
+        let manager = DuckdbConnectionManager::memory()?;
+        manager.set_init_hook(|conn| conn.execute_batch("CREATE TEMP MACRO add_one(x) AS x + 1"))?;
+        let pool = r2d2::Pool::builder().max_size(2).build(manager).unwrap();
+        let two: i32 = pool.get().unwrap().query_row("SELECT add_one(1)", [], |r| r.get(0))?;
+        assert_eq!(two, 2);
+
+        let manager = DuckdbConnectionManager::memory()?;
+        manager.set_init_hook(|conn| conn.execute_batch("SELECT * FROM missing"))?;
+        assert!(r2d2::ManageConnection::connect(&manager).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction_left_open() -> Result<()> {
+        let pool = r2d2::Pool::builder()
+            .max_size(1)
+            .build(DuckdbConnectionManager::memory()?)
+            .unwrap();
+        let conn = pool.get().unwrap();
+        conn.execute_batch("CREATE TABLE t (x INTEGER); BEGIN; INSERT INTO t VALUES (1)")?;
+        // Returning it with the transaction open gets it dropped, and replaced
+        // by a fresh connection to the same database.
+        drop(conn);
+        let conn = pool.get().unwrap();
+        let count: i64 = conn.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 0);
+
+        let manager = DuckdbConnectionManager::memory()?;
+        let mut conn = r2d2::ManageConnection::connect(&manager)?;
+        r2d2::ManageConnection::is_valid(&manager, &mut conn)?;
+        conn.execute_batch("BEGIN")?;
+        assert_eq!(
+            r2d2::ManageConnection::is_valid(&manager, &mut conn).unwrap_err(),
+            Error::NestedTransaction
+        );
+        assert!(!r2d2::ManageConnection::has_broken(&manager, &mut conn));
+        Ok(())
+    }
+
     #[test]
     fn test_is_valid() -> Result<()> {
         let manager = DuckdbConnectionManager::file(":memory:")?;
@@ -1,15 +1,23 @@
-use std::{ffi::CStr, ptr, rc::Rc, sync::Arc};
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::HashMap,
+    ffi::{CStr, CString},
+    ptr,
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
 
 use arrow::{
     array::StructArray,
-    datatypes::{DataType, Schema, SchemaRef},
+    datatypes::{DataType, Field, Fields, IntervalUnit, Schema, SchemaRef, TimeUnit, UnionFields, UnionMode},
     ffi::{from_ffi, FFI_ArrowArray, FFI_ArrowSchema},
 };
 
-use super::{ffi, Result};
+use super::{ffi, Error, Result};
 #[cfg(feature = "polars")]
 use crate::arrow2;
-use crate::error::result_from_duckdb_arrow;
+use crate::{error::result_from_duckdb_arrow, types::LogicalType};
 
 // Private newtype for raw sqlite3_stmts that finalize themselves when dropped.
 // TODO: destroy statement and result
@@ -17,7 +25,13 @@ use crate::error::result_from_duckdb_arrow;
 pub struct RawStatement {
     ptr: ffi::duckdb_prepared_statement,
     result: Option<ffi::duckdb_arrow>,
+    // The result of a streaming execution, read chunk by chunk.
+    chunked: RefCell<Option<ChunkedResult>>,
     schema: Option<SchemaRef>,
+    // The index of each column of `schema` by its ASCII-lowercased name.
+    column_indexes: OnceCell<HashMap<String, usize>>,
+    // The name and type of each column, as described before execution.
+    columns: OnceCell<Vec<(String, Option<LogicalType>)>>,
     // Cached SQL (trimmed) that we use as the key when we're in the statement
     // cache. This is None for statements which didn't come from the statement
     // cache.
@@ -29,6 +43,16 @@ pub struct RawStatement {
     // One example of a case where the result of `sqlite_sql` and the value in
     // `statement_cache_key` might differ is if the statement has a `tail`.
     statement_cache_key: Option<Arc<str>>,
+    // The SQL the statement was prepared from, if known.
+    sql: Option<Arc<str>>,
+    execution_times: ExecutionTimes,
+}
+
+/// How long the successful executions of a statement took.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct ExecutionTimes {
+    pub(crate) last: Option<Duration>,
+    pub(crate) total: Duration,
 }
 
 impl RawStatement {
@@ -37,8 +61,13 @@ impl RawStatement {
         RawStatement {
             ptr: stmt,
             result: None,
+            chunked: RefCell::new(None),
             schema: None,
+            column_indexes: OnceCell::new(),
+            columns: OnceCell::new(),
             statement_cache_key: None,
+            sql: None,
+            execution_times: ExecutionTimes::default(),
         }
     }
 
@@ -62,6 +91,22 @@ impl RawStatement {
         self.statement_cache_key.clone()
     }
 
+    #[inline]
+    pub(crate) fn execution_times(&self) -> ExecutionTimes {
+        self.execution_times
+    }
+
+    #[inline]
+    pub(crate) fn set_execution_times(&mut self, times: ExecutionTimes) {
+        self.execution_times = times;
+    }
+
+    /// Counts a successful execution that took `elapsed`.
+    pub(crate) fn record_execution(&mut self, elapsed: Duration) {
+        self.execution_times.last = Some(elapsed);
+        self.execution_times.total += elapsed;
+    }
+
     #[inline]
     pub fn clear_bindings(&self) -> ffi::duckdb_state {
         unsafe { ffi::duckdb_clear_bindings(self.ptr) }
@@ -72,13 +117,26 @@ impl RawStatement {
         self.result.unwrap()
     }
 
+    /// The number of rows of the result, `0` without one or while it is
+    /// streamed.
     #[inline]
     pub fn row_count(&self) -> usize {
-        unsafe { ffi::duckdb_arrow_row_count(self.result_unwrap()) as usize }
+        self.result
+            .map_or(0, |result| unsafe { ffi::duckdb_arrow_row_count(result) as usize })
+    }
+
+    /// Whether the result is read chunk by chunk, as after
+    /// [`execute_streaming`](RawStatement::execute_streaming).
+    #[inline]
+    pub fn is_chunked(&self) -> bool {
+        self.chunked.borrow().is_some()
     }
 
     #[inline]
     pub fn step(&self) -> Option<StructArray> {
+        if self.is_chunked() {
+            return self.step_chunked();
+        }
         self.result?;
         unsafe {
             let mut arrays = FFI_ArrowArray::empty();
@@ -127,19 +185,7 @@ impl RawStatement {
                 return None;
             }
 
-            let mut ffi_arrow2_schema = arrow2::ffi::ArrowSchema::empty();
-
-            if ffi::duckdb_query_arrow_schema(
-                self.result_unwrap(),
-                &mut std::ptr::addr_of_mut!(ffi_arrow2_schema) as *mut _ as *mut ffi::duckdb_arrow_schema,
-            )
-            .ne(&ffi::DuckDBSuccess)
-            {
-                return None;
-            }
-
-            let arrow2_field =
-                arrow2::ffi::import_field_from_c(&ffi_arrow2_schema).expect("Failed to import arrow2 Field from C");
+            let arrow2_field = self.field2()?;
             let import_arrow2_array = arrow2::ffi::import_array_from_c(ffi_arrow2_array, arrow2_field.data_type);
 
             if let Err(err) = import_arrow2_array {
@@ -163,9 +209,57 @@ impl RawStatement {
         }
     }
 
+    #[cfg(feature = "polars")]
+    #[inline]
+    pub fn field2(&self) -> Option<arrow2::datatypes::Field> {
+        self.result?;
+
+        unsafe {
+            let mut ffi_arrow2_schema = arrow2::ffi::ArrowSchema::empty();
+
+            if ffi::duckdb_query_arrow_schema(
+                self.result_unwrap(),
+                &mut std::ptr::addr_of_mut!(ffi_arrow2_schema) as *mut _ as *mut ffi::duckdb_arrow_schema,
+            )
+            .ne(&ffi::DuckDBSuccess)
+            {
+                return None;
+            }
+
+            Some(arrow2::ffi::import_field_from_c(&ffi_arrow2_schema).expect("Failed to import arrow2 Field from C"))
+        }
+    }
+
+    /// The number of columns of the result, `0` without one.
     #[inline]
     pub fn column_count(&self) -> usize {
-        unsafe { ffi::duckdb_arrow_column_count(self.result_unwrap()) as usize }
+        self.schema.as_ref().map_or(0, |schema| schema.fields().len())
+    }
+
+    #[inline]
+    pub fn has_result(&self) -> bool {
+        self.schema.is_some()
+    }
+
+    /// The columns described before execution, computing them with
+    /// `describe` if they aren't yet. Failures aren't kept, so they are
+    /// retried.
+    pub(crate) fn columns_or_else(
+        &self,
+        describe: impl FnOnce() -> Result<Vec<(String, Option<LogicalType>)>>,
+    ) -> Result<&[(String, Option<LogicalType>)]> {
+        if let Some(columns) = self.columns.get() {
+            return Ok(columns);
+        }
+        let columns = describe()?;
+        Ok(self.columns.get_or_init(|| columns))
+    }
+
+    /// Forgets the columns described before execution, which a change to
+    /// the catalog may have made stale.
+    #[inline]
+    pub(crate) fn forget_columns(&mut self) {
+        self.columns.take();
     }
 
     #[inline]
@@ -186,10 +280,25 @@ impl RawStatement {
 
     #[inline]
     pub fn column_name(&self, idx: usize) -> Option<&String> {
-        if idx >= self.column_count() {
-            return None;
+        self.schema.as_ref()?.fields().get(idx).map(|field| field.name())
+    }
+
+    /// The index of the first column of the result named `name`, ignoring
+    /// ASCII case, from a map built on the first lookup.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        let fields = self.schema.as_ref()?.fields();
+        let indexes = self.column_indexes.get_or_init(|| {
+            let mut indexes = HashMap::with_capacity(fields.len());
+            for (idx, field) in fields.iter().enumerate() {
+                indexes.entry(field.name().to_ascii_lowercase()).or_insert(idx);
+            }
+            indexes
+        });
+        if name.bytes().any(|b| b.is_ascii_uppercase()) {
+            indexes.get(&name.to_ascii_lowercase()).copied()
+        } else {
+            indexes.get(name).copied()
         }
-        Some(self.schema.as_ref().unwrap().field(idx).name())
     }
 
     #[allow(dead_code)]
@@ -231,8 +340,13 @@ impl RawStatement {
             let mut c_schema = Rc::into_raw(Rc::new(FFI_ArrowSchema::empty()));
             let rc = ffi::duckdb_query_arrow_schema(out, &mut c_schema as *mut _ as *mut ffi::duckdb_arrow_schema);
             if rc != ffi::DuckDBSuccess {
+                // The result itself succeeded, so there is no error to query.
                 Rc::from_raw(c_schema);
-                result_from_duckdb_arrow(rc, out)?;
+                ffi::duckdb_destroy_arrow(&mut out);
+                return Err(Error::DuckDBFailure(
+                    ffi::Error::new(rc),
+                    Some("result can't be converted to Arrow; cast columns of unsupported types such as TIMETZ to VARCHAR".to_owned()),
+                ));
             }
             self.schema = Some(Arc::new(Schema::try_from(&*c_schema).unwrap()));
             Rc::from_raw(c_schema);
@@ -242,9 +356,202 @@ impl RawStatement {
         }
     }
 
+    /// Executes the statement, streaming its result if DuckDB can stream it,
+    /// which [`step`](RawStatement::step) then reads a chunk at a time. The
+    /// result is converted to Arrow the way `options` say DuckDB does.
+    ///
+    /// NOTE: running anything else on the connection ends the result, see
+    /// [`close_chunked`](RawStatement::close_chunked).
+    pub fn execute_streaming(&mut self, options: &ArrowOptions) -> Result<usize> {
+        let mut pending = self.pending(true)?;
+        let res = unsafe { self.execute_pending(pending, options) };
+        unsafe { ffi::duckdb_destroy_pending(&mut pending) };
+        res
+    }
+
+    /// Starts executing the statement, streaming its result if `streaming`
+    /// and DuckDB can stream it. The pending result must be destroyed by the
+    /// caller.
+    pub fn pending(&mut self, streaming: bool) -> Result<ffi::duckdb_pending_result> {
+        self.reset_result();
+        unsafe {
+            let mut pending: ffi::duckdb_pending_result = ptr::null_mut();
+            let rc = if streaming {
+                ffi::duckdb_pending_prepared_streaming(self.ptr, &mut pending)
+            } else {
+                ffi::duckdb_pending_prepared(self.ptr, &mut pending)
+            };
+            if rc != ffi::DuckDBSuccess {
+                let message = error_message(ffi::duckdb_pending_error(pending));
+                ffi::duckdb_destroy_pending(&mut pending);
+                return Err(Error::DuckDBFailure(ffi::Error::new(ffi::DuckDBError), message));
+            }
+            Ok(pending)
+        }
+    }
+
+    /// Runs the rest of `pending`, a [`pending`](RawStatement::pending)
+    /// result of this statement, keeping its result for
+    /// [`step`](RawStatement::step) like
+    /// [`execute_streaming`](RawStatement::execute_streaming) does.
+    pub unsafe fn execute_pending(
+        &mut self,
+        pending: ffi::duckdb_pending_result,
+        options: &ArrowOptions,
+    ) -> Result<usize> {
+        unsafe {
+            let mut result: ffi::duckdb_result = std::mem::zeroed();
+            let rc = ffi::duckdb_execute_pending(pending, &mut result);
+            if rc != ffi::DuckDBSuccess {
+                let message = error_message(ffi::duckdb_result_error(&mut result));
+                ffi::duckdb_destroy_result(&mut result);
+                return Err(Error::DuckDBFailure(ffi::Error::new(rc), message));
+            }
+
+            let mut fields = Vec::new();
+            for col in 0..ffi::duckdb_column_count(&mut result) {
+                let name = CStr::from_ptr(ffi::duckdb_column_name(&mut result, col)).to_string_lossy();
+                let mut ty = ffi::duckdb_column_logical_type(&mut result, col);
+                let data_type = arrow_type(ty, options);
+                ffi::duckdb_destroy_logical_type(&mut ty);
+                match data_type {
+                    Some(data_type) => fields.push(Field::new(name, data_type, true)),
+                    None => {
+                        ffi::duckdb_destroy_result(&mut result);
+                        return Err(Error::DuckDBFailure(
+                            ffi::Error::new(ffi::DuckDBError),
+                            Some("result can't be converted to Arrow; cast columns of unsupported types such as TIMETZ to VARCHAR".to_owned()),
+                        ));
+                    }
+                }
+            }
+            let streaming = ffi::duckdb_result_is_streaming(result);
+            // DuckDB never streams statements that change rows, and can only
+            // count the rows of a materialized result.
+            let rows_changed = if streaming {
+                0
+            } else {
+                ffi::duckdb_rows_changed(&mut result)
+            };
+            self.schema = Some(Arc::new(Schema::new(fields)));
+            *self.chunked.get_mut() = Some(ChunkedResult {
+                streaming,
+                raw: result,
+                next_chunk: 0,
+            });
+            Ok(rows_changed as usize)
+        }
+    }
+
+    /// Executes the statement, streaming its result, and passes each chunk
+    /// of it to `f` as DuckDB holds it, without converting it to Arrow.
+    /// Returns the number of rows read.
+    pub(crate) fn for_each_data_chunk<F>(&mut self, mut f: F) -> Result<usize>
+    where
+        F: FnMut(ffi::duckdb_data_chunk) -> Result<()>,
+    {
+        let mut pending = self.pending(true)?;
+        unsafe {
+            let mut result: ffi::duckdb_result = std::mem::zeroed();
+            let rc = ffi::duckdb_execute_pending(pending, &mut result);
+            ffi::duckdb_destroy_pending(&mut pending);
+            if rc != ffi::DuckDBSuccess {
+                let message = error_message(ffi::duckdb_result_error(&mut result));
+                ffi::duckdb_destroy_result(&mut result);
+                return Err(Error::DuckDBFailure(ffi::Error::new(rc), message));
+            }
+            let streaming = ffi::duckdb_result_is_streaming(result);
+            let (mut rows, mut next_chunk) = (0, 0);
+            loop {
+                let mut chunk = if streaming {
+                    ffi::duckdb_stream_fetch_chunk(result)
+                } else {
+                    next_chunk += 1;
+                    ffi::duckdb_result_get_chunk(result, next_chunk - 1)
+                };
+                if chunk.is_null() {
+                    break;
+                }
+                let size = ffi::duckdb_data_chunk_get_size(chunk);
+                let res = if size == 0 { None } else { Some(f(chunk)) };
+                ffi::duckdb_destroy_data_chunk(&mut chunk);
+                match res {
+                    None => break,
+                    Some(Ok(())) => rows += size as usize,
+                    Some(Err(err)) => {
+                        ffi::duckdb_destroy_result(&mut result);
+                        return Err(err);
+                    }
+                }
+            }
+            // A streamed result that fails ends early, with its error.
+            let message = error_message(ffi::duckdb_result_error(&mut result));
+            ffi::duckdb_destroy_result(&mut result);
+            match message {
+                Some(message) => Err(Error::DuckDBFailure(ffi::Error::new(ffi::DuckDBError), Some(message))),
+                None => Ok(rows),
+            }
+        }
+    }
+
+    /// The next chunk of the result of
+    /// [`execute_streaming`](RawStatement::execute_streaming), or `None`
+    /// once it is read or fails, see
+    /// [`chunked_error`](RawStatement::chunked_error).
+    fn step_chunked(&self) -> Option<StructArray> {
+        let mut chunked = self.chunked.borrow_mut();
+        let chunked = chunked.as_mut()?;
+        unsafe {
+            let mut chunk = if chunked.streaming {
+                ffi::duckdb_stream_fetch_chunk(chunked.raw)
+            } else {
+                chunked.next_chunk += 1;
+                ffi::duckdb_result_get_chunk(chunked.raw, chunked.next_chunk - 1)
+            };
+            if chunk.is_null() {
+                return None;
+            }
+            if ffi::duckdb_data_chunk_get_size(chunk) == 0 {
+                ffi::duckdb_destroy_data_chunk(&mut chunk);
+                return None;
+            }
+            let mut arrays = FFI_ArrowArray::empty();
+            ffi::duckdb_result_arrow_array(
+                chunked.raw,
+                chunk,
+                &mut std::ptr::addr_of_mut!(arrays) as *mut _ as *mut ffi::duckdb_arrow_array,
+            );
+            ffi::duckdb_destroy_data_chunk(&mut chunk);
+            let fields = self.schema.as_ref()?.fields().clone();
+            let schema = FFI_ArrowSchema::try_from(DataType::Struct(fields)).ok()?;
+            let array_data = from_ffi(arrays, &schema).expect("ok");
+            Some(StructArray::from(array_data))
+        }
+    }
+
+    /// The error that ended the result of
+    /// [`execute_streaming`](RawStatement::execute_streaming) early, if any.
+    pub fn chunked_error(&self) -> Option<Error> {
+        let mut chunked = self.chunked.borrow_mut();
+        let chunked = chunked.as_mut()?;
+        let message = unsafe { error_message(ffi::duckdb_result_error(&mut chunked.raw)) }?;
+        Some(Error::DuckDBFailure(ffi::Error::new(ffi::DuckDBError), Some(message)))
+    }
+
+    /// Drops the result of
+    /// [`execute_streaming`](RawStatement::execute_streaming), if any, which
+    /// frees the connection for other statements. The columns are kept.
+    pub fn close_chunked(&self) {
+        if let Some(mut chunked) = self.chunked.borrow_mut().take() {
+            unsafe { ffi::duckdb_destroy_result(&mut chunked.raw) };
+        }
+    }
+
     #[inline]
     pub fn reset_result(&mut self) {
+        self.close_chunked();
         self.schema = None;
+        self.column_indexes.take();
         if self.result.is_some() {
             unsafe {
                 ffi::duckdb_destroy_arrow(&mut self.result_unwrap());
@@ -258,10 +565,182 @@ impl RawStatement {
         unsafe { ffi::duckdb_nparams(self.ptr) as usize }
     }
 
+    /// The name of the one-based parameter `idx`, `"1"` for a plain `?`.
+    pub fn bind_parameter_name(&self, idx: usize) -> Option<String> {
+        unsafe {
+            let name = ffi::duckdb_parameter_name(self.ptr, idx as u64);
+            if name.is_null() {
+                return None;
+            }
+            let owned = CStr::from_ptr(name).to_string_lossy().into_owned();
+            ffi::duckdb_free(name as *mut std::ffi::c_void);
+            Some(owned)
+        }
+    }
+
+    /// The type id DuckDB inferred for the one-based parameter `idx`.
+    #[inline]
+    pub fn bind_parameter_type(&self, idx: usize) -> ffi::duckdb_type {
+        unsafe { ffi::duckdb_param_type(self.ptr, idx as u64) }
+    }
+
+    /// The one-based index of the parameter `name`, which DuckDB matches
+    /// ignoring case.
+    pub fn bind_parameter_index(&self, name: &str) -> Option<usize> {
+        let name = CString::new(name).ok()?;
+        let mut index = 0;
+        let rc = unsafe { ffi::duckdb_bind_parameter_index(self.ptr, &mut index, name.as_ptr()) };
+        (rc == ffi::DuckDBSuccess).then_some(index as usize)
+    }
+
     #[inline]
-    pub fn sql(&self) -> Option<&CStr> {
-        panic!("not supported")
+    pub fn statement_type(&self) -> ffi::duckdb_statement_type {
+        unsafe { ffi::duckdb_prepared_statement_type(self.ptr) }
     }
+
+    #[inline]
+    pub(crate) fn set_sql(&mut self, sql: impl Into<Arc<str>>) {
+        self.sql = Some(sql.into());
+    }
+
+    #[inline]
+    pub(crate) fn sql(&self) -> Option<Arc<str>> {
+        self.sql.clone()
+    }
+}
+
+/// The result of [`RawStatement::execute_streaming`]. DuckDB only streams
+/// the results of queries; others are read a chunk at a time all the same.
+#[derive(Debug)]
+struct ChunkedResult {
+    raw: ffi::duckdb_result,
+    streaming: bool,
+    // The next chunk to read if the result isn't streamed.
+    next_chunk: u64,
+}
+
+/// How DuckDB converts results to Arrow on a connection, from its settings.
+#[derive(Clone, Debug)]
+pub(crate) struct ArrowOptions {
+    /// `arrow_large_buffer_size`: strings, blobs and lists have 64-bit
+    /// offsets.
+    pub large_buffers: bool,
+    /// `TimeZone`: the time zone of `TIMESTAMP WITH TIME ZONE` values.
+    pub time_zone: String,
+}
+
+/// The Arrow type DuckDB converts values of `ty` to, as its
+/// `ArrowConverter` does, or `None` if it can't convert them.
+unsafe fn arrow_type(ty: ffi::duckdb_logical_type, options: &ArrowOptions) -> Option<DataType> {
+    unsafe fn child(mut ty: ffi::duckdb_logical_type, options: &ArrowOptions) -> Option<DataType> {
+        let data_type = arrow_type(ty, options);
+        ffi::duckdb_destroy_logical_type(&mut ty);
+        data_type
+    }
+    unsafe fn owned(s: *mut std::os::raw::c_char) -> String {
+        let owned = CStr::from_ptr(s).to_string_lossy().into_owned();
+        ffi::duckdb_free(s.cast());
+        owned
+    }
+    let data_type = match ffi::duckdb_get_type_id(ty) {
+        // The type of `NULL`, among others the C API has no id for.
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_INVALID => DataType::Null,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_BOOLEAN => DataType::Boolean,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_TINYINT => DataType::Int8,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_SMALLINT => DataType::Int16,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_INTEGER => DataType::Int32,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_BIGINT => DataType::Int64,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_UTINYINT => DataType::UInt8,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_USMALLINT => DataType::UInt16,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_UINTEGER => DataType::UInt32,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_UBIGINT => DataType::UInt64,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_FLOAT => DataType::Float32,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_DOUBLE => DataType::Float64,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_HUGEINT => DataType::Decimal128(38, 0),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_DECIMAL => {
+            DataType::Decimal128(ffi::duckdb_decimal_width(ty), ffi::duckdb_decimal_scale(ty) as i8)
+        }
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_VARCHAR | ffi::DUCKDB_TYPE_DUCKDB_TYPE_UUID if options.large_buffers => {
+            DataType::LargeUtf8
+        }
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_VARCHAR | ffi::DUCKDB_TYPE_DUCKDB_TYPE_UUID => DataType::Utf8,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_BLOB | ffi::DUCKDB_TYPE_DUCKDB_TYPE_BIT if options.large_buffers => {
+            DataType::LargeBinary
+        }
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_BLOB | ffi::DUCKDB_TYPE_DUCKDB_TYPE_BIT => DataType::Binary,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_DATE => DataType::Date32,
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIME => DataType::Time64(TimeUnit::Microsecond),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_S => DataType::Timestamp(TimeUnit::Second, None),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_MS => DataType::Timestamp(TimeUnit::Millisecond, None),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP => DataType::Timestamp(TimeUnit::Microsecond, None),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_NS => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_TZ => {
+            DataType::Timestamp(TimeUnit::Microsecond, Some(options.time_zone.as_str().into()))
+        }
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_INTERVAL => DataType::Interval(IntervalUnit::MonthDayNano),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_LIST => {
+            let field = Arc::new(Field::new(
+                "l",
+                child(ffi::duckdb_list_type_child_type(ty), options)?,
+                true,
+            ));
+            if options.large_buffers {
+                DataType::LargeList(field)
+            } else {
+                DataType::List(field)
+            }
+        }
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_ARRAY => DataType::FixedSizeList(
+            Arc::new(Field::new(
+                "",
+                child(ffi::duckdb_array_type_child_type(ty), options)?,
+                true,
+            )),
+            ffi::duckdb_array_type_array_size(ty).try_into().ok()?,
+        ),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_STRUCT => DataType::Struct(
+            (0..ffi::duckdb_struct_type_child_count(ty))
+                .map(|i| {
+                    let name = owned(ffi::duckdb_struct_type_child_name(ty, i));
+                    let data_type = child(ffi::duckdb_struct_type_child_type(ty, i), options)?;
+                    Some(Field::new(name, data_type, true))
+                })
+                .collect::<Option<Fields>>()?,
+        ),
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_MAP => {
+            let key = child(ffi::duckdb_map_type_key_type(ty), options)?;
+            let value = child(ffi::duckdb_map_type_value_type(ty), options)?;
+            let entries = Fields::from(vec![Field::new("key", key, true), Field::new("value", value, true)]);
+            DataType::Map(Arc::new(Field::new("entries", DataType::Struct(entries), true)), false)
+        }
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_UNION => {
+            let members = (0..ffi::duckdb_union_type_member_count(ty))
+                .map(|i| {
+                    let name = owned(ffi::duckdb_union_type_member_name(ty, i));
+                    let data_type = child(ffi::duckdb_union_type_member_type(ty, i), options)?;
+                    Some(Field::new(name, data_type, true))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let type_ids = 0..members.len().try_into().ok()?;
+            DataType::Union(UnionFields::new(type_ids, members), UnionMode::Sparse)
+        }
+        ffi::DUCKDB_TYPE_DUCKDB_TYPE_ENUM => {
+            let index = match ffi::duckdb_enum_internal_type(ty) {
+                ffi::DUCKDB_TYPE_DUCKDB_TYPE_UTINYINT => DataType::UInt8,
+                ffi::DUCKDB_TYPE_DUCKDB_TYPE_USMALLINT => DataType::UInt16,
+                ffi::DUCKDB_TYPE_DUCKDB_TYPE_UINTEGER => DataType::UInt32,
+                _ => return None,
+            };
+            DataType::Dictionary(Box::new(index), Box::new(DataType::Utf8))
+        }
+        _ => return None,
+    };
+    Some(data_type)
+}
+
+/// An error message from the C API, which may be null.
+unsafe fn error_message(message: *const std::os::raw::c_char) -> Option<String> {
+    (!message.is_null()).then(|| CStr::from_ptr(message).to_string_lossy().into_owned())
 }
 
 impl Drop for RawStatement {
@@ -0,0 +1,364 @@
+//! Reading CSV files with `read_csv`, without writing its options as SQL
+
+use std::path::{Path, PathBuf};
+
+use arrow::record_batch::RecordBatch;
+
+use crate::{pragma::Sql, types::LogicalType, Connection, Error, Result};
+
+/// Reads CSV files into a table, a view or Arrow record batches, built with
+/// [`Connection::read_csv`].
+///
+/// Options that aren't set are left to DuckDB, which detects them from the
+/// first rows of the files.
+#[derive(Clone, Debug)]
+#[must_use = "a CsvReadBuilder reads nothing until create_table, create_view or query_arrow is called"]
+pub struct CsvReadBuilder<'conn> {
+    conn: &'conn Connection,
+    paths: Vec<PathBuf>,
+    delimiter: Option<char>,
+    header: Option<bool>,
+    quote: Option<char>,
+    null_str: Option<String>,
+    columns: Vec<(String, LogicalType)>,
+    sample_size: Option<i64>,
+    ignore_errors: bool,
+    union_by_name: bool,
+}
+
+impl Connection {
+    /// Start reading the CSV file at `path`, which may be a glob such as
+    /// `data/*.csv` matching several files.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{types::LogicalType, Connection, Result};
+    /// fn load(conn: &Connection) -> Result<()> {
+    ///     conn.read_csv("sales/*.csv")
+    ///         .delimiter(';')
+    ///         .header(true)
+    ///         .column("id", LogicalType::BigInt)
+    ///         .column("amount", LogicalType::Decimal { width: 10, scale: 2 })
+    ///         .create_table("sales")
+    /// }
+    /// ```
+    pub fn read_csv<P: AsRef<Path>>(&self, path: P) -> CsvReadBuilder<'_> {
+        CsvReadBuilder {
+            conn: self,
+            paths: vec![path.as_ref().to_owned()],
+            delimiter: None,
+            header: None,
+            quote: None,
+            null_str: None,
+            columns: Vec::new(),
+            sample_size: None,
+            ignore_errors: false,
+            union_by_name: false,
+        }
+    }
+}
+
+impl CsvReadBuilder<'_> {
+    /// Read the CSV file at `path` too, which may be a glob as well.
+    pub fn add_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.paths.push(path.as_ref().to_owned());
+        self
+    }
+
+    /// The character that separates the values of a row.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Whether the first line of each file names the columns.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// The character that quotes values holding the delimiter.
+    pub fn quote(mut self, quote: char) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    /// The text that stands for NULL, such as `NA`.
+    pub fn null_str(mut self, null_str: &str) -> Self {
+        self.null_str = Some(null_str.to_owned());
+        self
+    }
+
+    /// Add a column of the files, with its type. Once a column is added, the
+    /// files must have exactly the columns added, in order, and their types
+    /// aren't detected.
+    pub fn column(mut self, name: &str, ty: LogicalType) -> Self {
+        self.columns.push((name.to_owned(), ty));
+        self
+    }
+
+    /// How many rows to detect the options and column types from, or `-1`
+    /// for every row.
+    pub fn sample_size(mut self, rows: i64) -> Self {
+        self.sample_size = Some(rows);
+        self
+    }
+
+    /// Skip the rows that can't be read, instead of failing with
+    /// [`Error::CsvReadFailed`].
+    pub fn ignore_errors(mut self, ignore_errors: bool) -> Self {
+        self.ignore_errors = ignore_errors;
+        self
+    }
+
+    /// Match the columns of the files by name rather than position, so that
+    /// files with different columns can be read together. A column missing
+    /// from a file is NULL in its rows.
+    pub fn union_by_name(mut self, union_by_name: bool) -> Self {
+        self.union_by_name = union_by_name;
+        self
+    }
+
+    /// Create the table `name` holding the rows of the files.
+    ///
+    /// ## Failure
+    ///
+    /// Will return [`Error::CsvReadFailed`] if a row can't be read, and `Err`
+    /// if a path isn't valid UTF-8, no file matches, or the table can't be
+    /// created.
+    pub fn create_table(self, name: &str) -> Result<()> {
+        self.create("TABLE", name)
+    }
+
+    /// Create the view `name`, which reads the files each time it is
+    /// queried.
+    ///
+    /// ## Failure
+    ///
+    /// Will return `Err` if a path isn't valid UTF-8, no file matches, or the
+    /// view can't be created.
+    pub fn create_view(self, name: &str) -> Result<()> {
+        self.create("VIEW", name)
+    }
+
+    /// Read the rows of the files as Arrow record batches.
+    ///
+    /// ## Failure
+    ///
+    /// Will return [`Error::CsvReadFailed`] if a row can't be read, and `Err`
+    /// if a path isn't valid UTF-8 or no file matches.
+    pub fn query_arrow(self) -> Result<Vec<RecordBatch>> {
+        let sql = format!("SELECT * FROM {}", self.read_csv_sql()?);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let batches = stmt.query_arrow([]).map_err(with_csv_location)?;
+        Ok(batches.collect())
+    }
+
+    fn create(self, kind: &str, name: &str) -> Result<()> {
+        let mut target = Sql::new();
+        target.push_identifier(name);
+        let sql = format!("CREATE {kind} {} AS SELECT * FROM {}", &*target, self.read_csv_sql()?);
+        self.conn.execute_batch(&sql).map_err(with_csv_location)
+    }
+
+    /// The `read_csv(...)` call reading the files with the options set.
+    fn read_csv_sql(&self) -> Result<String> {
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| {
+                path.to_str()
+                    .map(literal)
+                    .ok_or_else(|| Error::InvalidPath(path.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut sql = format!("read_csv([{}]", paths.join(", "));
+        let mut option = |name: &str, value: String| {
+            sql.push_str(&format!(", {name} = {value}"));
+        };
+        if let Some(delimiter) = self.delimiter {
+            option("delim", literal(&delimiter.to_string()));
+        }
+        if let Some(header) = self.header {
+            option("header", header.to_string());
+        }
+        if let Some(quote) = self.quote {
+            option("quote", literal(&quote.to_string()));
+        }
+        if let Some(null_str) = &self.null_str {
+            option("nullstr", literal(null_str));
+        }
+        if !self.columns.is_empty() {
+            let columns: Vec<_> = self
+                .columns
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", literal(name), literal(&ty.to_string())))
+                .collect();
+            option("columns", format!("{{{}}}", columns.join(", ")));
+        }
+        if let Some(rows) = self.sample_size {
+            option("sample_size", rows.to_string());
+        }
+        if self.ignore_errors {
+            option("ignore_errors", "true".to_owned());
+        }
+        if self.union_by_name {
+            option("union_by_name", "true".to_owned());
+        }
+        sql.push(')');
+        Ok(sql)
+    }
+}
+
+/// `s` as an SQL string literal.
+pub(crate) fn literal(s: &str) -> String {
+    let mut sql = Sql::new();
+    sql.push_string_literal(s);
+    sql.as_str().to_owned()
+}
+
+/// Turns an error DuckDB reported about a line of a CSV file into an
+/// [`Error::CsvReadFailed`] naming the file and line.
+fn with_csv_location(err: Error) -> Error {
+    let Error::DuckDBFailure(_, Some(msg)) = err.without_context() else {
+        return err;
+    };
+    // DuckDB lists the options the file was read with, starting with it.
+    let Some(file) = msg
+        .split_once("\n  file=")
+        .and_then(|(_, rest)| rest.lines().next())
+        .map(str::to_owned)
+    else {
+        return err;
+    };
+    let line = msg
+        .split_once("CSV Error on Line: ")
+        .and_then(|(_, rest)| rest.lines().next())
+        .and_then(|line| line.trim().parse().ok());
+    Error::CsvReadFailed {
+        file,
+        line,
+        error: Box::new(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use arrow::array::{Array, AsArray};
+
+    use super::with_csv_location;
+    use crate::{ffi, types::LogicalType, Connection, Error, Result};
+
+    #[test]
+    fn test_read_csv_options() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("people.csv");
+        fs::write(&path, "name|age\n'Ann|Lee'|30\nBob|NA\n").unwrap();
+
+        let db = Connection::open_in_memory()?;
+        db.read_csv(&path)
+            .delimiter('|')
+            .quote('\'')
+            .header(true)
+            .null_str("NA")
+            .column("name", LogicalType::Text)
+            .column("it's age", LogicalType::SmallInt)
+            .create_table("people")?;
+        let rows: Vec<(String, Option<i16>)> = db
+            .prepare("SELECT name, \"it's age\" FROM people ORDER BY name")?
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_>>()?;
+        assert_eq!(rows, [("Ann|Lee".to_owned(), Some(30)), ("Bob".to_owned(), None)]);
+        let ty: String = db.query_row("SELECT typeof(\"it's age\") FROM people LIMIT 1", [], |r| r.get(0))?;
+        assert_eq!(ty, "SMALLINT");
+
+        // A view reads the file again.
+        db.read_csv(&path)
+            .delimiter('|')
+            .quote('\'')
+            .sample_size(-1)
+            .create_view("people view")?;
+        fs::write(&path, "name|age\nCy|5\n").unwrap();
+        let count: i64 = db.query_row("SELECT count(*) FROM \"people view\"", [], |r| r.get(0))?;
+        assert_eq!(count, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_csv_union_by_name() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.csv"), "id,x\n1,10\n2,20\n").unwrap();
+        fs::write(dir.path().join("b.csv"), "y,id\nq,3\n").unwrap();
+        let extra = dir.path().join("extra.txt");
+        fs::write(&extra, "id,x\n4,40\n").unwrap();
+
+        let db = Connection::open_in_memory()?;
+        let batches = db
+            .read_csv(dir.path().join("*.csv"))
+            .add_path(&extra)
+            .union_by_name(true)
+            .query_arrow()?;
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 4);
+        let schema = batches[0].schema();
+        let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, ["id", "x", "y"]);
+        let nulls: usize = batches.iter().map(|b| b.column(2).null_count()).sum();
+        assert_eq!(nulls, 3);
+        let ids: i64 = batches
+            .iter()
+            .flat_map(|b| {
+                b.column(0)
+                    .as_primitive::<arrow::datatypes::Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .sum();
+        assert_eq!(ids, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_csv_malformed_line() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.csv");
+        fs::write(&path, "a,b\n1,2\nx,3\n4,5\n").unwrap();
+
+        let db = Connection::open_in_memory()?;
+        let read = || {
+            db.read_csv(&path)
+                .header(true)
+                .column("a", LogicalType::Int)
+                .column("b", LogicalType::Int)
+        };
+        let err = read().create_table("t").unwrap_err();
+        match &err {
+            Error::CsvReadFailed { file, line, .. } => {
+                assert_eq!(file, path.to_str().unwrap());
+                assert_eq!(*line, Some(3));
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+        assert!(err.to_string().contains("bad.csv"), "{err}");
+        assert!(read().query_arrow().is_err());
+
+        read().ignore_errors(true).create_table("t")?;
+        let count: i64 = db.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_csv_location() {
+        let failure = |msg: &str| Error::DuckDBFailure(ffi::Error::new(ffi::DuckDBError), Some(msg.to_owned()));
+        let err = with_csv_location(failure(
+            "Invalid Input Error: CSV Error on Line: 7\nOriginal Line: 1,2,3\nExpected 2 columns\n\n  file=/d/a.csv\n  delimiter = ,",
+        ));
+        assert!(matches!(err, Error::CsvReadFailed { ref file, line: Some(7), .. } if file == "/d/a.csv"));
+        let err = with_csv_location(failure("Binder Error: no such column"));
+        assert!(matches!(err, Error::DuckDBFailure(..)));
+    }
+}
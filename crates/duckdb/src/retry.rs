@@ -0,0 +1,153 @@
+//! Retrying transactions that conflict with other connections
+
+use std::{thread, time::Duration};
+
+use crate::{Connection, Result, Transaction};
+
+/// How [`retry`] re-runs a transaction that failed with a retryable error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times to run the transaction at most, including the first
+    /// time. `0` is taken as `1`.
+    pub max_attempts: u32,
+    /// How long to wait before the first retry. The wait doubles with each
+    /// retry.
+    pub initial_backoff: Duration,
+    /// The longest to wait between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Five attempts, waiting from 10 milliseconds up to a second.
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Run `f` in a transaction on `conn` and commit it, running it again in a
+/// new transaction, after waiting as `policy` says, for as long as it fails
+/// with an error that is [retryable](crate::Error::is_retryable), such as a
+/// write-write conflict with another connection.
+///
+/// `f` may run several times, so it shouldn't have effects outside the
+/// transaction it is given, other than ones that are safe to repeat.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use duckdb::{retry, Connection, Result, RetryPolicy};
+/// fn transfer(conn: &mut Connection, from: i64, to: i64, amount: i64) -> Result<()> {
+///     retry(conn, &RetryPolicy::default(), |tx| {
+///         tx.execute("UPDATE accounts SET balance = balance - ? WHERE id = ?", [amount, from])?;
+///         tx.execute("UPDATE accounts SET balance = balance + ? WHERE id = ?", [amount, to])?;
+///         Ok(())
+///     })
+/// }
+/// ```
+///
+/// # Failure
+///
+/// Will return the error of the last attempt if it isn't retryable or if
+/// `policy.max_attempts` is reached, and `Err` if beginning a transaction
+/// fails.
+pub fn retry<T, F>(conn: &mut Connection, policy: &RetryPolicy, mut f: F) -> Result<T>
+where
+    F: FnMut(&Transaction<'_>) -> Result<T>,
+{
+    let mut backoff = policy.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        // Dropping the transaction rolls it back if `f` or committing fails.
+        let tx = conn.transaction()?;
+        let res = f(&tx).and_then(|value| tx.commit().map(|_| value));
+        match res {
+            Err(err) if err.is_retryable() && attempt < policy.max_attempts => {
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(policy.max_backoff);
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{retry, RetryPolicy};
+    use crate::{Connection, Result};
+
+    fn conflicting_connections() -> Result<(tempfile::TempDir, Connection, Connection)> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open(dir.path().join("retry.db"))?;
+        db.execute_batch(
+            "CREATE TABLE counter (id INTEGER PRIMARY KEY, n INTEGER); INSERT INTO counter VALUES (1, 0)",
+        )?;
+        let other = db.try_clone()?;
+        Ok((dir, db, other))
+    }
+
+    // Increments the counter in a transaction, after another connection
+    // incremented it since the transaction began, the first `conflicts`
+    // times it is called.
+    fn increment(other: &Connection, conflicts: u32) -> impl FnMut(&Connection) -> Result<u32> + '_ {
+        let mut attempts = 0;
+        move |tx| {
+            attempts += 1;
+            // The transaction takes its snapshot when it first reads the table.
+            tx.query_row("SELECT n FROM counter WHERE id = 1", [], |r| r.get::<_, i32>(0))?;
+            if attempts <= conflicts {
+                other.execute("UPDATE counter SET n = n + 1 WHERE id = 1", [])?;
+            }
+            tx.execute("UPDATE counter SET n = n + 1 WHERE id = 1", [])?;
+            Ok(attempts)
+        }
+    }
+
+    #[test]
+    fn test_retry_conflict() -> Result<()> {
+        let (_dir, mut db, other) = conflicting_connections()?;
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        };
+        let mut f = increment(&other, 2);
+        let attempts = retry(&mut db, &policy, |tx| f(tx))?;
+        assert_eq!(attempts, 3);
+        let n: i32 = db.query_row("SELECT n FROM counter WHERE id = 1", [], |r| r.get(0))?;
+        assert_eq!(n, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_gives_up() -> Result<()> {
+        let (_dir, mut db, other) = conflicting_connections()?;
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        };
+        let mut f = increment(&other, 5);
+        let err = retry(&mut db, &policy, |tx| f(tx)).unwrap_err();
+        assert!(err.is_retryable(), "{err}");
+        // The failed attempts were rolled back.
+        let n: i32 = db.query_row("SELECT n FROM counter WHERE id = 1", [], |r| r.get(0))?;
+        assert_eq!(n, 2);
+
+        // Other errors aren't retried.
+        let mut attempts = 0;
+        let err = retry(&mut db, &policy, |tx| {
+            attempts += 1;
+            tx.execute("INSERT INTO counter VALUES (1, 0)", [])
+        })
+        .unwrap_err();
+        assert!(!err.is_retryable(), "{err}");
+        assert_eq!(attempts, 1);
+        Ok(())
+    }
+}
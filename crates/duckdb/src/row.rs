@@ -1,7 +1,9 @@
 use std::{convert, sync::Arc};
 
 use super::{Error, Result, Statement};
-use crate::types::{self, EnumType, FromSql, FromSqlError, ListType, ValueRef};
+#[cfg(feature = "serde")]
+use crate::error::UNKNOWN_COLUMN;
+use crate::types::{self, EnumType, FromSql, FromSqlError, FromSqlRef, ListType, ValueRef};
 
 use arrow::{
     array::{self, Array, ArrayRef, DictionaryArray, FixedSizeListArray, ListArray, MapArray, StructArray},
@@ -9,7 +11,6 @@ use arrow::{
 };
 use fallible_iterator::FallibleIterator;
 use fallible_streaming_iterator::FallibleStreamingIterator;
-use rust_decimal::prelude::*;
 
 /// An handle for the resulting rows of a query.
 #[must_use = "Rows is lazy and will do nothing unless consumed"]
@@ -85,6 +86,30 @@ impl<'stmt> Rows<'stmt> {
         MappedRows { rows: self, map: f }
     }
 
+    /// Convert this `Rows` into a [`RowIter`], which implements `Iterator`,
+    /// yielding each row by value. The rows stay readable after the iterator
+    /// moves on.
+    ///
+    /// Once reading a row fails, the iterator yields the error and then ends.
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Result, Statement};
+    /// fn query(stmt: &mut Statement) -> Result<Vec<String>> {
+    ///     let mut names = Vec::new();
+    ///     for row in stmt.query([])?.iterator() {
+    ///         names.push(row?.get("name")?);
+    ///     }
+    ///     Ok(names)
+    /// }
+    /// ```
+    #[inline]
+    pub fn iterator(self) -> RowIter<'stmt> {
+        RowIter {
+            rows: self,
+            done: false,
+        }
+    }
+
     /// Map over this `Rows` with a fallible function, converting it to a
     /// [`AndThenRows`], which implements `Iterator` (instead of
     /// `FallibleStreamingIterator`).
@@ -195,14 +220,44 @@ where
     }
 }
 
+/// An iterator over the resulting rows of a query, see [`Rows::iterator`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct RowIter<'stmt> {
+    rows: Rows<'stmt>,
+    done: bool,
+}
+
+impl<'stmt> Iterator for RowIter<'stmt> {
+    type Item = Result<Row<'stmt>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Result<Row<'stmt>>> {
+        if self.done {
+            return None;
+        }
+        match self.rows.advance() {
+            Ok(()) => {
+                let row = self.rows.row.take();
+                self.done = row.is_none();
+                row.map(Ok)
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
 /// `FallibleStreamingIterator` differs from the standard library's `Iterator`
 /// in two ways:
 /// * each call to `next` (sqlite3_step) can fail.
 /// * returned `Row` is valid until `next` is called again or `Statement` is
 ///   reset or finalized.
 ///
-/// While these iterators cannot be used with Rust `for` loops, `while let`
-/// loops offer a similar level of ergonomics:
+/// While these iterators cannot be used with Rust `for` loops, unless
+/// converted with [`Rows::iterator`], `while let` loops offer a similar level
+/// of ergonomics:
 /// ```rust,no_run
 /// # use duckdb::{Result, Statement};
 /// fn query(stmt: &mut Statement) -> Result<()> {
@@ -221,12 +276,16 @@ impl<'stmt> FallibleStreamingIterator for Rows<'stmt> {
     fn advance(&mut self) -> Result<()> {
         match self.stmt {
             Some(stmt) => {
-                if self.current_row < stmt.row_count() {
+                // The number of rows of a streamed result isn't known.
+                if stmt.stmt.is_chunked() || self.current_row < stmt.row_count() {
                     if self.current_batch_row >= self.batch_row_count() {
                         self.arr = Arc::new(stmt.step());
                         if self.arr.is_none() {
                             self.row = None;
-                            return Ok(());
+                            // Free the connection from a streamed result.
+                            let err = stmt.stmt.chunked_error();
+                            stmt.stmt.close_chunked();
+                            return err.map_or(Ok(()), Err);
                         }
                         self.current_batch_row = 0;
                     }
@@ -265,6 +324,16 @@ pub struct Row<'stmt> {
 }
 
 impl<'stmt> Row<'stmt> {
+    /// Return the names of the columns of the row, in order, as
+    /// [`Statement::column_names`] does.
+    pub fn column_names(&self) -> Vec<&'stmt str> {
+        let stmt = self.stmt;
+        (0..stmt.column_count())
+            .filter_map(|i| stmt.column_name(i).ok())
+            .map(String::as_str)
+            .collect()
+    }
+
     /// Get the value of a particular column of the result row.
     ///
     /// ## Failure
@@ -277,7 +346,7 @@ impl<'stmt> Row<'stmt> {
     /// * If the underlying DuckDB integral value is outside the range
     ///   representable by `T`
     /// * If `idx` is outside the range of columns in the returned query
-    pub fn get_unwrap<I: RowIndex, T: FromSql>(&self, idx: I) -> T {
+    pub fn get_unwrap<'r, I: RowIndex, T: FromSqlRef<'r>>(&'r self, idx: I) -> T {
         self.get(idx).unwrap()
     }
 
@@ -285,8 +354,15 @@ impl<'stmt> Row<'stmt> {
     ///
     /// ## Failure
     ///
-    /// Returns an `Error::InvalidColumnType` if the underlying DuckDB column
-    /// type is not a valid type as a source for `T`.
+    /// Returns an `Error::ColumnConversionFailure` naming the column, its
+    /// type and `T` if the value can't be converted to `T`, with the
+    /// [`FromSqlError`] of the conversion as its source: `InvalidType` if the
+    /// underlying DuckDB column type is not a valid type as a source for `T`,
+    /// and `OutOfRange` if the value doesn't fit in `T`. A `UNION` column is
+    /// converted from its active member if `T` can't be read from the union
+    /// itself, failing with an error naming the member otherwise. In [strict
+    /// mode](crate::Connection::set_strict_types) `InvalidType` is also the
+    /// source for column types outside the family `T` accepts.
     ///
     /// Returns an `Error::InvalidColumnIndex` if `idx` is outside the valid
     /// column range for this row.
@@ -294,23 +370,63 @@ impl<'stmt> Row<'stmt> {
     /// Returns an `Error::InvalidColumnName` if `idx` is not a valid column
     /// name for this row.
     ///
-    /// If the result type is i128 (which requires the `i128_blob` feature to be
-    /// enabled), and the underlying DuckDB column is a blob whose size is not
-    /// 16 bytes, `Error::InvalidColumnType` will also be returned.
-    pub fn get<I: RowIndex, T: FromSql>(&self, idx: I) -> Result<T> {
+    /// `T` may borrow from the row through [`FromSqlRef`](types::FromSqlRef):
+    /// `Cow<str>` borrows `VARCHAR` values rather than copying them.
+    pub fn get<'r, I: RowIndex, T: FromSqlRef<'r>>(&'r self, idx: I) -> Result<T> {
         let idx = idx.idx(self.stmt)?;
-        let value = self.value_ref(self.current_row, idx);
-        FromSql::column_result(value).map_err(|err| match err {
-            FromSqlError::InvalidType => {
-                Error::InvalidColumnType(idx, self.stmt.column_name_unwrap(idx).into(), value.data_type())
+        let conversion_failure = |source| Error::ColumnConversionFailure {
+            index: idx,
+            name: self.stmt.column_name_unwrap(idx).clone(),
+            column_type: types::Type::from(&self.stmt.column_type(idx)),
+            rust_type: std::any::type_name::<T>(),
+            source,
+        };
+        let value = match self.value_ref(self.current_row, idx) {
+            value if self.stmt.lenient_bools() && reads_bool::<T>() => {
+                integer_as_bool(value).map_err(conversion_failure)?
             }
-            FromSqlError::OutOfRange(i) => Error::IntegralValueOutOfRange(idx, i),
-            FromSqlError::Other(err) => Error::FromSqlConversionFailure(idx, value.data_type(), err),
-            #[cfg(feature = "uuid")]
-            FromSqlError::InvalidUuidSize(_) => {
-                Error::InvalidColumnType(idx, self.stmt.column_name_unwrap(idx).into(), value.data_type())
+            value => value,
+        };
+        if let ValueRef::Timestamp(unit, t) = value {
+            if self.stmt.lenient_timestamps() {
+                // Types holding an instant, which accept a `TIMESTAMPTZ` but
+                // not a `TIMESTAMP`, read it as one in UTC, and so accept it
+                // in strict mode too.
+                let reads_timestamp = T::accepts_ref(&types::Type::Timestamp);
+                let reads_instant = !reads_timestamp && T::accepts_ref(&types::Type::TimestampTz);
+                if self.stmt.strict_types() && !reads_timestamp && !reads_instant {
+                    return Err(conversion_failure(FromSqlError::InvalidType));
+                }
+                let value = if reads_instant {
+                    ValueRef::TimestampTz(unit, t)
+                } else {
+                    value
+                };
+                return T::column_result_ref(value).map_err(conversion_failure);
             }
-        })
+        }
+        if self.stmt.strict_types() && !strictly_accepts::<T>(value) {
+            return Err(conversion_failure(FromSqlError::InvalidType));
+        }
+        T::column_result_ref(value)
+            .or_else(|err| match (err, value.as_union()) {
+                // Fall through to the active member of a UNION.
+                (FromSqlError::InvalidType, Ok((tag, member))) => {
+                    T::column_result_ref(member).map_err(|err| match err {
+                        FromSqlError::InvalidType => FromSqlError::Other(
+                            format!(
+                                "UNION member {tag:?} of type {} cannot be converted to {}",
+                                member.data_type(),
+                                std::any::type_name::<T>()
+                            )
+                            .into(),
+                        ),
+                        err => err,
+                    })
+                }
+                (err, _) => Err(err),
+            })
+            .map_err(conversion_failure)
     }
 
     /// Get the value of a particular column of the result row as a `ValueRef`,
@@ -321,6 +437,41 @@ impl<'stmt> Row<'stmt> {
     /// it can be somewhat difficult to use, and most callers will be better
     /// served by [`get`](Row::get) or [`get_unwrap`](Row::get_unwrap).
     ///
+    /// The row is a shared view of its result chunk, so the `ValueRef`s of
+    /// several columns can be held at once.
+    ///
+    /// Text and blobs borrow straight from the result chunk, so scanning a
+    /// `VARCHAR` column with [`as_str`](ValueRef::as_str) doesn't allocate:
+    ///
+    /// ```rust
+    /// # use duckdb::{Connection, Result};
+    /// fn total_len(conn: &Connection) -> Result<usize> {
+    ///     let mut stmt = conn.prepare("SELECT 'row ' || range FROM range(10000)")?;
+    ///     let mut rows = stmt.query([])?;
+    ///     let mut len = 0;
+    ///     while let Some(row) = rows.next()? {
+    ///         len += row.get_ref(0)?.as_str()?.len();
+    ///     }
+    ///     Ok(len)
+    /// }
+    /// # assert_eq!(78_890, total_len(&Connection::open_in_memory().unwrap()).unwrap());
+    /// ```
+    ///
+    /// The borrow can't be kept past the next call to
+    /// [`Rows::next`](crate::Rows::next):
+    ///
+    /// ```rust,compile_fail
+    /// # use duckdb::{Connection, Result};
+    /// fn print_first(conn: &Connection) -> Result<()> {
+    ///     let mut stmt = conn.prepare("SELECT 'a' UNION ALL SELECT 'b'")?;
+    ///     let mut rows = stmt.query([])?;
+    ///     let first = rows.next()?.unwrap().get_ref(0)?.as_str()?;
+    ///     rows.next()?;
+    ///     println!("{first}");
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
     /// ## Failure
     ///
     /// Returns an `Error::InvalidColumnIndex` if `idx` is outside the valid
@@ -483,52 +634,53 @@ impl<'stmt> Row<'stmt> {
                 if array.is_null(row) {
                     return ValueRef::Null;
                 }
-                // hugeint: d:38,0
-                if array.scale() == 0 {
+                // DuckDB exports HUGEINT as DECIMAL(38, 0), so that's what
+                // an actual DECIMAL(38, 0) reads as too.
+                if array.precision() == 38 && array.scale() == 0 {
                     return ValueRef::HugeInt(array.value(row));
                 }
-                ValueRef::Decimal(Decimal::from_i128_with_scale(array.value(row), array.scale() as u32))
-            }
-            DataType::Timestamp(unit, _) if *unit == TimeUnit::Second => {
-                let array = column.as_any().downcast_ref::<array::TimestampSecondArray>().unwrap();
-
-                if array.is_null(row) {
-                    return ValueRef::Null;
-                }
-                ValueRef::Timestamp(types::TimeUnit::Second, array.value(row))
-            }
-            DataType::Timestamp(unit, _) if *unit == TimeUnit::Millisecond => {
-                let array = column
-                    .as_any()
-                    .downcast_ref::<array::TimestampMillisecondArray>()
-                    .unwrap();
-
-                if array.is_null(row) {
-                    return ValueRef::Null;
+                ValueRef::Decimal {
+                    value: array.value(row),
+                    width: array.precision(),
+                    scale: array.scale() as u8,
                 }
-                ValueRef::Timestamp(types::TimeUnit::Millisecond, array.value(row))
             }
-            DataType::Timestamp(unit, _) if *unit == TimeUnit::Microsecond => {
-                let array = column
-                    .as_any()
-                    .downcast_ref::<array::TimestampMicrosecondArray>()
-                    .unwrap();
-
-                if array.is_null(row) {
-                    return ValueRef::Null;
-                }
-                ValueRef::Timestamp(types::TimeUnit::Microsecond, array.value(row))
-            }
-            DataType::Timestamp(unit, _) if *unit == TimeUnit::Nanosecond => {
-                let array = column
-                    .as_any()
-                    .downcast_ref::<array::TimestampNanosecondArray>()
-                    .unwrap();
-
-                if array.is_null(row) {
-                    return ValueRef::Null;
+            DataType::Timestamp(unit, tz) => {
+                let column = column.as_any();
+                let (unit, value) = match unit {
+                    TimeUnit::Second => (
+                        types::TimeUnit::Second,
+                        column.downcast_ref::<array::TimestampSecondArray>().unwrap().value(row),
+                    ),
+                    TimeUnit::Millisecond => (
+                        types::TimeUnit::Millisecond,
+                        column
+                            .downcast_ref::<array::TimestampMillisecondArray>()
+                            .unwrap()
+                            .value(row),
+                    ),
+                    TimeUnit::Microsecond => (
+                        types::TimeUnit::Microsecond,
+                        column
+                            .downcast_ref::<array::TimestampMicrosecondArray>()
+                            .unwrap()
+                            .value(row),
+                    ),
+                    TimeUnit::Nanosecond => (
+                        types::TimeUnit::Nanosecond,
+                        column
+                            .downcast_ref::<array::TimestampNanosecondArray>()
+                            .unwrap()
+                            .value(row),
+                    ),
+                };
+                // TIMESTAMPTZ is exported with the session time zone attached,
+                // but the value itself is always relative to the UTC epoch
+                if tz.is_some() {
+                    ValueRef::TimestampTz(unit, value)
+                } else {
+                    ValueRef::Timestamp(unit, value)
                 }
-                ValueRef::Timestamp(types::TimeUnit::Nanosecond, array.value(row))
             }
             DataType::Date32 => {
                 let array = column.as_any().downcast_ref::<array::Date32Array>().unwrap();
@@ -619,7 +771,11 @@ impl<'stmt> Row<'stmt> {
                 let arr = column.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
                 ValueRef::Array(arr, row)
             }
-            DataType::Union(..) => ValueRef::Union(column, row),
+            DataType::Union(..) => match ValueRef::Union(column, row).as_union() {
+                // Union arrays have no validity of their own, a NULL union is a NULL member.
+                Ok((_, ValueRef::Null)) => ValueRef::Null,
+                _ => ValueRef::Union(column, row),
+            },
             _ => unreachable!("invalid value: {}, {}", col, column.data_type()),
         }
     }
@@ -642,6 +798,75 @@ impl<'stmt> Row<'stmt> {
     pub fn get_ref_unwrap<I: RowIndex>(&self, idx: I) -> ValueRef<'_> {
         self.get_ref(idx).unwrap()
     }
+
+    /// Get the values of all the columns of the result row as `ValueRef`s,
+    /// in order, without copying, as [`get_ref`](Row::get_ref) does.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{types::ValueRef, Connection, Result};
+    /// fn null_counts(conn: &Connection) -> Result<Vec<usize>> {
+    ///     let mut stmt = conn.prepare("SELECT * FROM people")?;
+    ///     let mut counts = vec![0; stmt.column_count()];
+    ///     let mut rows = stmt.query([])?;
+    ///     while let Some(row) = rows.next()? {
+    ///         for (count, value) in counts.iter_mut().zip(row.get_refs()) {
+    ///             *count += usize::from(value == ValueRef::Null);
+    ///         }
+    ///     }
+    ///     Ok(counts)
+    /// }
+    /// ```
+    pub fn get_refs(&self) -> impl Iterator<Item = ValueRef<'_>> + '_ {
+        let columns = self.arr.as_ref().as_ref().map_or(0, |arr| arr.num_columns());
+        (0..columns).map(move |col| self.value_ref(self.current_row, col))
+    }
+
+    /// `feature = "serde"` Deserialize the whole row into `T`, matching
+    /// columns to struct fields by name, as if the row were a `STRUCT` read
+    /// through [`from_value`](types::from_value).
+    ///
+    /// `Option` fields read nullable columns, nested structs read `STRUCT`
+    /// columns and `Vec`s read `LIST`s. Columns without a matching field are
+    /// ignored, unless `T` is `#[serde(deny_unknown_fields)]`.
+    ///
+    /// ```rust
+    /// # use duckdb::{Connection, Result};
+    /// #[derive(serde::Deserialize)]
+    /// struct Person {
+    ///     name: String,
+    ///     #[serde(rename = "years")]
+    ///     age: Option<u8>,
+    /// }
+    ///
+    /// fn get_person(conn: &Connection) -> Result<Person> {
+    ///     conn.query_row("SELECT 'ann' AS name, 42 AS years, 1 AS id", [], |row| row.deserialize())
+    /// }
+    /// ```
+    ///
+    /// ## Failure
+    ///
+    /// Returns an `Error::FromSqlConversionFailure` if a field has no column,
+    /// or a column can't be read into its field.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        let columns: Vec<_> = (0..self.stmt.column_count())
+            .map(|idx| {
+                let name = self.stmt.column_name_unwrap(idx).clone();
+                (name, self.value_ref(self.current_row, idx).to_owned())
+            })
+            .collect();
+        types::from_value(types::Value::Struct(columns.into())).map_err(|err| {
+            let ty = (0..self.stmt.column_count())
+                .map(|idx| {
+                    let name = self.stmt.column_name_unwrap(idx).clone();
+                    (name, types::Type::from(&self.stmt.column_type(idx)))
+                })
+                .collect();
+            Error::FromSqlConversionFailure(UNKNOWN_COLUMN, types::Type::Struct(ty), Box::new(err))
+        })
+    }
 }
 
 impl<'stmt> AsRef<Statement<'stmt>> for Row<'stmt> {
@@ -719,11 +944,225 @@ macro_rules! tuples_try_from_row {
 
 tuples_try_from_row!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
 
+/// Whether `T` accepts `value` in strict mode. `NULL` is always accepted, and
+/// a `UNION` is accepted if its active member is.
+fn strictly_accepts<'a, T: FromSqlRef<'a>>(value: ValueRef<'_>) -> bool {
+    match value {
+        ValueRef::Null => true,
+        _ if T::accepts_ref(&value.data_type()) => true,
+        _ => matches!(value.as_union(), Ok((_, member)) if strictly_accepts::<T>(member)),
+    }
+}
+
+/// Whether `T` is a `bool`-like type, accepting `BOOLEAN` columns but no
+/// integer ones, and so subject to lenient bool mode.
+fn reads_bool<'a, T: FromSqlRef<'a>>() -> bool {
+    T::accepts_ref(&types::Type::Boolean) && !T::accepts_ref(&types::Type::TinyInt)
+}
+
+/// Reads an integer `0` or `1` as a `BOOLEAN`, failing with
+/// [`FromSqlError::OutOfRange`] for any other integer. Other values are left
+/// alone.
+fn integer_as_bool(value: ValueRef<'_>) -> std::result::Result<ValueRef<'_>, FromSqlError> {
+    let integer = matches!(
+        value,
+        ValueRef::TinyInt(_)
+            | ValueRef::SmallInt(_)
+            | ValueRef::Int(_)
+            | ValueRef::BigInt(_)
+            | ValueRef::HugeInt(_)
+            | ValueRef::UTinyInt(_)
+            | ValueRef::USmallInt(_)
+            | ValueRef::UInt(_)
+            | ValueRef::UBigInt(_)
+            | ValueRef::UHugeInt(_)
+    );
+    if !integer {
+        return Ok(value);
+    }
+    match i128::column_result(value) {
+        Ok(0) => Ok(ValueRef::Boolean(false)),
+        Ok(1) => Ok(ValueRef::Boolean(true)),
+        Ok(i) => Err(FromSqlError::out_of_range(i, "bool")),
+        Err(FromSqlError::OutOfRange { value, .. }) => Err(FromSqlError::OutOfRange { value, target: "bool" }),
+        Err(_) => Ok(value),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::redundant_closure)] // false positives due to lifetime issues; clippy issue #5594
     use crate::{Connection, Result};
 
+    #[test]
+    fn test_lenient_bools() -> Result<()> {
+        use crate::{
+            types::{FromSqlError, Type, Value},
+            Error,
+        };
+
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            "CREATE TABLE t (i INTEGER, b TINYINT); INSERT INTO t VALUES (0, 0), (1, 1), (2, 2), (3, NULL)",
+        )?;
+        let read = |i: i32| conn.query_row("SELECT b FROM t WHERE i = ?", [i], |r| r.get::<_, bool>(0));
+        let read_opt = |i: i32| conn.query_row("SELECT b FROM t WHERE i = ?", [i], |r| r.get::<_, Option<bool>>(0));
+
+        let failure = |source| {
+            Err(Error::ColumnConversionFailure {
+                index: 0,
+                name: "b".to_owned(),
+                column_type: Type::TinyInt,
+                rust_type: "bool",
+                source,
+            })
+        };
+        for i in 0..4 {
+            assert_eq!(read(i), failure(FromSqlError::InvalidType));
+        }
+        assert_eq!(read_opt(3)?, None);
+        assert!(conn.query_row("SELECT true", [], |r| r.get::<_, bool>(0))?);
+
+        conn.set_lenient_bools(true);
+        assert!(!read(0)?);
+        assert!(read(1)?);
+        assert_eq!(read(2), failure(FromSqlError::out_of_range(2, "bool")));
+        assert_eq!(
+            read(2).unwrap_err().to_string(),
+            "Cannot read column 0 (b) of type TinyInt as bool: Value 2 out of range for bool"
+        );
+        assert_eq!(read(3), failure(FromSqlError::InvalidType));
+        assert_eq!(read_opt(1)?, Some(true));
+        assert_eq!(read_opt(3)?, None);
+        assert!(conn.query_row("SELECT true", [], |r| r.get::<_, bool>(0))?);
+        assert!(conn.query_row("SELECT 1::UBIGINT", [], |r| r.get::<_, bool>(0))?);
+        assert!(conn.query_row("SELECT 'true'", [], |r| r.get::<_, bool>(0)).is_err());
+
+        // Other types still read the integer itself.
+        let (i, v) = conn.query_row("SELECT b, b FROM t WHERE i = 2", [], |r| {
+            Ok((r.get::<_, i8>(0)?, r.get::<_, Value>(1)?))
+        })?;
+        assert_eq!((i, v), (2, Value::TinyInt(2)));
+        let (ty, v) = conn.query_row("SELECT typeof(?1), ?1", [1i8], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, Value>(1)?))
+        })?;
+        assert_eq!((ty.as_str(), v), ("TINYINT", Value::TinyInt(1)));
+
+        conn.set_strict_types(true);
+        assert!(read(1)?);
+        assert_eq!(read(2), failure(FromSqlError::out_of_range(2, "bool")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_conversion_failure() -> Result<()> {
+        use crate::{types::FromSqlError, Error, ErrorKind};
+        use std::error::Error as _;
+
+        let conn = Connection::open_in_memory()?;
+        let err = conn
+            .query_row(
+                "SELECT 1 AS id, 'ann' AS name, 2.5::DOUBLE AS score, [1, 2] AS tags",
+                [],
+                |r| r.get::<_, i64>("tags"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot read column 3 (tags) of type List as i64: Invalid type"
+        );
+        assert_eq!(err.kind(), ErrorKind::Conversion);
+        let source = err.source().and_then(|e| e.downcast_ref::<FromSqlError>());
+        assert_eq!(source, Some(&FromSqlError::InvalidType));
+
+        let err = conn
+            .query_row("SELECT 300 AS n", [], |r| r.get::<_, Option<u8>>(0))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot read column 0 (n) of type Int as core::option::Option<u8>: Value 300 out of range for u8"
+        );
+        assert_eq!(err.kind(), ErrorKind::OutOfRange);
+        assert!(matches!(err, Error::ColumnConversionFailure { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_types() -> Result<()> {
+        use crate::{
+            types::{FromSql, Value},
+            Error,
+        };
+        use rust_decimal::Decimal;
+        use std::collections::HashMap;
+
+        fn reads<T: FromSql>(conn: &Connection, sql: &str) -> Result<()> {
+            conn.query_row(sql, [], |r| r.get::<_, T>(0)).map(|_| ())
+        }
+
+        type Read = fn(&Connection, &str) -> Result<()>;
+        // (query, conversion, allowed when lenient, allowed when strict)
+        let cases: &[(&str, Read, bool, bool)] = &[
+            ("SELECT 1::SMALLINT", reads::<i64>, true, true),
+            ("SELECT 1::HUGEINT", reads::<u8>, true, true),
+            ("SELECT '123'", reads::<i64>, true, false),
+            ("SELECT 1.5::DOUBLE", reads::<i64>, true, false),
+            ("SELECT 1.5::REAL", reads::<f64>, true, true),
+            ("SELECT 1", reads::<f64>, true, false),
+            ("SELECT true", reads::<bool>, true, true),
+            ("SELECT 1", reads::<bool>, false, false),
+            ("SELECT 'a'", reads::<String>, true, true),
+            ("SELECT 'a'::ENUM('a')", reads::<String>, true, true),
+            ("SELECT 42", reads::<String>, false, false),
+            (
+                "SELECT TIMESTAMP '2024-01-01 00:00:00'",
+                reads::<String>,
+                cfg!(feature = "chrono"),
+                false,
+            ),
+            ("SELECT 1.5::DECIMAL(4, 1)", reads::<Decimal>, true, true),
+            ("SELECT 1", reads::<Decimal>, true, false),
+            ("SELECT 'a'::BLOB", reads::<Vec<u8>>, true, true),
+            ("SELECT 'a'", reads::<Vec<u8>>, true, false),
+            ("SELECT [1, 2]", reads::<Vec<i64>>, true, true),
+            ("SELECT ['1']", reads::<Vec<i64>>, true, false),
+            ("SELECT array_value(1, 2)", reads::<[i64; 2]>, true, true),
+            ("SELECT MAP {'a': 1}", reads::<HashMap<String, i32>>, true, true),
+            ("SELECT MAP {1: 1}", reads::<HashMap<String, i32>>, false, false),
+            ("SELECT MAP {'a': '1'}", reads::<HashMap<String, i32>>, true, false),
+            ("SELECT NULL::VARCHAR", reads::<Option<i64>>, true, true),
+            ("SELECT '1'", reads::<Option<i64>>, true, false),
+            ("SELECT union_value(n := 1)", reads::<i32>, true, true),
+            ("SELECT union_value(s := '1')", reads::<i32>, true, false),
+            ("SELECT 'a'", reads::<Value>, true, true),
+        ];
+
+        let conn = Connection::open_in_memory()?;
+        for &(sql, read, lenient, _) in cases {
+            assert_eq!(lenient, read(&conn, sql).is_ok(), "lenient {sql}");
+        }
+        conn.set_strict_types(true);
+        for &(sql, read, _, strict) in cases {
+            match read(&conn, sql) {
+                Ok(()) => assert!(strict, "strict {sql} was allowed"),
+                Err(err) => assert!(!strict, "strict {sql}: {err}"),
+            }
+        }
+
+        let err = reads::<i64>(&conn, "SELECT '123' AS n").unwrap_err();
+        assert_eq!(
+            Error::ColumnConversionFailure {
+                index: 0,
+                name: "n".to_owned(),
+                column_type: crate::types::Type::Text,
+                rust_type: "i64",
+                source: crate::types::FromSqlError::InvalidType,
+            },
+            err
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_try_from_row_for_tuple_1() -> Result<()> {
         use crate::ToSql;
@@ -842,4 +1281,76 @@ mod tests {
         // We don't test one bigger because it's unimplemented
         Ok(())
     }
+
+    #[test]
+    fn test_row_iterator() -> Result<()> {
+        use crate::Row;
+
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare("SELECT range AS x, 'row ' || range AS Name FROM range(3000)")?;
+        let rows: Vec<Row<'_>> = stmt.query([])?.iterator().collect::<Result<_>>()?;
+        assert_eq!(rows.len(), 3000);
+        // Rows of earlier chunks stay readable.
+        assert_eq!(rows[1].get::<_, i64>("x")?, 1);
+        assert_eq!(rows[2999].get::<_, String>("name")?, "row 2999");
+        assert_eq!(rows[0].column_names(), ["x", "Name"]);
+
+        let mut stmt =
+            db.prepare("SELECT CASE WHEN i = 100000 THEN error('boom') ELSE i END FROM range(200000) t(i)")?;
+        let mut iter = stmt.query_streaming([])?.iterator();
+        let mut count = 0;
+        let err = loop {
+            match iter.next() {
+                Some(Ok(_)) => count += 1,
+                Some(Err(err)) => break err,
+                None => panic!("no error after {count} rows"),
+            }
+        };
+        assert!(count <= 100000);
+        assert!(err.to_string().contains("boom"), "{err}");
+        assert!(iter.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_refs() -> Result<()> {
+        use crate::types::ValueRef;
+
+        let db = Connection::open_in_memory()?;
+        let mut stmt =
+            db.prepare("SELECT 'duck' AS name, '\\x01\\xFF'::BLOB AS data, 42 AS n, NULL::INTEGER AS missing")?;
+        let mut rows = stmt.query([])?;
+        let row = rows.next()?.unwrap();
+        let name = row.get_ref("name")?;
+        let data = row.get_ref(1)?;
+        let n = row.get_ref("n")?;
+        assert_eq!(name.as_str()?, "duck");
+        assert_eq!(data.as_blob()?, [1, 255]);
+        assert_eq!(n, ValueRef::Int(42));
+        // Only the kept value is copied.
+        let kept = name.as_str()?.to_owned();
+
+        let all: Vec<_> = row.get_refs().collect();
+        assert_eq!(all, [name, data, n, ValueRef::Null]);
+        assert_eq!(kept, "duck");
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_index_by_name() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare("SELECT 1 AS a, 2 AS \"B\", 3 AS \"Émile\", 4 AS b_1")?;
+        // Before execution the columns are described.
+        assert_eq!(stmt.column_index("b")?, 1);
+        let mut rows = stmt.query([])?;
+        let row = rows.next()?.unwrap();
+        assert_eq!(row.get::<_, i32>("A")?, 1);
+        assert_eq!(row.get::<_, i32>("b")?, 2);
+        assert_eq!(row.get::<_, i32>("B")?, 2);
+        assert_eq!(row.get::<_, i32>("Émile")?, 3);
+        assert_eq!(row.get::<_, i32>("b_1")?, 4);
+        assert!(row.get::<_, i32>("émile").is_err());
+        assert!(row.get::<_, i32>("c").is_err());
+        Ok(())
+    }
 }
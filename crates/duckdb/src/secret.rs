@@ -0,0 +1,252 @@
+//! Creating, listing and dropping secrets, the credentials DuckDB uses to
+//! reach cloud storage
+
+use crate::{pragma::Sql, Connection, Result};
+
+/// A secret to create with [`Connection::create_secret`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SecretDef {
+    /// The name of the secret. DuckDB lowercases it.
+    pub name: String,
+    /// The type of the secret, such as `s3`, `gcs` or `r2`. Types are added
+    /// by extensions, `httpfs` for those three.
+    pub type_: String,
+    /// The path prefixes the secret is used for, such as `s3://my-bucket`.
+    /// When empty, it is used for every path its type applies to.
+    pub scope: Vec<String>,
+    /// The options of the secret, such as `("KEY_ID", "...")`, and its
+    /// provider, as `("PROVIDER", "credential_chain")`. Which options there
+    /// are depends on the type.
+    pub key_values: Vec<(String, String)>,
+    /// Store the secret in the secret directory, so that it outlives the
+    /// database instance, instead of keeping it in memory.
+    pub persistent: bool,
+}
+
+/// A secret, as listed by [`Connection::list_secrets`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecretInfo {
+    /// The name of the secret.
+    pub name: String,
+    /// The type of the secret, such as `s3`.
+    pub type_: String,
+    /// How the secret gets its credentials, `config` when they were given
+    /// as options.
+    pub provider: String,
+    /// Whether the secret outlives the database instance.
+    pub persistent: bool,
+    /// Where the secret is stored, `memory` or `local_file`.
+    pub storage: String,
+    /// The path prefixes the secret is used for.
+    pub scope: Vec<String>,
+    /// The options of the secret, with the values of sensitive ones, such as
+    /// `secret`, replaced by `redacted`.
+    pub secret_string: String,
+}
+
+impl Connection {
+    /// Create the secret `secret`, for DuckDB to use when reading and
+    /// writing the paths in its scope.
+    ///
+    /// Values are passed as string literals, so they need no escaping.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result, SecretDef};
+    /// fn read_bucket(conn: &Connection, key_id: &str, secret: &str) -> Result<()> {
+    ///     conn.create_secret(SecretDef {
+    ///         name: "bucket".to_owned(),
+    ///         type_: "s3".to_owned(),
+    ///         scope: vec!["s3://my-bucket".to_owned()],
+    ///         key_values: vec![("KEY_ID".to_owned(), key_id.to_owned()), ("SECRET".to_owned(), secret.to_owned())],
+    ///         ..Default::default()
+    ///     })?;
+    ///     conn.execute_batch("CREATE TABLE t AS SELECT * FROM 's3://my-bucket/data.parquet'")
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if an option name isn't a bare identifier, and if
+    /// the underlying DuckDB call fails, such as when there is already a
+    /// secret named `secret.name`, or its type or an option is unknown.
+    pub fn create_secret(&self, secret: SecretDef) -> Result<()> {
+        let mut sql = Sql::new();
+        sql.push_keyword("CREATE")?;
+        sql.push_space();
+        sql.push_keyword(if secret.persistent { "PERSISTENT" } else { "TEMPORARY" })?;
+        sql.push_space();
+        sql.push_keyword("SECRET")?;
+        sql.push_space();
+        sql.push_identifier(&secret.name);
+        sql.push_space();
+        sql.open_brace();
+        sql.push_keyword("TYPE")?;
+        sql.push_space();
+        sql.push_string_literal(&secret.type_);
+        if !secret.scope.is_empty() {
+            sql.push_comma();
+            sql.push_space();
+            sql.push_keyword("SCOPE")?;
+            sql.push_space();
+            sql.open_brace();
+            for (i, prefix) in secret.scope.iter().enumerate() {
+                if i > 0 {
+                    sql.push_comma();
+                    sql.push_space();
+                }
+                sql.push_string_literal(prefix);
+            }
+            sql.close_brace();
+        }
+        for (key, value) in &secret.key_values {
+            sql.push_comma();
+            sql.push_space();
+            sql.push_keyword(key)?;
+            sql.push_space();
+            sql.push_string_literal(value);
+        }
+        sql.close_brace();
+        self.execute_batch(&sql)
+    }
+
+    /// Drop the secret `name`, wherever it is stored.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if there is no secret named `name`.
+    pub fn drop_secret(&self, name: &str) -> Result<()> {
+        let mut sql = Sql::new();
+        sql.push_keyword("DROP")?;
+        sql.push_space();
+        sql.push_keyword("SECRET")?;
+        sql.push_space();
+        sql.push_identifier(name);
+        self.execute_batch(&sql)
+    }
+
+    /// List the secrets of the database instance, temporary and persistent,
+    /// ordered by name. Sensitive values are redacted.
+    pub fn list_secrets(&self) -> Result<Vec<SecretInfo>> {
+        let mut stmt = self.prepare(
+            "SELECT name, type, provider, persistent, storage, scope, secret_string FROM duckdb_secrets() ORDER BY name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(SecretInfo {
+                name: row.get(0)?,
+                type_: row.get(1)?,
+                provider: row.get(2)?,
+                persistent: row.get(3)?,
+                storage: row.get(4)?,
+                scope: row.get(5)?,
+                secret_string: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SecretDef;
+    use crate::{Connection, Error, Result};
+
+    #[cfg(feature = "httpfs")]
+    fn s3_secret(name: &str, persistent: bool) -> SecretDef {
+        SecretDef {
+            name: name.to_owned(),
+            type_: "s3".to_owned(),
+            scope: vec!["s3://bucket-a".to_owned(), "s3://it's/b".to_owned()],
+            key_values: vec![
+                ("KEY_ID".to_owned(), "AKIAFAKE".to_owned()),
+                ("SECRET".to_owned(), "sh'h; DROP TABLE t; --".to_owned()),
+                ("REGION".to_owned(), "eu-west-1".to_owned()),
+            ],
+            persistent,
+        }
+    }
+
+    #[test]
+    fn test_secret_errors() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        assert!(db.list_secrets()?.is_empty());
+
+        let err = db
+            .create_secret(SecretDef {
+                name: "s".to_owned(),
+                type_: "no_such_type".to_owned(),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(matches!(err, Error::DuckDBFailure(..)), "{err:?}");
+
+        let bad_key = SecretDef {
+            name: "s".to_owned(),
+            type_: "s3".to_owned(),
+            key_values: vec![("KEY_ID) --".to_owned(), "x".to_owned())],
+            ..Default::default()
+        };
+        assert!(db.create_secret(bad_key).is_err());
+
+        assert!(db.drop_secret("missing").is_err());
+        assert!(db.list_secrets()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "httpfs")]
+    fn test_s3_secret() -> Result<()> {
+        use super::SecretInfo;
+
+        let db = Connection::open_in_memory()?;
+        db.create_secret(s3_secret("My \"Secret\"", false))?;
+        let secrets = db.list_secrets()?;
+        assert_eq!(secrets.len(), 1);
+        let SecretInfo {
+            name,
+            type_,
+            provider,
+            persistent,
+            storage,
+            scope,
+            secret_string,
+        } = &secrets[0];
+        assert_eq!(name, "my \"secret\"");
+        assert_eq!(type_, "s3");
+        assert_eq!(provider, "config");
+        assert!(!persistent);
+        assert_eq!(storage, "memory");
+        assert_eq!(scope, &["s3://bucket-a", "s3://it's/b"]);
+        assert!(secret_string.contains("key_id=AKIAFAKE"), "{secret_string}");
+        assert!(secret_string.contains("region=eu-west-1"), "{secret_string}");
+        assert!(!secret_string.contains("sh'h"), "{secret_string}");
+
+        assert!(db.create_secret(s3_secret("my \"secret\"", false)).is_err());
+        db.drop_secret("My \"Secret\"")?;
+        assert!(db.list_secrets()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "httpfs")]
+    fn test_persistent_secret() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(&format!("SET secret_directory = '{}'", dir.path().display()))?;
+        db.create_secret(s3_secret("kept", true))?;
+        db.create_secret(s3_secret("scratch", false))?;
+
+        let secrets = db.list_secrets()?;
+        let stored: Vec<_> = secrets
+            .iter()
+            .map(|secret| (secret.name.as_str(), secret.persistent, secret.storage.as_str()))
+            .collect();
+        assert_eq!(stored, [("kept", true, "local_file"), ("scratch", false, "memory")]);
+        assert!(dir.path().join("kept.duckdb_secret").exists());
+
+        db.drop_secret("kept")?;
+        assert!(!dir.path().join("kept.duckdb_secret").exists());
+        Ok(())
+    }
+}
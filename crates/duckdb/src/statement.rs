@@ -1,4 +1,13 @@
-use std::{convert, ffi::c_void, fmt, mem, os::raw::c_char, ptr, str};
+use std::{
+    cell::{OnceCell, RefCell, RefMut},
+    convert,
+    ffi::c_void,
+    fmt, mem,
+    os::raw::c_char,
+    ptr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use arrow::{array::StructArray, datatypes::DataType};
 
@@ -7,21 +16,37 @@ use super::{ffi, AndThenRows, Connection, Error, MappedRows, Params, RawStatemen
 use crate::{arrow2, polars_dataframe::Polars};
 use crate::{
     arrow_batch::Arrow,
+    batch::{placeholders, Placeholder},
+    cache::is_catalog_change,
+    cancel::Executions,
     error::result_from_duckdb_prepare,
-    types::{TimeUnit, ToSql, ToSqlOutput},
+    raw_statement::ArrowOptions,
+    types::{check_decimal, timestamptz_to_string, LogicalType, TimeUnit, ToSql, ToSqlOutput, Value, ValueHandle},
 };
+#[cfg(feature = "polars")]
+use polars::prelude::{DataType as PolarsDataType, Series};
 
 /// A prepared statement.
 pub struct Statement<'conn> {
-    conn: &'conn Connection,
+    pub(crate) conn: &'conn Connection,
     pub(crate) stmt: RawStatement,
+    // The JSON profile of the last execution, while profiling is enabled.
+    #[cfg_attr(not(feature = "serde_json"), allow(dead_code))]
+    pub(crate) profile: Option<String>,
+    // The values last bound to the parameters, by position.
+    bound: RefCell<Vec<Option<Value>>>,
+    // The executions cancellation tokens see, once one is handed out.
+    pub(crate) executions: OnceCell<Arc<Mutex<Executions>>>,
 }
 
 impl Statement<'_> {
     /// Execute the prepared statement.
     ///
     /// On success, returns the number of rows that were changed or inserted or
-    /// deleted.
+    /// deleted by an `INSERT`, `UPDATE` or `DELETE`, as DuckDB counts them, and
+    /// `0` for any other statement, such as DDL or a query. Statements with a
+    /// `RETURNING` clause return rows instead of a count, so they return `0`
+    /// too; [`query`](Statement::query) them to see the rows they changed.
     ///
     /// ## Example
     ///
@@ -87,9 +112,96 @@ impl Statement<'_> {
         }
     }
 
+    /// Execute an `INSERT`, `UPDATE` or `DELETE` with a `RETURNING` clause,
+    /// mapping each row it returns with `f`.
+    ///
+    /// This is equivalent to collecting
+    /// [`query_map`](Statement::query_map), and works for any statement that
+    /// returns rows.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn add_people(conn: &Connection) -> Result<Vec<i64>> {
+    ///     let mut stmt = conn.prepare("INSERT INTO people (name) VALUES (?), (?) RETURNING id")?;
+    ///     stmt.insert_returning(["Alice", "Bob"], |row| row.get(0))
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if binding parameters fails, the statement fails, or
+    /// `f` fails for any row.
+    pub fn insert_returning<T, P, F>(&mut self, params: P, f: F) -> Result<Vec<T>>
+    where
+        P: Params,
+        F: FnMut(&Row<'_>) -> Result<T>,
+    {
+        self.query_map(params, f)?.collect()
+    }
+
+    /// Execute the statement once for each set of parameters, returning the
+    /// total number of rows changed, counted as by
+    /// [`execute`](Statement::execute).
+    ///
+    /// The executions run in a transaction, committed once they all
+    /// succeeded, unless one is already open on the connection, in which
+    /// case they are part of it.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result, ToSql};
+    /// fn add_people(conn: &Connection, people: &[(String, u8)]) -> Result<usize> {
+    ///     let mut stmt = conn.prepare("INSERT INTO people (name, age) VALUES (?, ?)")?;
+    ///     stmt.execute_batch_params(people.iter().map(|(name, age)| [name as &dyn ToSql, age]))
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return [`Error::ParamSetFailed`] with the zero-based index of the
+    /// set of parameters whose execution failed, and its error. The
+    /// transaction this opened is then rolled back, so none of the sets have
+    /// any effect; a transaction that was already open is failed, and has to
+    /// be rolled back. Will return `Err` if beginning or committing the
+    /// transaction fails.
+    pub fn execute_batch_params<I>(&mut self, param_sets: I) -> Result<usize>
+    where
+        I: IntoIterator,
+        I::Item: Params,
+    {
+        let conn = self.conn;
+        let tx = match conn.is_autocommit()? {
+            true => Some(conn.unchecked_transaction()?),
+            false => None,
+        };
+        let mut changed = 0;
+        for (index, params) in param_sets.into_iter().enumerate() {
+            // Dropping the transaction rolls it back.
+            changed += self
+                .execute(params)
+                .map_err(|err| Error::ParamSetFailed(index, Box::new(err)))?;
+        }
+        if let Some(tx) = tx {
+            tx.commit()?;
+        }
+        Ok(changed)
+    }
+
     /// Execute the prepared statement, returning a handle to the resulting
     /// vector of arrow RecordBatch
     ///
+    /// The statement can be run again with other parameters, reusing its
+    /// plan, and its result has the same schema each time, even when its
+    /// columns hold only NULLs. That is, as long as each parameter is bound
+    /// to a value of the type DuckDB inferred for it when preparing the
+    /// statement: DuckDB binds the statement again for values of another
+    /// type, including NULL, which changes the type of columns typed after a
+    /// parameter, as in `SELECT ? + 1`. Casting the parameter, as in
+    /// `SELECT ?::INTEGER + 1`, keeps the type.
+    ///
     /// ## Example
     ///
     /// ```rust,no_run
@@ -98,6 +210,15 @@ impl Statement<'_> {
     /// fn get_arrow_data(conn: &Connection) -> Result<Vec<RecordBatch>> {
     ///     Ok(conn.prepare("SELECT * FROM test")?.query_arrow([])?.collect())
     /// }
+    ///
+    /// fn get_arrow_pages(conn: &Connection) -> Result<Vec<Vec<RecordBatch>>> {
+    ///     let mut stmt = conn.prepare("SELECT * FROM test LIMIT 100 OFFSET ?")?;
+    ///     let mut pages = Vec::new();
+    ///     for offset in [0, 100, 200] {
+    ///         pages.push(stmt.query_arrow([offset])?.collect());
+    ///     }
+    ///     Ok(pages)
+    /// }
     /// ```
     ///
     /// # Failure
@@ -112,6 +233,10 @@ impl Statement<'_> {
     /// Execute the prepared statement, returning a handle to the resulting
     /// vector of polars DataFrame.
     ///
+    /// Like [`query_arrow`](Statement::query_arrow), the statement can be
+    /// run again with other parameters, and its DataFrames keep the same
+    /// schema as long as the parameters keep their types.
+    ///
     /// ## Example
     ///
     /// ```rust,no_run
@@ -153,6 +278,53 @@ impl Statement<'_> {
         Ok(Polars::new(self))
     }
 
+    /// Execute the prepared statement, returning its single result column as
+    /// a polars Series.
+    ///
+    /// The Series is assembled from the arrow chunks of the result, so the
+    /// DuckDB column type maps to the polars dtype the same way as in
+    /// [`query_polars`](Statement::query_polars), and NULLs are preserved.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Result, Connection};
+    /// # use polars::prelude::Series;
+    ///
+    /// fn get_names(conn: &Connection) -> Result<Series> {
+    ///     conn.prepare("SELECT name FROM test")?.query_series([])
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if binding parameters fails, or
+    /// `Error::InvalidColumnCount` if the query does not return exactly one
+    /// column.
+    #[cfg(feature = "polars")]
+    pub fn query_series<P: Params>(&mut self, params: P) -> Result<Series> {
+        self.execute(params)?;
+        let column_count = self.column_count();
+        if column_count != 1 {
+            return Err(Error::InvalidColumnCount(column_count, 1));
+        }
+        let name = self.column_name(0)?.to_owned();
+
+        let mut chunks = Vec::new();
+        while let Some(struct_array) = self.step2() {
+            chunks.push(struct_array.values()[0].clone());
+        }
+        if chunks.is_empty() {
+            let dtype = match self.stmt.field2().map(|f| f.data_type) {
+                Some(arrow2::datatypes::ArrowDataType::Struct(fields)) => PolarsDataType::from(fields[0].data_type()),
+                _ => PolarsDataType::Null,
+            };
+            return Ok(Series::new_empty(&name, &dtype));
+        }
+        Series::try_from((name.as_str(), chunks))
+            .map_err(|err| Error::FromSqlConversionFailure(0, (&self.column_type(0)).into(), Box::new(err)))
+    }
+
     /// Execute the prepared statement, returning a handle to the resulting
     /// rows.
     ///
@@ -161,6 +333,10 @@ impl Statement<'_> {
     /// [`query_map`](Statement::query_map) or
     /// [`query_and_then`](Statement::query_and_then) instead, which do.
     ///
+    /// `INSERT`, `UPDATE` and `DELETE` statements with a `RETURNING` clause
+    /// return their rows too, see
+    /// [`insert_returning`](Statement::insert_returning).
+    ///
     /// ## Example
     ///
     /// ### Use without parameters
@@ -217,6 +393,85 @@ impl Statement<'_> {
         Ok(Rows::new(self))
     }
 
+    /// Like [`query`](Statement::query), but streams the result: DuckDB
+    /// computes its rows a chunk at a time as they are read, instead of all
+    /// of them before this returns, so a large result doesn't have to fit in
+    /// memory.
+    ///
+    /// The result holds on to the connection until it is read to the end or
+    /// the statement is executed again or dropped. Running another
+    /// statement on the connection in the meantime is fine, but ends the
+    /// rows early, so that `Rows::next` returns `None`. DuckDB only streams
+    /// the results of queries; those of other statements are computed in
+    /// full as usual. [`row_count`] is `0` for a streamed result.
+    ///
+    /// [`row_count`]: Statement::row_count
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn first_ids(conn: &Connection) -> Result<Vec<i64>> {
+    ///     let mut stmt = conn.prepare("SELECT id FROM huge_table")?;
+    ///     let mut rows = stmt.query_streaming([])?;
+    ///     let mut ids = Vec::new();
+    ///     while let Some(row) = rows.next()? {
+    ///         ids.push(row.get(0)?);
+    ///         if ids.len() == 10 {
+    ///             break;
+    ///         }
+    ///     }
+    ///     Ok(ids)
+    /// }
+    /// ```
+    ///
+    /// ## Failure
+    ///
+    /// Will return `Err` if binding parameters fails or the statement fails
+    /// to start. An error computing a later chunk is returned by
+    /// [`Rows::next`].
+    pub fn query_streaming<P: Params>(&mut self, params: P) -> Result<Rows<'_>> {
+        params.__bind_in(self)?;
+        let options = self.conn.db.borrow_mut().arrow_options()?;
+        self.run_with_bound_parameters(Some(&options))?;
+        Ok(Rows::new(self))
+    }
+
+    /// Like [`query`](Statement::query), but interrupts the query if it
+    /// hasn't finished after `timeout`.
+    ///
+    /// DuckDB computes the whole result before this returns, so reading the
+    /// rows can't time out, and the connection can be used as usual
+    /// afterwards, whether the query timed out or not.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Error, Result};
+    /// # use std::time::Duration;
+    /// fn count(conn: &Connection) -> Result<Option<i64>> {
+    ///     let mut stmt = conn.prepare("SELECT count(*) FROM big_table")?;
+    ///     match stmt.query_with_timeout([], Duration::from_secs(5)) {
+    ///         Ok(mut rows) => rows.next()?.unwrap().get(0).map(Some),
+    ///         Err(Error::Timeout(_)) => Ok(None),
+    ///         Err(err) => Err(err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ## Failure
+    ///
+    /// Will return [`Error::Timeout`] if the query is interrupted because of
+    /// the timeout, and `Err` if binding parameters fails or the query fails
+    /// otherwise.
+    pub fn query_with_timeout<P: Params>(&mut self, params: P, timeout: Duration) -> Result<Rows<'_>> {
+        params.__bind_in(self)?;
+        self.conn
+            .interrupt_handle()
+            .run_with_timeout(timeout, || self.execute_with_bound_parameters())?;
+        Ok(Rows::new(self))
+    }
+
     /// Executes the prepared statement and maps a function over the resulting
     /// rows, returning an iterator over the mapped function results.
     ///
@@ -255,6 +510,41 @@ impl Statement<'_> {
         self.query(params).map(|rows| rows.mapped(f))
     }
 
+    /// `feature = "serde"` Executes the prepared statement and deserializes
+    /// each resulting row into `T` with [`Row::deserialize`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// #[derive(serde::Deserialize)]
+    /// struct Person {
+    ///     name: String,
+    ///     age: Option<u8>,
+    /// }
+    ///
+    /// fn get_people(conn: &Connection) -> Result<Vec<Person>> {
+    ///     let mut stmt = conn.prepare("SELECT * FROM people")?;
+    ///     let rows = stmt.query_map_into::<Person, _>([])?;
+    ///     rows.collect()
+    /// }
+    /// ```
+    ///
+    /// ## Failure
+    ///
+    /// Will return `Err` if binding parameters fails.
+    #[cfg(feature = "serde")]
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn query_map_into<T, P>(&mut self, params: P) -> Result<MappedRows<'_, fn(&Row<'_>) -> Result<T>>>
+    where
+        T: serde::de::DeserializeOwned,
+        P: Params,
+    {
+        let deserialize: fn(&Row<'_>) -> Result<T> = |row| row.deserialize();
+        self.query_map(params, deserialize)
+    }
+
     /// Executes the prepared statement and maps a function over the resulting
     /// rows, where the function returns a `Result` with `Error` type
     /// implementing `std::convert::From<Error>` (so errors can be unified).
@@ -295,10 +585,14 @@ impl Statement<'_> {
 
     /// Return `true` if a query in the SQL statement it executes returns one
     /// or more rows and `false` if the SQL returns an empty set.
+    ///
+    /// The result is [streamed](Statement::query_streaming), so DuckDB stops
+    /// computing it once the first row is found.
     #[inline]
     pub fn exists<P: Params>(&mut self, params: P) -> Result<bool> {
-        let mut rows = self.query(params)?;
+        let mut rows = self.query_streaming(params)?;
         let exists = rows.next()?.is_some();
+        self.stmt.close_chunked();
         Ok(exists)
     }
 
@@ -325,6 +619,107 @@ impl Statement<'_> {
         self.query(params)?.get_expected_row().and_then(f)
     }
 
+    /// Convenience method to execute a query that is expected to return
+    /// exactly one row, mapped with `f`.
+    ///
+    /// Unlike [`query_row`](Statement::query_row), which ignores all rows
+    /// but the first, this fails if the query returns more than one.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn email(conn: &Connection, id: i64) -> Result<String> {
+    ///     let mut stmt = conn.prepare("SELECT email FROM users WHERE id = ?")?;
+    ///     stmt.query_one([id], |row| row.get(0))
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err(QueryReturnedNoRows)` if no rows are returned,
+    /// `Err(QueryReturnedMoreThanOneRow)` with their number if more than one
+    /// are, and `Err` if binding parameters, the query or `f` fails.
+    pub fn query_one<T, P, F>(&mut self, params: P, f: F) -> Result<T>
+    where
+        P: Params,
+        F: FnOnce(&Row<'_>) -> Result<T>,
+    {
+        self.query_opt(params, f)?.ok_or(Error::QueryReturnedNoRows)
+    }
+
+    /// Convenience method to execute a query that is expected to return at
+    /// most one row, mapped with `f`, or `None` if it returns none.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn email(conn: &Connection, id: i64) -> Result<Option<String>> {
+    ///     let mut stmt = conn.prepare("SELECT email FROM users WHERE id = ?")?;
+    ///     stmt.query_opt([id], |row| row.get(0))
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err(QueryReturnedMoreThanOneRow)` with the number of rows
+    /// if more than one are returned, and `Err` if binding parameters, the
+    /// query or `f` fails.
+    pub fn query_opt<T, P, F>(&mut self, params: P, f: F) -> Result<Option<T>>
+    where
+        P: Params,
+        F: FnOnce(&Row<'_>) -> Result<T>,
+    {
+        let mut rows = self.query(params)?;
+        let count = rows.as_ref().map_or(0, Statement::row_count);
+        if count > 1 {
+            return Err(Error::QueryReturnedMoreThanOneRow(count));
+        }
+        rows.next()?.map(f).transpose()
+    }
+
+    /// How long the last successful execution of this statement took, or
+    /// `None` if it hasn't succeeded yet.
+    ///
+    /// This is the time DuckDB took to run the statement, not the time spent
+    /// reading or mapping its rows afterwards. For a
+    /// [streamed](Statement::query_streaming) query, DuckDB computes most of
+    /// the result while the rows are read, so it is only the time until the
+    /// first rows were ready.
+    ///
+    /// Failed executions aren't counted. The times belong to the prepared
+    /// statement: a statement from
+    /// [`prepare_cached`](Connection::prepare_cached) keeps them while it is
+    /// in the cache, including when it is prepared again after the tables it
+    /// uses changed, and starts from `None` when it is prepared anew.
+    /// Statements can't be shared between threads, so they only count the
+    /// executions of their own thread.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn count_slowly(conn: &Connection) -> Result<i64> {
+    ///     let mut stmt = conn.prepare("SELECT count(*) FROM range(1000000) a, range(1000) b")?;
+    ///     let count = stmt.query_row([], |row| row.get(0))?;
+    ///     println!("counted in {:?}", stmt.last_execution_time().unwrap());
+    ///     Ok(count)
+    /// }
+    /// ```
+    #[inline]
+    pub fn last_execution_time(&self) -> Option<Duration> {
+        self.stmt.execution_times().last
+    }
+
+    /// How long all the successful executions of this statement took
+    /// together, measured as [`last_execution_time`](Statement::last_execution_time)
+    /// is.
+    #[inline]
+    pub fn total_execution_time(&self) -> Duration {
+        self.stmt.execution_times().total
+    }
+
     /// Return the row count
     #[inline]
     pub fn row_count(&self) -> usize {
@@ -359,19 +754,202 @@ impl Statement<'_> {
             }
             self.bind_parameter(&p, index)?;
         }
-        if index != expected {
+        if index < expected {
+            let missing = (index + 1..=expected)
+                .map(|index| self.parameter_name(index).unwrap_or_else(|| index.to_string()))
+                .collect();
+            Err(Error::MissingParameters(missing))
+        } else if index != expected {
             Err(Error::InvalidParameterCount(index, expected))
         } else {
             Ok(())
         }
     }
 
+    /// Unbind every parameter of the statement.
+    ///
+    /// Parameters keep the value last bound to them across executions, so
+    /// with [`raw_bind_parameter`](Statement::raw_bind_parameter) only the
+    /// ones that changed need binding again. Clearing them makes sure none of
+    /// them is left over: executing the statement fails until all of them are
+    /// bound again.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn insert(conn: &Connection, rows: &[(i32, Option<&str>)]) -> Result<()> {
+    ///     let mut stmt = conn.prepare("INSERT INTO test (id, name) VALUES (?, ?)")?;
+    ///     for &(id, name) in rows {
+    ///         stmt.clear_bindings();
+    ///         stmt.raw_bind_parameter(1, id)?;
+    ///         if let Some(name) = name {
+    ///             stmt.raw_bind_parameter(2, name)?;
+    ///         }
+    ///         // Fails for rows without a name, instead of reusing the last one.
+    ///         stmt.raw_execute()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn clear_bindings(&mut self) {
+        self.stmt.clear_bindings();
+        self.bound.get_mut().clear();
+    }
+
     /// Return the number of parameters that can be bound to this statement.
     #[inline]
     pub fn parameter_count(&self) -> usize {
         self.stmt.bind_parameter_count()
     }
 
+    /// Return the name of the one-based parameter `index`, without its `$`
+    /// prefix: `name` for `$name`, and the position for `?` or `$1`.
+    ///
+    /// Returns `None` if `index` is out of range.
+    #[inline]
+    pub fn parameter_name(&self, index: usize) -> Option<String> {
+        self.stmt.bind_parameter_name(index)
+    }
+
+    /// Return the type DuckDB inferred for the one-based parameter `index`
+    /// from how the statement uses it, such as `INTEGER` for `?` in
+    /// `WHERE id = ?` when `id` is an `INTEGER` column.
+    ///
+    /// Returns `None` if `index` is out of range, or if DuckDB doesn't report
+    /// the type in full: when the statement leaves it open, as in `SELECT ?`,
+    /// and for `DECIMAL`, `ENUM` and nested types, whose details the C API
+    /// leaves out. The bundled DuckDB only reports the types of positional
+    /// parameters, `?` and `$1`, not of `$name` ones.
+    #[inline]
+    pub fn parameter_type(&self, index: usize) -> Option<LogicalType> {
+        LogicalType::from_duckdb_type(self.stmt.bind_parameter_type(index))
+    }
+
+    /// Return the one-based index of the parameter `name`, with or without
+    /// its `$` prefix: `$name` and `name` both find `$name`. Positional
+    /// parameters are named by their position, so `"2"` finds `$2`, or the
+    /// second `?`.
+    ///
+    /// Returns `None` if the statement has no such parameter.
+    #[inline]
+    pub fn parameter_index(&self, name: &str) -> Option<usize> {
+        let name = name.strip_prefix('$').unwrap_or(name);
+        self.stmt.bind_parameter_index(name)
+    }
+
+    /// Return the SQL of the statement with each parameter replaced by the
+    /// value last bound to it, written as by [`Value::to_sql_literal`], or by
+    /// `NULL` if none was. Blobs are written as `'\xHH...'::BLOB`.
+    ///
+    /// This is meant for logging, and for running the statement by hand, as
+    /// with `EXPLAIN`.
+    ///
+    /// Returns `None` if the SQL of the statement isn't known.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn log_query(conn: &Connection) -> Result<()> {
+    ///     let mut stmt = conn.prepare("SELECT * FROM people WHERE name = ?")?;
+    ///     stmt.raw_bind_parameter(1, "O'Brien")?;
+    ///     // SELECT * FROM people WHERE name = 'O''Brien'
+    ///     println!("{}", stmt.expanded_sql().unwrap());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn expanded_sql(&self) -> Option<String> {
+        self.expand_sql(usize::MAX)
+    }
+
+    /// Like [`expanded_sql`](Statement::expanded_sql), but cuts the literals
+    /// of values longer than `max_literal_len` characters short, ending them
+    /// with `...`. The result may then not be valid SQL.
+    #[inline]
+    pub fn expanded_sql_capped(&self, max_literal_len: usize) -> Option<String> {
+        self.expand_sql(max_literal_len)
+    }
+
+    fn expand_sql(&self, max_literal_len: usize) -> Option<String> {
+        let sql = self.stmt.sql()?;
+        let bound = self.bound.borrow();
+        let mut expanded = String::with_capacity(sql.len());
+        let mut copied = 0;
+        // The index DuckDB gives the next `?`.
+        let mut next = 1;
+        for (range, placeholder) in placeholders(&sql) {
+            let index = match placeholder {
+                Placeholder::Next => Some(next),
+                Placeholder::Numbered(index) => Some(index),
+                Placeholder::Named(name) => self.parameter_index(name),
+            };
+            next = next.max(index.map_or(0, |index| index + 1));
+            let value = index.and_then(|index| bound.get(index.checked_sub(1)?).cloned().flatten());
+            let mut literal = match value {
+                Some(Value::Blob(bytes)) => {
+                    let hex: String = bytes.iter().map(|b| format!("\\x{b:02X}")).collect();
+                    format!("'{hex}'::BLOB")
+                }
+                Some(value) => value.to_sql_literal(),
+                None => "NULL".to_owned(),
+            };
+            if let Some((cut, _)) = literal.char_indices().nth(max_literal_len) {
+                literal.truncate(cut);
+                literal.push_str("...");
+            }
+            expanded.push_str(&sql[copied..range.start]);
+            expanded.push_str(&literal);
+            copied = range.end;
+        }
+        expanded.push_str(&sql[copied..]);
+        Some(expanded)
+    }
+
+    /// Bind each value to the parameter of its name, as for
+    /// [`parameter_index`](Statement::parameter_index), checking that every
+    /// parameter got a value.
+    pub(crate) fn bind_parameters_named(&mut self, params: &[(&str, &dyn ToSql)]) -> Result<()> {
+        let mut bound = vec![false; self.parameter_count()];
+        for &(name, value) in params {
+            let Some(index) = self.parameter_index(name) else {
+                return Err(Error::InvalidParameterName(name.to_owned()));
+            };
+            self.bind_parameter(value, index)?;
+            bound[index - 1] = true;
+        }
+        let missing: Vec<_> = (1..=bound.len())
+            .filter(|&index| !bound[index - 1])
+            .map(|index| self.parameter_name(index).unwrap_or_else(|| index.to_string()))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::MissingParameters(missing))
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn bind_named_parameters(&mut self, params: &[(String, Value)], strict: bool) -> Result<()> {
+        let mut used = vec![false; params.len()];
+        for index in 1..=self.parameter_count() {
+            let name = self.parameter_name(index).unwrap_or_else(|| index.to_string());
+            let Some(field) = params.iter().position(|(field, _)| *field == name) else {
+                return Err(Error::ToSqlConversionFailure(
+                    format!("no field for parameter ${name}").into(),
+                ));
+            };
+            used[field] = true;
+            self.bind_parameter(&params[field].1, index)?;
+        }
+        match used.iter().position(|used| !used) {
+            Some(field) if strict => Err(Error::InvalidParameterName(params[field].0.clone())),
+            _ => Ok(()),
+        }
+    }
+
     /// Low level API to directly bind a parameter to a given index.
     ///
     /// Note that the index is one-based, that is, the first parameter index is
@@ -389,6 +967,9 @@ impl Statement<'_> {
     /// - binding parameters where a gap exists.
     /// - binding named and positional parameters in the same query.
     /// - separating parameter binding from query execution.
+    /// - binding only the parameters that changed since the last execution,
+    ///   as parameters keep their values until
+    ///   [`clear_bindings`](Statement::clear_bindings).
     ///
     /// Statements that have had their parameters bound this way should be
     /// queried or executed by [`Statement::raw_query`] or
@@ -421,15 +1002,16 @@ impl Statement<'_> {
     ///
     /// # Caveats
     ///
-    /// Any unbound parameters will have `NULL` as their value.
+    /// Parameters bound for an earlier execution keep their value, unless
+    /// [`clear_bindings`](Statement::clear_bindings) was called since.
     ///
     /// This should not generally be used outside of special cases, and
     /// functions in the [`Statement::execute`] family should be preferred.
     ///
     /// # Failure
     ///
-    /// Will return `Err` if the executed statement returns rows (in which case
-    /// `query` should be used instead), or the underlying DuckDB call fails.
+    /// Will return `Err` if a parameter isn't bound, or the underlying DuckDB
+    /// call fails.
     #[inline]
     pub fn raw_execute(&mut self) -> Result<usize> {
         self.execute_with_bound_parameters()
@@ -440,7 +1022,8 @@ impl Statement<'_> {
     ///
     /// # Caveats
     ///
-    /// Any unbound parameters will have `NULL` as their value.
+    /// Parameters bound for an earlier execution keep their value, unless
+    /// [`clear_bindings`](Statement::clear_bindings) was called since.
     ///
     /// This should not generally be used outside of special cases, and
     /// functions in the [`Statement::query`] family should be preferred.
@@ -454,14 +1037,163 @@ impl Statement<'_> {
 
     // generic because many of these branches can constant fold away.
     fn bind_parameter<P: ?Sized + ToSql>(&self, param: &P, col: usize) -> Result<()> {
-        let value = param.to_sql()?;
+        match param.to_sql()? {
+            ToSqlOutput::Borrowed(v) => self.bind_value_ref_at(v, col),
+            ToSqlOutput::Owned(ref v) => self.bind_value_at(v, col),
+        }
+    }
 
-        let ptr = unsafe { self.stmt.ptr() };
-        let value = match value {
-            ToSqlOutput::Borrowed(v) => v,
-            ToSqlOutput::Owned(ref v) => ValueRef::from(v),
+    /// Binds `value` to the parameter at `one_based_col_index`, dispatching
+    /// straight to the matching `duckdb_bind_*` call instead of going
+    /// through [`ToSql`].
+    ///
+    /// Nested values bind as they do as parameters of the
+    /// [`Statement::execute`] family, `ENUM`s as their text and `UNION`s as
+    /// their active member, which DuckDB casts to the parameter's type.
+    ///
+    /// The caveats of [`Statement::raw_bind_parameter`] apply.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the index is out of range, or `value` can't be
+    /// bound, such as an empty `ARRAY`.
+    #[inline]
+    pub fn bind_value(&mut self, one_based_col_index: usize, value: &Value) -> Result<()> {
+        self.bind_value_at(value, one_based_col_index)
+    }
+
+    /// Binds `value` to the parameter at `one_based_col_index`, like
+    /// [`Statement::bind_value`].
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the index is out of range, or `value` can't be
+    /// bound.
+    #[inline]
+    pub fn bind_value_ref(&mut self, one_based_col_index: usize, value: ValueRef<'_>) -> Result<()> {
+        self.bind_value_ref_at(value, one_based_col_index)
+    }
+
+    /// Binds `values` to the parameters of the statement in order, with
+    /// [`Statement::bind_value`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{types::Value, Connection, Result};
+    /// fn insert_rows(conn: &Connection, rows: &[Vec<Value>]) -> Result<()> {
+    ///     let mut stmt = conn.prepare("INSERT INTO people (name, age) VALUES (?, ?)")?;
+    ///     for row in rows {
+    ///         stmt.bind_values(row)?;
+    ///         stmt.raw_execute()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the number of values doesn't match the number of
+    /// parameters, or a value can't be bound.
+    pub fn bind_values(&mut self, values: &[Value]) -> Result<()> {
+        let expected = self.parameter_count();
+        if values.len() < expected {
+            let missing = (values.len() + 1..=expected)
+                .map(|index| self.parameter_name(index).unwrap_or_else(|| index.to_string()))
+                .collect();
+            return Err(Error::MissingParameters(missing));
+        } else if values.len() > expected {
+            return Err(Error::InvalidParameterCount(values.len(), expected));
+        }
+        for (index, value) in values.iter().enumerate() {
+            self.bind_value_at(value, index + 1)?;
+        }
+        Ok(())
+    }
+
+    fn bind_value_ref_at(&self, value: ValueRef<'_>, col: usize) -> Result<()> {
+        match value {
+            ValueRef::List(..)
+            | ValueRef::Array(..)
+            | ValueRef::Struct(..)
+            | ValueRef::Map(..)
+            | ValueRef::Union(..)
+            | ValueRef::Enum(..) => {
+                let owned = value.to_owned();
+                let res = self.bind_unrecorded(&owned, col);
+                self.record_bound(owned, col);
+                res
+            }
+            value => {
+                let res = self.bind_scalar(value, col);
+                self.record_bound_ref(value, col);
+                res
+            }
+        }
+    }
+
+    fn bind_value_at(&self, value: &Value, col: usize) -> Result<()> {
+        let res = self.bind_unrecorded(value, col);
+        self.record_bound(value.clone(), col);
+        res
+    }
+
+    /// Remembers the value bound at `col`, for error messages.
+    fn record_bound(&self, value: Value, col: usize) {
+        *self.bound_slot(col) = Some(value);
+    }
+
+    /// Like [`record_bound`](Statement::record_bound), but copies text and
+    /// blobs into the value last bound at `col` when it has the same type, so
+    /// rebinding a statement in a loop doesn't allocate each time.
+    fn record_bound_ref(&self, value: ValueRef<'_>, col: usize) {
+        let mut slot = self.bound_slot(col);
+        match (&mut *slot, value) {
+            (Some(Value::Text(s)), ValueRef::Text(t)) => {
+                s.clear();
+                s.push_str(&String::from_utf8_lossy(t));
+            }
+            (Some(Value::Blob(b)), ValueRef::Blob(t)) => {
+                b.clear();
+                b.extend_from_slice(t);
+            }
+            (slot, ValueRef::Text(t)) => *slot = Some(Value::Text(String::from_utf8_lossy(t).into_owned())),
+            (slot, value) => *slot = Some(value.to_owned()),
+        }
+    }
+
+    fn bound_slot(&self, col: usize) -> RefMut<'_, Option<Value>> {
+        RefMut::map(self.bound.borrow_mut(), |bound| {
+            if bound.len() < col {
+                bound.resize(col, None);
+            }
+            &mut bound[col - 1]
+        })
+    }
+
+    fn bind_unrecorded(&self, value: &Value, col: usize) -> Result<()> {
+        let nested = match value {
+            Value::List(items) => ValueHandle::list(items)?,
+            Value::Array(items) => ValueHandle::array(items)?,
+            Value::Struct(fields) => ValueHandle::structure(fields)?,
+            // Maps bind as their text, which DuckDB casts.
+            Value::Map(_) => {
+                return match value.to_sql()? {
+                    ToSqlOutput::Borrowed(v) => self.bind_scalar(v, col),
+                    ToSqlOutput::Owned(ref v) => self.bind_unrecorded(v, col),
+                };
+            }
+            Value::Enum(s) => return self.bind_scalar(ValueRef::Text(s.as_bytes()), col),
+            Value::Union { value, .. } => return self.bind_unrecorded(value, col),
+            value => return self.bind_scalar(ValueRef::from(value), col),
         };
-        // TODO: bind more
+        let ptr = unsafe { self.stmt.ptr() };
+        let rc = unsafe { ffi::duckdb_bind_value(ptr, col as u64, nested.ptr()) };
+        result_from_duckdb_prepare(rc, ptr)
+    }
+
+    fn bind_scalar(&self, value: ValueRef<'_>, col: usize) -> Result<()> {
+        let ptr = unsafe { self.stmt.ptr() };
         let rc = match value {
             ValueRef::Null => unsafe { ffi::duckdb_bind_null(ptr, col as u64) },
             ValueRef::Boolean(i) => unsafe { ffi::duckdb_bind_boolean(ptr, col as u64, i) },
@@ -480,8 +1212,32 @@ impl Statement<'_> {
             ValueRef::USmallInt(i) => unsafe { ffi::duckdb_bind_uint16(ptr, col as u64, i) },
             ValueRef::UInt(i) => unsafe { ffi::duckdb_bind_uint32(ptr, col as u64, i) },
             ValueRef::UBigInt(i) => unsafe { ffi::duckdb_bind_uint64(ptr, col as u64, i) },
+            ValueRef::UHugeInt(i) => unsafe {
+                let hi = ffi::duckdb_uhugeint {
+                    lower: i as u64,
+                    upper: (i >> 64) as u64,
+                };
+                ffi::duckdb_bind_uhugeint(ptr, col as u64, hi)
+            },
             ValueRef::Float(r) => unsafe { ffi::duckdb_bind_float(ptr, col as u64, r) },
             ValueRef::Double(r) => unsafe { ffi::duckdb_bind_double(ptr, col as u64, r) },
+            ValueRef::Decimal { value, width, scale } => {
+                check_decimal(value, width, scale)?;
+                unsafe {
+                    ffi::duckdb_bind_decimal(
+                        ptr,
+                        col as u64,
+                        ffi::duckdb_decimal {
+                            width,
+                            scale,
+                            value: ffi::duckdb_hugeint {
+                                lower: value as u64,
+                                upper: (value >> 64) as i64,
+                            },
+                        },
+                    )
+                }
+            }
             ValueRef::Text(s) => unsafe {
                 ffi::duckdb_bind_varchar_length(ptr, col as u64, s.as_ptr() as *const c_char, s.len() as u64)
             },
@@ -497,10 +1253,18 @@ impl Statement<'_> {
                 };
                 ffi::duckdb_bind_timestamp(ptr, col as u64, ffi::duckdb_timestamp { micros })
             },
+            ValueRef::TimestampTz(u, i) => unsafe {
+                let s = timestamptz_to_string(u, i);
+                ffi::duckdb_bind_varchar_length(ptr, col as u64, s.as_ptr() as *const c_char, s.len() as u64)
+            },
             ValueRef::Interval { months, days, nanos } => unsafe {
                 let micros = nanos / 1_000;
                 ffi::duckdb_bind_interval(ptr, col as u64, ffi::duckdb_interval { months, days, micros })
             },
+            ValueRef::Date32(days) => unsafe { ffi::duckdb_bind_date(ptr, col as u64, ffi::duckdb_date { days }) },
+            ValueRef::Time64(u, i) => unsafe {
+                ffi::duckdb_bind_time(ptr, col as u64, ffi::duckdb_time { micros: u.to_micros(i) })
+            },
             _ => unreachable!("not supported: {}", value.data_type()),
         };
         result_from_duckdb_prepare(rc, ptr)
@@ -508,7 +1272,77 @@ impl Statement<'_> {
 
     #[inline]
     fn execute_with_bound_parameters(&mut self) -> Result<usize> {
-        self.stmt.execute()
+        self.run_with_bound_parameters(None)
+    }
+
+    /// Executes the statement, streaming its result converted to Arrow with
+    /// `streaming` if given.
+    fn run_with_bound_parameters(&mut self, streaming: Option<&ArrowOptions>) -> Result<usize> {
+        // Drop the profile of whatever the connection ran before.
+        self.conn.take_profile();
+        let conn = self.conn;
+        let sql = self.stmt.sql().unwrap_or_default();
+        let executions = self.executions.get().cloned();
+        let res = match executions {
+            Some(executions) => Executions::run(&executions, || {
+                conn.traced(&sql, || self.execute_and_reprepare(streaming))
+            }),
+            None => conn.traced(&sql, || self.execute_and_reprepare(streaming)),
+        };
+        self.profile = match res {
+            Ok(_) => self.conn.take_profile(),
+            Err(_) => None,
+        };
+        let changed = res.map_err(|err| match err {
+            Error::Cancelled => err,
+            err => conn.error_context(err, &sql, Some(&self.bound_parameters())),
+        })?;
+        // Reading the warnings would end a streamed result.
+        if streaming.is_none() {
+            conn.collect_warnings(&sql);
+        }
+        Ok(changed)
+    }
+
+    /// The name and value of each parameter, as last bound. Parameters that
+    /// weren't bound are `NULL`.
+    fn bound_parameters(&self) -> Vec<(String, Value)> {
+        let bound = self.bound.borrow();
+        (1..=self.parameter_count())
+            .map(|index| {
+                let name = self.parameter_name(index).unwrap_or_else(|| index.to_string());
+                let value = bound.get(index - 1).cloned().flatten().unwrap_or(Value::Null);
+                (name, value)
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn execute_and_reprepare(&mut self, streaming: Option<&ArrowOptions>) -> Result<usize> {
+        let execute = |stmt: &mut RawStatement| match streaming {
+            Some(options) => stmt.execute_streaming(options),
+            None => stmt.execute(),
+        };
+        let start = Instant::now();
+        let res = match execute(&mut self.stmt) {
+            // A cached statement may be stale, re-preparing it once might help.
+            // DuckDB always rebinds statements with parameters itself.
+            Err(err)
+                if self.stmt.statement_cache_key().is_some()
+                    && self.parameter_count() == 0
+                    && is_catalog_change(&err) =>
+            {
+                match self.reprepare() {
+                    Ok(()) => execute(&mut self.stmt),
+                    Err(_) => Err(err),
+                }
+            }
+            res => res,
+        };
+        if res.is_ok() {
+            self.stmt.record_execution(start.elapsed());
+        }
+        res
     }
 
     /// Safety: This is unsafe, because using `sqlite3_stmt` after the
@@ -524,15 +1358,11 @@ impl Statement<'_> {
 
 impl fmt::Debug for Statement<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let sql = if self.stmt.is_null() {
-            Ok("")
-        } else {
-            str::from_utf8(self.stmt.sql().unwrap().to_bytes())
-        };
+        let sql = self.stmt.sql();
         f.debug_struct("Statement")
             .field("conn", self.conn)
             .field("stmt", &self.stmt)
-            .field("sql", &sql)
+            .field("sql", &sql.as_deref().unwrap_or_default())
             .finish()
     }
 }
@@ -540,10 +1370,39 @@ impl fmt::Debug for Statement<'_> {
 impl Statement<'_> {
     #[inline]
     pub(super) fn new(conn: &Connection, stmt: RawStatement) -> Statement<'_> {
-        Statement { conn, stmt }
+        Statement {
+            conn,
+            stmt,
+            profile: None,
+            bound: RefCell::new(Vec::new()),
+            executions: OnceCell::new(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn lenient_timestamps(&self) -> bool {
+        self.conn.lenient_timestamps()
+    }
+
+    #[inline]
+    pub(crate) fn lenient_bools(&self) -> bool {
+        self.conn.lenient_bools()
+    }
+
+    #[inline]
+    pub(crate) fn strict_types(&self) -> bool {
+        self.conn.strict_types()
     }
 
-    /// column_type
+    /// Returns the Arrow type of column `idx` of the result, as DuckDB
+    /// converted it. See
+    /// [`column_logical_type`](Statement::column_logical_type) for its DuckDB
+    /// type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the statement hasn't been executed, or if `idx` is out of
+    /// range.
     #[inline]
     pub fn column_type(&self, idx: usize) -> DataType {
         self.stmt.column_type(idx)
@@ -552,7 +1411,11 @@ impl Statement<'_> {
 
 #[cfg(test)]
 mod test {
-    use crate::{params_from_iter, types::ToSql, Connection, Error, Result};
+    use crate::{
+        params, params_from_iter,
+        types::{FromSqlError, ToSql},
+        Connection, Error, ErrorKind, Result,
+    };
 
     #[test]
     fn test_execute() -> Result<()> {
@@ -574,6 +1437,244 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_execute_rows_changed() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        assert_eq!(db.execute("CREATE TABLE foo(x INTEGER, y TEXT)", [])?, 0);
+        assert_eq!(db.execute("INSERT INTO foo SELECT range, 'a' FROM range(5)", [])?, 5);
+
+        let mut update = db.prepare("UPDATE foo SET y = ? WHERE x >= ?")?;
+        assert_eq!(update.execute(crate::params!["b", 10])?, 0);
+        assert_eq!(update.execute(crate::params!["b", 2])?, 3);
+        // Rows are counted when they match, whether or not they change.
+        assert_eq!(update.execute(crate::params!["b", 2])?, 3);
+
+        assert_eq!(db.execute("DELETE FROM foo WHERE x < ?", [2])?, 2);
+        assert_eq!(db.execute("DELETE FROM foo WHERE x < ?", [2])?, 0);
+        assert_eq!(db.execute("UPDATE foo SET x = x + 1 RETURNING x", [])?, 0);
+        assert_eq!(db.execute("SELECT * FROM foo", [])?, 0);
+        assert_eq!(db.execute("ALTER TABLE foo ADD COLUMN z INTEGER", [])?, 0);
+        assert_eq!(db.execute("DROP TABLE foo", [])?, 0);
+
+        // Several statements can't be executed at once.
+        let err = db
+            .execute("CREATE TABLE bar(x INTEGER); INSERT INTO bar VALUES (1)", [])
+            .unwrap_err();
+        assert!(err.to_string().contains("multiple statements"), "{err}");
+        assert!(db.execute("SELECT * FROM bar", []).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_batch_params() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE people(id INTEGER PRIMARY KEY, name TEXT)")?;
+        let count = || db.query_row("SELECT count(*) FROM people", [], |r| r.get::<_, i64>(0));
+
+        let mut stmt = db.prepare("INSERT INTO people VALUES (?, ?)")?;
+        let people: Vec<(i32, String)> = (0..1000).map(|i| (i, format!("p{i}"))).collect();
+        let changed = stmt.execute_batch_params(people.iter().map(|(id, name)| [id as &dyn ToSql, name]))?;
+        assert_eq!(changed, 1000);
+        assert_eq!(count()?, 1000);
+        assert!(db.is_autocommit()?);
+        assert_eq!(stmt.execute_batch_params(Vec::<[i32; 2]>::new())?, 0);
+
+        // A failing set rolls back the ones before it.
+        let err = stmt
+            .execute_batch_params([
+                params![1000, "a"],
+                params![1001, "b"],
+                params![5, "dup"],
+                params![1002, "c"],
+            ])
+            .unwrap_err();
+        match err {
+            Error::ParamSetFailed(2, ref inner) => assert_eq!(inner.kind(), ErrorKind::Constraint, "{inner}"),
+            _ => panic!("unexpected error: {err}"),
+        }
+        assert_eq!(count()?, 1000);
+        assert!(db.is_autocommit()?);
+
+        // Parameters that fail to bind are reported the same way.
+        let err = stmt.execute_batch_params([[2000], [2001]]).unwrap_err();
+        assert!(matches!(err, Error::ParamSetFailed(0, _)), "{err}");
+
+        // An open transaction is used as it is.
+        db.execute_batch("BEGIN")?;
+        let mut stmt = db.prepare("UPDATE people SET name = ? WHERE id < ?")?;
+        assert_eq!(stmt.execute_batch_params([params!["x", 10], params!["y", 5]])?, 15);
+        assert!(!db.is_autocommit()?);
+        db.execute_batch("ROLLBACK")?;
+        let name: String = db.query_row("SELECT name FROM people WHERE id = 1", [], |r| r.get(0))?;
+        assert_eq!(name, "p1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_bind_value() -> Result<()> {
+        use crate::types::{OrderedMap, TimeUnit, Value, ValueRef};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TYPE mood AS ENUM ('sad', 'happy')")?;
+        let text = |s: &str| Value::Text(s.to_owned());
+        let cases = [
+            ("SELECT ?", Value::Null),
+            ("SELECT ?", Value::Boolean(true)),
+            ("SELECT ?", Value::TinyInt(-8)),
+            ("SELECT ?", Value::SmallInt(-16)),
+            ("SELECT ?", Value::Int(-32)),
+            ("SELECT ?", Value::BigInt(-64)),
+            ("SELECT ?", Value::HugeInt(-(1 << 100))),
+            ("SELECT ?", Value::UTinyInt(8)),
+            ("SELECT ?", Value::USmallInt(16)),
+            ("SELECT ?", Value::UInt(32)),
+            ("SELECT ?", Value::UBigInt(u64::MAX)),
+            ("SELECT ?", Value::UHugeInt(u128::MAX)),
+            ("SELECT ?", Value::Float(1.5)),
+            ("SELECT ?", Value::Double(-2.25)),
+            (
+                "SELECT ?",
+                Value::Decimal {
+                    value: 1234,
+                    width: 6,
+                    scale: 2,
+                },
+            ),
+            (
+                "SELECT ?",
+                Value::Timestamp(TimeUnit::Microsecond, 1_700_000_000_000_000),
+            ),
+            (
+                "SELECT ?::TIMESTAMPTZ",
+                Value::TimestampTz(TimeUnit::Microsecond, 1_700_000_000_000_000),
+            ),
+            (
+                "SELECT ?::TIMESTAMPTZ",
+                Value::TimestampTz(TimeUnit::Microsecond, -62_135_596_800_000_001),
+            ),
+            (
+                "SELECT ?::TIMESTAMPTZ",
+                Value::TimestampTz(TimeUnit::Microsecond, i64::MAX),
+            ),
+            (
+                "SELECT ?::TIMESTAMPTZ",
+                Value::TimestampTz(TimeUnit::Microsecond, -i64::MAX),
+            ),
+            ("SELECT ?", text("duck")),
+            ("SELECT ?", Value::Blob(vec![0, 1, 255])),
+            ("SELECT ?", Value::Date32(19_000)),
+            ("SELECT ?", Value::Time64(TimeUnit::Microsecond, 3_600_000_001)),
+            (
+                "SELECT ?",
+                Value::Interval {
+                    months: 1,
+                    days: 2,
+                    nanos: 3_000,
+                },
+            ),
+            ("SELECT ?", Value::List(vec![Value::Int(1), Value::Int(2)])),
+            ("SELECT ?::mood", Value::Enum("happy".to_owned())),
+            (
+                "SELECT ?",
+                Value::Struct(OrderedMap::from(vec![
+                    ("a".to_owned(), Value::Int(1)),
+                    ("b".to_owned(), text("x")),
+                ])),
+            ),
+            ("SELECT ?", Value::Array(vec![Value::Int(1), Value::Int(2)])),
+            (
+                "SELECT ?::MAP(VARCHAR, INTEGER)",
+                Value::Map(OrderedMap::from(vec![(text("k"), Value::Int(1))])),
+            ),
+            (
+                "SELECT ?::UNION(num INTEGER, str VARCHAR)",
+                Value::Union {
+                    tag: "str".to_owned(),
+                    value: Box::new(text("s")),
+                },
+            ),
+        ];
+        for (sql, value) in cases {
+            let mut stmt = db.prepare(sql)?;
+            stmt.bind_value(1, &value)?;
+            let read: Value = stmt.raw_query().next()?.unwrap().get(0)?;
+            assert_eq!(read, value, "{sql}");
+
+            stmt.clear_bindings();
+            stmt.bind_value_ref(1, ValueRef::Text(b"7"))?;
+            stmt.bind_values(std::slice::from_ref(&value))?;
+            let read: Value = stmt.raw_query().next()?.unwrap().get(0)?;
+            assert_eq!(read, value, "{sql}");
+        }
+
+        // Nested values borrowed from a row bind as their owned forms.
+        let mut stmt = db.prepare("SELECT ? = [1, 2], ? = {'a': 1}")?;
+        db.query_row("SELECT [1, 2], {'a': 1}", [], |r| {
+            stmt.bind_value_ref(1, r.get_ref(0)?)?;
+            stmt.bind_value_ref(2, r.get_ref(1)?)
+        })?;
+        let mut rows = stmt.raw_query();
+        let row = rows.next()?.unwrap();
+        assert_eq!((row.get::<_, bool>(0)?, row.get::<_, bool>(1)?), (true, true));
+        drop(rows);
+
+        assert_eq!(
+            stmt.bind_values(&[Value::Int(1)]).unwrap_err(),
+            Error::MissingParameters(vec!["2".to_owned()])
+        );
+        assert_eq!(
+            stmt.bind_values(&[Value::Int(1), Value::Int(2), Value::Int(3)])
+                .unwrap_err(),
+            Error::InvalidParameterCount(3, 2)
+        );
+        assert!(stmt.bind_value(3, &Value::Int(1)).is_err());
+
+        // Rebinding text reuses the recorded value.
+        let mut stmt = db.prepare("SELECT ?, ?")?;
+        stmt.bind_value_ref(1, ValueRef::Text(b"first"))?;
+        stmt.bind_value_ref(2, ValueRef::Blob(b"\x01"))?;
+        stmt.bind_value_ref(1, ValueRef::Text(b"second"))?;
+        stmt.bind_value_ref(2, ValueRef::Int(2))?;
+        assert_eq!(stmt.expanded_sql().as_deref(), Some("SELECT 'second', 2"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_returning() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE SEQUENCE seq START 10; CREATE TABLE people(id INTEGER DEFAULT nextval('seq'), name TEXT)",
+        )?;
+        let mut stmt = db.prepare("INSERT INTO people (name) VALUES (?), (?) RETURNING id, name")?;
+        let people: Vec<(i64, String)> = stmt.insert_returning(["a", "b"], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        assert_eq!(people, [(10, "a".to_owned()), (11, "b".to_owned())]);
+        let ids: Vec<i64> = stmt.insert_returning(["c", "d"], |r| r.get(0))?;
+        assert_eq!(ids, [12, 13]);
+
+        let mut stmt = db.prepare("UPDATE people SET name = upper(name) WHERE id > ? RETURNING name")?;
+        let mut names: Vec<String> = stmt.insert_returning([11], |r| r.get(0))?;
+        names.sort();
+        assert_eq!(names, ["C", "D"]);
+        let mut stmt = db.prepare("DELETE FROM people WHERE id = ? RETURNING id")?;
+        assert!(stmt.insert_returning([99], |r| r.get::<_, i64>(0))?.is_empty());
+
+        assert_eq!(
+            db.insert("INSERT INTO people (name) VALUES (?) RETURNING id", ["e"])?,
+            14
+        );
+        assert_eq!(
+            db.insert("INSERT INTO people (name) SELECT 'f' WHERE false RETURNING id", [])
+                .unwrap_err(),
+            Error::QueryReturnedNoRows
+        );
+        assert_eq!(
+            db.insert("INSERT INTO people (name) VALUES ('g'), ('h') RETURNING id", [])
+                .unwrap_err(),
+            Error::QueryReturnedMoreThanOneRow(2)
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_stmt_execute() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -654,6 +1755,136 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_named_parameters() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE test (name TEXT, value INTEGER, flag BOOLEAN)")?;
+
+        let mut stmt = db.prepare("INSERT INTO test VALUES ($name, $value, $flag)")?;
+        assert_eq!(stmt.parameter_index("$value"), Some(2));
+        assert_eq!(stmt.parameter_index("flag"), Some(3));
+        assert_eq!(stmt.parameter_index("$missing"), None);
+        stmt.execute(crate::named_params! { "$flag": true, "$value": 1, "$name": "one" })?;
+        // The statement can be reused with other values, in another order.
+        stmt.execute(crate::named_params! { "value": 2, "$name": "two", "flag": false })?;
+        let params: &[(&str, &dyn ToSql)] = &[("$name", &"three"), ("$flag", &None::<bool>), ("$value", &3)];
+        stmt.execute(params)?;
+
+        let rows: Vec<(String, i32, Option<bool>)> = db
+            .prepare("SELECT * FROM test ORDER BY value")?
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+            .collect::<Result<_>>()?;
+        assert_eq!(
+            rows,
+            [
+                ("one".to_owned(), 1, Some(true)),
+                ("two".to_owned(), 2, Some(false)),
+                ("three".to_owned(), 3, None),
+            ]
+        );
+
+        // A parameter used twice is bound once.
+        let value: i32 = db.query_row("SELECT $x + $x", crate::named_params! { "$x": 21 }, |r| r.get(0))?;
+        assert_eq!(value, 42);
+        // Positional parameters are named by their position.
+        let value: String = db.query_row("SELECT $2 || $1", crate::named_params! { "1": "a", "$2": "b" }, |r| {
+            r.get(0)
+        })?;
+        assert_eq!(value, "ba");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parameter_introspection() -> Result<()> {
+        use crate::types::{LogicalType, TimeUnit};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE test (id INTEGER, name TEXT, at TIMESTAMP, price DECIMAL(6, 2))")?;
+
+        let stmt = db.prepare("SELECT 1")?;
+        assert_eq!(stmt.parameter_count(), 0);
+        assert_eq!(stmt.parameter_name(1), None);
+        assert_eq!(stmt.parameter_type(1), None);
+
+        let stmt = db.prepare("SELECT * FROM test WHERE id = ? AND name = ? AND at < ? AND price > ?")?;
+        assert_eq!(stmt.parameter_count(), 4);
+        assert_eq!(stmt.parameter_name(1).as_deref(), Some("1"));
+        assert_eq!(stmt.parameter_name(4).as_deref(), Some("4"));
+        assert_eq!(stmt.parameter_name(5), None);
+        assert_eq!(stmt.parameter_type(1), Some(LogicalType::Int));
+        assert_eq!(stmt.parameter_type(2), Some(LogicalType::Text));
+        assert_eq!(
+            stmt.parameter_type(3),
+            Some(LogicalType::Timestamp(TimeUnit::Microsecond))
+        );
+        // The C API doesn't give the width and scale of a decimal.
+        assert_eq!(stmt.parameter_type(4), None);
+        assert_eq!(stmt.parameter_type(0), None);
+        assert_eq!(stmt.parameter_type(5), None);
+
+        let stmt = db.prepare("SELECT $2::DOUBLE, $1::DATE")?;
+        assert_eq!(stmt.parameter_count(), 2);
+        assert_eq!(stmt.parameter_name(2).as_deref(), Some("2"));
+        assert_eq!(stmt.parameter_type(1), Some(LogicalType::Date32));
+        assert_eq!(stmt.parameter_type(2), Some(LogicalType::Double));
+
+        let stmt = db.prepare("SELECT * FROM test WHERE name = $name AND id = $id")?;
+        assert_eq!(stmt.parameter_count(), 2);
+        let names: Vec<_> = (1..=2).filter_map(|i| stmt.parameter_name(i)).collect();
+        assert_eq!(names.len(), 2);
+        assert!(
+            names.contains(&"name".to_owned()) && names.contains(&"id".to_owned()),
+            "{names:?}"
+        );
+        assert_eq!(
+            stmt.parameter_index("$id")
+                .and_then(|i| stmt.parameter_name(i))
+                .as_deref(),
+            Some("id")
+        );
+
+        // A parameter used twice is one parameter.
+        let stmt = db.prepare("SELECT $a + $a, $b")?;
+        assert_eq!(stmt.parameter_count(), 2);
+        assert_eq!(
+            stmt.parameter_index("a")
+                .and_then(|i| stmt.parameter_name(i))
+                .as_deref(),
+            Some("a")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_named_parameters_errors() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE test (name TEXT, value INTEGER, flag BOOLEAN)")?;
+
+        let mut stmt = db.prepare("INSERT INTO test VALUES ($name, $value, $flag)")?;
+        let err = stmt.execute(crate::named_params! { "$value": 1 }).unwrap_err();
+        assert_eq!(
+            err,
+            Error::MissingParameters(vec!["name".to_owned(), "flag".to_owned()])
+        );
+        assert_eq!(err.to_string(), "No value bound for parameters: $name, $flag");
+
+        let err = stmt
+            .execute(crate::named_params! { "$name": "x", "$value": 1, "$flag": true, "$nope": 1 })
+            .unwrap_err();
+        assert_eq!(err, Error::InvalidParameterName("$nope".to_owned()));
+
+        let count: i64 = db.query_row("SELECT count(*) FROM test", [], |r| r.get(0))?;
+        assert_eq!(count, 0);
+
+        // DuckDB doesn't allow mixing named and positional parameters.
+        let err = db.prepare("SELECT $a, ?").unwrap_err();
+        assert!(
+            err.to_string().contains("Mixing named and positional parameters"),
+            "{err}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_insert_empty_text_is_none() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -668,6 +1899,48 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_expanded_sql() -> Result<()> {
+        use crate::types::Value;
+
+        let db = Connection::open_in_memory()?;
+        let stmt = db.prepare("SELECT 1 -- ?")?;
+        assert_eq!(stmt.expanded_sql().as_deref(), Some("SELECT 1 -- ?"));
+
+        let mut stmt = db.prepare("SELECT ?, '?', ?, ?::VARCHAR, ?")?;
+        // Parameters that weren't bound are NULL.
+        assert_eq!(
+            stmt.expanded_sql().as_deref(),
+            Some("SELECT NULL, '?', NULL, NULL::VARCHAR, NULL")
+        );
+        stmt.raw_bind_parameter(1, "it's")?;
+        stmt.raw_bind_parameter(2, Option::<i32>::None)?;
+        stmt.raw_bind_parameter(3, Value::Date32(19723))?;
+        stmt.raw_bind_parameter(4, vec![0u8, b'a', 0xff])?;
+        let expanded = stmt.expanded_sql().unwrap();
+        assert_eq!(
+            expanded,
+            "SELECT 'it''s', '?', NULL, DATE '2024-01-01'::VARCHAR, '\\x00\\x61\\xFF'::BLOB"
+        );
+        let expected: (String, Option<i32>, String, Vec<u8>) =
+            stmt.query_row([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?;
+        let got = db.query_row(&expanded, [], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?;
+        assert_eq!(expected, got);
+
+        assert_eq!(
+            stmt.expanded_sql_capped(4).as_deref(),
+            Some("SELECT 'it'..., '?', NULL, DATE...::VARCHAR, '\\x0...::BLOB")
+        );
+
+        let mut stmt = db.prepare("SELECT $name || $other, $name")?;
+        stmt.execute(crate::named_params! { "$name": "x", "$other": 1 })?;
+        assert_eq!(stmt.expanded_sql().as_deref(), Some("SELECT 'x' || 1, 'x'"));
+        let mut stmt = db.prepare("SELECT $2, $1, ?")?;
+        stmt.execute([1, 2, 3])?;
+        assert_eq!(stmt.expanded_sql().as_deref(), Some("SELECT 2, 1, 3"));
+        Ok(())
+    }
+
     #[test]
     fn test_raw_binding() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -727,6 +2000,58 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_clear_bindings() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE test (id INTEGER, name TEXT)")?;
+        let mut stmt = db.prepare("INSERT INTO test (id, name) VALUES (?, ?)")?;
+        stmt.raw_bind_parameter(1, 1)?;
+        stmt.raw_bind_parameter(2, "a")?;
+        stmt.raw_execute()?;
+        // Only the changed parameter is bound again, the other keeps its value.
+        stmt.raw_bind_parameter(1, 2)?;
+        stmt.raw_execute()?;
+
+        stmt.clear_bindings();
+        assert_eq!(
+            stmt.expanded_sql().as_deref(),
+            Some("INSERT INTO test (id, name) VALUES (NULL, NULL)")
+        );
+        stmt.raw_bind_parameter(1, 3)?;
+        let err = stmt.raw_execute().unwrap_err();
+        assert!(err.to_string().contains("Values were not provided"), "{err}");
+
+        let names: Vec<(i32, String)> = db
+            .prepare("SELECT id, name FROM test ORDER BY id")?
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_>>()?;
+        assert_eq!(names, [(1, "a".to_owned()), (2, "a".to_owned())]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parameter_arity() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE test (id INTEGER, name TEXT, flag BOOLEAN)")?;
+        let mut stmt = db.prepare("INSERT INTO test VALUES (?, ?, ?)")?;
+        assert_eq!(
+            stmt.execute([1]).unwrap_err(),
+            Error::MissingParameters(vec!["2".to_owned(), "3".to_owned()])
+        );
+        assert_eq!(
+            stmt.execute(params_from_iter([1, 2, 3, 4])).unwrap_err(),
+            Error::InvalidParameterCount(4, 3)
+        );
+        let mut stmt = db.prepare("SELECT $id, $name")?;
+        let err = stmt.query(["x"]).err().unwrap();
+        assert_eq!(err, Error::MissingParameters(vec!["name".to_owned()]));
+        assert_eq!(err.to_string(), "No value bound for parameters: $name");
+
+        let count: i64 = db.query_row("SELECT count(*) FROM test", [], |r| r.get(0))?;
+        assert_eq!(count, 0);
+        Ok(())
+    }
+
     #[test]
     #[cfg_attr(windows, ignore = "Windows doesn't allow concurrent writes to a file")]
     fn test_insert_duplicate() -> Result<()> {
@@ -774,6 +2099,91 @@ mod test {
         assert!(stmt.exists([1i32])?);
         assert!(stmt.exists([2i32])?);
         assert!(!stmt.exists([0i32])?);
+
+        // Only the first row is computed.
+        let mut stmt = db.prepare("SELECT * FROM range(1000000000000)")?;
+        assert!(stmt.exists([])?);
+        assert!(stmt.exists([])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_time() -> Result<()> {
+        use std::time::Duration;
+
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare(
+            "SELECT count(*) FROM (SELECT a.range + b.range FROM range(3000) a, range(3000) b LIMIT 5000000)",
+        )?;
+        assert_eq!(stmt.last_execution_time(), None);
+        assert_eq!(stmt.total_execution_time(), Duration::ZERO);
+
+        let count: i64 = stmt.query_row([], |r| r.get(0))?;
+        assert_eq!(count, 5_000_000);
+        let first = stmt.last_execution_time().unwrap();
+        assert!(first > Duration::ZERO);
+        assert_eq!(stmt.total_execution_time(), first);
+
+        stmt.query_row([], |r| r.get::<_, i64>(0))?;
+        let second = stmt.last_execution_time().unwrap();
+        assert_eq!(stmt.total_execution_time(), first + second);
+
+        // Failures aren't counted.
+        let mut stmt = db.prepare("SELECT CAST(? AS INTEGER)")?;
+        stmt.execute(["1"])?;
+        let total = stmt.total_execution_time();
+        stmt.execute(["x"]).unwrap_err();
+        assert_eq!(stmt.total_execution_time(), total);
+
+        // Cached statements keep their times, even when prepared again.
+        db.execute_batch("CREATE TABLE foo(x INTEGER)")?;
+        let sql = "SELECT * FROM foo";
+        db.prepare_cached(sql)?.execute([])?;
+        let total = db.prepare_cached(sql)?.total_execution_time();
+        assert!(total > Duration::ZERO);
+        db.execute_batch("DROP TABLE foo; CREATE TABLE foo(x INTEGER, y INTEGER)")?;
+        let mut stmt = db.prepare_cached(sql)?;
+        stmt.execute([])?;
+        assert!(stmt.total_execution_time() > total);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_one_and_opt() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE foo(x INTEGER, y TEXT); INSERT INTO foo VALUES (1, 'a'), (2, 'b'), (2, 'c')")?;
+        let mut stmt = db.prepare("SELECT y FROM foo WHERE x = ?")?;
+
+        assert_eq!(stmt.query_one([1], |r| r.get::<_, String>(0))?, "a");
+        assert_eq!(stmt.query_opt([1], |r| r.get::<_, String>(0))?.as_deref(), Some("a"));
+
+        assert_eq!(
+            stmt.query_one([0], |r| r.get::<_, String>(0)).unwrap_err(),
+            Error::QueryReturnedNoRows
+        );
+        assert_eq!(stmt.query_opt([0], |r| r.get::<_, String>(0))?, None);
+
+        assert_eq!(
+            stmt.query_one([2], |r| r.get::<_, String>(0)).unwrap_err(),
+            Error::QueryReturnedMoreThanOneRow(2)
+        );
+        assert_eq!(
+            stmt.query_opt([2], |r| r.get::<_, String>(0)).unwrap_err(),
+            Error::QueryReturnedMoreThanOneRow(2)
+        );
+
+        // Errors from the mapping are returned as they are.
+        let err = stmt.query_one([1], |r| r.get::<_, i32>(0)).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::ColumnConversionFailure {
+                    source: FromSqlError::InvalidType,
+                    ..
+                }
+            ),
+            "{err}"
+        );
         Ok(())
     }
 
@@ -899,4 +2309,137 @@ mod test {
         assert_eq!(expected, actual);
         Ok(())
     }
+
+    #[test]
+    fn test_query_with_timeout() -> Result<()> {
+        use std::time::{Duration, Instant};
+
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare("SELECT sum(range) FROM range(1000000000000)")?;
+        let start = Instant::now();
+        let err = stmt
+            .query_with_timeout([], Duration::from_millis(100))
+            .map(drop)
+            .unwrap_err();
+        assert_eq!(err, Error::Timeout(Duration::from_millis(100)));
+        assert!(start.elapsed() < Duration::from_secs(30), "{:?}", start.elapsed());
+
+        // The connection, and the statement, can be used again.
+        let x: i64 = db.query_row("SELECT 42", [], |r| r.get(0))?;
+        assert_eq!(x, 42);
+        assert_eq!(
+            stmt.query_with_timeout([], Duration::from_millis(10))
+                .map(drop)
+                .unwrap_err(),
+            Error::Timeout(Duration::from_millis(10))
+        );
+
+        let mut stmt = db.prepare("SELECT ?::INTEGER + 1")?;
+        let mut rows = stmt.query_with_timeout([1], Duration::from_secs(60))?;
+        assert_eq!(rows.next()?.unwrap().get::<_, i32>(0)?, 2);
+
+        // Whether a quick query beats the deadline or not, the next query mustn't
+        // see a stray interrupt.
+        for micros in 0..50 {
+            match stmt.query_with_timeout([micros], Duration::from_micros(micros as u64)) {
+                Ok(mut rows) => assert_eq!(rows.next()?.unwrap().get::<_, i32>(0)?, micros + 1),
+                Err(err) => assert!(matches!(err, Error::Timeout(_)), "{err:?}"),
+            }
+            let x: i32 = db.query_row("SELECT ?::INTEGER", [micros], |r| r.get(0))?;
+            assert_eq!(x, micros);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_with_timeout_error() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare("SELECT 1 / ?::INTEGER > 0 OR error('failed')")?;
+        let err = stmt
+            .query_with_timeout([0], std::time::Duration::from_secs(60))
+            .map(drop)
+            .unwrap_err();
+        assert!(matches!(err, Error::DuckDBFailure(..)), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_streaming() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE SEQUENCE computed")?;
+        let mut stmt = db.prepare("SELECT i, nextval('computed') FROM generate_series(1, 50000000) t(i)")?;
+        let mut rows = stmt.query_streaming([])?;
+        let mut read = 0;
+        while let Some(row) = rows.next()? {
+            read += 1;
+            assert_eq!(row.get::<_, i64>(0)?, read);
+            if read == 10 {
+                break;
+            }
+        }
+        assert_eq!(read, 10);
+        assert_eq!(stmt.row_count(), 0);
+
+        // Only a few chunks were computed, and the connection is free again.
+        let computed: i64 = db.query_row("SELECT currval('computed')", [], |r| r.get(0))?;
+        assert!((10..1_000_000).contains(&computed), "{computed}");
+
+        // The same statement can run again, streamed or not.
+        let mut rows = stmt.query_streaming([])?;
+        assert_eq!(rows.next()?.unwrap().get::<_, i64>(0)?, 1);
+        let mut stmt = db.prepare("SELECT i FROM range(5000) t(i)")?;
+        let all: Vec<i64> = stmt.query_streaming([])?.mapped(|r| r.get(0)).collect::<Result<_>>()?;
+        assert_eq!(all, (0..5000).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_streaming_types() -> Result<()> {
+        use crate::types::Value;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TYPE mood AS ENUM ('sad', 'happy')")?;
+        let sql = "SELECT 1::TINYINT, 2::HUGEINT, 3.25::DECIMAL(5, 2), 'a', 'ab'::BLOB, NULL,
+                          DATE '2024-01-02', TIMESTAMP '2024-01-02 03:04:05', TIMESTAMPTZ '2024-01-02 03:04:05+00',
+                          INTERVAL 3 DAY, 'happy'::mood, [1, NULL], [1, 2]::INTEGER[2], {'a': 1, 'b': 'x'},
+                          MAP {'k': 1.5}, union_value(n := 2), gen_random_uuid() IS NOT NULL
+                   FROM range(3)";
+        let mut stmt = db.prepare(sql)?;
+        let expected: Vec<Vec<Value>> = stmt
+            .query_map([], |row| (0..17).map(|i| row.get(i)).collect())?
+            .collect::<Result<_>>()?;
+        let types: Vec<_> = (0..17).map(|i| stmt.column_type(i)).collect();
+
+        let mut rows = stmt.query_streaming([])?;
+        let mut streamed = Vec::new();
+        while let Some(row) = rows.next()? {
+            streamed.push((0..17).map(|i| row.get(i)).collect::<Result<Vec<Value>>>()?);
+        }
+        assert_eq!(streamed, expected);
+        assert_eq!((0..17).map(|i| stmt.column_type(i)).collect::<Vec<_>>(), types);
+
+        // Results DuckDB doesn't stream are read all the same.
+        db.execute_batch("CREATE TABLE t (x INTEGER)")?;
+        let mut stmt = db.prepare("INSERT INTO t VALUES (1), (2) RETURNING x")?;
+        let inserted: Vec<i32> = stmt.query_streaming([])?.mapped(|r| r.get(0)).collect::<Result<_>>()?;
+        assert_eq!(inserted, [1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_streaming_error() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let mut stmt =
+            db.prepare("SELECT CASE WHEN i = 100000 THEN error('boom') ELSE i END FROM range(200000) t(i)")?;
+        let res = stmt.query_streaming([]).and_then(|mut rows| {
+            while rows.next()?.is_some() {}
+            Ok(())
+        });
+        let err = res.unwrap_err();
+        assert!(err.to_string().contains("boom"), "{err}");
+
+        let x: i32 = db.query_row("SELECT 42", [], |r| r.get(0))?;
+        assert_eq!(x, 42);
+        Ok(())
+    }
 }
@@ -1,5 +1,4 @@
 use pretty_assertions::assert_eq;
-use rust_decimal::Decimal;
 
 use crate::{
     types::{OrderedMap, TimeUnit, Type, Value, ValueRef},
@@ -20,9 +19,9 @@ fn test_large_arrow_types() -> crate::Result<()> {
 }
 
 fn test_with_database(database: &Connection) -> crate::Result<()> {
-    // uhugeint, time_tz, and dec38_10 aren't supported in the duckdb arrow layer
+    // uhugeint and time_tz aren't supported in the duckdb arrow layer
     // union is currently blocked by https://github.com/duckdb/duckdb/pull/11326
-    let excluded = ["uhugeint", "time_tz", "dec38_10", "union"];
+    let excluded = ["uhugeint", "time_tz", "union"];
 
     let mut binding = database.prepare(&format!(
         "SELECT * EXCLUDE ({}) FROM test_all_types()",
@@ -117,8 +116,22 @@ fn test_single(idx: &mut i32, column: String, value: ValueRef) {
             _ => assert_eq!(value, ValueRef::Null),
         },
         "decimal" => match idx {
-            0 => assert_eq!(value, ValueRef::Decimal(Decimal::from_i128_with_scale(0, 0))),
-            1 => assert_eq!(value, ValueRef::Decimal(Decimal::from_i128_with_scale(1, 0))),
+            0 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: 0,
+                    width: 1,
+                    scale: 0
+                }
+            ),
+            1 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: 1,
+                    width: 1,
+                    scale: 0
+                }
+            ),
             _ => assert_eq!(value, ValueRef::Null),
         },
         "date" => match idx {
@@ -152,28 +165,86 @@ fn test_single(idx: &mut i32, column: String, value: ValueRef) {
             _ => assert_eq!(value, ValueRef::Null),
         },
         "timestamp_tz" => match idx {
-            0 => assert_eq!(value, ValueRef::Timestamp(TimeUnit::Microsecond, -9223372022400000000)),
-            1 => assert_eq!(value, ValueRef::Timestamp(TimeUnit::Microsecond, 9223372036854775806)),
+            0 => assert_eq!(
+                value,
+                ValueRef::TimestampTz(TimeUnit::Microsecond, -9223372022400000000)
+            ),
+            1 => assert_eq!(value, ValueRef::TimestampTz(TimeUnit::Microsecond, 9223372036854775806)),
             _ => assert_eq!(value, ValueRef::Null),
         },
         "dec_4_1" => match idx {
-            0 => assert_eq!(value, ValueRef::Decimal(Decimal::from_i128_with_scale(-9999, 1))),
-            1 => assert_eq!(value, ValueRef::Decimal(Decimal::from_i128_with_scale(9999, 1))),
+            0 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: -9999,
+                    width: 4,
+                    scale: 1
+                }
+            ),
+            1 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: 9999,
+                    width: 4,
+                    scale: 1
+                }
+            ),
             _ => assert_eq!(value, ValueRef::Null),
         },
         "dec_9_4" => match idx {
-            0 => assert_eq!(value, ValueRef::Decimal(Decimal::from_i128_with_scale(-999999999, 4))),
-            1 => assert_eq!(value, ValueRef::Decimal(Decimal::from_i128_with_scale(999999999, 4))),
+            0 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: -999999999,
+                    width: 9,
+                    scale: 4
+                }
+            ),
+            1 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: 999999999,
+                    width: 9,
+                    scale: 4
+                }
+            ),
             _ => assert_eq!(value, ValueRef::Null),
         },
         "dec_18_6" => match idx {
             0 => assert_eq!(
                 value,
-                ValueRef::Decimal(Decimal::from_i128_with_scale(-999999999999999999, 6))
+                ValueRef::Decimal {
+                    value: -999999999999999999,
+                    width: 18,
+                    scale: 6
+                }
             ),
             1 => assert_eq!(
                 value,
-                ValueRef::Decimal(Decimal::from_i128_with_scale(999999999999999999, 6))
+                ValueRef::Decimal {
+                    value: 999999999999999999,
+                    width: 18,
+                    scale: 6
+                }
+            ),
+            _ => assert_eq!(value, ValueRef::Null),
+        },
+        "dec38_10" => match idx {
+            0 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: -99999999999999999999999999999999999999,
+                    width: 38,
+                    scale: 10
+                }
+            ),
+            1 => assert_eq!(
+                value,
+                ValueRef::Decimal {
+                    value: 99999999999999999999999999999999999999,
+                    width: 38,
+                    scale: 10
+                }
             ),
             _ => assert_eq!(value, ValueRef::Null),
         },
@@ -272,11 +343,11 @@ fn test_single(idx: &mut i32, column: String, value: ValueRef) {
             1 => assert_eq!(
                 value.to_owned(),
                 Value::List(vec![
-                    Value::Timestamp(TimeUnit::Microsecond, 0,),
-                    Value::Timestamp(TimeUnit::Microsecond, 9223372036854775807,),
-                    Value::Timestamp(TimeUnit::Microsecond, -9223372036854775807,),
+                    Value::TimestampTz(TimeUnit::Microsecond, 0,),
+                    Value::TimestampTz(TimeUnit::Microsecond, 9223372036854775807,),
+                    Value::TimestampTz(TimeUnit::Microsecond, -9223372036854775807,),
                     Value::Null,
-                    Value::Timestamp(TimeUnit::Microsecond, 1652397825000000,),
+                    Value::TimestampTz(TimeUnit::Microsecond, 1652397825000000,),
                 ])
             ),
             _ => assert_eq!(value, ValueRef::Null),
@@ -651,7 +722,7 @@ fn test_single(idx: &mut i32, column: String, value: ValueRef) {
         },
         "medium_enum" => match idx {
             0 => assert_eq!(value.to_owned(), Value::Enum("enum_0".to_string())),
-            1 => assert_eq!(value.to_owned(), Value::Enum("enum_1".to_string())),
+            1 => assert_eq!(value.to_owned(), Value::Enum("enum_299".to_string())),
             _ => assert_eq!(value, ValueRef::Null),
         },
         "large_enum" => match idx {
@@ -662,10 +733,19 @@ fn test_single(idx: &mut i32, column: String, value: ValueRef) {
         "union" => match idx {
             0 => assert_eq!(
                 value.to_owned(),
-                Value::Union(Box::new(Value::Text("Frank".to_owned())))
+                Value::Union {
+                    tag: "name".to_owned(),
+                    value: Box::new(Value::Text("Frank".to_owned()))
+                }
+            ),
+            1 => assert_eq!(
+                value.to_owned(),
+                Value::Union {
+                    tag: "age".to_owned(),
+                    value: Box::new(Value::SmallInt(5))
+                }
             ),
-            1 => assert_eq!(value.to_owned(), Value::Union(Box::new(Value::SmallInt(5)))),
-            _ => assert_eq!(value.to_owned(), Value::Union(Box::new(Value::Null))),
+            _ => assert_eq!(value, ValueRef::Null),
         },
         _ => todo!("{column:?}"),
     }
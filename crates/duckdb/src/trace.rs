@@ -0,0 +1,311 @@
+//! Tracing and profiling of the SQL a connection runs, and the SQL its
+//! errors show
+
+use std::time::{Duration, Instant};
+
+use crate::{types::Value, Connection, Error, ErrorVerbosity, Result};
+
+/// A callback registered with [`Connection::profile`].
+pub(crate) type ProfileFn = fn(&str, Duration);
+
+/// A callback registered with [`Connection::set_param_redactor`].
+pub(crate) type RedactFn = fn(&str, &Value) -> Option<String>;
+
+impl Connection {
+    /// Register or clear a callback function that is called with the text of
+    /// every SQL statement this connection runs, before it runs.
+    ///
+    /// That's the SQL passed to [`execute_batch`](Connection::execute_batch),
+    /// as one string, and the SQL of each execution of a prepared
+    /// statement, including those of [`execute`](Connection::execute),
+    /// [`query_row`](Connection::query_row) and cached statements.
+    /// Appenders write rows without SQL, so they aren't traced. Clones made
+    /// with [`try_clone`](Connection::try_clone) inherit the callback.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn log_sql(conn: &Connection) -> Result<()> {
+    ///     conn.trace(Some(|sql| eprintln!("duckdb: {sql}")));
+    ///     conn.execute_batch("CREATE TABLE foo (x INTEGER)")?;
+    ///     conn.trace(None);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn trace(&self, trace_fn: Option<fn(&str)>) {
+        self.trace_hook.set(trace_fn);
+    }
+
+    /// Register or clear a callback function that is called with the text of
+    /// every SQL statement this connection runs, as for
+    /// [`trace`](Connection::trace), along with the wall-clock time it took,
+    /// once it finished, whether it failed or not.
+    ///
+    /// The time of a query covers computing its whole result, which DuckDB
+    /// does before any row is read. For per-operator timings, see
+    /// [`enable_profiling`](Connection::enable_profiling).
+    #[inline]
+    pub fn profile(&self, profile_fn: Option<fn(&str, Duration)>) {
+        self.profile_hook.set(profile_fn);
+    }
+
+    /// Set how much of a failing statement the errors of this connection
+    /// show. The default, [`ErrorVerbosity::Message`], shows DuckDB's message
+    /// alone. Otherwise, errors of [`execute_batch`](Connection::execute_batch),
+    /// [`prepare`](Connection::prepare) and of executing prepared statements
+    /// are wrapped in [`Error::StatementFailed`], which adds the SQL and,
+    /// with [`ErrorVerbosity::WithSqlAndParams`], the bound parameters to
+    /// the message.
+    ///
+    /// Parameters may hold secrets that shouldn't end up in logs; see
+    /// [`set_param_redactor`](Connection::set_param_redactor). Clones made
+    /// with [`try_clone`](Connection::try_clone) inherit the verbosity.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, ErrorVerbosity};
+    /// fn debug_errors(conn: &Connection) {
+    ///     conn.set_error_verbosity(ErrorVerbosity::WithSqlAndParams);
+    ///     if let Err(err) = conn.execute("INSERT INTO missing VALUES (?)", [1]) {
+    ///         // Catalog Error: Table with name missing does not exist! ...
+    ///         // SQL: INSERT INTO missing VALUES (?)
+    ///         // Parameters: $1 = 1
+    ///         eprintln!("{err}");
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn set_error_verbosity(&self, verbosity: ErrorVerbosity) {
+        self.error_verbosity.set(verbosity);
+    }
+
+    /// The verbosity set with
+    /// [`set_error_verbosity`](Connection::set_error_verbosity).
+    #[inline]
+    pub fn error_verbosity(&self) -> ErrorVerbosity {
+        self.error_verbosity.get()
+    }
+
+    /// Register or clear a callback function that decides how errors show
+    /// bound parameters, with [`ErrorVerbosity::WithSqlAndParams`]. It is
+    /// called with the name of each parameter, as for
+    /// [`Statement::parameter_name`](crate::Statement::parameter_name), and
+    /// its value; returning `Some` shows the string returned in place of
+    /// the value. Without a callback, or when it returns `None`, values are
+    /// shown as SQL literals.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{types::Value, Connection, ErrorVerbosity};
+    /// fn redact_passwords(conn: &Connection) {
+    ///     conn.set_error_verbosity(ErrorVerbosity::WithSqlAndParams);
+    ///     conn.set_param_redactor(Some(|name: &str, _: &Value| {
+    ///         (name == "password").then(|| "<redacted>".to_owned())
+    ///     }));
+    /// }
+    /// ```
+    #[inline]
+    pub fn set_param_redactor(&self, redact_fn: Option<fn(&str, &Value) -> Option<String>>) {
+        self.param_redactor.set(redact_fn);
+    }
+
+    /// Adds the SQL and parameters of the statement that failed with `err`
+    /// to it, as far as the error verbosity asks for.
+    pub(crate) fn error_context(&self, err: Error, sql: &str, params: Option<&[(String, Value)]>) -> Error {
+        let verbosity = self.error_verbosity.get();
+        if verbosity == ErrorVerbosity::Message || matches!(err, Error::StatementFailed { .. }) {
+            return err;
+        }
+        let params = match params {
+            Some(params) if verbosity == ErrorVerbosity::WithSqlAndParams => {
+                let redact_fn = self.param_redactor.get();
+                let params = params
+                    .iter()
+                    .map(|(name, value)| {
+                        let shown = redact_fn
+                            .and_then(|redact_fn| redact_fn(name, value))
+                            .unwrap_or_else(|| value.to_sql_literal());
+                        (name.clone(), shown)
+                    })
+                    .collect();
+                Some(params)
+            }
+            _ => None,
+        };
+        Error::StatementFailed {
+            error: Box::new(err),
+            sql: sql.to_owned(),
+            params,
+        }
+    }
+
+    /// Runs `f`, which runs `sql`, reporting it to the registered callbacks.
+    #[inline]
+    pub(crate) fn traced<T>(&self, sql: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        if let Some(trace_fn) = self.trace_hook.get() {
+            trace_fn(sql);
+        }
+        let Some(profile_fn) = self.profile_hook.get() else {
+            return f();
+        };
+        let start = Instant::now();
+        let res = f();
+        profile_fn(sql, start.elapsed());
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Mutex, time::Duration};
+
+    use crate::{types::Value, Connection, Error, ErrorKind, ErrorVerbosity, Result};
+
+    static TRACED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static PROFILED: Mutex<Vec<(String, Duration)>> = Mutex::new(Vec::new());
+
+    #[test]
+    fn test_trace() -> Result<()> {
+        fn tracer(sql: &str) {
+            TRACED.lock().unwrap().push(sql.to_owned());
+        }
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE untraced (x INTEGER)")?;
+        db.trace(Some(tracer));
+        db.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1)")?;
+        db.execute("INSERT INTO t VALUES (?)", [2])?;
+        let mut stmt = db.prepare_cached("SELECT sum(x) FROM t")?;
+        let sum: i32 = stmt.query_row([], |r| r.get(0))?;
+        assert_eq!(sum, 3);
+        drop(stmt);
+        assert!(db.execute_batch("SELECT * FROM missing").is_err());
+        let clone = db.try_clone()?;
+        clone.execute_batch("SELECT 1")?;
+        db.trace(None);
+        db.execute_batch("SELECT 2")?;
+
+        assert_eq!(
+            *TRACED.lock().unwrap(),
+            [
+                "CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1)",
+                "INSERT INTO t VALUES (?)",
+                "SELECT sum(x) FROM t",
+                "SELECT * FROM missing",
+                "SELECT 1",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile() -> Result<()> {
+        fn profiler(sql: &str, duration: Duration) {
+            PROFILED.lock().unwrap().push((sql.to_owned(), duration));
+        }
+
+        let db = Connection::open_in_memory()?;
+        db.profile(Some(profiler));
+        db.execute_batch("CREATE TABLE t AS SELECT range AS x FROM range(1000000)")?;
+        let count: i64 = db.query_row("SELECT count(DISTINCT x) FROM t", [], |r| r.get(0))?;
+        assert_eq!(count, 1000000);
+        db.profile(None);
+        db.execute_batch("SELECT 1")?;
+
+        let profiled = PROFILED.lock().unwrap();
+        let sql: Vec<_> = profiled.iter().map(|(sql, _)| sql.as_str()).collect();
+        assert_eq!(
+            sql,
+            [
+                "CREATE TABLE t AS SELECT range AS x FROM range(1000000)",
+                "SELECT count(DISTINCT x) FROM t",
+            ]
+        );
+        for (sql, duration) in profiled.iter() {
+            assert!(
+                *duration > Duration::ZERO && *duration < Duration::from_secs(60),
+                "{sql}: {duration:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_verbosity_default() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        assert_eq!(db.error_verbosity(), ErrorVerbosity::Message);
+        let err = db.execute("SELECT * FROM missing WHERE x = ?", [1]).unwrap_err();
+        assert!(matches!(err, Error::DuckDBFailure(..)), "{err:?}");
+        let msg = err.to_string();
+        assert!(msg.starts_with("Catalog Error: "), "{msg}");
+        assert!(!msg.contains("SQL:"), "{msg}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_verbosity_with_sql() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.set_error_verbosity(ErrorVerbosity::WithSql);
+        let err = db.execute("SELECT * FROM missing WHERE x = ?", [1]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Catalog);
+        let msg = err.to_string();
+        assert!(msg.starts_with("Catalog Error: "), "{msg}");
+        assert!(msg.ends_with("\nSQL: SELECT * FROM missing WHERE x = ?"), "{msg}");
+
+        let err = db.execute_batch("CREATE TABLE t (x INTEGER); SELEC 1").unwrap_err();
+        assert!(err.sql_error_position().is_some(), "{err:?}");
+        assert!(
+            err.to_string().ends_with("\nSQL: CREATE TABLE t (x INTEGER); SELEC 1"),
+            "{err}"
+        );
+
+        // Errors are still recognized under the context.
+        let _tx = db.unchecked_transaction()?;
+        assert_eq!(db.unchecked_transaction().unwrap_err(), Error::NestedTransaction);
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_verbosity_with_params() -> Result<()> {
+        fn redact(name: &str, _: &Value) -> Option<String> {
+            (name == "password").then(|| "<redacted>".to_owned())
+        }
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE users (name TEXT, password TEXT NOT NULL)")?;
+        db.set_error_verbosity(ErrorVerbosity::WithSqlAndParams);
+        let err = db
+            .execute(
+                "INSERT INTO users VALUES (?, ?)",
+                crate::params!["o'neil", None::<String>],
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err.without_context(), Error::ConstraintViolation { .. }),
+            "{err:?}"
+        );
+        assert!(
+            err.to_string()
+                .ends_with("\nSQL: INSERT INTO users VALUES (?, ?)\nParameters: $1 = 'o''neil', $2 = NULL"),
+            "{err}"
+        );
+
+        db.set_param_redactor(Some(redact));
+        let mut stmt = db.prepare("INSERT INTO users VALUES ($name, $password)")?;
+        let err = stmt
+            .execute(crate::params!["a", "hunter2"])
+            .and_then(|_| stmt.execute(crate::params!["x", None::<String>]))
+            .unwrap_err();
+        let msg = err.to_string();
+        assert!(
+            msg.ends_with("Parameters: $name = 'x', $password = <redacted>"),
+            "{msg}"
+        );
+        assert!(!msg.contains("hunter2"), "{msg}");
+        Ok(())
+    }
+}
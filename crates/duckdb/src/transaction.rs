@@ -1,22 +1,38 @@
-use crate::{Connection, Result};
-use std::ops::Deref;
+use crate::{Connection, Error, Result};
+use std::{ops::Deref, thread};
 
-/// Options for transaction behavior. See [BEGIN
-/// TRANSACTION](http://www.sqlite.org/lang_transaction.html) for details.
-#[derive(Copy, Clone)]
+/// Options for when a transaction takes its snapshot of the databases
+/// attached to the connection. See [Transaction
+/// Management](https://duckdb.org/docs/sql/statements/transactions) for
+/// details.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum TransactionBehavior {
-    /// DEFERRED means that the transaction does not actually start until the
-    /// database is first accessed.
+    /// The transaction sees each database as it was when the transaction
+    /// first accesses it. This is the default.
     Deferred,
-    /// IMMEDIATE cause the database connection to start a new write
-    /// immediately, without waiting for a writes statement.
+    /// The transaction sees every attached database as it was when the
+    /// transaction began, whenever it first accesses it.
     Immediate,
-    /// EXCLUSIVE prevents other database connections from reading the database
-    /// while the transaction is underway.
+    /// DuckDB has no transactions that keep other connections out, so this
+    /// is the same as `Immediate`.
     Exclusive,
 }
 
+/// Whether a transaction is open on a connection, as returned by
+/// [`Connection::transaction_state`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TransactionState {
+    /// No transaction is open: each statement commits on its own.
+    None,
+    /// A transaction is open, through [`Connection::transaction`] or a
+    /// `BEGIN` statement.
+    Active,
+    /// A statement of the open transaction failed, so every statement but
+    /// `ROLLBACK` fails until it is rolled back.
+    Failed,
+}
+
 /// Options for how a Transaction or Savepoint should behave when it is dropped.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -24,7 +40,10 @@ pub enum DropBehavior {
     /// Roll back the changes. This is the default.
     Rollback,
 
-    /// Commit the changes.
+    /// Commit the changes, or roll them back if committing fails.
+    ///
+    /// `Drop` can't return the error from a failed commit, use
+    /// [`Transaction::finish`] or [`Savepoint::finish`] to see it.
     Commit,
 
     /// Do not commit or roll back changes - this will leave the transaction or
@@ -32,6 +51,9 @@ pub enum DropBehavior {
     Ignore,
 
     /// Panic. Used to enforce intentional behavior during development.
+    ///
+    /// When dropped while the thread is already panicking, the changes are
+    /// rolled back instead, as a second panic would abort the process.
     Panic,
 }
 
@@ -41,7 +63,8 @@ pub enum DropBehavior {
 ///
 /// Transactions will roll back by default. Use `commit` method to explicitly
 /// commit the transaction, or use `set_drop_behavior` to change what happens
-/// when the transaction is dropped.
+/// when the transaction is dropped. Errors when committing or rolling back
+/// on drop are ignored; use `finish` to see them.
 ///
 /// ## Example
 ///
@@ -104,27 +127,48 @@ impl Transaction<'_> {
     /// where this is unacceptable, [`Transaction::new_unchecked`] is available.
     #[inline]
     pub fn new(conn: &mut Connection) -> Result<Transaction<'_>> {
-        Self::new_unchecked(conn, TransactionBehavior::Deferred)
+        Self::begin(conn, TransactionBehavior::Deferred)
     }
 
     /// Begin a new transaction, failing if a transaction is open.
     ///
-    /// If a transaction is already open, this will return an error. Where
-    /// possible, [`Transaction::new`] should be preferred, as it provides a
-    /// compile-time guarantee that transactions are not nested.
-    #[inline]
-    fn new_unchecked(conn: &Connection, _: TransactionBehavior) -> Result<Transaction<'_>> {
-        // TODO(wangfenjin): not supported
-        // let query = match behavior {
-        //     TransactionBehavior::Deferred => "BEGIN DEFERRED",
-        //     TransactionBehavior::Immediate => "BEGIN IMMEDIATE",
-        //     TransactionBehavior::Exclusive => "BEGIN EXCLUSIVE",
-        // };
-        let query = "BEGIN Transaction";
-        conn.execute_batch(query).map(move |_| Transaction {
+    /// If a transaction is already open, this will return
+    /// [`Error::NestedTransaction`]. Where possible, [`Transaction::new`]
+    /// should be preferred, as it provides a compile-time guarantee that
+    /// transactions are not nested.
+    pub fn new_unchecked(conn: &Connection, behavior: TransactionBehavior) -> Result<Transaction<'_>> {
+        // A second `BEGIN` would fail, and abort the open transaction.
+        match conn.transaction_state()? {
+            TransactionState::None => Self::begin(conn, behavior),
+            TransactionState::Active => Err(Error::NestedTransaction),
+            TransactionState::Failed => Err(Error::FailedTransaction),
+        }
+    }
+
+    fn begin(conn: &Connection, behavior: TransactionBehavior) -> Result<Transaction<'_>> {
+        conn.execute_batch("BEGIN TRANSACTION")
+            .map_err(|err| match err.without_context() {
+                Error::DuckDBFailure(_, Some(msg))
+                    if msg.contains("cannot start a transaction within a transaction") =>
+                {
+                    Error::NestedTransaction
+                }
+                Error::DuckDBFailure(_, Some(msg)) if msg.contains(ABORTED_TRANSACTION) => Error::FailedTransaction,
+                _ => err,
+            })?;
+        let tx = Transaction {
             conn,
             drop_behavior: DropBehavior::Rollback,
-        })
+        };
+        match behavior {
+            TransactionBehavior::Deferred => {}
+            // Listing the schemas starts the transaction in every attached
+            // database.
+            TransactionBehavior::Immediate | TransactionBehavior::Exclusive => {
+                tx.execute_batch("SELECT count(*) FROM duckdb_schemas()")?
+            }
+        }
+        Ok(tx)
     }
 
     /// Starts a new [savepoint](http://www.sqlite.org/lang_savepoint.html), allowing nested
@@ -182,7 +226,10 @@ impl Transaction<'_> {
     /// A convenience method which consumes and commits a transaction.
     #[inline]
     pub fn commit(mut self) -> Result<()> {
-        self.commit_()
+        self.commit_()?;
+        // Nothing is left to do, or to panic about, when it is dropped.
+        self.drop_behavior = DropBehavior::Ignore;
+        Ok(())
     }
 
     #[inline]
@@ -194,7 +241,9 @@ impl Transaction<'_> {
     /// A convenience method which consumes and rolls back a transaction.
     #[inline]
     pub fn rollback(mut self) -> Result<()> {
-        self.rollback_()
+        self.rollback_()?;
+        self.drop_behavior = DropBehavior::Ignore;
+        Ok(())
     }
 
     #[inline]
@@ -207,7 +256,8 @@ impl Transaction<'_> {
     /// current setting (see `drop_behavior`).
     ///
     /// Functionally equivalent to the `Drop` implementation, but allows
-    /// callers to see any errors that occur.
+    /// callers to see any errors that occur. With `DropBehavior::Commit`, a
+    /// failed commit returns its error after rolling back.
     #[inline]
     pub fn finish(mut self) -> Result<()> {
         self.finish_()
@@ -215,19 +265,22 @@ impl Transaction<'_> {
 
     #[inline]
     fn finish_(&mut self) -> Result<()> {
-        // if self.conn.is_autocommit() {
-        //     println!("is autocommit");
-        //     return Ok(());
-        // }
         match self.drop_behavior() {
-            DropBehavior::Commit => self.commit_().or_else(|_| self.rollback_()),
+            DropBehavior::Commit => self.commit_().inspect_err(|_| {
+                // DuckDB already rolls back a transaction it fails to commit.
+                let _ = self.rollback_();
+            }),
             DropBehavior::Rollback => self.rollback_(),
             DropBehavior::Ignore => Ok(()),
+            DropBehavior::Panic if thread::panicking() => self.rollback_(),
             DropBehavior::Panic => panic!("Transaction dropped unexpectedly."),
         }
     }
 }
 
+/// What DuckDB says when a statement runs in a transaction that failed.
+const ABORTED_TRANSACTION: &str = "Current transaction is aborted";
+
 impl Deref for Transaction<'_> {
     type Target = Connection;
 
@@ -342,9 +395,12 @@ impl Savepoint<'_> {
             return Ok(());
         }
         match self.drop_behavior() {
-            DropBehavior::Commit => self.commit_().or_else(|_| self.rollback()),
+            DropBehavior::Commit => self.commit_().inspect_err(|_| {
+                let _ = self.rollback();
+            }),
             DropBehavior::Rollback => self.rollback(),
             DropBehavior::Ignore => Ok(()),
+            DropBehavior::Panic if thread::panicking() => self.rollback(),
             DropBehavior::Panic => panic!("Savepoint dropped unexpectedly."),
         }
     }
@@ -403,13 +459,27 @@ impl Connection {
     ///
     /// See [`transaction`](Connection::transaction).
     ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result, TransactionBehavior};
+    /// fn report(conn: &mut Connection) -> Result<(i64, i64)> {
+    ///     // Both counts come from the same snapshot, even though `archive`
+    ///     // isn't read until after `users`.
+    ///     let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    ///     let users = tx.query_row("SELECT count(*) FROM users", [], |r| r.get(0))?;
+    ///     let archived = tx.query_row("SELECT count(*) FROM archive.users", [], |r| r.get(0))?;
+    ///     tx.commit()?;
+    ///     Ok((users, archived))
+    /// }
+    /// ```
+    ///
     /// # Failure
     ///
     /// Will return `Err` if the underlying DuckDB call fails.
     #[inline]
-    #[allow(dead_code)]
-    fn transaction_with_behavior(&mut self, behavior: TransactionBehavior) -> Result<Transaction<'_>> {
-        Transaction::new_unchecked(self, behavior)
+    pub fn transaction_with_behavior(&mut self, behavior: TransactionBehavior) -> Result<Transaction<'_>> {
+        Transaction::begin(self, behavior)
     }
 
     /// Begin a new transaction with the default behavior (DEFERRED).
@@ -441,12 +511,44 @@ impl Connection {
     ///
     /// # Failure
     ///
-    /// Will return `Err` if the underlying DuckDB call fails. The specific
-    /// error returned if transactions are nested is currently unspecified.
+    /// Will return [`Error::NestedTransaction`] if a transaction is already
+    /// active on the connection, [`Error::FailedTransaction`] if it failed,
+    /// and `Err` if the underlying DuckDB call fails.
     pub fn unchecked_transaction(&self) -> Result<Transaction<'_>> {
         Transaction::new_unchecked(self, TransactionBehavior::Deferred)
     }
 
+    /// Whether a transaction is open on this connection, and whether it
+    /// failed. DuckDB has no API for this, so it is found out by running
+    /// queries. They bypass the statement cache, and aren't reported to
+    /// [`trace`](Connection::trace) or [`profile`](Connection::profile)
+    /// callbacks.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the underlying DuckDB call fails.
+    pub fn transaction_state(&self) -> Result<TransactionState> {
+        // Only in a transaction do two queries get the same transaction id.
+        let ids = || -> Result<(i64, i64)> {
+            let mut stmt = self.prepare("SELECT txid_current()")?;
+            let mut id = || -> Result<i64> {
+                stmt.stmt.execute()?;
+                let mut rows = stmt.raw_query();
+                let id = rows.next()?.ok_or(Error::QueryReturnedNoRows)?.get(0)?;
+                Ok(id)
+            };
+            Ok((id()?, id()?))
+        };
+        match ids() {
+            Ok((first, second)) if first == second => Ok(TransactionState::Active),
+            Ok(_) => Ok(TransactionState::None),
+            Err(err) => match err.without_context() {
+                Error::DuckDBFailure(_, Some(msg)) if msg.contains(ABORTED_TRANSACTION) => Ok(TransactionState::Failed),
+                _ => Err(err),
+            },
+        }
+    }
+
     /// Begin a new savepoint with the default behavior (DEFERRED).
     ///
     /// The savepoint defaults to rolling back when it is dropped. If you want
@@ -493,8 +595,9 @@ impl Connection {
 
 #[cfg(test)]
 mod test {
-    use super::DropBehavior;
-    use crate::{Connection, Result};
+    use super::{DropBehavior, TransactionBehavior, TransactionState};
+    use crate::{Connection, Error, Result};
+    use std::sync::Mutex;
 
     fn checked_no_autocommit_memory_handle() -> Result<Connection> {
         let db = Connection::open_in_memory()?;
@@ -530,6 +633,116 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_drop_behavior() -> Result<()> {
+        let mut db = checked_no_autocommit_memory_handle()?;
+        for (behavior, x) in [(DropBehavior::Rollback, 1), (DropBehavior::Commit, 2)] {
+            let mut tx = db.transaction()?;
+            assert_eq!(tx.drop_behavior(), DropBehavior::Rollback);
+            tx.set_drop_behavior(behavior);
+            insert(x, &tx)?;
+        }
+        assert_current_sum(2, &db)?;
+
+        // The transaction is left open.
+        {
+            let mut tx = db.transaction()?;
+            tx.set_drop_behavior(DropBehavior::Ignore);
+            insert(4, &tx)?;
+        }
+        assert_eq!(db.unchecked_transaction().unwrap_err(), Error::NestedTransaction);
+        db.execute_batch("COMMIT")?;
+        assert_current_sum(6, &db)?;
+
+        let mut tx = db.transaction()?;
+        tx.set_drop_behavior(DropBehavior::Commit);
+        insert(8, &tx)?;
+        tx.finish()?;
+        assert_current_sum(14, &db)?;
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Transaction dropped unexpectedly.")]
+    fn test_drop_behavior_panic() {
+        let mut db = checked_no_autocommit_memory_handle().unwrap();
+        let mut tx = db.transaction().unwrap();
+        tx.set_drop_behavior(DropBehavior::Panic);
+    }
+
+    #[test]
+    fn test_drop_behavior_panic_while_unwinding() -> Result<()> {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut db = checked_no_autocommit_memory_handle()?;
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut tx = db.transaction().unwrap();
+            tx.set_drop_behavior(DropBehavior::Panic);
+            tx.execute_batch("INSERT INTO foo VALUES (1)").unwrap();
+            panic!("unrelated failure");
+        }));
+        assert_eq!(res.unwrap_err().downcast_ref::<&str>(), Some(&"unrelated failure"));
+        // The transaction was rolled back instead.
+        assert_eq!(db.transaction_state()?, TransactionState::None);
+        let count: i64 = db.query_row("SELECT count(*) FROM foo", [], |r| r.get(0))?;
+        assert_eq!(count, 0);
+
+        // A committed transaction has nothing to panic about.
+        let mut tx = db.transaction()?;
+        tx.set_drop_behavior(DropBehavior::Panic);
+        tx.execute_batch("INSERT INTO foo VALUES (2)")?;
+        tx.commit()?;
+        assert_current_sum(2, &db)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish_commit_error() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = Connection::open(dir.path().join("finish.db"))?;
+        db.execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY)")?;
+        let other = db.try_clone()?;
+
+        let mut tx = db.transaction()?;
+        tx.set_drop_behavior(DropBehavior::Commit);
+        tx.execute_batch("INSERT INTO t VALUES (1)")?;
+        other.execute_batch("INSERT INTO t VALUES (1)")?;
+        // The duplicate key is only found when committing.
+        match tx.finish() {
+            Err(Error::DuckDBFailure(_, Some(msg))) => assert!(msg.contains("Failed to commit"), "{msg}"),
+            res => panic!("unexpected {res:?}"),
+        }
+        // The failed transaction was rolled back.
+        db.transaction()?.commit()?;
+        let n: i64 = db.query_row("SELECT count(*) FROM t", [], |r| r.get(0))?;
+        assert_eq!(n, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction_behavior() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = Connection::open(dir.path().join("behavior.db"))?;
+        db.execute_batch("CREATE TABLE t (x INTEGER); ATTACH ':memory:' AS other; CREATE TABLE other.t (x INTEGER);")?;
+        let writer = db.try_clone()?;
+
+        let mut seen = vec![];
+        for behavior in [
+            TransactionBehavior::Deferred,
+            TransactionBehavior::Immediate,
+            TransactionBehavior::Exclusive,
+        ] {
+            let tx = db.transaction_with_behavior(behavior)?;
+            writer.execute_batch("INSERT INTO t VALUES (1)")?;
+            seen.push(tx.query_row("SELECT count(*) FROM t", [], |r| r.get::<_, i64>(0))?);
+            tx.commit()?;
+        }
+        // A deferred transaction sees what was committed before it first read
+        // the table, the others don't.
+        assert_eq!(seen, [1, 1, 2]);
+        Ok(())
+    }
+
     #[test]
     fn test_unchecked_nesting() -> Result<()> {
         let db = checked_no_autocommit_memory_handle()?;
@@ -542,8 +755,7 @@ mod test {
             let tx = db.unchecked_transaction()?;
             tx.execute_batch("INSERT INTO foo VALUES(1)")?;
             // Ensure this doesn't interfere with ongoing transaction
-            // let e = tx.unchecked_transaction().unwrap_err();
-            // assert_nested_tx_error(e);
+            assert_eq!(tx.unchecked_transaction().unwrap_err(), Error::NestedTransaction);
             tx.execute_batch("INSERT INTO foo VALUES(1)")?;
             tx.commit()?;
         }
@@ -555,6 +767,53 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_transaction_state() -> Result<()> {
+        let mut db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (x INTEGER PRIMARY KEY)")?;
+        assert_eq!(db.transaction_state()?, TransactionState::None);
+        assert!(db.is_autocommit()?);
+
+        {
+            let tx = db.transaction()?;
+            assert_eq!(tx.transaction_state()?, TransactionState::Active);
+            assert!(!tx.is_autocommit()?);
+            tx.commit()?;
+        }
+        assert_eq!(db.transaction_state()?, TransactionState::None);
+
+        db.execute_batch("BEGIN; INSERT INTO t VALUES (1)")?;
+        assert_eq!(db.transaction_state()?, TransactionState::Active);
+        assert_eq!(db.unchecked_transaction().unwrap_err(), Error::NestedTransaction);
+        assert_eq!(db.transaction().unwrap_err(), Error::NestedTransaction);
+        assert!(db.execute_batch("INSERT INTO t VALUES (1)").is_err());
+        assert_eq!(db.transaction_state()?, TransactionState::Failed);
+        assert!(!db.is_autocommit()?);
+        assert_eq!(db.unchecked_transaction().unwrap_err(), Error::FailedTransaction);
+        assert_eq!(db.transaction().unwrap_err(), Error::FailedTransaction);
+
+        db.execute_batch("ROLLBACK")?;
+        assert_eq!(db.transaction_state()?, TransactionState::None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction_state_untraced() -> Result<()> {
+        static TRACED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        fn tracer(sql: &str) {
+            TRACED.lock().unwrap().push(sql.to_owned());
+        }
+
+        let db = Connection::open_in_memory()?;
+        db.trace(Some(tracer));
+        assert_eq!(db.transaction_state()?, TransactionState::None);
+        db.execute_batch("BEGIN")?;
+        assert!(!db.is_autocommit()?);
+        db.trace(None);
+        assert_eq!(*TRACED.lock().unwrap(), ["BEGIN"]);
+        Ok(())
+    }
+
     #[test]
     fn test_explicit_rollback_commit() -> Result<()> {
         let mut db = checked_no_autocommit_memory_handle()?;
@@ -0,0 +1,257 @@
+use std::{fmt, ops::Index, str::FromStr};
+
+use super::{FromSql, FromSqlError, FromSqlResult, ListElement, ToSql, ToSqlOutput, Type, ValueRef};
+use crate::Result;
+
+/// A DuckDB `BIT` value, a string of bits of any length.
+///
+/// `BIT` columns reach Rust as blobs in DuckDB's own layout: a leading byte
+/// counting the padding bits, followed by the bits packed most significant
+/// first, with the padding bits set to 1 at the start of the first byte.
+/// `BitString` keeps that layout, so reading one is a copy and bit indexing
+/// skips the padding. It is bound as a string of `0`s and `1`s, which DuckDB
+/// casts to `BIT`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitString(Vec<u8>);
+
+impl BitString {
+    /// Returns the number of bits.
+    #[inline]
+    pub fn len(&self) -> usize {
+        (self.0.len() - 1) * 8 - self.padding()
+    }
+
+    /// Returns `true` if there are no bits.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bit at `idx`, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<bool> {
+        if idx >= self.len() {
+            return None;
+        }
+        let bit = self.padding() + idx;
+        Some(self.0[1 + bit / 8] & (0x80 >> (bit % 8)) != 0)
+    }
+
+    /// Returns an iterator over the bits.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len()).map(|idx| self.get(idx).unwrap())
+    }
+
+    #[inline]
+    fn padding(&self) -> usize {
+        usize::from(self.0[0])
+    }
+}
+
+impl Default for BitString {
+    #[inline]
+    fn default() -> Self {
+        BitString(vec![0])
+    }
+}
+
+impl FromIterator<bool> for BitString {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let bits: Vec<bool> = iter.into_iter().collect();
+        let padding = (8 - bits.len() % 8) % 8;
+        let mut data = vec![0; 1 + (padding + bits.len()) / 8];
+        data[0] = padding as u8;
+        for (bit, set) in std::iter::repeat_n(true, padding).chain(bits).enumerate() {
+            if set {
+                data[1 + bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+        BitString(data)
+    }
+}
+
+impl From<&[bool]> for BitString {
+    #[inline]
+    fn from(bits: &[bool]) -> Self {
+        bits.iter().copied().collect()
+    }
+}
+
+impl From<BitString> for Vec<bool> {
+    #[inline]
+    fn from(bits: BitString) -> Self {
+        bits.iter().collect()
+    }
+}
+
+impl Index<usize> for BitString {
+    type Output = bool;
+
+    fn index(&self, idx: usize) -> &bool {
+        match self.get(idx) {
+            Some(true) => &true,
+            Some(false) => &false,
+            None => panic!("index out of bounds: the len is {} but the index is {idx}", self.len()),
+        }
+    }
+}
+
+impl fmt::Display for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s: String = self.iter().map(|set| if set { '1' } else { '0' }).collect();
+        f.pad(&s)
+    }
+}
+
+impl fmt::Debug for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BitString({self})")
+    }
+}
+
+/// Error returned when parsing a [`BitString`] from a string containing
+/// anything but `0`s and `1`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBitStringError(char);
+
+impl fmt::Display for ParseBitStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid character {:?} in bit string", self.0)
+    }
+}
+
+impl std::error::Error for ParseBitStringError {}
+
+impl FromStr for BitString {
+    type Err = ParseBitStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                c => Err(ParseBitStringError(c)),
+            })
+            .collect()
+    }
+}
+
+impl FromSql for BitString {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Blob
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Blob(b) => match b.split_first() {
+                Some((&padding, bits)) if padding < 8 && (padding == 0 || !bits.is_empty()) => {
+                    Ok(BitString(b.to_vec()))
+                }
+                _ => Err(FromSqlError::Other(
+                    format!("invalid BIT representation of {} bytes", b.len()).into(),
+                )),
+            },
+            ValueRef::Text(_) => value
+                .as_str()?
+                .parse()
+                .map_err(|err| FromSqlError::Other(Box::new(err))),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl ToSql for BitString {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl ListElement for BitString {}
+
+#[cfg(test)]
+mod test {
+    use super::BitString;
+    use crate::{types::ValueRef, Connection, Error, Result};
+
+    #[test]
+    fn test_bit_string() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        for s in ["10101", "101010101", "10000001", "1", "0000000000000000"] {
+            let bits: BitString = db.query_row(&format!("SELECT '{s}'::BIT"), [], |r| r.get(0))?;
+            assert_eq!(s, bits.to_string());
+            assert_eq!(s.len(), bits.len());
+            assert_eq!(s.parse::<BitString>().unwrap(), bits);
+        }
+
+        let bits: BitString = db.query_row("SELECT BIT '10101'", [], |r| r.get(0))?;
+        assert!(bits[0] && !bits[1] && bits[4]);
+        assert_eq!(None, bits.get(5));
+        assert_eq!(vec![true, false, true, false, true], Vec::<bool>::from(bits));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bit_vec_bool() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE bloom (b BIT); INSERT INTO bloom VALUES ('1011000000111'), ('0'), (NULL)")?;
+        let mut stmt = db.prepare("SELECT b FROM bloom")?;
+        let rows: Vec<Option<Vec<bool>>> = stmt.query_map([], |r| r.get(0))?.collect::<Result<_>>()?;
+        let bits = "1011000000111".chars().map(|c| c == '1').collect();
+        assert_eq!(vec![Some(bits), Some(vec![false]), None], rows);
+
+        let bits: [bool; 5] = db.query_row("SELECT BIT '10101'", [], |r| r.get(0))?;
+        assert_eq!([true, false, true, false, true], bits);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bit_string_param() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let bits = BitString::from(&[true, false, true, true, false, false, true, true, true][..]);
+        let count: i64 = db.query_row("SELECT bit_count(?::BIT)", [&bits], |r| r.get(0))?;
+        assert_eq!(6, count);
+
+        db.execute_batch("CREATE TABLE bloom (b BIT)")?;
+        db.execute("INSERT INTO bloom VALUES (?)", [&bits])?;
+        {
+            let mut app = db.appender("bloom")?;
+            app.append_row([&bits])?;
+        }
+        let (count, get): (i64, BitString) = db.query_row(
+            "SELECT count(*) FILTER (WHERE b = '101100111'), any_value(b) FROM bloom",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        assert_eq!((2, bits), (count, get));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bit_string_empty() -> Result<()> {
+        let empty = BitString::default();
+        assert!(empty.is_empty());
+        assert_eq!("", empty.to_string());
+        assert_eq!(empty, "".parse().unwrap());
+        assert_eq!(empty, BitString::from(&[][..]));
+
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .query_row("SELECT b::BIT FROM (SELECT ? AS b)", [&empty], |r| {
+                r.get::<_, BitString>(0)
+            })
+            .unwrap_err();
+        assert!(matches!(err, Error::DuckDBFailure(..)), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_bit_string_invalid_blob() {
+        use crate::types::FromSql;
+        for blob in [&[][..], &[8, 0], &[3]] {
+            assert!(BitString::column_result(ValueRef::Blob(blob)).is_err(), "{blob:?}");
+        }
+        assert!(BitString::column_result(ValueRef::Blob(&[0])).unwrap().is_empty());
+    }
+}
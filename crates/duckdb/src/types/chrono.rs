@@ -1,65 +1,86 @@
 //! Convert most of the [Time Strings](http://sqlite.org/lang_datefunc.html) to chrono types.
 
-use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
-use num_integer::Integer;
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
 
 use crate::{
-    types::{FromSql, FromSqlError, FromSqlResult, TimeUnit, ToSql, ToSqlOutput, ValueRef},
-    Result,
+    types::{FromSql, FromSqlError, FromSqlResult, ListElement, TimeUnit, ToSql, ToSqlOutput, Type, ValueRef},
+    Error, Result,
 };
 
-use super::Value;
+use super::{from_sql::interval_months_error, Value};
 
-/// ISO 8601 calendar date without timezone => "YYYY-MM-DD"
+/// ISO 8601 calendar date without timezone => `DATE`
 impl ToSql for NaiveDate {
     #[inline]
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        let date_str = self.format("%F").to_string();
-        Ok(ToSqlOutput::from(date_str))
+        // Every chrono date is within `i32` days of the epoch.
+        let days = self.signed_duration_since(NaiveDate::default()).num_days();
+        Ok(ToSqlOutput::Owned(Value::Date32(days as i32)))
     }
 }
 
+impl ListElement for NaiveDate {}
+
 /// "YYYY-MM-DD" => ISO 8601 calendar date without timezone.
 impl FromSql for NaiveDate {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Date32
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         Ok(NaiveDateTime::column_result(value)?.date())
     }
 }
 
-/// ISO 8601 time without timezone => "HH:MM:SS.SSS"
+/// ISO 8601 time without timezone => `TIME`, truncated to microseconds
 impl ToSql for NaiveTime {
     #[inline]
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        let date_str = self.format("%T%.f").to_string();
-        Ok(ToSqlOutput::from(date_str))
+        let micros = i64::from(self.num_seconds_from_midnight()) * 1_000_000 + i64::from(self.nanosecond() / 1000);
+        Ok(ToSqlOutput::Owned(Value::Time64(TimeUnit::Microsecond, micros)))
     }
 }
 
+impl ListElement for NaiveTime {}
+
 /// "HH:MM"/"HH:MM:SS"/"HH:MM:SS.SSS" => ISO 8601 time without timezone.
 impl FromSql for NaiveTime {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Time64
+    }
+
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         Ok(NaiveDateTime::column_result(value)?.time())
     }
 }
 
-/// ISO 8601 combined date and time without timezone =>
-/// "YYYY-MM-DD HH:MM:SS.SSS"
+/// ISO 8601 combined date and time without timezone => `TIMESTAMP`,
+/// truncated to microseconds
 impl ToSql for NaiveDateTime {
     #[inline]
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        let date_str = self.format("%F %T%.f").to_string();
-        Ok(ToSqlOutput::from(date_str))
+        let micros = self.and_utc().timestamp_micros();
+        Ok(ToSqlOutput::Owned(Value::Timestamp(TimeUnit::Microsecond, micros)))
     }
 }
 
+impl ListElement for NaiveDateTime {}
+
 /// "YYYY-MM-DD HH:MM:SS"/"YYYY-MM-DD HH:MM:SS.SSS" => ISO 8601 combined date
 /// and time without timezone. ("YYYY-MM-DDTHH:MM:SS"/"YYYY-MM-DDTHH:MM:SS.SSS"
 /// also supported)
 impl FromSql for NaiveDateTime {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Timestamp
+    }
+
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         match value {
-            ValueRef::Timestamp(tu, t) => {
+            ValueRef::Timestamp(tu, t) | ValueRef::TimestampTz(tu, t) => {
                 let (secs, nsecs) = match tu {
                     TimeUnit::Second => (t, 0),
                     TimeUnit::Millisecond => (t / 1000, (t % 1000) * 1_000_000),
@@ -76,7 +97,7 @@ impl FromSql for NaiveDateTime {
             .unwrap()
             .naive_utc()),
             ValueRef::Text(s) => {
-                let mut s = std::str::from_utf8(s).unwrap();
+                let mut s = std::str::from_utf8(s).map_err(FromSqlError::InvalidUtf8)?;
                 let format = match s.len() {
                     //23:56:04
                     8 => "%T",
@@ -103,25 +124,76 @@ impl FromSql for NaiveDateTime {
     }
 }
 
-/// Date and time with time zone => UTC RFC3339 timestamp
-/// ("YYYY-MM-DD HH:MM:SS.SSS+00:00").
+/// Date and time with time zone => `TIMESTAMPTZ`, truncated to
+/// microseconds
 impl<Tz: TimeZone> ToSql for DateTime<Tz> {
     #[inline]
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        let date_str = self.with_timezone(&Utc).format("%F %T%.f%:z").to_string();
-        Ok(ToSqlOutput::from(date_str))
+        let micros = self.timestamp_micros();
+        Ok(ToSqlOutput::Owned(Value::TimestampTz(TimeUnit::Microsecond, micros)))
     }
 }
 
-/// RFC3339 ("YYYY-MM-DD HH:MM:SS.SSS[+-]HH:MM") into `DateTime<Utc>`.
+impl<Tz: TimeZone> ListElement for DateTime<Tz> {}
+
+/// TIMESTAMPTZ or RFC3339 ("YYYY-MM-DD HH:MM:SS.SSS[+-]HH:MM") into
+/// `DateTime<Utc>`.
+///
+/// A plain TIMESTAMP doesn't identify an instant, so reading one is an error
+/// unless [`Connection::set_lenient_timestamps`](crate::Connection::set_lenient_timestamps)
+/// was enabled.
 impl FromSql for DateTime<Utc> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TimestampTz
+    }
+
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
-        NaiveDateTime::column_result(value).map(|dt| Utc.from_utc_datetime(&dt))
+        match value {
+            ValueRef::Timestamp(..) => Err(FromSqlError::InvalidType),
+            ValueRef::Text(s) => match parse_with_offset(s) {
+                Some(dt) => Ok(dt.with_timezone(&Utc)),
+                None => NaiveDateTime::column_result(value).map(|dt| Utc.from_utc_datetime(&dt)),
+            },
+            _ => NaiveDateTime::column_result(value).map(|dt| Utc.from_utc_datetime(&dt)),
+        }
     }
 }
 
+/// TIMESTAMPTZ or RFC3339 ("YYYY-MM-DD HH:MM:SS.SSS[+-]HH:MM") into
+/// `DateTime<FixedOffset>`. TIMESTAMPTZ values are always returned in UTC,
+/// strings keep their offset.
+impl FromSql for DateTime<FixedOffset> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TimestampTz
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Text(s) => match parse_with_offset(s) {
+                Some(dt) => Ok(dt),
+                None => DateTime::<Utc>::column_result(value).map(|dt| dt.fixed_offset()),
+            },
+            _ => DateTime::<Utc>::column_result(value).map(|dt| dt.fixed_offset()),
+        }
+    }
+}
+
+fn parse_with_offset(s: &[u8]) -> Option<DateTime<FixedOffset>> {
+    let s = std::str::from_utf8(s).ok()?;
+    DateTime::parse_from_str(s, "%F %T%.f%#z")
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .ok()
+}
+
 /// RFC3339 ("YYYY-MM-DD HH:MM:SS.SSS[+-]HH:MM") into `DateTime<Local>`.
 impl FromSql for DateTime<Local> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TimestampTz
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         let utc_dt = DateTime::<Utc>::column_result(value)?;
@@ -129,59 +201,61 @@ impl FromSql for DateTime<Local> {
     }
 }
 
+/// `INTERVAL` without a month component => `Duration`, counting a day as 24
+/// hours.
 impl FromSql for Duration {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Interval
+    }
+
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         match value {
-            ValueRef::Interval { months, days, nanos } => {
-                let days = days + (months * 30);
-                let (additional_seconds, nanos) = nanos.div_mod_floor(&NANOS_PER_SECOND);
-                let seconds = additional_seconds + (i64::from(days) * 24 * 3600);
-
-                match nanos.try_into() {
-                    Ok(nanos) => {
-                        if let Some(duration) = Duration::new(seconds, nanos) {
-                            Ok(duration)
-                        } else {
-                            Err(FromSqlError::Other("Invalid duration".into()))
-                        }
-                    }
-                    Err(err) => Err(FromSqlError::Other(format!("Invalid duration: {err}").into())),
-                }
-            }
+            ValueRef::Interval { months: 0, days, nanos } => Duration::try_days(days.into())
+                .and_then(|days| days.checked_add(&Duration::nanoseconds(nanos)))
+                .ok_or_else(|| {
+                    let nanos = i128::from(days) * 86_400_000_000_000 + i128::from(nanos);
+                    FromSqlError::out_of_range(format!("{nanos}ns"), "chrono::Duration")
+                }),
+            ValueRef::Interval { months, .. } => Err(interval_months_error(months)),
             _ => Err(FromSqlError::InvalidType),
         }
     }
 }
 
-const DAYS_PER_MONTH: i64 = 30;
-const SECONDS_PER_DAY: i64 = 24 * 3600;
-const NANOS_PER_SECOND: i64 = 1_000_000_000;
-const NANOS_PER_DAY: i64 = SECONDS_PER_DAY * NANOS_PER_SECOND;
-
+/// `Duration` => `INTERVAL` without month or day components, so it adds the
+/// exact elapsed time to a timestamp.
 impl ToSql for Duration {
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        let nanos = self.num_nanoseconds().unwrap();
-        let (days, nanos) = nanos.div_mod_floor(&NANOS_PER_DAY);
-        let (months, days) = days.div_mod_floor(&DAYS_PER_MONTH);
+        let nanos = self
+            .num_nanoseconds()
+            .ok_or_else(|| Error::ToSqlConversionFailure(format!("{self} does not fit in an INTERVAL").into()))?;
         Ok(ToSqlOutput::Owned(Value::Interval {
-            months: months.try_into().unwrap(),
-            days: days.try_into().unwrap(),
+            months: 0,
+            days: 0,
             nanos,
         }))
     }
 }
 
+impl ListElement for Duration {}
+
 #[cfg(test)]
 mod test {
     use crate::{
-        types::{FromSql, ToSql, ToSqlOutput, ValueRef},
+        params,
+        types::{FromSql, TimeUnit, ToSql, ToSqlOutput, Value, ValueRef},
         Connection, Result,
     };
-    use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+    use chrono::{
+        DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc,
+    };
 
     fn checked_memory_handle() -> Result<Connection> {
         let db = Connection::open_in_memory()?;
-        db.execute_batch("CREATE TABLE foo (d DATE, t Text, i INTEGER, f FLOAT, b TIMESTAMP, tt time)")?;
+        db.execute_batch(
+            "CREATE TABLE foo (d DATE, t Text, i INTEGER, f FLOAT, b TIMESTAMP, tt time, tz TIMESTAMPTZ)",
+        )?;
         Ok(db)
     }
 
@@ -234,6 +308,25 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_typed_params() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let date = NaiveDate::from_ymd_opt(-43, 3, 15).unwrap();
+        let at = NaiveDate::from_ymd_opt(12_024, 2, 29)
+            .unwrap()
+            .and_hms_micro_opt(12, 30, 0, 250)
+            .unwrap();
+        let time = NaiveTime::from_hms_micro_opt(23, 59, 59, 999_999).unwrap();
+        let (types, d, t, ts, tz): (String, NaiveDate, NaiveTime, NaiveDateTime, DateTime<Utc>) = db.query_row(
+            "SELECT concat_ws(',', typeof($1), typeof($2), typeof($3)), $1, $2, $3, $4::TIMESTAMPTZ",
+            params![date, time, at, at.and_utc()],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+        )?;
+        assert_eq!("DATE,TIME,TIMESTAMP", types);
+        assert_eq!((date, time, at, at.and_utc()), (d, t, ts, tz));
+        Ok(())
+    }
+
     #[test]
     fn test_date_time_utc() -> Result<()> {
         let db = checked_memory_handle()?;
@@ -242,12 +335,14 @@ mod test {
         let dt = NaiveDateTime::new(date, time);
         let utc = Utc.from_utc_datetime(&dt);
 
-        db.execute("INSERT INTO foo (b) VALUES (?)", [utc])?;
+        db.execute("INSERT INTO foo (b, tz) VALUES (?, ?)", [utc, utc])?;
 
         let s: String = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
         assert_eq!("2016-02-23 23:56:04.789", s);
+        let s: String = db.query_row("SELECT tz FROM foo", [], |r| r.get(0))?;
+        assert_eq!("2016-02-23 23:56:04.789+00:00", s);
 
-        let v1: DateTime<Utc> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        let v1: DateTime<Utc> = db.query_row("SELECT tz FROM foo", [], |r| r.get(0))?;
         assert_eq!(utc, v1);
 
         let v2: DateTime<Utc> = db.query_row("SELECT '2016-02-23 23:56:04.789'", [], |r| r.get(0))?;
@@ -261,6 +356,142 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_timestamp_requires_timezone() -> Result<()> {
+        let db = checked_memory_handle()?;
+        db.execute_batch("INSERT INTO foo (b) VALUES ('2016-02-23 23:56:04.789')")?;
+
+        let err = db
+            .query_row("SELECT b FROM foo", [], |r| r.get::<_, DateTime<Utc>>(0))
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                crate::Error::ColumnConversionFailure {
+                    source: crate::types::FromSqlError::InvalidType,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+
+        let naive: NaiveDateTime = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        let expected = Utc.from_utc_datetime(&naive);
+
+        db.set_lenient_timestamps(true);
+        let utc: DateTime<Utc> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(expected, utc);
+        let fixed: DateTime<FixedOffset> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(expected.fixed_offset(), fixed);
+        let local: DateTime<Local> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(expected, local);
+        let optional: Option<DateTime<Utc>> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(Some(expected), optional);
+
+        // Types without an instant read the TIMESTAMP as they always do.
+        let s: String = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!("2016-02-23 23:56:04.789", s);
+        let v: Value = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(Value::Timestamp(TimeUnit::Microsecond, expected.timestamp_micros()), v);
+        let n: NaiveDateTime = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(naive, n);
+
+        // Strict mode accepts them both.
+        db.set_strict_types(true);
+        let utc: DateTime<Utc> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(expected, utc);
+        let n: NaiveDateTime = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(naive, n);
+        Ok(())
+    }
+
+    #[test]
+    fn test_timestamptz_session_time_zone() -> Result<()> {
+        let db = checked_memory_handle()?;
+        db.set_lenient_timestamps(true);
+        // Across the 2021 US and EU daylight saving transitions.
+        let instants = [
+            Utc.with_ymd_and_hms(2021, 3, 14, 6, 59, 59).unwrap(),
+            Utc.with_ymd_and_hms(2021, 3, 14, 7, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 10, 31, 0, 59, 59).unwrap(),
+            Utc.with_ymd_and_hms(2021, 10, 31, 1, 0, 0).unwrap(),
+        ];
+        for zone in ["America/New_York", "Europe/Berlin", "Asia/Kolkata"] {
+            db.execute_batch(&format!("SET TimeZone = '{zone}'; DELETE FROM foo"))?;
+            for instant in instants {
+                db.execute(
+                    "INSERT INTO foo (b, tz) VALUES (?, ?)",
+                    params![instant.naive_utc(), instant],
+                )?;
+            }
+            let mut stmt = db.prepare("SELECT b, tz, tz::VARCHAR FROM foo ORDER BY tz")?;
+            let rows = stmt
+                .query_map([], |r| {
+                    Ok((
+                        r.get::<_, DateTime<Utc>>(0)?,
+                        r.get::<_, DateTime<Utc>>(1)?,
+                        r.get::<_, DateTime<FixedOffset>>(1)?,
+                        r.get::<_, String>(2)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>>>()?;
+            for ((b, tz, fixed, text), instant) in rows.into_iter().zip(instants) {
+                // The session's zone only changes how DuckDB writes the instant.
+                assert_eq!((instant, instant, instant.fixed_offset()), (b, tz, fixed), "{zone}");
+                assert_eq!(fixed.offset().local_minus_utc(), 0, "{zone}");
+                let written = DateTime::parse_from_str(&text, "%F %T%#z").unwrap();
+                assert_eq!(instant, written, "{zone} {text}");
+                assert_ne!(written.offset().local_minus_utc(), 0, "{zone} {text}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_date_time_fixed_offset() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let offset = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let dt = offset.with_ymd_and_hms(2016, 2, 23, 23, 56, 4).unwrap();
+
+        db.execute("INSERT INTO foo (tz) VALUES (?)", [dt])?;
+
+        // TIMESTAMPTZ values are stored as instants, so they come back in UTC.
+        let v: DateTime<FixedOffset> = db.query_row("SELECT tz FROM foo", [], |r| r.get(0))?;
+        assert_eq!(dt, v);
+        assert_eq!(v.offset(), &FixedOffset::east_opt(0).unwrap());
+        let utc: DateTime<Utc> = db.query_row("SELECT tz FROM foo", [], |r| r.get(0))?;
+        assert_eq!(dt, utc);
+
+        // Text keeps whatever offset it was written with.
+        let text: DateTime<FixedOffset> = db.query_row("SELECT '2016-02-23 23:56:04+05:30'", [], |r| r.get(0))?;
+        assert_eq!(dt, text);
+        assert_eq!(text.offset(), &offset);
+        Ok(())
+    }
+
+    #[test]
+    fn test_timestamptz_dst_boundaries() -> Result<()> {
+        let db = checked_memory_handle()?;
+        // Instants either side of the 2021 US and EU daylight saving transitions.
+        let instants = [
+            Utc.with_ymd_and_hms(2021, 3, 14, 6, 59, 59).unwrap(),
+            Utc.with_ymd_and_hms(2021, 3, 14, 7, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 3, 28, 0, 59, 59).unwrap(),
+            Utc.with_ymd_and_hms(2021, 3, 28, 1, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 10, 31, 0, 59, 59).unwrap(),
+            Utc.with_ymd_and_hms(2021, 10, 31, 1, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 11, 7, 5, 59, 59).unwrap(),
+            Utc.with_ymd_and_hms(2021, 11, 7, 6, 0, 0).unwrap(),
+        ];
+        for instant in instants {
+            let v: DateTime<Utc> = db.query_row("SELECT ?::TIMESTAMPTZ", [instant], |r| r.get(0))?;
+            assert_eq!(instant, v);
+            let v: DateTime<Utc> = db.query_row("SELECT to_timestamp(?)", [instant.timestamp()], |r| r.get(0))?;
+            assert_eq!(instant, v);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_time_delta_roundtrip() {
         roundtrip_type(TimeDelta::new(3600, 0).unwrap());
@@ -279,6 +510,47 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_interval() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let d: TimeDelta = db.query_row("SELECT INTERVAL '90 minutes'", [], |r| r.get(0))?;
+        assert_eq!(TimeDelta::try_minutes(90).unwrap(), d);
+        let d: TimeDelta = db.query_row("SELECT INTERVAL '-1 day -2 hours'", [], |r| r.get(0))?;
+        assert_eq!(-TimeDelta::try_hours(26).unwrap(), d);
+        let d: TimeDelta = db.query_row("SELECT INTERVAL '1 day' - INTERVAL '1 hour'", [], |r| r.get(0))?;
+        assert_eq!(TimeDelta::try_hours(23).unwrap(), d);
+
+        let err = db
+            .query_row("SELECT INTERVAL '1 month 2 days'", [], |r| r.get::<_, TimeDelta>(0))
+            .unwrap_err();
+        assert!(
+            err.to_string().ends_with("INTERVAL of 1 months has no fixed duration"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_interval_param() -> Result<()> {
+        let db = checked_memory_handle()?;
+        db.execute_batch(
+            "CREATE TABLE events (ts TIMESTAMP);
+             INSERT INTO events VALUES (now()::TIMESTAMP - INTERVAL '1 hour'), (now()::TIMESTAMP - INTERVAL '3 hours');",
+        )?;
+        let count: i64 = db.query_row(
+            "SELECT count(*) FROM events WHERE ts > now()::TIMESTAMP - ?",
+            [TimeDelta::try_minutes(90).unwrap()],
+            |r| r.get(0),
+        )?;
+        assert_eq!(1, count);
+
+        let s: String = db.query_row("SELECT (?)::VARCHAR", [-TimeDelta::try_hours(49).unwrap()], |r| {
+            r.get(0)
+        })?;
+        assert_eq!("-49:00:00", s);
+        Ok(())
+    }
+
     fn roundtrip_type<T: FromSql + ToSql + Eq + std::fmt::Debug>(td: T) {
         let sqled = td.to_sql().unwrap();
         let value = match sqled {
@@ -298,12 +570,12 @@ mod test {
         let dt = NaiveDateTime::new(date, time);
         let local = Local.from_local_datetime(&dt).single().unwrap();
 
-        db.execute("INSERT INTO foo (b) VALUES (?)", [local])?;
+        db.execute("INSERT INTO foo (b, tz) VALUES (?, ?)", [local, local])?;
 
         let s: String = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
         assert_eq!(DateTime::<Utc>::from(local).format("%F %T%.f").to_string(), s);
 
-        let v: DateTime<Local> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        let v: DateTime<Local> = db.query_row("SELECT tz FROM foo", [], |r| r.get(0))?;
         assert_eq!(local, v);
         Ok(())
     }
@@ -326,7 +598,7 @@ mod test {
     fn test_naive_date_time_param() -> Result<()> {
         let db = checked_memory_handle()?;
         let result: Result<bool> = db.query_row(
-            "SELECT 1 WHERE ? BETWEEN (now()::timestamp - INTERVAL '1 minute') AND (now()::timestamp + INTERVAL '1 minute')",
+            "SELECT true WHERE ? BETWEEN (now()::timestamp - INTERVAL '1 minute') AND (now()::timestamp + INTERVAL '1 minute')",
             [Utc::now().naive_utc()],
             |r| r.get(0),
         );
@@ -339,7 +611,7 @@ mod test {
         let db = checked_memory_handle()?;
         // TODO(wangfenjin): why need 2 params?
         let result: Result<bool> = db.query_row(
-            "SELECT 1 WHERE ? BETWEEN (now()::timestamp - INTERVAL '1 minute') AND (now()::timestamp + INTERVAL '1 minute')",
+            "SELECT true WHERE ? BETWEEN (now()::timestamp - INTERVAL '1 minute') AND (now()::timestamp + INTERVAL '1 minute')",
             [Utc::now()],
             |r| r.get(0),
         );
@@ -0,0 +1,160 @@
+//! Map a Rust enum onto the entries of a DuckDB `ENUM`.
+
+/// Implements [`FromSql`](crate::types::FromSql) and [`ToSql`](crate::ToSql)
+/// for a field-less Rust enum, mapping each variant to a DuckDB `ENUM` entry.
+///
+/// Values are bound as text, which DuckDB casts to the `ENUM` when inserting
+/// or appending. Comparisons against an `ENUM` column need an explicit cast
+/// (`?::my_enum`) to compare by entry order rather than as text. Reading an
+/// entry that has no variant fails with an error listing the variants.
+///
+/// ```rust
+/// # use duckdb::{enum_mapping, Connection, Result};
+/// #[derive(Debug, PartialEq)]
+/// enum Level {
+///     Debug,
+///     Info,
+///     Warn,
+///     Error,
+/// }
+///
+/// enum_mapping!(Level {
+///     Debug => "debug",
+///     Info => "info",
+///     Warn => "warn",
+///     Error => "error",
+/// });
+///
+/// fn highest(conn: &Connection) -> Result<Level> {
+///     conn.execute_batch(
+///         "CREATE TYPE level AS ENUM ('debug', 'info', 'warn', 'error');
+///          CREATE TABLE logs (level level);",
+///     )?;
+///     conn.execute("INSERT INTO logs VALUES (?), (?)", [Level::Info, Level::Warn])?;
+///     conn.query_row("SELECT max(level) FROM logs", [], |row| row.get(0))
+/// }
+/// # assert_eq!(Level::Warn, highest(&Connection::open_in_memory().unwrap()).unwrap());
+/// ```
+#[macro_export]
+macro_rules! enum_mapping {
+    ($ty:ident { $($variant:ident => $entry:literal),+ $(,)? }) => {
+        impl $crate::types::FromSql for $ty {
+            #[inline]
+            fn accepts(ty: &$crate::types::Type) -> bool {
+                *ty == $crate::types::Type::Enum
+            }
+
+            fn column_result(value: $crate::types::ValueRef<'_>) -> $crate::types::FromSqlResult<Self> {
+                match value.as_str()? {
+                    $($entry => Ok($ty::$variant),)+
+                    entry => Err($crate::types::FromSqlError::Other(
+                        format!(
+                            "unknown {} entry {:?}, expected one of: {}",
+                            stringify!($ty),
+                            entry,
+                            [$(concat!(stringify!($variant), " (", stringify!($entry), ")")),+].join(", "),
+                        )
+                        .into(),
+                    )),
+                }
+            }
+        }
+
+        impl $crate::ToSql for $ty {
+            #[inline]
+            fn to_sql(&self) -> $crate::Result<$crate::types::ToSqlOutput<'_>> {
+                Ok($crate::types::ToSqlOutput::from(match self {
+                    $($ty::$variant => $entry,)+
+                }))
+            }
+        }
+
+        impl $crate::types::ListElement for $ty {}
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Connection, Error, Result};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Level {
+        Debug,
+        Info,
+        Warn,
+        Error,
+    }
+
+    enum_mapping!(Level {
+        Debug => "debug",
+        Info => "info",
+        Warn => "warning",
+        Error => "error",
+    });
+
+    const LEVELS: [Level; 4] = [Level::Debug, Level::Info, Level::Warn, Level::Error];
+
+    fn checked_memory_handle() -> Result<Connection> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TYPE level AS ENUM ('debug', 'info', 'warning', 'error', 'fatal');
+             CREATE TABLE logs (id INTEGER, level level);",
+        )?;
+        Ok(db)
+    }
+
+    #[test]
+    fn test_enum_mapping() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let mut stmt = db.prepare("INSERT INTO logs VALUES (?, ?)")?;
+        for (id, level) in LEVELS.iter().enumerate() {
+            stmt.execute(crate::params![id as i32, level])?;
+        }
+
+        let mut stmt = db.prepare("SELECT level FROM logs ORDER BY id")?;
+        let levels = stmt
+            .query_map([], |r| r.get::<_, Level>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(LEVELS.to_vec(), levels);
+
+        let count: i64 = db.query_row(
+            "SELECT count(*) FROM logs WHERE level >= ?::level",
+            [Level::Warn],
+            |r| r.get(0),
+        )?;
+        assert_eq!(2, count);
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum_mapping_appender() -> Result<()> {
+        let db = checked_memory_handle()?;
+        {
+            let mut app = db.appender("logs")?;
+            for (id, level) in LEVELS.iter().enumerate() {
+                app.append_row(crate::params![id as i32, level])?;
+            }
+        }
+        let levels: Vec<String> =
+            db.query_row("SELECT list(level::VARCHAR ORDER BY id) FROM logs", [], |r| r.get(0))?;
+        assert_eq!(vec!["debug", "info", "warning", "error"], levels);
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum_mapping_unknown_entry() -> Result<()> {
+        let db = checked_memory_handle()?;
+        db.execute_batch("INSERT INTO logs VALUES (0, 'fatal')")?;
+        let err = db
+            .query_row("SELECT level FROM logs", [], |r| r.get::<_, Level>(0))
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::ColumnConversionFailure { index: 0, .. }),
+            "{err:?}"
+        );
+        assert!(err.to_string().ends_with(
+            r#"unknown Level entry "fatal", expected one of: Debug ("debug"), Info ("info"), Warn ("warning"), Error ("error")"#
+        ));
+        Ok(())
+    }
+}
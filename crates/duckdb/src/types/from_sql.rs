@@ -1,7 +1,19 @@
 extern crate cast;
 
-use super::{TimeUnit, Value, ValueRef};
-use std::{error::Error, fmt};
+use super::{value::decimal_text, BitString, ListType, TimeUnit, Type, Value, ValueRef};
+use crate::Row;
+use arrow::array::ArrayRef;
+use rust_decimal::Decimal;
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt,
+    hash::{BuildHasher, Hash},
+    ops::Range,
+    str::{FromStr, Utf8Error},
+};
 
 /// Enum listing possible errors from [`FromSql`] trait.
 #[derive(Debug)]
@@ -13,24 +25,52 @@ pub enum FromSqlError {
 
     /// Error when the value returned by DuckDB cannot be stored into the
     /// requested type.
-    OutOfRange(i128),
+    OutOfRange {
+        /// The value, written out in full.
+        value: String,
+        /// The name of the type it doesn't fit in.
+        target: &'static str,
+    },
 
     /// `feature = "uuid"` Error returned when reading a `uuid` from a blob with
     /// a size other than 16. Only available when the `uuid` feature is enabled.
     #[cfg(feature = "uuid")]
     InvalidUuidSize(usize),
 
+    /// Error when a `TEXT` value read as a string is not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+
+    /// Error returned when reading a `char` from a string that doesn't hold
+    /// exactly one Unicode scalar value, with the number it does hold.
+    InvalidCharLength(usize),
+
     /// An error case available for implementors of the [`FromSql`] trait.
     Other(Box<dyn Error + Send + Sync + 'static>),
 }
 
+impl FromSqlError {
+    /// An [`OutOfRange`](FromSqlError::OutOfRange) error for `value`, which
+    /// doesn't fit in the type named `target`.
+    pub(crate) fn out_of_range(value: impl fmt::Display, target: &'static str) -> FromSqlError {
+        FromSqlError::OutOfRange {
+            value: value.to_string(),
+            target,
+        }
+    }
+}
+
 impl PartialEq for FromSqlError {
     fn eq(&self, other: &FromSqlError) -> bool {
         match (self, other) {
             (FromSqlError::InvalidType, FromSqlError::InvalidType) => true,
-            (FromSqlError::OutOfRange(n1), FromSqlError::OutOfRange(n2)) => n1 == n2,
+            (
+                FromSqlError::OutOfRange { value: v1, target: t1 },
+                FromSqlError::OutOfRange { value: v2, target: t2 },
+            ) => v1 == v2 && t1 == t2,
             #[cfg(feature = "uuid")]
             (FromSqlError::InvalidUuidSize(s1), FromSqlError::InvalidUuidSize(s2)) => s1 == s2,
+            (FromSqlError::InvalidUtf8(e1), FromSqlError::InvalidUtf8(e2)) => e1 == e2,
+            (FromSqlError::InvalidCharLength(n1), FromSqlError::InvalidCharLength(n2)) => n1 == n2,
             (..) => false,
         }
     }
@@ -40,11 +80,15 @@ impl fmt::Display for FromSqlError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             FromSqlError::InvalidType => write!(f, "Invalid type"),
-            FromSqlError::OutOfRange(i) => write!(f, "Value {i} out of range"),
+            FromSqlError::OutOfRange { ref value, target } => write!(f, "Value {value} out of range for {target}"),
             #[cfg(feature = "uuid")]
             FromSqlError::InvalidUuidSize(s) => {
                 write!(f, "Cannot read UUID value out of {s} byte blob")
             }
+            FromSqlError::InvalidUtf8(ref err) => err.fmt(f),
+            FromSqlError::InvalidCharLength(n) => {
+                write!(f, "Cannot read char value out of {n} character string")
+            }
             FromSqlError::Other(ref err) => err.fmt(f),
         }
     }
@@ -52,10 +96,10 @@ impl fmt::Display for FromSqlError {
 
 impl Error for FromSqlError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        if let FromSqlError::Other(ref err) = self {
-            Some(&**err)
-        } else {
-            None
+        match self {
+            FromSqlError::InvalidUtf8(ref err) => Some(err),
+            FromSqlError::Other(ref err) => Some(&**err),
+            _ => None,
         }
     }
 }
@@ -67,53 +111,126 @@ pub type FromSqlResult<T> = Result<T, FromSqlError>;
 pub trait FromSql: Sized {
     /// Converts DuckDB value into Rust value.
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self>;
+
+    /// Returns whether columns of type `ty` belong to the family this type
+    /// naturally reads from, such as any integer type for `i64`. Only
+    /// consulted in strict mode, see
+    /// [`Connection::set_strict_types`](crate::Connection::set_strict_types);
+    /// the default accepts every type.
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        let _ = ty;
+        true
+    }
+}
+
+/// A trait for types that can be created from a DuckDB value borrowed from
+/// the current row, such as `Cow<'a, str>`, which [`Row::get`](crate::Row::get)
+/// reads through.
+///
+/// It is implemented for every [`FromSql`] type, which owns its data; only
+/// implement it directly for types that borrow.
+pub trait FromSqlRef<'a>: Sized {
+    /// Converts a DuckDB value, borrowed for `'a`, into a Rust value.
+    fn column_result_ref(value: ValueRef<'a>) -> FromSqlResult<Self>;
+
+    /// See [`FromSql::accepts`].
+    #[inline]
+    fn accepts_ref(ty: &Type) -> bool {
+        let _ = ty;
+        true
+    }
+}
+
+impl<T: FromSql> FromSqlRef<'_> for T {
+    #[inline]
+    fn column_result_ref(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        T::column_result(value)
+    }
+
+    #[inline]
+    fn accepts_ref(ty: &Type) -> bool {
+        T::accepts(ty)
+    }
+}
+
+const INTEGER_TYPES: &[Type] = &[
+    Type::TinyInt,
+    Type::SmallInt,
+    Type::Int,
+    Type::BigInt,
+    Type::HugeInt,
+    Type::UTinyInt,
+    Type::USmallInt,
+    Type::UInt,
+    Type::UBigInt,
+    Type::UHugeInt,
+];
+
+const FLOAT_TYPES: &[Type] = &[Type::Float, Type::Double];
+
+// Casts a number read from DuckDB into `$t`, returning
+// `FromSqlError::OutOfRange` instead of panicking when it doesn't fit.
+macro_rules! checked_cast(
+    ($t:ident, $from:ident, $i:expr) => {{
+        let v = <$t as cast::From<$from>>::cast($i);
+        if v.is_ok() {
+            Ok(v.unwrap())
+        } else {
+            Err(FromSqlError::out_of_range($i, stringify!($t)))
+        }
+    }}
+);
+
+// The integral part of a decimal `value` at `scale`.
+fn decimal_integer_part(value: i128, scale: u8) -> i128 {
+    10i128.checked_pow(scale.into()).map_or(0, |factor| value / factor)
 }
 
 macro_rules! from_sql_integral(
-    ($t:ident) => (
+    ($t:ident, $family:ident) => (
         impl FromSql for $t {
+            #[inline]
+            fn accepts(ty: &Type) -> bool {
+                $family.contains(ty)
+            }
+
             #[inline]
             fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
                 match value {
-                    // TODO: Update all cast operation same to HugeInt
-                    ValueRef::TinyInt(i) => Ok(<$t as cast::From<i8>>::cast(i).unwrap()),
-                    ValueRef::SmallInt(i) => Ok(<$t as cast::From<i16>>::cast(i).unwrap()),
-                    ValueRef::Int(i) => Ok(<$t as cast::From<i32>>::cast(i).unwrap()),
-                    ValueRef::BigInt(i) => Ok(<$t as cast::From<i64>>::cast(i).unwrap()),
-                    ValueRef::HugeInt(i) => {
-                        let v = <$t as cast::From<i128>>::cast(i);
-                        if v.is_ok() {
-                            Ok(v.unwrap())
-                        } else {
-                            Err(FromSqlError::OutOfRange(i))
-                        }
-                    },
+                    ValueRef::TinyInt(i) => checked_cast!($t, i8, i),
+                    ValueRef::SmallInt(i) => checked_cast!($t, i16, i),
+                    ValueRef::Int(i) => checked_cast!($t, i32, i),
+                    ValueRef::BigInt(i) => checked_cast!($t, i64, i),
+                    ValueRef::HugeInt(i) => checked_cast!($t, i128, i),
 
-                    ValueRef::UTinyInt(i) => Ok(<$t as cast::From<u8>>::cast(i).unwrap()),
-                    ValueRef::USmallInt(i) => Ok(<$t as cast::From<u16>>::cast(i).unwrap()),
-                    ValueRef::UInt(i) => Ok(<$t as cast::From<u32>>::cast(i).unwrap()),
-                    ValueRef::UBigInt(i) => Ok(<$t as cast::From<u64>>::cast(i).unwrap()),
+                    ValueRef::UTinyInt(i) => checked_cast!($t, u8, i),
+                    ValueRef::USmallInt(i) => checked_cast!($t, u16, i),
+                    ValueRef::UInt(i) => checked_cast!($t, u32, i),
+                    ValueRef::UBigInt(i) => checked_cast!($t, u64, i),
+                    ValueRef::UHugeInt(i) => checked_cast!($t, u128, i),
 
-                    ValueRef::Float(i) => Ok(<$t as cast::From<f32>>::cast(i).unwrap()),
-                    ValueRef::Double(i) => Ok(<$t as cast::From<f64>>::cast(i).unwrap()),
+                    ValueRef::Float(i) => checked_cast!($t, f32, i),
+                    ValueRef::Double(i) => checked_cast!($t, f64, i),
 
                     // TODO: more efficient way?
-                    ValueRef::Decimal(i) => Ok(i.to_string().parse::<$t>().unwrap()),
+                    ValueRef::Decimal { value, scale, .. } => match decimal_text(value, scale).parse::<$t>() {
+                        Ok(v) => Ok(v),
+                        // Fractions are truncated, as for floats.
+                        Err(_) => checked_cast!($t, i128, decimal_integer_part(value, scale)),
+                    },
 
-                    ValueRef::Timestamp(_, i) => Ok(<$t as cast::From<i64>>::cast(i).unwrap()),
-                    ValueRef::Date32(i) => Ok(<$t as cast::From<i32>>::cast(i).unwrap()),
-                    ValueRef::Time64(TimeUnit::Microsecond, i) => Ok(<$t as cast::From<i64>>::cast(i).unwrap()),
+                    ValueRef::Timestamp(_, i) | ValueRef::TimestampTz(_, i) => checked_cast!($t, i64, i),
+                    ValueRef::Date32(i) => checked_cast!($t, i32, i),
+                    ValueRef::Time64(TimeUnit::Microsecond, i) => checked_cast!($t, i64, i),
                     ValueRef::Text(_) => {
-                        let v = value.as_str()?.parse::<$t>();
-                        match v {
+                        let s = value.as_str()?;
+                        match s.parse::<$t>() {
                             Ok(i) => Ok(i),
-                            Err(_) => {
-                                let v = value.as_str()?.parse::<i128>();
-                                match v {
-                                    Ok(i) => Err(FromSqlError::OutOfRange(i)),
-                                    _ => Err(FromSqlError::InvalidType),
-                                }
-                            },
+                            Err(_) if s.parse::<i128>().is_ok() || s.parse::<u128>().is_ok() => {
+                                Err(FromSqlError::out_of_range(s, stringify!($t)))
+                            }
+                            Err(_) => Err(FromSqlError::InvalidType),
                         }
                     }
                     _ => Err(FromSqlError::InvalidType),
@@ -159,35 +276,93 @@ unwrap_integral!(u8);
 unwrap_integral!(u16);
 unwrap_integral!(u32);
 unwrap_integral!(u64);
+unwrap_integral!(u128);
 unwrap_integral!(usize);
 unwrap_integral!(f32);
 unwrap_integral!(f64);
 
-from_sql_integral!(i8);
-from_sql_integral!(i16);
-from_sql_integral!(i32);
-from_sql_integral!(i64);
-from_sql_integral!(i128);
-from_sql_integral!(isize);
-from_sql_integral!(u8);
-from_sql_integral!(u16);
-from_sql_integral!(u32);
-from_sql_integral!(u64);
-from_sql_integral!(usize);
-from_sql_integral!(f32);
-from_sql_integral!(f64);
+from_sql_integral!(i8, INTEGER_TYPES);
+from_sql_integral!(i16, INTEGER_TYPES);
+from_sql_integral!(i32, INTEGER_TYPES);
+from_sql_integral!(i64, INTEGER_TYPES);
+from_sql_integral!(i128, INTEGER_TYPES);
+from_sql_integral!(isize, INTEGER_TYPES);
+from_sql_integral!(u8, INTEGER_TYPES);
+from_sql_integral!(u16, INTEGER_TYPES);
+from_sql_integral!(u32, INTEGER_TYPES);
+from_sql_integral!(u64, INTEGER_TYPES);
+from_sql_integral!(u128, INTEGER_TYPES);
+from_sql_integral!(usize, INTEGER_TYPES);
+from_sql_integral!(f32, FLOAT_TYPES);
+from_sql_integral!(f64, FLOAT_TYPES);
+
+// Checked conversions out of an owned `Value`, going through `FromSql` so
+// that narrowing fails with `FromSqlError::OutOfRange` exactly as
+// `Row::get` does.
+macro_rules! try_from_value(
+    ($t:ident) => (
+        impl TryFrom<&Value> for $t {
+            type Error = FromSqlError;
+
+            #[inline]
+            fn try_from(value: &Value) -> FromSqlResult<Self> {
+                match value {
+                    Value::List(..)
+                    | Value::Array(..)
+                    | Value::Struct(..)
+                    | Value::Map(..)
+                    | Value::Union { .. }
+                    | Value::Enum(..) => Err(FromSqlError::InvalidType),
+                    _ => <$t as FromSql>::column_result(ValueRef::from(value)),
+                }
+            }
+        }
+
+        impl TryFrom<Value> for $t {
+            type Error = FromSqlError;
+
+            #[inline]
+            fn try_from(value: Value) -> FromSqlResult<Self> {
+                <$t>::try_from(&value)
+            }
+        }
+    )
+);
+
+try_from_value!(i8);
+try_from_value!(i16);
+try_from_value!(i32);
+try_from_value!(i64);
+try_from_value!(i128);
+try_from_value!(isize);
+try_from_value!(u8);
+try_from_value!(u16);
+try_from_value!(u32);
+try_from_value!(u64);
+try_from_value!(u128);
+try_from_value!(usize);
 
 impl FromSql for bool {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Boolean
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         match value {
             ValueRef::Boolean(b) => Ok(b),
-            _ => i8::column_result(value).map(|i| i != 0),
+            _ => Err(FromSqlError::InvalidType),
         }
     }
 }
 
 impl FromSql for String {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Text | Type::Enum)
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         match value {
@@ -199,12 +374,38 @@ impl FromSql for String {
             ValueRef::Timestamp(..) => Ok(chrono::NaiveDateTime::column_result(value)?
                 .format("%F %T%.f")
                 .to_string()),
+            #[cfg(feature = "chrono")]
+            ValueRef::TimestampTz(..) => Ok(chrono::DateTime::<chrono::Utc>::column_result(value)?
+                .format("%F %T%.f%:z")
+                .to_string()),
             _ => value.as_str().map(ToString::to_string),
         }
     }
 }
 
+impl FromSql for char {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Text | Type::Enum)
+    }
+
+    #[inline]
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let s = value.as_str()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(FromSqlError::InvalidCharLength(s.chars().count())),
+        }
+    }
+}
+
 impl FromSql for Box<str> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Text | Type::Enum)
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         value.as_str().map(Into::into)
@@ -212,6 +413,11 @@ impl FromSql for Box<str> {
 }
 
 impl FromSql for std::rc::Rc<str> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Text | Type::Enum)
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         value.as_str().map(Into::into)
@@ -219,21 +425,224 @@ impl FromSql for std::rc::Rc<str> {
 }
 
 impl FromSql for std::sync::Arc<str> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Text | Type::Enum)
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         value.as_str().map(Into::into)
     }
 }
 
-impl FromSql for Vec<u8> {
+/// Borrows `VARCHAR` and `ENUM` values from the result chunk, and formats the
+/// types [`String`] reads, like timestamps, into an owned string.
+impl<'a> FromSqlRef<'a> for Cow<'a, str> {
     #[inline]
+    fn column_result_ref(value: ValueRef<'a>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Text(_) | ValueRef::Enum(..) => value.as_str().map(Cow::Borrowed),
+            _ => String::column_result(value).map(Cow::Owned),
+        }
+    }
+
+    #[inline]
+    fn accepts_ref(ty: &Type) -> bool {
+        String::accepts(ty)
+    }
+}
+
+/// See the `Cow<str>` impl; a generic impl for `Option<T>` would overlap the
+/// one through [`FromSql`].
+impl<'a> FromSqlRef<'a> for Option<Cow<'a, str>> {
+    #[inline]
+    fn column_result_ref(value: ValueRef<'a>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Null => Ok(None),
+            _ => Cow::column_result_ref(value).map(Some),
+        }
+    }
+
+    #[inline]
+    fn accepts_ref(ty: &Type) -> bool {
+        String::accepts(ty)
+    }
+}
+
+/// `LIST` and `ARRAY` values are converted element by element, so `NULL`
+/// elements need `Vec<Option<T>>`. `Vec<u8>` also reads `BLOB` columns, as
+/// their bytes, and `Vec<bool>` reads `BIT` columns, which reach Rust as
+/// blobs, as their bits.
+impl<T: FromSql + 'static> FromSql for Vec<T> {
+    fn accepts(ty: &Type) -> bool {
+        match ty {
+            Type::List(item) | Type::Array(item, _) => T::accepts(item),
+            Type::Blob => reads_bytes::<T>(),
+            _ => false,
+        }
+    }
+
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
-        value.as_blob().map(|b| b.to_vec())
+        match value {
+            ValueRef::List(ListType::Regular(items), idx) => {
+                let offsets = items.offsets();
+                from_list(offsets[idx] as usize..offsets[idx + 1] as usize, idx, items.values())
+            }
+            ValueRef::List(ListType::Large(items), idx) => {
+                let offsets = items.offsets();
+                from_list(offsets[idx] as usize..offsets[idx + 1] as usize, idx, items.values())
+            }
+            ValueRef::Array(items, idx) => {
+                let value_length = items.value_length() as usize;
+                from_list(idx * value_length..(idx + 1) * value_length, idx, items.values())
+            }
+            ValueRef::Blob(_) | ValueRef::Text(_) => bytes_result(value),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// Whether `Vec<T>` reads blobs: only `Vec<u8>` and `Vec<bool>` do.
+fn reads_bytes<T: 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<u8>() || TypeId::of::<T>() == TypeId::of::<bool>()
+}
+
+/// Reads a `BLOB` or `TEXT` value as the bytes of a `Vec<u8>` or the bits
+/// of a `Vec<bool>`.
+fn bytes_result<T: 'static>(value: ValueRef<'_>) -> FromSqlResult<Vec<T>> {
+    let items: Box<dyn Any> = if TypeId::of::<T>() == TypeId::of::<u8>() {
+        Box::new(value.as_blob()?.to_vec())
+    } else if TypeId::of::<T>() == TypeId::of::<bool>() {
+        Box::new(Vec::<bool>::from(BitString::column_result(value)?))
+    } else {
+        return Err(FromSqlError::InvalidType);
+    };
+    Ok(*items.downcast().expect("checked the element type"))
+}
+
+/// Reads like [`Vec<T>`], failing if the number of elements isn't `N`.
+impl<T: FromSql + 'static, const N: usize> FromSql for [T; N] {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        Vec::<T>::accepts(ty)
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Vec::<T>::column_result(value)?
+            .try_into()
+            .map_err(|items: Vec<T>| FromSqlError::Other(format!("expected {N} elements, got {}", items.len()).into()))
+    }
+}
+
+/// `MAP` values are converted entry by entry. DuckDB rejects duplicate keys
+/// when building a map, but one read into a map whose keys compare equal
+/// after conversion is an error rather than silently dropping entries.
+impl<K: FromSql + Eq + Hash, V: FromSql, S: BuildHasher + Default> FromSql for HashMap<K, V, S> {
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Map(k, v) if K::accepts(k) && V::accepts(v))
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let mut map = HashMap::default();
+        for entry in map_entries(value)? {
+            let (k, v) = entry?;
+            if map.insert(k, v).is_some() {
+                return Err(FromSqlError::Other("duplicate key in MAP".into()));
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// See the [`HashMap`] impl.
+impl<K: FromSql + Ord, V: FromSql> FromSql for BTreeMap<K, V> {
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Map(k, v) if K::accepts(k) && V::accepts(v))
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let mut map = BTreeMap::new();
+        for entry in map_entries(value)? {
+            let (k, v) = entry?;
+            if map.insert(k, v).is_some() {
+                return Err(FromSqlError::Other("duplicate key in MAP".into()));
+            }
+        }
+        Ok(map)
+    }
+}
+
+fn map_entries<'a, K: FromSql + 'a, V: FromSql + 'a>(
+    value: ValueRef<'a>,
+) -> FromSqlResult<impl Iterator<Item = FromSqlResult<(K, V)>> + 'a> {
+    match value {
+        ValueRef::Map(arr, idx) => {
+            let offsets = arr.offsets();
+            let (keys, values) = (arr.keys(), arr.values());
+            Ok((offsets[idx] as usize..offsets[idx + 1] as usize).map(move |row| {
+                Ok((
+                    K::column_result(Row::value_ref_internal(row, idx, keys))?,
+                    V::column_result(Row::value_ref_internal(row, idx, values))?,
+                ))
+            }))
+        }
+        _ => Err(FromSqlError::InvalidType),
+    }
+}
+
+fn from_list<T: FromSql>(rows: Range<usize>, idx: usize, values: &ArrayRef) -> FromSqlResult<Vec<T>> {
+    rows.map(|row| T::column_result(Row::value_ref_internal(row, idx, values)))
+        .collect()
+}
+
+impl FromSql for Decimal {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Decimal
+    }
+
+    #[inline]
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Decimal { value, scale, .. } => {
+                // rust_decimal only has a 96 bit mantissa, so drop trailing
+                // zeros of wide decimals until the value fits.
+                let (mut mantissa, mut exp) = (value, u32::from(scale));
+                while Decimal::try_from_i128_with_scale(mantissa, exp).is_err() && exp > 0 && mantissa % 10 == 0 {
+                    mantissa /= 10;
+                    exp -= 1;
+                }
+                Decimal::try_from_i128_with_scale(mantissa, exp).map_err(|_| {
+                    let text = decimal_text(value, scale);
+                    FromSqlError::Other(format!("DECIMAL value {text} doesn't fit into rust_decimal::Decimal").into())
+                })
+            }
+            ValueRef::TinyInt(i) => Ok(i.into()),
+            ValueRef::SmallInt(i) => Ok(i.into()),
+            ValueRef::Int(i) => Ok(i.into()),
+            ValueRef::BigInt(i) => Ok(i.into()),
+            ValueRef::HugeInt(i) => Decimal::try_from_i128_with_scale(i, 0)
+                .map_err(|_| FromSqlError::out_of_range(i, "rust_decimal::Decimal")),
+            ValueRef::UTinyInt(i) => Ok(i.into()),
+            ValueRef::USmallInt(i) => Ok(i.into()),
+            ValueRef::UInt(i) => Ok(i.into()),
+            ValueRef::UBigInt(i) => Ok(i.into()),
+            ValueRef::Float(f) => Decimal::try_from(f).map_err(|e| FromSqlError::Other(Box::new(e))),
+            ValueRef::Double(f) => Decimal::try_from(f).map_err(|e| FromSqlError::Other(Box::new(e))),
+            ValueRef::Text(_) => Decimal::from_str(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e))),
+            _ => Err(FromSqlError::InvalidType),
+        }
     }
 }
 
 #[cfg(feature = "uuid")]
 impl FromSql for uuid::Uuid {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Text
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         match value {
@@ -252,6 +661,11 @@ impl FromSql for uuid::Uuid {
 }
 
 impl<T: FromSql> FromSql for Option<T> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        T::accepts(ty)
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         match value {
@@ -261,6 +675,71 @@ impl<T: FromSql> FromSql for Option<T> {
     }
 }
 
+/// `INTERVAL` without a month component and not negative => `Duration`,
+/// counting a day as 24 hours.
+impl FromSql for std::time::Duration {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Interval
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Interval { months: 0, days, nanos } => {
+                let nanos = i128::from(days) * 86_400_000_000_000 + i128::from(nanos);
+                let out_of_range = || FromSqlError::out_of_range(format!("{nanos}ns"), "std::time::Duration");
+                let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| out_of_range())?;
+                if nanos < 0 {
+                    return Err(out_of_range());
+                }
+                Ok(std::time::Duration::new(secs, (nanos % 1_000_000_000) as u32))
+            }
+            ValueRef::Interval { months, .. } => Err(interval_months_error(months)),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// `TIMESTAMP` or `TIMESTAMPTZ` => `SystemTime`, which may be before the Unix
+/// epoch.
+impl FromSql for std::time::SystemTime {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, Type::Timestamp | Type::TimestampTz)
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Timestamp(unit, t) | ValueRef::TimestampTz(unit, t) => {
+                let nanos = i128::from(t)
+                    * match unit {
+                        TimeUnit::Second => 1_000_000_000,
+                        TimeUnit::Millisecond => 1_000_000,
+                        TimeUnit::Microsecond => 1_000,
+                        TimeUnit::Nanosecond => 1,
+                    };
+                let offset = std::time::Duration::new(
+                    (nanos.unsigned_abs() / 1_000_000_000) as u64,
+                    (nanos.unsigned_abs() % 1_000_000_000) as u32,
+                );
+                let time = if nanos < 0 {
+                    std::time::UNIX_EPOCH.checked_sub(offset)
+                } else {
+                    std::time::UNIX_EPOCH.checked_add(offset)
+                };
+                time.ok_or_else(|| FromSqlError::out_of_range(format!("{nanos}ns"), "std::time::SystemTime"))
+            }
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// Months have no fixed length, so converting them to a duration would be
+/// lossy.
+pub(crate) fn interval_months_error(months: i32) -> FromSqlError {
+    FromSqlError::Other(format!("INTERVAL of {months} months has no fixed duration").into())
+}
+
 impl FromSql for Value {
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
@@ -270,8 +749,23 @@ impl FromSql for Value {
 
 #[cfg(test)]
 mod test {
-    use super::FromSql;
-    use crate::{Connection, Error, Result};
+    use super::{FromSql, FromSqlError, FromSqlResult};
+    use crate::{
+        params,
+        types::{Type, Value, ValueRef},
+        Connection, Error, ErrorKind, Result,
+    };
+
+    // The value of the error when a column is read into a type it doesn't fit.
+    fn out_of_range_value(err: &Error) -> Option<i128> {
+        match err {
+            Error::ColumnConversionFailure {
+                source: FromSqlError::OutOfRange { value, .. },
+                ..
+            } => value.trim_end_matches("ns").parse().ok(),
+            _ => None,
+        }
+    }
 
     #[test]
     fn test_timestamp_raw() -> Result<()> {
@@ -318,6 +812,376 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_char() -> Result<()> {
+        use super::FromSqlError;
+        use crate::types::Type;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE flags (id INTEGER, flag VARCHAR)")?;
+        db.execute("INSERT INTO flags VALUES (1, ?)", ['Y'])?;
+        db.execute("INSERT INTO flags VALUES (2, ?)", ['🦆'])?;
+        {
+            let mut app = db.appender("flags")?;
+            app.append_row(params![3, 'é'])?;
+        }
+
+        for (id, c) in [(1, 'Y'), (2, '🦆'), (3, 'é')] {
+            let (flag, len): (char, i64) =
+                db.query_row("SELECT flag, length(flag) FROM flags WHERE id = ?", [id], |r| {
+                    Ok((r.get(0)?, r.get(1)?))
+                })?;
+            assert_eq!((flag, len), (c, 1));
+            let n: i64 = db.query_row("SELECT count(*) FROM flags WHERE flag = ?", [c], |r| r.get(0))?;
+            assert_eq!(n, 1);
+            assert_eq!(Value::from(c), Value::Text(c.to_string()));
+        }
+        let flags: Vec<char> = db.query_row("SELECT list(flag ORDER BY id) FROM flags", [], |r| r.get(0))?;
+        assert_eq!(flags, ['Y', '🦆', 'é']);
+        let back: Vec<char> = db.query_row("SELECT ?", [&flags], |r| r.get(0))?;
+        assert_eq!(back, flags);
+        let e: char = db.query_row("SELECT 'e'::ENUM('e', 'f')", [], |r| r.get(0))?;
+        assert_eq!(e, 'e');
+
+        // Lengths count Unicode scalar values, so a skin-toned emoji is two.
+        for (sql, len) in [("SELECT ''", 0), ("SELECT 'ab'", 2), ("SELECT '👍🏽'", 2)] {
+            let err = db.query_row(sql, [], |r| r.get::<_, char>(0)).unwrap_err();
+            match err {
+                Error::ColumnConversionFailure {
+                    index: 0,
+                    column_type: Type::Text,
+                    source: err,
+                    ..
+                } => {
+                    assert_eq!(
+                        err.to_string(),
+                        format!("Cannot read char value out of {len} character string")
+                    );
+                }
+                err => panic!("{sql}: unexpected error {err:?}"),
+            }
+        }
+        assert_eq!(
+            char::column_result(ValueRef::from("ab")),
+            Err(FromSqlError::InvalidCharLength(2))
+        );
+        assert!(db.query_row("SELECT 1", [], |r| r.get::<_, char>(0)).is_err());
+        let none: Option<char> = db.query_row("SELECT NULL::VARCHAR", [], |r| r.get(0))?;
+        assert_eq!(none, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_system_time_and_duration() -> Result<()> {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (at TIMESTAMP, took INTERVAL)")?;
+        let at = UNIX_EPOCH + Duration::new(1_704_067_200, 123_456_789);
+        let took = Duration::new(90, 999);
+        let before = UNIX_EPOCH - Duration::new(2_208_988_800, 1_500);
+        db.execute("INSERT INTO t VALUES (?, ?)", params![at, took])?;
+        db.execute("INSERT INTO t VALUES (?, ?)", params![before, Duration::ZERO])?;
+        {
+            let mut app = db.appender("t")?;
+            app.append_row(params![at, took])?;
+        }
+
+        // Sub-microsecond precision is truncated.
+        let rows: Vec<(bool, bool)> = db
+            .prepare("SELECT at = TIMESTAMP '2024-01-01 00:00:00.123456', took = INTERVAL '90 seconds' FROM t WHERE at > '1970-01-01'")?
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_>>()?;
+        assert_eq!(vec![(true, true); 2], rows);
+        // Rounded down for times before the epoch.
+        let is_before: bool = db.query_row(
+            "SELECT at = TIMESTAMP '1899-12-31 23:59:59.999998' FROM t WHERE at < '1970-01-01'",
+            [],
+            |r| r.get(0),
+        )?;
+        assert!(is_before);
+
+        let read: Vec<(SystemTime, Duration)> = db
+            .prepare("SELECT at, took FROM t ORDER BY at")?
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_>>()?;
+        let at = UNIX_EPOCH + Duration::new(1_704_067_200, 123_456_000);
+        let took = Duration::from_secs(90);
+        assert_eq!(
+            vec![
+                (UNIX_EPOCH - Duration::new(2_208_988_800, 2_000), Duration::ZERO),
+                (at, took),
+                (at, took)
+            ],
+            read
+        );
+        let t: SystemTime = db.query_row("SELECT TIMESTAMPTZ '1970-01-01 00:00:01+00'", [], |r| r.get(0))?;
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(1), t);
+        Ok(())
+    }
+
+    #[test]
+    fn test_interval_std_duration() -> Result<()> {
+        use std::time::Duration;
+        let db = Connection::open_in_memory()?;
+        let d: Duration = db.query_row("SELECT INTERVAL '1 day 1.5 seconds'", [], |r| r.get(0))?;
+        assert_eq!(Duration::from_millis(86_401_500), d);
+
+        let err = db
+            .query_row("SELECT INTERVAL '-90 minutes'", [], |r| r.get::<_, Duration>(0))
+            .unwrap_err();
+        assert_eq!(out_of_range_value(&err), Some(-5_400_000_000_000), "{err:?}");
+        let err = db
+            .query_row("SELECT INTERVAL '2 years'", [], |r| r.get::<_, Duration>(0))
+            .unwrap_err();
+        match err {
+            Error::ColumnConversionFailure {
+                index: 0, source: err, ..
+            } => {
+                assert_eq!("INTERVAL of 24 months has no fixed duration", err.to_string())
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_real_exact() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (id INTEGER, x REAL); INSERT INTO t VALUES (1, 0.1), (2, 0.2)")?;
+        {
+            let mut app = db.appender("t")?;
+            app.append_row(params![3, 0.3f32])?;
+        }
+
+        let ty: String = db.query_row("SELECT typeof(?)", [0.1f32], |r| r.get(0))?;
+        assert_eq!("FLOAT", ty);
+        let id: i32 = db.query_row("SELECT id FROM t WHERE x = ?", [0.1f32], |r| r.get(0))?;
+        assert_eq!(1, id);
+        let id: i32 = db.query_row("SELECT id FROM t WHERE x = 0.3", [], |r| r.get(0))?;
+        assert_eq!(3, id);
+
+        let (x, wide): (f32, f64) =
+            db.query_row("SELECT x, x FROM t WHERE id = 2", [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        assert_eq!(0.2f32.to_bits(), x.to_bits());
+        assert_eq!(f64::from(0.2f32), wide);
+        Ok(())
+    }
+
+    #[test]
+    fn test_float_out_of_range() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .query_row("SELECT 1e300::DOUBLE", [], |r| r.get::<_, f32>(0))
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::ColumnConversionFailure { index: 0, .. }),
+            "{err:?}"
+        );
+        let err = db
+            .query_row("SELECT 'nan'::REAL", [], |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::ColumnConversionFailure { index: 0, .. }),
+            "{err:?}"
+        );
+        let err = db
+            .query_row("SELECT 1e10::REAL", [], |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::ColumnConversionFailure { index: 0, .. }),
+            "{err:?}"
+        );
+
+        let err = db
+            .query_row("SELECT 1e20::DOUBLE", [], |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OutOfRange);
+        assert_eq!(out_of_range_value(&err), Some(100_000_000_000_000_000_000));
+        let err = Error::from(i32::column_result(ValueRef::Double(1e20)).unwrap_err());
+        assert_eq!(err.kind(), ErrorKind::OutOfRange);
+        // Too large for the value to be kept as an integer.
+        let err = Error::from(i32::column_result(ValueRef::Double(1e40)).unwrap_err());
+        assert!(matches!(err, Error::FromSqlConversionFailure(..)), "{err:?}");
+        assert_eq!(err.kind(), ErrorKind::OutOfRange);
+
+        let inf: f32 = db.query_row("SELECT 'inf'::DOUBLE", [], |r| r.get(0))?;
+        assert_eq!(f32::INFINITY, inf);
+        Ok(())
+    }
+
+    // NaNs with a payload and a sign, infinities, -0.0 and subnormals.
+    const SPECIAL_F64: [u64; 7] = [
+        0x7ff8_0000_0000_0001,
+        0xfff8_0000_0000_0000,
+        0x7ff0_0000_0000_0000,
+        0xfff0_0000_0000_0000,
+        0x8000_0000_0000_0000,
+        0x0000_0000_0000_0001,
+        0x8008_0000_0000_0000,
+    ];
+    const SPECIAL_F32: [u32; 7] = [
+        0x7fc0_0001,
+        0xffc0_0000,
+        0x7f80_0000,
+        0xff80_0000,
+        0x8000_0000,
+        0x0000_0001,
+        0x8040_0000,
+    ];
+
+    #[test]
+    fn test_float_special_values() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE floats (i INTEGER, d DOUBLE, f FLOAT, l DOUBLE[], a FLOAT[1])")?;
+        let mut app = db.appender("floats")?;
+        for (i, (&d, &f)) in SPECIAL_F64.iter().zip(&SPECIAL_F32).enumerate() {
+            let (d, f) = (f64::from_bits(d), f32::from_bits(f));
+            let (d2, f2): (f64, f32) = db.query_row("SELECT ?, ?", params![d, f], |r| Ok((r.get(0)?, r.get(1)?)))?;
+            assert_eq!(d.to_bits(), d2.to_bits());
+            assert_eq!(f.to_bits(), f2.to_bits());
+            app.append_row(params![i as i32, d, f, vec![d], [f]])?;
+        }
+        app.flush()?;
+
+        // Lists and arrays travel as text, which only loses NaN payloads.
+        let same_f64 = |a: f64, b: f64| {
+            a.to_bits() == b.to_bits() || a.is_nan() && b.is_nan() && a.is_sign_negative() == b.is_sign_negative()
+        };
+        let same_f32 = |a: f32, b: f32| {
+            a.to_bits() == b.to_bits() || a.is_nan() && b.is_nan() && a.is_sign_negative() == b.is_sign_negative()
+        };
+        let mut stmt = db.prepare("SELECT d, f, l, a FROM floats ORDER BY i")?;
+        let mut rows = stmt.query([])?;
+        for (&d, &f) in SPECIAL_F64.iter().zip(&SPECIAL_F32) {
+            let (d, f) = (f64::from_bits(d), f32::from_bits(f));
+            let row = rows.next()?.unwrap();
+            assert_eq!(d.to_bits(), row.get::<_, f64>(0)?.to_bits());
+            assert_eq!(f.to_bits(), row.get::<_, f32>(1)?.to_bits());
+            let l = row.get::<_, Vec<f64>>(2)?[0];
+            assert!(same_f64(d, l), "{d:?} was appended as {l:?}");
+            assert!(same_f32(f, row.get::<_, [f32; 1]>(3)?[0]));
+
+            let (l, a): (Vec<f64>, [f32; 1]) =
+                db.query_row("SELECT ?, ?", params![vec![d], [f]], |r| Ok((r.get(0)?, r.get(1)?)))?;
+            assert!(same_f64(d, l[0]), "{d:?} came back as {:?}", l[0]);
+            assert!(same_f32(f, a[0]), "{f:?} came back as {:?}", a[0]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_borrowed_text() -> Result<()> {
+        use super::FromSqlError;
+
+        let db = Connection::open_in_memory()?;
+        let mut stmt = db.prepare("SELECT 'duck', 'goose'::BLOB, 1")?;
+        let mut rows = stmt.query([])?;
+        let row = rows.next()?.unwrap();
+        assert_eq!("duck", row.get_ref(0)?.as_str()?);
+        assert_eq!(b"duck", row.get_ref(0)?.as_bytes()?);
+        assert_eq!(b"goose", row.get_ref(1)?.as_bytes()?);
+        assert_eq!(Err(FromSqlError::InvalidType), row.get_ref(1)?.as_str());
+        assert_eq!(Err(FromSqlError::InvalidType), row.get_ref(2)?.as_bytes());
+
+        let invalid = ValueRef::Text(b"\xF0\x9F\xA6");
+        assert!(matches!(invalid.as_str(), Err(FromSqlError::InvalidUtf8(..))));
+        assert_eq!(b"\xF0\x9F\xA6", invalid.as_bytes()?);
+        assert!(matches!(
+            String::column_result(invalid),
+            Err(FromSqlError::InvalidUtf8(..))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cow_str() -> Result<()> {
+        use super::{FromSqlError, FromSqlRef};
+        use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TYPE mood AS ENUM ('sad', 'happy')")?;
+        let mut stmt = db.prepare("SELECT 'duck', 'happy'::mood, TIMESTAMP '2024-01-02 03:04:05', NULL, 1")?;
+        let mut rows = stmt.query([])?;
+        let row = rows.next()?.unwrap();
+        let text: Cow<str> = row.get(0)?;
+        assert!(matches!(text, Cow::Borrowed("duck")));
+        let entry: Cow<str> = row.get(1)?;
+        assert!(matches!(entry, Cow::Borrowed("happy")));
+        let timestamp: Cow<str> = row.get(2)?;
+        assert!(matches!(timestamp, Cow::Owned(ref s) if s == "2024-01-02 03:04:05"));
+        assert_eq!(None, row.get::<_, Option<Cow<str>>>(3)?);
+        assert!(row.get::<_, Cow<str>>(4).is_err());
+        assert_eq!("duck", &*row.get::<_, Arc<str>>(0)?);
+        assert_eq!("happy", &*row.get::<_, Rc<str>>(1)?);
+        assert_eq!("duck", &*row.get::<_, Box<str>>(0)?);
+
+        let invalid = ValueRef::Text(b"\xF0\x9F\xA6");
+        assert!(matches!(
+            Cow::<str>::column_result_ref(invalid),
+            Err(FromSqlError::InvalidUtf8(..))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_union() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TABLE ids (id UNION(i INTEGER, s VARCHAR));
+             INSERT INTO ids VALUES (1), ('abc'), (NULL);",
+        )?;
+        let mut stmt = db.prepare("SELECT id FROM ids")?;
+        let values = stmt
+            .query_map([], |r| r.get::<_, Value>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            vec![
+                Value::Union {
+                    tag: "i".to_owned(),
+                    value: Box::new(Value::Int(1)),
+                },
+                Value::Union {
+                    tag: "s".to_owned(),
+                    value: Box::new(Value::Text("abc".to_owned())),
+                },
+                Value::Null,
+            ],
+            values
+        );
+
+        let mut rows = stmt.query([])?;
+        let row = rows.next()?.unwrap();
+        assert_eq!(("i", ValueRef::Int(1)), row.get_ref(0)?.as_union().unwrap());
+        assert_eq!(1, row.get::<_, i32>(0)?);
+        assert_eq!(1, row.get::<_, i64>(0)?);
+        let row = rows.next()?.unwrap();
+        assert_eq!("abc", row.get::<_, String>(0)?);
+        let row = rows.next()?.unwrap();
+        assert_eq!(None, row.get::<_, Option<i32>>(0)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_union_invalid_member() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .query_row("SELECT 'abc'::UNION(i INTEGER, s VARCHAR)", [], |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        match err {
+            Error::ColumnConversionFailure {
+                index: 0, source: err, ..
+            } => {
+                assert_eq!(
+                    r#"UNION member "s" of type Text cannot be converted to i32"#,
+                    err.to_string()
+                )
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_unsigned_integer() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -347,6 +1211,292 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_hugeint_full_range() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE huge_int (h hugeint);")?;
+        db.execute("INSERT INTO huge_int VALUES (?), (?);", [i128::MIN, i128::MAX])?;
+        let v = db
+            .prepare("SELECT h FROM huge_int ORDER BY h")?
+            .query_map([], |row| row.get::<_, i128>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(v, vec![i128::MIN, i128::MAX]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hugeint_parameter_arithmetic() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let big: i128 = i64::MAX as i128 * 4;
+        let v: i128 = db.query_row("SELECT ? * 2 + 1", [big], |row| row.get(0))?;
+        assert_eq!(v, big * 2 + 1);
+        let is_hugeint: bool = db.query_row("SELECT typeof(?) = 'HUGEINT'", [big], |row| row.get(0))?;
+        assert!(is_hugeint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hugeint_narrowing() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let v: i64 = db.query_row("SELECT 42::HUGEINT", [], |row| row.get(0))?;
+        assert_eq!(v, 42);
+        let err = db
+            .query_row("SELECT ?", [i64::MAX as i128 + 1], |row| row.get::<_, i64>(0))
+            .unwrap_err();
+        assert_eq!(out_of_range_value(&err), Some(i64::MAX as i128 + 1));
+        let err = db
+            .query_row("SELECT 300::INTEGER", [], |row| row.get::<_, u8>(0))
+            .unwrap_err();
+        assert_eq!(out_of_range_value(&err), Some(300));
+        Ok(())
+    }
+
+    // UHUGEINT results can't be exported through arrow yet, so they are read
+    // back as text here.
+    #[test]
+    fn test_uhugeint() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE uhuge_int (u uhugeint);")?;
+        db.execute(
+            "INSERT INTO uhuge_int VALUES (?), (?), (?);",
+            [u128::MAX, 1, u64::MAX as u128],
+        )?;
+
+        let v: u128 = db.query_row("SELECT max(u)::VARCHAR FROM uhuge_int", [], |row| row.get(0))?;
+        assert_eq!(v, u128::MAX);
+        let v: u128 = db.query_row(
+            "SELECT (min(u) + ?)::VARCHAR FROM uhuge_int WHERE u > 1",
+            [u64::MAX as u128],
+            |row| row.get(0),
+        )?;
+        assert_eq!(v, u64::MAX as u128 * 2);
+        let is_uhugeint: bool = db.query_row("SELECT typeof(?) = 'UHUGEINT'", [u128::MAX], |row| row.get(0))?;
+        assert!(is_uhugeint);
+
+        let v: u128 = db.query_row("SELECT 42::UBIGINT", [], |row| row.get(0))?;
+        assert_eq!(v, 42);
+        let err = db
+            .query_row("SELECT -1::HUGEINT", [], |row| row.get::<_, u128>(0))
+            .unwrap_err();
+        assert_eq!(out_of_range_value(&err), Some(-1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_list() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+
+        let v: Vec<i64> = db.query_row("SELECT list_value(1, 2, 3)", [], |r| r.get(0))?;
+        assert_eq!(vec![1, 2, 3], v);
+        let v: Vec<i32> = db.query_row("SELECT []::INTEGER[]", [], |r| r.get(0))?;
+        assert!(v.is_empty());
+        let v: Vec<Vec<i32>> = db.query_row("SELECT [[1, 2], [], [3]]", [], |r| r.get(0))?;
+        assert_eq!(vec![vec![1, 2], vec![], vec![3]], v);
+        let v: Vec<u8> = db.query_row("SELECT [1, 255]::UTINYINT[]", [], |r| r.get(0))?;
+        assert_eq!(vec![1, 255], v);
+        let v: Vec<u8> = db.query_row("SELECT '\\x01\\x02'::BLOB", [], |r| r.get(0))?;
+        assert_eq!(vec![1, 2], v);
+        // Only bytes read from blobs and strings.
+        for sql in ["SELECT '123'", "SELECT '\\x01\\x02'::BLOB"] {
+            let err = db.query_row(sql, [], |r| r.get::<_, Vec<i32>>(0)).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    Error::ColumnConversionFailure {
+                        source: FromSqlError::InvalidType,
+                        ..
+                    }
+                ),
+                "{err:?}"
+            );
+        }
+        assert!(!Vec::<i32>::accepts(&Type::Blob));
+        assert!(Vec::<u8>::accepts(&Type::Blob));
+        let v: Vec<i32> = db.query_row("SELECT [1, 2]::INTEGER[2]", [], |r| r.get(0))?;
+        assert_eq!(vec![1, 2], v);
+
+        let err = db
+            .query_row("SELECT [1, 300]", [], |r| r.get::<_, Vec<u8>>(0))
+            .unwrap_err();
+        assert_eq!(out_of_range_value(&err), Some(300), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_array() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE embeddings (id INTEGER, e FLOAT[4])")?;
+        db.execute("INSERT INTO embeddings VALUES (1, ?)", [[0.5f32, -1.0, 2.25, f32::MAX]])?;
+        db.execute("INSERT INTO embeddings VALUES (3, ?)", [[9.0f32; 4]])?;
+        {
+            let mut app = db.appender("embeddings")?;
+            app.append_row(params![2, [Some(1.0f32), None, Some(3.0), Some(4.0)]])?;
+        }
+
+        let e: [f32; 4] = db.query_row("SELECT e FROM embeddings WHERE id = 1", [], |r| r.get(0))?;
+        assert_eq!([0.5, -1.0, 2.25, f32::MAX], e);
+        let e: [Option<f32>; 4] = db.query_row("SELECT e FROM embeddings WHERE id = 2", [], |r| r.get(0))?;
+        assert_eq!([Some(1.0), None, Some(3.0), Some(4.0)], e);
+        let e: Vec<Option<f32>> = db.query_row("SELECT e FROM embeddings WHERE id = 2", [], |r| r.get(0))?;
+        assert_eq!(vec![Some(1.0), None, Some(3.0), Some(4.0)], e);
+
+        let id: i32 = db.query_row(
+            "SELECT id FROM embeddings WHERE id <> 2 ORDER BY array_distance(e, ?) LIMIT 1",
+            [[0.5f32, -1.0, 2.0, f32::MAX]],
+            |r| r.get(0),
+        )?;
+        assert_eq!(1, id);
+        let v: [String; 2] = db.query_row("SELECT ?", [["a", "b"]], |r| r.get(0))?;
+        assert_eq!(["a", "b"], v);
+        Ok(())
+    }
+
+    #[test]
+    fn test_array_length_mismatch() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE embeddings (e FLOAT[4]); INSERT INTO embeddings VALUES ([1, 2, 3, 4])")?;
+        let err = db
+            .query_row("SELECT e FROM embeddings", [], |r| r.get::<_, [f32; 3]>(0))
+            .unwrap_err();
+        match err {
+            Error::ColumnConversionFailure { index: 0, source, .. } => {
+                assert_eq!("expected 3 elements, got 4", source.to_string())
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+
+        assert!(db
+            .execute("INSERT INTO embeddings VALUES (?)", [[1.0f32, 2.0]])
+            .is_err());
+        let err = db
+            .execute("INSERT INTO embeddings VALUES (?)", [[Some(1.0f32), None, None, None]])
+            .unwrap_err();
+        match err {
+            Error::ToSqlConversionFailure(err) => {
+                assert_eq!("NULL elements can't be bound inside an ARRAY", err.to_string())
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_nulls() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TABLE l (id INTEGER, s VARCHAR[]); INSERT INTO l VALUES (1, NULL), (2, []), (3, ['a', NULL, 'c']);",
+        )?;
+
+        let get = |id: i32| {
+            db.query_row("SELECT s FROM l WHERE id = ?", [id], |r| {
+                r.get::<_, Option<Vec<Option<String>>>>(0)
+            })
+        };
+        assert_eq!(None, get(1)?);
+        assert_eq!(Some(vec![]), get(2)?);
+        assert_eq!(Some(vec![Some("a".to_owned()), None, Some("c".to_owned())]), get(3)?);
+
+        let err = db
+            .query_row("SELECT s FROM l WHERE id = 3", [], |r| r.get::<_, Vec<String>>(0))
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::ColumnConversionFailure {
+                    source: FromSqlError::InvalidType,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+        assert!(db
+            .query_row("SELECT s FROM l WHERE id = 1", [], |r| r.get::<_, Vec<String>>(0))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_map() -> Result<()> {
+        use std::collections::{BTreeMap, HashMap};
+
+        let db = Connection::open_in_memory()?;
+        let m: HashMap<String, i32> = db.query_row("SELECT map(['a', 'b'], [1, 2])", [], |r| r.get(0))?;
+        assert_eq!(HashMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)]), m);
+        let m: BTreeMap<String, i32> = db.query_row("SELECT map([]::VARCHAR[], []::INTEGER[])", [], |r| r.get(0))?;
+        assert!(m.is_empty());
+        let m: BTreeMap<i32, Option<String>> = db.query_row("SELECT map([1, 2], ['x', NULL])", [], |r| r.get(0))?;
+        assert_eq!(BTreeMap::from([(1, Some("x".to_owned())), (2, None)]), m);
+        assert!(db
+            .query_row("SELECT map([1, 2], ['x', NULL])", [], |r| r
+                .get::<_, HashMap<i32, String>>(0))
+            .is_err());
+
+        // Distinct DuckDB keys that collide after conversion.
+        #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        struct AnyKey;
+        impl FromSql for AnyKey {
+            fn column_result(_: ValueRef<'_>) -> FromSqlResult<Self> {
+                Ok(AnyKey)
+            }
+        }
+        let err = db
+            .query_row("SELECT map(['a', 'b'], [1, 2])", [], |r| {
+                r.get::<_, HashMap<AnyKey, i32>>(0)
+            })
+            .unwrap_err();
+        assert!(err.to_string().ends_with("duplicate key in MAP"), "{err}");
+        assert!(db
+            .query_row("SELECT map(['a', 'b'], [1, 2])", [], |r| r
+                .get::<_, BTreeMap<AnyKey, i32>>(0))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_parameter() -> Result<()> {
+        use std::collections::{BTreeMap, HashMap};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE m (m MAP(VARCHAR, VARCHAR))")?;
+        let map = BTreeMap::from([
+            ("plain", Some("x y")),
+            ("it's", Some("say \"hi\"")),
+            ("{=, }", None),
+            ("", Some("")),
+        ]);
+        db.execute("INSERT INTO m VALUES (?)", [&map])?;
+        let back: BTreeMap<String, Option<String>> = db.query_row("SELECT m FROM m", [], |r| r.get(0))?;
+        assert_eq!(
+            map.iter()
+                .map(|(k, v)| (k.to_string(), v.map(str::to_owned)))
+                .collect::<BTreeMap<_, _>>(),
+            back
+        );
+
+        let ints = HashMap::from([("a".to_owned(), 1), ("b".to_owned(), -2)]);
+        let sum: i32 = db.query_row("SELECT list_sum(map_values(?::MAP(VARCHAR, INTEGER)))", [&ints], |r| {
+            r.get(0)
+        })?;
+        assert_eq!(-1, sum);
+        let empty: HashMap<String, i32> = db.query_row(
+            "SELECT ?::MAP(VARCHAR, INTEGER)",
+            [&HashMap::<String, i32>::new()],
+            |r| r.get(0),
+        )?;
+        assert!(empty.is_empty());
+
+        let value: Value = db.query_row("SELECT m FROM m", [], |r| r.get(0))?;
+        let round_trip: Value = db.query_row("SELECT ?::MAP(VARCHAR, VARCHAR)", [&value], |r| r.get(0))?;
+        assert_eq!(value, round_trip);
+
+        let both_quotes = HashMap::from([("a", "it's \"x\"")]);
+        assert!(matches!(
+            db.query_row("SELECT ?", [&both_quotes], |r| r.get::<_, String>(0)),
+            Err(Error::ToSqlConversionFailure(..))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_integral_ranges() -> Result<()> {
         let db = Connection::open_in_memory()?;
@@ -357,10 +1507,7 @@ mod test {
         {
             for n in out_of_range {
                 let err = db.query_row("SELECT ?", [n], |r| r.get::<_, T>(0)).unwrap_err();
-                match err {
-                    Error::IntegralValueOutOfRange(_, value) => assert_eq!(*n, value),
-                    _ => panic!("unexpected error: {err}"),
-                }
+                assert_eq!(out_of_range_value(&err), Some(*n), "{err}");
             }
             for n in in_range {
                 assert_eq!(*n, db.query_row("SELECT ?", [n], |r| r.get::<_, T>(0)).unwrap().into());
@@ -380,6 +1527,219 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_integral_narrowing_matrix() -> Result<()> {
+        use super::FromSqlError;
+        use std::fmt::Debug;
+
+        // Every width at both of its bounds and next to zero, paired with the
+        // value it holds.
+        let sources: Vec<(Value, i128)> = vec![
+            (Value::TinyInt(i8::MIN), i8::MIN.into()),
+            (Value::TinyInt(-1), -1),
+            (Value::TinyInt(i8::MAX), i8::MAX.into()),
+            (Value::SmallInt(i16::MIN), i16::MIN.into()),
+            (Value::SmallInt(i16::MAX), i16::MAX.into()),
+            (Value::Int(i32::MIN), i32::MIN.into()),
+            (Value::Int(i32::MAX), i32::MAX.into()),
+            (Value::BigInt(i64::MIN), i64::MIN.into()),
+            (Value::BigInt(-1), -1),
+            (Value::BigInt(0), 0),
+            (Value::BigInt(300), 300),
+            (Value::BigInt(i64::MAX), i64::MAX.into()),
+            (Value::HugeInt(i128::MIN), i128::MIN),
+            (Value::HugeInt(-1), -1),
+            (Value::HugeInt(i128::MAX), i128::MAX),
+            (Value::UTinyInt(u8::MAX), u8::MAX.into()),
+            (Value::USmallInt(u16::MAX), u16::MAX.into()),
+            (Value::UInt(u32::MAX), u32::MAX.into()),
+            (Value::UBigInt(0), 0),
+            (Value::UBigInt(u64::MAX), u64::MAX.into()),
+            (Value::UHugeInt(i128::MAX as u128), i128::MAX),
+        ];
+
+        fn check<T>(db: &Connection, sources: &[(Value, i128)])
+        where
+            T: TryFrom<Value, Error = FromSqlError> + TryFrom<i128> + FromSql + PartialEq + Debug + Copy,
+        {
+            for (value, n) in sources {
+                let expected = T::try_from(*n).ok();
+                let converted = T::try_from(value.clone());
+                // UHUGEINT results can't be exported through arrow yet.
+                let sql = match value {
+                    Value::UHugeInt(_) => "SELECT ?::HUGEINT",
+                    _ => "SELECT ?",
+                };
+                let read = db.query_row(sql, [value], |r| r.get::<_, T>(0));
+                match expected {
+                    Some(expected) => {
+                        assert_eq!(converted, Ok(expected), "{value:?}");
+                        assert_eq!(read.unwrap(), expected, "{value:?}");
+                    }
+                    None => {
+                        let err = FromSqlError::OutOfRange {
+                            value: n.to_string(),
+                            target: std::any::type_name::<T>(),
+                        };
+                        assert_eq!(converted, Err(err), "{value:?}");
+                        assert_eq!(out_of_range_value(&read.unwrap_err()), Some(*n), "{value:?}");
+                    }
+                }
+            }
+        }
+
+        let db = Connection::open_in_memory()?;
+        check::<i8>(&db, &sources);
+        check::<i16>(&db, &sources);
+        check::<i32>(&db, &sources);
+        check::<i64>(&db, &sources);
+        check::<i128>(&db, &sources);
+        check::<isize>(&db, &sources);
+        check::<u8>(&db, &sources);
+        check::<u16>(&db, &sources);
+        check::<u32>(&db, &sources);
+        check::<u64>(&db, &sources);
+        check::<u128>(&db, &sources);
+        check::<usize>(&db, &sources);
+
+        // Decimals and text narrow the same way, and nested values never
+        // convert.
+        let d = Value::Decimal {
+            value: 3005,
+            width: 4,
+            scale: 1,
+        };
+        let out_of_range = |value: &str, target| FromSqlError::OutOfRange {
+            value: value.to_owned(),
+            target,
+        };
+        assert_eq!(u8::try_from(d.clone()), Err(out_of_range("300", "u8")));
+        assert_eq!(u16::try_from(d), Ok(300));
+        assert_eq!(u64::try_from(Value::Text("-1".into())), Err(out_of_range("-1", "u64")));
+        // Values past `i128` keep all their digits.
+        assert_eq!(
+            i128::try_from(Value::UHugeInt(u128::MAX)),
+            Err(out_of_range("340282366920938463463374607431768211455", "i128"))
+        );
+        assert_eq!(
+            i64::try_from(Value::Text(u128::MAX.to_string())),
+            Err(out_of_range("340282366920938463463374607431768211455", "i64"))
+        );
+        assert_eq!(
+            u8::try_from(Value::UHugeInt(u128::MAX)).unwrap_err().to_string(),
+            "Value 340282366920938463463374607431768211455 out of range for u8"
+        );
+        assert_eq!(i32::try_from(Value::List(vec![])), Err(FromSqlError::InvalidType));
+        assert_eq!(i32::try_from(Value::Null), Err(FromSqlError::InvalidType));
+        let err = db
+            .query_row("SELECT 300.5::DECIMAL(4, 1)", [], |r| r.get::<_, u8>(0))
+            .unwrap_err();
+        assert_eq!(out_of_range_value(&err), Some(300));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal() -> Result<()> {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let db = Connection::open_in_memory()?;
+        let v: Decimal = db.query_row("SELECT 0.1 + 0.2", [], |row| row.get(0))?;
+        assert_eq!(v, Decimal::from_str("0.3").unwrap());
+
+        let a = Decimal::from_str("0.1").unwrap();
+        let b = Decimal::from_str("0.2").unwrap();
+        let (v, is_decimal): (Decimal, bool) =
+            db.query_row("SELECT ? + ?, typeof(?) LIKE 'DECIMAL%'", params![a, b, a], |row| {
+                <(Decimal, bool)>::try_from(row)
+            })?;
+        assert_eq!(v, a + b);
+        assert!(is_decimal);
+
+        db.execute_batch("CREATE TABLE dec (d DECIMAL(38, 10));")?;
+        let values = [
+            Decimal::from_str("-1234567890.0123456789").unwrap(),
+            Decimal::from_str("999999999999999999.9999999999").unwrap(),
+            Decimal::from_str("-0.0000000001").unwrap(),
+        ];
+        for d in &values {
+            db.execute("INSERT INTO dec VALUES (?)", [d])?;
+        }
+        let read = db
+            .prepare("SELECT d FROM dec")?
+            .query_map([], |row| row.get::<_, Decimal>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(read, values);
+        assert_eq!(read[2].scale(), 10);
+
+        // trailing zeros are stripped when the scale doesn't fit rust_decimal
+        let v: Decimal = db.query_row("SELECT 1.5::DECIMAL(38, 37)", [], |row| row.get(0))?;
+        assert_eq!(v, Decimal::from_str("1.5").unwrap());
+
+        let v: Decimal = db.query_row("SELECT 12::HUGEINT", [], |row| row.get(0))?;
+        assert_eq!(v, Decimal::from(12));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal_value() -> Result<()> {
+        let decimal = |value, width, scale| Value::Decimal { value, width, scale };
+        let db = Connection::open_in_memory()?;
+        for (value, text) in [
+            (decimal(-42, 5, 0), "-42"),
+            (decimal(150, 4, 2), "1.50"),
+            (decimal(-5, 18, 9), "-0.000000005"),
+            (
+                decimal(10i128.pow(38) - 1, 38, 9),
+                "99999999999999999999999999999.999999999",
+            ),
+        ] {
+            assert_eq!(value.to_string(), text);
+            let Value::Decimal { width, scale, .. } = value else {
+                unreachable!()
+            };
+
+            // Compared through `Debug` to check the width and scale as well.
+            let bound: Value = db.query_row("SELECT ?", [&value], |r| r.get(0))?;
+            assert_eq!(format!("{bound:?}"), format!("{value:?}"));
+            let varchar: String = db.query_row("SELECT ?::VARCHAR", [&value], |r| r.get(0))?;
+            assert_eq!(varchar, text);
+
+            db.execute_batch(&format!("CREATE OR REPLACE TABLE d (d DECIMAL({width}, {scale}))"))?;
+            {
+                let mut app = db.appender("d")?;
+                app.append_row([&value])?;
+            }
+            let appended: Value = db.query_row("SELECT d FROM d", [], |r| r.get(0))?;
+            assert_eq!(format!("{appended:?}"), format!("{value:?}"));
+        }
+
+        // Equality goes by value, whatever the scales.
+        assert_eq!(decimal(150, 4, 2), decimal(15, 2, 1));
+        assert_eq!(decimal(0, 1, 0), decimal(0, 38, 37));
+        assert_ne!(decimal(151, 4, 2), decimal(15, 2, 1));
+        assert_ne!(decimal(1, 38, 0), Value::HugeInt(1));
+        let v: Value = db.query_row("SELECT 1.50::DECIMAL(4, 2)", [], |r| r.get(0))?;
+        assert_eq!(v, decimal(15, 2, 1));
+
+        assert!(matches!(
+            db.query_row("SELECT ?", [decimal(1000, 3, 0)], |r| r.get::<_, Value>(0)),
+            Err(Error::ToSqlConversionFailure(..))
+        ));
+        assert!(matches!(
+            db.query_row("SELECT ?", [decimal(1, 39, 0)], |r| r.get::<_, Value>(0)),
+            Err(Error::ToSqlConversionFailure(..))
+        ));
+        // Too wide for `rust_decimal`, but not for `Value`.
+        assert!(matches!(
+            db.query_row("SELECT ?", [decimal(10i128.pow(38) - 1, 38, 9)], |r| {
+                r.get::<_, rust_decimal::Decimal>(0)
+            }),
+            Err(Error::ColumnConversionFailure { .. })
+        ));
+        Ok(())
+    }
+
     // Don't need uuid crate if we only care about the string value of uuid
     #[test]
     fn test_uuid_string() -> Result<()> {
@@ -402,6 +1762,48 @@ mod test {
         Ok(())
     }
 
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid_extremes() -> crate::Result<()> {
+        use uuid::Uuid;
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE uuid (u uuid);")?;
+        let nil = Uuid::nil();
+        let max = Uuid::from_u128(u128::MAX);
+        let mid = Uuid::parse_str("7fffffff-ffff-ffff-ffff-ffffffffffff").unwrap();
+        db.execute("INSERT INTO uuid VALUES (?), (?), (?)", params![max, nil, mid])?;
+
+        let sorted = db
+            .prepare("SELECT u FROM uuid ORDER BY u")?
+            .query_map([], |row| row.get::<_, Uuid>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(sorted, vec![nil, mid, max]);
+
+        for id in [nil, mid, max] {
+            let (found, s): (Uuid, String) = db.query_row("SELECT u, u FROM uuid WHERE u = ?", [id], |row| {
+                <(Uuid, String)>::try_from(row)
+            })?;
+            assert_eq!(found, id);
+            assert_eq!(s, id.to_string());
+        }
+        assert_eq!(max.to_string(), "ffffffff-ffff-ffff-ffff-ffffffffffff");
+        Ok(())
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid_not_from_hugeint() -> crate::Result<()> {
+        use uuid::Uuid;
+
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .query_row("SELECT 1::HUGEINT", [], |row| row.get::<_, Uuid>(0))
+            .unwrap_err();
+        assert!(matches!(err, Error::ColumnConversionFailure { .. }), "{err:?}");
+        Ok(())
+    }
+
     #[cfg(feature = "uuid")]
     #[test]
     fn test_uuid_from_string() -> crate::Result<()> {
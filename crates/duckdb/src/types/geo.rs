@@ -0,0 +1,216 @@
+//! [`ToSql`] and [`FromSql`] implementations for [`Wkb`] and the
+//! [`geo_types`] geometries.
+//!
+//! DuckDB's spatial extension stores `GEOMETRY` values in its own internal
+//! format, so they are exchanged as Well-Known Binary: read them with
+//! `ST_AsWKB(geom)`, and bind geometries into `ST_GeomFromWKB(?)`. `BLOB` and
+//! `WKB_BLOB` columns holding WKB can be read, written and appended to
+//! directly.
+use geo_traits::to_geo::ToGeoGeometry;
+use geo_types::{
+    Geometry, GeometryCollection, Line, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon, Rect,
+    Triangle,
+};
+
+use crate::{
+    types::{FromSql, FromSqlError, FromSqlResult, ListElement, ToSql, ToSqlOutput, Type, ValueRef},
+    Error, Result,
+};
+
+/// A geometry encoded as [Well-Known Binary], read from and bound as a
+/// `BLOB`.
+///
+/// Reading a `Wkb` checks that the blob is valid WKB, and
+/// [`to_geometry`](Wkb::to_geometry) decodes it into a [`geo_types`]
+/// geometry.
+///
+/// [Well-Known Binary]: https://libgeos.org/specifications/wkb/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Wkb(pub Vec<u8>);
+
+impl Wkb {
+    /// Encodes `geometry` as little endian WKB.
+    pub fn from_geometry(geometry: &Geometry<f64>) -> Result<Wkb> {
+        let mut buf = Vec::new();
+        wkb::writer::write_geometry(&mut buf, geometry, &Default::default())
+            .map_err(|err| Error::ToSqlConversionFailure(Box::new(err)))?;
+        Ok(Wkb(buf))
+    }
+
+    /// Decodes the geometry, failing if the bytes aren't valid WKB or hold an
+    /// empty point, which [`geo_types`] can't represent.
+    pub fn to_geometry(&self) -> FromSqlResult<Geometry<f64>> {
+        read(&self.0)?.try_to_geometry().ok_or_else(|| {
+            FromSqlError::Other("WKB geometry holding an empty point can't be read into geo_types".into())
+        })
+    }
+}
+
+/// Parses `bytes` as WKB.
+fn read(bytes: &[u8]) -> FromSqlResult<wkb::reader::Wkb<'_>> {
+    wkb::reader::read_wkb(bytes).map_err(|err| {
+        FromSqlError::Other(
+            format!("invalid WKB geometry ({err}); read spatial GEOMETRY columns through ST_AsWKB").into(),
+        )
+    })
+}
+
+impl ToSql for Wkb {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Blob(&self.0)))
+    }
+}
+
+impl ListElement for Wkb {}
+
+impl FromSql for Wkb {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Blob
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let bytes = value.as_blob()?;
+        read(bytes)?;
+        Ok(Wkb(bytes.to_vec()))
+    }
+}
+
+impl ToSql for Geometry<f64> {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(Wkb::from_geometry(self)?.0))
+    }
+}
+
+impl FromSql for Geometry<f64> {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Blob
+    }
+
+    #[inline]
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Wkb(value.as_blob()?.to_vec()).to_geometry()
+    }
+}
+
+/// Binds and reads each kind of geometry through [`Geometry`], failing to
+/// read a blob holding another kind.
+macro_rules! geometry_kind(
+    ($($t:ident),+ $(,)?) => ($(
+        impl ToSql for $t<f64> {
+            #[inline]
+            fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(Wkb::from_geometry(&Geometry::$t(self.clone()))?.0))
+            }
+        }
+
+        impl FromSql for $t<f64> {
+            #[inline]
+            fn accepts(ty: &Type) -> bool {
+                *ty == Type::Blob
+            }
+
+            #[inline]
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                $t::try_from(Geometry::column_result(value)?).map_err(|err| FromSqlError::Other(Box::new(err)))
+            }
+        }
+    )+)
+);
+
+geometry_kind!(
+    Point,
+    Line,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    GeometryCollection,
+    Rect,
+    Triangle,
+);
+
+#[cfg(test)]
+mod test {
+    use super::Wkb;
+    use crate::{params, Connection, Error, Result};
+    use geo_types::{line_string, point, polygon, Geometry, LineString, Point, Polygon};
+
+    #[test]
+    fn test_wkb_blob() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE shapes (id INTEGER, shape BLOB)")?;
+        let p: Point<f64> = point!(x: 1.5, y: -2.0);
+        let line: LineString<f64> = line_string![(x: 0.0, y: 0.0), (x: 1.0, y: 1.0)];
+        let square: Polygon<f64> = polygon![(x: 0.0, y: 0.0), (x: 1.0, y: 0.0), (x: 1.0, y: 1.0), (x: 0.0, y: 0.0)];
+        db.execute("INSERT INTO shapes VALUES (1, ?), (2, ?)", params![p, line])?;
+        {
+            let mut app = db.appender("shapes")?;
+            app.append_row(params![3, Wkb::from_geometry(&Geometry::from(square.clone()))?])?;
+        }
+
+        let get = |id: i32| {
+            db.query_row("SELECT shape FROM shapes WHERE id = ?", [id], |r| {
+                r.get::<_, Geometry<f64>>(0)
+            })
+        };
+        assert_eq!(get(1)?, Geometry::Point(p));
+        assert_eq!(get(2)?, Geometry::LineString(line.clone()));
+        assert_eq!(get(3)?, Geometry::Polygon(square.clone()));
+        let back: Point<f64> = db.query_row("SELECT shape FROM shapes WHERE id = 1", [], |r| r.get(0))?;
+        assert_eq!(back, p);
+        let back: Polygon<f64> = db.query_row("SELECT shape FROM shapes WHERE id = 3", [], |r| r.get(0))?;
+        assert_eq!(back, square);
+        let wkb: Wkb = db.query_row("SELECT shape FROM shapes WHERE id = 2", [], |r| r.get(0))?;
+        assert_eq!(wkb.to_geometry()?, Geometry::LineString(line));
+
+        // A line string isn't a point.
+        let err = db
+            .query_row("SELECT shape FROM shapes WHERE id = 2", [], |r| {
+                r.get::<_, Point<f64>>(0)
+            })
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::ColumnConversionFailure { index: 0, .. }),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains("LineString"), "{err}");
+
+        for sql in ["SELECT '\\x01\\x02'::BLOB", "SELECT ''::BLOB"] {
+            let err = db.query_row(sql, [], |r| r.get::<_, Wkb>(0)).unwrap_err();
+            assert!(err.to_string().contains("invalid WKB geometry"), "{sql}: {err}");
+            let err = db.query_row(sql, [], |r| r.get::<_, Point<f64>>(0)).unwrap_err();
+            assert!(err.to_string().contains("ST_AsWKB"), "{sql}: {err}");
+        }
+        assert!(db
+            .query_row("SELECT 'POINT (1 2)'", [], |r| r.get::<_, Wkb>(0))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_spatial() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        if db.execute_batch("INSTALL spatial; LOAD spatial;").is_err() {
+            eprintln!("skipping test_spatial: the spatial extension can't be loaded");
+            return Ok(());
+        }
+        let p: Point<f64> = db.query_row("SELECT ST_AsWKB(ST_Point(1, 2))", [], |r| r.get(0))?;
+        assert_eq!(p, point!(x: 1.0, y: 2.0));
+
+        db.execute_batch("CREATE TABLE places (geom GEOMETRY)")?;
+        db.execute(
+            "INSERT INTO places VALUES (ST_GeomFromWKB(?))",
+            [point!(x: 3.0, y: 4.0)],
+        )?;
+        let (x, p): (f64, Point<f64>) = db.query_row("SELECT ST_X(geom), ST_AsWKB(geom) FROM places", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })?;
+        assert_eq!((x, p), (3.0, point!(x: 3.0, y: 4.0)));
+        Ok(())
+    }
+}
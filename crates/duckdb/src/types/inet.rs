@@ -0,0 +1,383 @@
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use super::{FromSql, FromSqlError, FromSqlResult, ListElement, ToSql, ToSqlOutput, Type, ValueRef};
+use crate::Result;
+
+/// An IP address together with the length of its network prefix, as held by
+/// the `inet` extension's `INET` type, such as `192.168.0.0/16`.
+///
+/// An `INET` column reads as the `STRUCT` the extension stores it as, with
+/// its `ip_type`, `address` and `mask`. Text in the canonical
+/// `address[/prefix]` form, which leaves out a prefix covering the whole
+/// address, reads too, and values bind as such text, which DuckDB casts to
+/// `INET`. [`IpAddr`], [`Ipv4Addr`] and [`Ipv6Addr`] read and bind the same
+/// way, reading only values without a prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InetAddr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl InetAddr {
+    /// Creates an `InetAddr` from an address and a prefix length, up to 32
+    /// for IPv4 and 128 for IPv6.
+    ///
+    /// Returns `None` if the prefix is longer than the address.
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<InetAddr> {
+        if prefix <= max_prefix(addr) {
+            Some(InetAddr { addr, prefix })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address.
+    #[inline]
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the prefix length in bits.
+    #[inline]
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+}
+
+/// A single address, with a prefix covering all of it.
+impl From<IpAddr> for InetAddr {
+    #[inline]
+    fn from(addr: IpAddr) -> InetAddr {
+        InetAddr {
+            addr,
+            prefix: max_prefix(addr),
+        }
+    }
+}
+
+fn max_prefix(addr: IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+impl fmt::Display for InetAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.addr)?;
+        if self.prefix != max_prefix(self.addr) {
+            write!(f, "/{}", self.prefix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when parsing an [`InetAddr`] or an IP address from a
+/// malformed string, or when reading an IP address from a value that has a
+/// prefix or is of the other IP version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseInetAddrError(String);
+
+impl fmt::Display for ParseInetAddrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseInetAddrError {}
+
+impl FromStr for InetAddr {
+    type Err = ParseInetAddrError;
+
+    /// Parses `address` or `address/prefix`, with the address in any form
+    /// [`IpAddr`] parses.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseInetAddrError(format!("invalid INET {s:?}"));
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| err())?;
+        match prefix {
+            // `u8` parsing would accept a leading `+`.
+            Some(prefix) if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_digit()) => Err(err()),
+            Some(prefix) => InetAddr::new(addr, prefix.parse().map_err(|_| err())?).ok_or_else(err),
+            None => Ok(InetAddr::from(addr)),
+        }
+    }
+}
+
+/// Whether values of type `ty` may be `INET`s: text, or the `STRUCT` the
+/// `inet` extension stores them as.
+fn accepts_inet(ty: &Type) -> bool {
+    match ty {
+        Type::Text => true,
+        Type::Struct(fields) => fields
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .eq(["ip_type", "address", "mask"]),
+        _ => false,
+    }
+}
+
+/// Reads the `STRUCT` the `inet` extension stores an `INET` as.
+fn inet_from_struct(value: ValueRef<'_>) -> FromSqlResult<InetAddr> {
+    let fields = value.as_struct()?;
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| *value)
+            .ok_or(FromSqlError::InvalidType)
+    };
+    let (ip_type, address, mask) = (
+        u8::column_result(field("ip_type")?)?,
+        i128::column_result(field("address")?)?,
+        u16::column_result(field("mask")?)?,
+    );
+    let err = || {
+        let err = ParseInetAddrError(format!(
+            "invalid INET {{ip_type: {ip_type}, address: {address}, mask: {mask}}}"
+        ));
+        FromSqlError::Other(Box::new(err))
+    };
+    let addr = match ip_type {
+        1 => IpAddr::V4(Ipv4Addr::from(u32::try_from(address).map_err(|_| err())?)),
+        // IPv6 addresses have their top bit flipped, so that they sort as
+        // signed numbers.
+        2 => IpAddr::V6(Ipv6Addr::from(address as u128 ^ (1 << 127))),
+        _ => return Err(err()),
+    };
+    u8::try_from(mask)
+        .ok()
+        .and_then(|prefix| InetAddr::new(addr, prefix))
+        .ok_or_else(err)
+}
+
+impl FromSql for InetAddr {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        accepts_inet(ty)
+    }
+
+    #[inline]
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        if let ValueRef::Struct(..) = value {
+            return inet_from_struct(value);
+        }
+        value
+            .as_str()?
+            .parse()
+            .map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+impl ToSql for InetAddr {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl ListElement for InetAddr {}
+
+impl FromSql for IpAddr {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        accepts_inet(ty)
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let inet = InetAddr::column_result(value)?;
+        if inet.prefix != max_prefix(inet.addr) {
+            let err = ParseInetAddrError(format!("{inet} is a network, read it as an InetAddr"));
+            return Err(FromSqlError::Other(Box::new(err)));
+        }
+        Ok(inet.addr)
+    }
+}
+
+impl FromSql for Ipv4Addr {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        accepts_inet(ty)
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match IpAddr::column_result(value)? {
+            IpAddr::V4(addr) => Ok(addr),
+            addr => Err(FromSqlError::Other(Box::new(ParseInetAddrError(format!(
+                "{addr} is not an IPv4 address"
+            ))))),
+        }
+    }
+}
+
+impl FromSql for Ipv6Addr {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        accepts_inet(ty)
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match IpAddr::column_result(value)? {
+            IpAddr::V6(addr) => Ok(addr),
+            addr => Err(FromSqlError::Other(Box::new(ParseInetAddrError(format!(
+                "{addr} is not an IPv6 address"
+            ))))),
+        }
+    }
+}
+
+macro_rules! to_sql_display(
+    ($($t:ty),+) => ($(
+        impl ToSql for $t {
+            #[inline]
+            fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.to_string()))
+            }
+        }
+
+        impl ListElement for $t {}
+    )+)
+);
+
+to_sql_display!(IpAddr, Ipv4Addr, Ipv6Addr);
+
+#[cfg(test)]
+mod test {
+    use super::InetAddr;
+    use crate::{Connection, Error, Result};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn test_inet_text() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE hosts (id INTEGER, ip VARCHAR)")?;
+        let v4 = Ipv4Addr::new(192, 168, 1, 20);
+        let v6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let net = InetAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8).unwrap();
+        db.execute(
+            "INSERT INTO hosts VALUES (1, ?), (2, ?), (3, ?)",
+            crate::params![v4, v6, net],
+        )?;
+        {
+            let mut app = db.appender("hosts")?;
+            app.append_row(crate::params![4, IpAddr::V6(Ipv6Addr::LOCALHOST)])?;
+        }
+
+        let get = |id: i32| db.query_row("SELECT ip FROM hosts WHERE id = ?", [id], |r| r.get::<_, String>(0));
+        assert_eq!(get(1)?, "192.168.1.20");
+        assert_eq!(get(2)?, "2001:db8::1");
+        assert_eq!(get(3)?, "10.0.0.0/8");
+        assert_eq!(get(4)?, "::1");
+
+        let read = |id: i32| db.query_row("SELECT ip FROM hosts WHERE id = ?", [id], |r| r.get::<_, IpAddr>(0));
+        assert_eq!(read(1)?, IpAddr::V4(v4));
+        assert_eq!(read(2)?, IpAddr::V6(v6));
+        assert_eq!(read(4)?, IpAddr::V6(Ipv6Addr::LOCALHOST));
+        let addr: Ipv4Addr = db.query_row("SELECT ip FROM hosts WHERE id = 1", [], |r| r.get(0))?;
+        assert_eq!(addr, v4);
+        let addr: Ipv6Addr = db.query_row("SELECT ip FROM hosts WHERE id = 2", [], |r| r.get(0))?;
+        assert_eq!(addr, v6);
+        let back: InetAddr = db.query_row("SELECT ip FROM hosts WHERE id = 3", [], |r| r.get(0))?;
+        assert_eq!((back, back.addr(), back.prefix()), (net, net.addr(), 8));
+        let whole: InetAddr = db.query_row("SELECT '192.168.1.20/32'", [], |r| r.get(0))?;
+        assert_eq!(whole, InetAddr::from(IpAddr::V4(v4)));
+        assert_eq!(whole.to_string(), "192.168.1.20");
+        let n: i64 = db.query_row("SELECT count(*) FROM hosts WHERE ip = ?", [v6], |r| r.get(0))?;
+        assert_eq!(n, 1);
+
+        let message = |sql: &str, read: fn(&crate::Row<'_>) -> Result<()>| match db.query_row(sql, [], read) {
+            Err(Error::ColumnConversionFailure { index: 0, source, .. }) => source.to_string(),
+            res => panic!("{sql}: unexpected {res:?}"),
+        };
+        let ip = |r: &crate::Row<'_>| r.get::<_, IpAddr>(0).map(|_| ());
+        let inet = |r: &crate::Row<'_>| r.get::<_, InetAddr>(0).map(|_| ());
+        let v4_only = |r: &crate::Row<'_>| r.get::<_, Ipv4Addr>(0).map(|_| ());
+        assert_eq!(
+            message("SELECT '10.0.0.0/8'", ip),
+            "10.0.0.0/8 is a network, read it as an InetAddr"
+        );
+        assert_eq!(message("SELECT '::1'", v4_only), "::1 is not an IPv4 address");
+        for s in [
+            "",
+            "localhost",
+            "1.2.3",
+            "1.2.3.4/33",
+            "::1/129",
+            "1.2.3.4/",
+            "1.2.3.4/+8",
+            "1.2.3.4/8/8",
+        ] {
+            assert_eq!(
+                message(&format!("SELECT '{s}'"), inet),
+                format!("invalid INET {s:?}"),
+                "{s}"
+            );
+            assert!(s.parse::<InetAddr>().is_err(), "{s}");
+        }
+        assert!(InetAddr::new(IpAddr::V6(v6), 129).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_inet_struct() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let read = |ip_type: u8, address: i128, mask: u16| {
+            db.query_row(
+                "SELECT {'ip_type': ?::UTINYINT, 'address': ?::HUGEINT, 'mask': ?::USMALLINT}",
+                crate::params![ip_type, address, mask],
+                |r| r.get::<_, InetAddr>(0),
+            )
+        };
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+        assert_eq!(read(1, 0xC0A8_0114, 32)?, InetAddr::from(v4));
+        assert_eq!(read(1, 0xC0A8_0000, 16)?, "192.168.0.0/16".parse().unwrap());
+        let v6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let flipped = (u128::from(v6) ^ (1 << 127)) as i128;
+        assert_eq!(read(2, flipped, 128)?, InetAddr::from(IpAddr::V6(v6)));
+        assert_eq!(read(2, flipped, 32)?.to_string(), "2001:db8::1/32");
+
+        assert!(read(0, 0, 0).is_err());
+        assert!(read(1, 1 << 32, 32).is_err());
+        assert!(read(1, 0, 33).is_err());
+        assert!(read(2, flipped, 129).is_err());
+
+        let ip: Ipv4Addr = db.query_row(
+            "SELECT {'ip_type': 1::UTINYINT, 'address': 167772161::HUGEINT, 'mask': 32::USMALLINT}",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(ip, Ipv4Addr::new(10, 0, 0, 1));
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs the inet extension, which INSTALL downloads"]
+    fn test_inet_extension() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("INSTALL inet; LOAD inet;")?;
+        db.execute_batch("CREATE TABLE hosts (ip INET)")?;
+        let net: InetAddr = "192.168.0.0/16".parse().unwrap();
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+        let v6 = IpAddr::V6("2001:db8::1".parse().unwrap());
+        db.execute("INSERT INTO hosts VALUES (?), (?), (?)", crate::params![net, v4, v6])?;
+
+        let mut stmt = db.prepare("SELECT ip FROM hosts WHERE ip <<= ? ORDER BY ip")?;
+        let inside = stmt
+            .query_map([net], |r| r.get::<_, InetAddr>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(inside, [net, InetAddr::from(v4)]);
+        let found: IpAddr = db.query_row("SELECT ip FROM hosts WHERE ip = ?", [v6], |r| r.get(0))?;
+        assert_eq!(found, v6);
+        // Without a cast, the values read as the extension stores them.
+        let ty = db.query_row("SELECT ip FROM hosts LIMIT 1", [], |r| Ok(r.get_ref(0)?.data_type()))?;
+        assert!(matches!(ty, crate::types::Type::Struct(_)), "{ty:?}");
+        Ok(())
+    }
+}
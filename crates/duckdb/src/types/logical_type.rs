@@ -0,0 +1,870 @@
+use std::{ffi::CStr, fmt, os::raw::c_char, sync::Arc};
+
+use arrow::{
+    array::{Array, AsArray, UnionArray},
+    datatypes::{self, DataType, Field, Fields, UnionFields, UnionMode},
+};
+
+use super::{TimeUnit, Type, Value};
+use crate::ffi;
+
+/// The full DuckDB type of a value, as reported by
+/// [`ValueRef::logical_type`](super::ValueRef::logical_type) and
+/// [`Value::logical_type`].
+///
+/// Unlike [`Type`], which only names the kind of value, a `LogicalType` keeps
+/// everything needed to tell two types of the same kind apart: the width and
+/// scale of a `DECIMAL`, the precision of a `TIMESTAMP`, the dictionary of an
+/// `ENUM` and the children of nested types. It displays as the SQL name of
+/// the type, spelled the way DuckDB's `typeof` spells it, such as
+/// `DECIMAL(4,1)` or `STRUCT(a INTEGER, "b c" VARCHAR[])`.
+///
+/// Types reach Rust through Arrow, which has no counterpart for some of
+/// DuckDB's: `UUID` and `JSON` report as [`Text`](LogicalType::Text), `BIT`
+/// as [`Blob`](LogicalType::Blob), and a `DECIMAL(38, 0)` as
+/// [`HugeInt`](LogicalType::HugeInt), matching the values they read as.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogicalType {
+    /// NULL
+    Null,
+    /// BOOLEAN
+    Boolean,
+    /// TINYINT
+    TinyInt,
+    /// SMALLINT
+    SmallInt,
+    /// INTEGER
+    Int,
+    /// BIGINT
+    BigInt,
+    /// HUGEINT
+    HugeInt,
+    /// UTINYINT
+    UTinyInt,
+    /// USMALLINT
+    USmallInt,
+    /// UINTEGER
+    UInt,
+    /// UBIGINT
+    UBigInt,
+    /// UHUGEINT
+    UHugeInt,
+    /// FLOAT
+    Float,
+    /// DOUBLE
+    Double,
+    /// DECIMAL(width, scale)
+    Decimal {
+        /// total number of digits
+        width: u8,
+        /// number of digits after the decimal point
+        scale: u8,
+    },
+    /// TIMESTAMP, with `TIMESTAMP_S`, `TIMESTAMP_MS` and `TIMESTAMP_NS` told
+    /// apart by their unit
+    Timestamp(TimeUnit),
+    /// TIMESTAMP WITH TIME ZONE
+    TimestampTz(TimeUnit),
+    /// VARCHAR
+    Text,
+    /// BLOB
+    Blob,
+    /// DATE
+    Date32,
+    /// TIME
+    Time64,
+    /// INTERVAL
+    Interval,
+    /// LIST of the element type
+    List(Box<LogicalType>),
+    /// ARRAY of the element type and size
+    Array(Box<LogicalType>, u32),
+    /// ENUM with its dictionary, in order
+    Enum(Vec<String>),
+    /// STRUCT with its fields, in order
+    Struct(Vec<(String, LogicalType)>),
+    /// MAP of the key and value types
+    Map(Box<LogicalType>, Box<LogicalType>),
+    /// UNION with its members, in order
+    Union(Vec<(String, LogicalType)>),
+}
+
+impl LogicalType {
+    /// Returns the type of the values in `array`, looking into its children
+    /// for the dictionaries of nested `ENUM`s, or `None` if it isn't one
+    /// DuckDB converts values to.
+    pub(crate) fn of_array(array: &dyn Array) -> Option<LogicalType> {
+        let ty = match array.data_type() {
+            DataType::List(_) => LogicalType::List(Box::new(Self::of_array(array.as_list_opt::<i32>()?.values())?)),
+            DataType::LargeList(_) => {
+                LogicalType::List(Box::new(Self::of_array(array.as_list_opt::<i64>()?.values())?))
+            }
+            DataType::FixedSizeList(_, size) => LogicalType::Array(
+                Box::new(Self::of_array(array.as_fixed_size_list_opt()?.values())?),
+                (*size).try_into().ok()?,
+            ),
+            DataType::Struct(fields) => LogicalType::Struct(
+                fields
+                    .iter()
+                    .zip(array.as_struct_opt()?.columns())
+                    .map(|(field, column)| Some((field.name().to_owned(), Self::of_array(column)?)))
+                    .collect::<Option<_>>()?,
+            ),
+            DataType::Map(..) => {
+                let map = array.as_map_opt()?;
+                LogicalType::Map(
+                    Box::new(Self::of_array(map.keys())?),
+                    Box::new(Self::of_array(map.values())?),
+                )
+            }
+            DataType::Union(fields, _) => {
+                let union = array.as_any().downcast_ref::<UnionArray>()?;
+                LogicalType::Union(
+                    fields
+                        .iter()
+                        .map(|(type_id, field)| Some((field.name().to_owned(), Self::of_array(union.child(type_id))?)))
+                        .collect::<Option<_>>()?,
+                )
+            }
+            DataType::Dictionary(..) => {
+                let values = array.as_any_dictionary_opt()?.values();
+                LogicalType::Enum(
+                    values
+                        .as_string_opt::<i32>()?
+                        .iter()
+                        .map(|s| s.unwrap_or_default().to_owned())
+                        .collect(),
+                )
+            }
+            data_type => Self::of_data_type(data_type)?,
+        };
+        Some(ty)
+    }
+
+    /// Returns the type of the values of an Arrow `data_type`, or `None` if
+    /// it isn't one DuckDB converts values to. `ENUM`s, whose dictionary is
+    /// only in the values, are `None` too.
+    pub(crate) fn of_data_type(data_type: &DataType) -> Option<LogicalType> {
+        let ty = match data_type {
+            DataType::List(item) | DataType::LargeList(item) => {
+                LogicalType::List(Box::new(Self::of_data_type(item.data_type())?))
+            }
+            DataType::FixedSizeList(item, size) => LogicalType::Array(
+                Box::new(Self::of_data_type(item.data_type())?),
+                (*size).try_into().ok()?,
+            ),
+            DataType::Struct(fields) => LogicalType::Struct(
+                fields
+                    .iter()
+                    .map(|field| Some((field.name().to_owned(), Self::of_data_type(field.data_type())?)))
+                    .collect::<Option<_>>()?,
+            ),
+            DataType::Map(entries, _) => match entries.data_type() {
+                DataType::Struct(fields) if fields.len() == 2 => LogicalType::Map(
+                    Box::new(Self::of_data_type(fields[0].data_type())?),
+                    Box::new(Self::of_data_type(fields[1].data_type())?),
+                ),
+                _ => return None,
+            },
+            DataType::Union(fields, _) => LogicalType::Union(
+                fields
+                    .iter()
+                    .map(|(_, field)| Some((field.name().to_owned(), Self::of_data_type(field.data_type())?)))
+                    .collect::<Option<_>>()?,
+            ),
+            DataType::Null => LogicalType::Null,
+            DataType::Boolean => LogicalType::Boolean,
+            DataType::Int8 => LogicalType::TinyInt,
+            DataType::Int16 => LogicalType::SmallInt,
+            DataType::Int32 => LogicalType::Int,
+            DataType::Int64 => LogicalType::BigInt,
+            DataType::UInt8 => LogicalType::UTinyInt,
+            DataType::UInt16 => LogicalType::USmallInt,
+            DataType::UInt32 => LogicalType::UInt,
+            DataType::UInt64 => LogicalType::UBigInt,
+            DataType::Float32 => LogicalType::Float,
+            DataType::Float64 => LogicalType::Double,
+            DataType::Decimal128(38, 0) => LogicalType::HugeInt,
+            DataType::Decimal128(width, scale) => LogicalType::Decimal {
+                width: *width,
+                scale: *scale as u8,
+            },
+            DataType::Timestamp(unit, None) => LogicalType::Timestamp(time_unit(unit)),
+            DataType::Timestamp(unit, Some(_)) => LogicalType::TimestampTz(time_unit(unit)),
+            DataType::Date32 => LogicalType::Date32,
+            DataType::Time64(_) => LogicalType::Time64,
+            DataType::Interval(_) => LogicalType::Interval,
+            DataType::Utf8 | DataType::LargeUtf8 => LogicalType::Text,
+            DataType::Binary | DataType::LargeBinary => LogicalType::Blob,
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    /// Returns the Arrow type DuckDB converts values of this type to, with
+    /// its default settings: strings, blobs and lists with 32-bit offsets,
+    /// and `TIMESTAMP WITH TIME ZONE` in `UTC`. It matches
+    /// [`Statement::column_type`](crate::Statement::column_type) for a
+    /// column of this type.
+    ///
+    /// Returns `None` for types DuckDB can't convert to Arrow, which are
+    /// `UHUGEINT` and the types nesting it.
+    pub fn arrow_data_type(&self) -> Option<DataType> {
+        let field = |name: &str, ty: &LogicalType| Some(Field::new(name, ty.arrow_data_type()?, true));
+        let data_type = match self {
+            LogicalType::Null => DataType::Null,
+            LogicalType::Boolean => DataType::Boolean,
+            LogicalType::TinyInt => DataType::Int8,
+            LogicalType::SmallInt => DataType::Int16,
+            LogicalType::Int => DataType::Int32,
+            LogicalType::BigInt => DataType::Int64,
+            LogicalType::HugeInt => DataType::Decimal128(38, 0),
+            LogicalType::UTinyInt => DataType::UInt8,
+            LogicalType::USmallInt => DataType::UInt16,
+            LogicalType::UInt => DataType::UInt32,
+            LogicalType::UBigInt => DataType::UInt64,
+            LogicalType::UHugeInt => return None,
+            LogicalType::Float => DataType::Float32,
+            LogicalType::Double => DataType::Float64,
+            LogicalType::Decimal { width, scale } => DataType::Decimal128(*width, *scale as i8),
+            LogicalType::Timestamp(unit) => DataType::Timestamp(arrow_time_unit(*unit), None),
+            LogicalType::TimestampTz(unit) => DataType::Timestamp(arrow_time_unit(*unit), Some("UTC".into())),
+            LogicalType::Text => DataType::Utf8,
+            LogicalType::Blob => DataType::Binary,
+            LogicalType::Date32 => DataType::Date32,
+            LogicalType::Time64 => DataType::Time64(datatypes::TimeUnit::Microsecond),
+            LogicalType::Interval => DataType::Interval(datatypes::IntervalUnit::MonthDayNano),
+            LogicalType::List(item) => DataType::List(Arc::new(field("l", item)?)),
+            LogicalType::Array(item, size) => DataType::FixedSizeList(Arc::new(field("", item)?), *size as i32),
+            LogicalType::Enum(entries) => {
+                let index = match entries.len() {
+                    len if len <= u8::MAX as usize => DataType::UInt8,
+                    len if len <= u16::MAX as usize => DataType::UInt16,
+                    _ => DataType::UInt32,
+                };
+                DataType::Dictionary(Box::new(index), Box::new(DataType::Utf8))
+            }
+            LogicalType::Struct(fields) => DataType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, ty)| field(name, ty))
+                    .collect::<Option<Fields>>()?,
+            ),
+            LogicalType::Map(key, value) => {
+                let entries = Fields::from(vec![field("key", key)?, field("value", value)?]);
+                DataType::Map(Arc::new(Field::new("entries", DataType::Struct(entries), true)), false)
+            }
+            LogicalType::Union(members) => {
+                let fields = members
+                    .iter()
+                    .map(|(name, ty)| field(name, ty))
+                    .collect::<Option<Vec<_>>>()?;
+                DataType::Union(UnionFields::new(0..members.len() as i8, fields), UnionMode::Sparse)
+            }
+        };
+        Some(data_type)
+    }
+}
+
+impl LogicalType {
+    /// The type of a `duckdb_type` id, for the types it describes in full.
+    /// Returns `None` for `DUCKDB_TYPE_INVALID`, for types with details the
+    /// id leaves out, such as `DECIMAL`, `ENUM` and nested types, and for
+    /// types this crate can't read.
+    pub(crate) fn from_duckdb_type(id: ffi::duckdb_type) -> Option<LogicalType> {
+        let ty = match id {
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_BOOLEAN => LogicalType::Boolean,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_TINYINT => LogicalType::TinyInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_SMALLINT => LogicalType::SmallInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_INTEGER => LogicalType::Int,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_BIGINT => LogicalType::BigInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_HUGEINT => LogicalType::HugeInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_UTINYINT => LogicalType::UTinyInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_USMALLINT => LogicalType::USmallInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_UINTEGER => LogicalType::UInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_UBIGINT => LogicalType::UBigInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_UHUGEINT => LogicalType::UHugeInt,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_FLOAT => LogicalType::Float,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_DOUBLE => LogicalType::Double,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_S => LogicalType::Timestamp(TimeUnit::Second),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_MS => LogicalType::Timestamp(TimeUnit::Millisecond),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP => LogicalType::Timestamp(TimeUnit::Microsecond),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_NS => LogicalType::Timestamp(TimeUnit::Nanosecond),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_TZ => LogicalType::TimestampTz(TimeUnit::Microsecond),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_VARCHAR | ffi::DUCKDB_TYPE_DUCKDB_TYPE_UUID => LogicalType::Text,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_BLOB | ffi::DUCKDB_TYPE_DUCKDB_TYPE_BIT => LogicalType::Blob,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_DATE => LogicalType::Date32,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIME => LogicalType::Time64,
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_INTERVAL => LogicalType::Interval,
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    /// The type `ty` describes, with the details of `DECIMAL`, `ENUM` and
+    /// nested types. Returns `None` for types this crate can't read, and for
+    /// the type of `NULL`, which the C API reports as invalid.
+    ///
+    /// # Safety
+    ///
+    /// `ty` must be a valid `duckdb_logical_type`; it isn't destroyed.
+    pub(crate) unsafe fn from_duckdb_logical_type(ty: ffi::duckdb_logical_type) -> Option<LogicalType> {
+        unsafe fn child(mut ty: ffi::duckdb_logical_type) -> Option<LogicalType> {
+            let child = LogicalType::from_duckdb_logical_type(ty);
+            ffi::duckdb_destroy_logical_type(&mut ty);
+            child
+        }
+        unsafe fn owned(s: *mut c_char) -> String {
+            let owned = CStr::from_ptr(s).to_string_lossy().into_owned();
+            ffi::duckdb_free(s.cast());
+            owned
+        }
+        let ty = match ffi::duckdb_get_type_id(ty) {
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_DECIMAL => {
+                match (ffi::duckdb_decimal_width(ty), ffi::duckdb_decimal_scale(ty)) {
+                    (38, 0) => LogicalType::HugeInt,
+                    (width, scale) => LogicalType::Decimal { width, scale },
+                }
+            }
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_ENUM => LogicalType::Enum(
+                (0..ffi::duckdb_enum_dictionary_size(ty))
+                    .map(|i| owned(ffi::duckdb_enum_dictionary_value(ty, i as u64)))
+                    .collect(),
+            ),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_LIST => {
+                LogicalType::List(Box::new(child(ffi::duckdb_list_type_child_type(ty))?))
+            }
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_ARRAY => LogicalType::Array(
+                Box::new(child(ffi::duckdb_array_type_child_type(ty))?),
+                ffi::duckdb_array_type_array_size(ty).try_into().ok()?,
+            ),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_MAP => LogicalType::Map(
+                Box::new(child(ffi::duckdb_map_type_key_type(ty))?),
+                Box::new(child(ffi::duckdb_map_type_value_type(ty))?),
+            ),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_STRUCT => LogicalType::Struct(
+                (0..ffi::duckdb_struct_type_child_count(ty))
+                    .map(|i| {
+                        let name = owned(ffi::duckdb_struct_type_child_name(ty, i));
+                        Some((name, child(ffi::duckdb_struct_type_child_type(ty, i))?))
+                    })
+                    .collect::<Option<_>>()?,
+            ),
+            ffi::DUCKDB_TYPE_DUCKDB_TYPE_UNION => LogicalType::Union(
+                (0..ffi::duckdb_union_type_member_count(ty))
+                    .map(|i| {
+                        let name = owned(ffi::duckdb_union_type_member_name(ty, i));
+                        Some((name, child(ffi::duckdb_union_type_member_type(ty, i))?))
+                    })
+                    .collect::<Option<_>>()?,
+            ),
+            id => Self::from_duckdb_type(id)?,
+        };
+        Some(ty)
+    }
+
+    /// Parses a type spelled the way DuckDB spells it, such as in the
+    /// `data_type` column of `duckdb_columns()`. Types without an Arrow
+    /// counterpart map to the type their values read as, like `UUID` to
+    /// [`Text`](LogicalType::Text). Returns `None` for types this crate can't
+    /// read, such as `TIME WITH TIME ZONE`.
+    pub(crate) fn parse(s: &str) -> Option<LogicalType> {
+        let mut parser = TypeParser { rest: s };
+        let ty = parser.parse_type()?;
+        parser.rest.is_empty().then_some(ty)
+    }
+}
+
+/// A recursive descent parser for DuckDB's spelling of types.
+struct TypeParser<'a> {
+    rest: &'a str,
+}
+
+impl TypeParser<'_> {
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, prefix: &str) -> Option<()> {
+        self.eat(prefix).then_some(())
+    }
+
+    fn parse_type(&mut self) -> Option<LogicalType> {
+        let mut ty = self.parse_base()?;
+        while self.eat("[") {
+            ty = if self.eat("]") {
+                LogicalType::List(Box::new(ty))
+            } else {
+                let size = self.number()?;
+                self.expect("]")?;
+                LogicalType::Array(Box::new(ty), size)
+            };
+        }
+        Some(ty)
+    }
+
+    fn parse_base(&mut self) -> Option<LogicalType> {
+        if self.eat("TIMESTAMP WITH TIME ZONE") {
+            return Some(LogicalType::TimestampTz(TimeUnit::Microsecond));
+        }
+        if self.eat("STRUCT(") {
+            return self.fields().map(LogicalType::Struct);
+        }
+        if self.eat("UNION(") {
+            return self.fields().map(LogicalType::Union);
+        }
+        if self.eat("MAP(") {
+            let key = self.parse_type()?;
+            self.expect(", ")?;
+            let value = self.parse_type()?;
+            self.expect(")")?;
+            return Some(LogicalType::Map(Box::new(key), Box::new(value)));
+        }
+        if self.eat("ENUM(") {
+            let mut entries = vec![self.string()?];
+            while self.eat(", ") {
+                entries.push(self.string()?);
+            }
+            self.expect(")")?;
+            return Some(LogicalType::Enum(entries));
+        }
+        if self.eat("DECIMAL(") {
+            let width = self.number()?.try_into().ok()?;
+            self.expect(",")?;
+            let scale = self.number()?.try_into().ok()?;
+            self.expect(")")?;
+            return Some(match (width, scale) {
+                (38, 0) => LogicalType::HugeInt,
+                (width, scale) => LogicalType::Decimal { width, scale },
+            });
+        }
+        let len = self
+            .rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest.len());
+        let ty = match &self.rest[..len] {
+            "NULL" => LogicalType::Null,
+            "BOOLEAN" => LogicalType::Boolean,
+            "TINYINT" => LogicalType::TinyInt,
+            "SMALLINT" => LogicalType::SmallInt,
+            "INTEGER" => LogicalType::Int,
+            "BIGINT" => LogicalType::BigInt,
+            "HUGEINT" => LogicalType::HugeInt,
+            "UTINYINT" => LogicalType::UTinyInt,
+            "USMALLINT" => LogicalType::USmallInt,
+            "UINTEGER" => LogicalType::UInt,
+            "UBIGINT" => LogicalType::UBigInt,
+            "UHUGEINT" => LogicalType::UHugeInt,
+            "FLOAT" => LogicalType::Float,
+            "DOUBLE" => LogicalType::Double,
+            "TIMESTAMP_S" => LogicalType::Timestamp(TimeUnit::Second),
+            "TIMESTAMP_MS" => LogicalType::Timestamp(TimeUnit::Millisecond),
+            "TIMESTAMP" => LogicalType::Timestamp(TimeUnit::Microsecond),
+            "TIMESTAMP_NS" => LogicalType::Timestamp(TimeUnit::Nanosecond),
+            "VARCHAR" | "UUID" | "JSON" => LogicalType::Text,
+            "BLOB" | "BIT" => LogicalType::Blob,
+            "DATE" => LogicalType::Date32,
+            "TIME" => LogicalType::Time64,
+            "INTERVAL" => LogicalType::Interval,
+            _ => return None,
+        };
+        self.rest = &self.rest[len..];
+        Some(ty)
+    }
+
+    /// The `name TYPE, ...)` ending a `STRUCT` or `UNION`.
+    fn fields(&mut self) -> Option<Vec<(String, LogicalType)>> {
+        let mut fields = Vec::new();
+        loop {
+            let name = if self.eat("\"") {
+                self.quoted('"')?
+            } else {
+                let len = self.rest.find(' ')?;
+                let name = self.rest[..len].to_owned();
+                self.rest = &self.rest[len..];
+                name
+            };
+            self.expect(" ")?;
+            fields.push((name, self.parse_type()?));
+            if self.eat(")") {
+                return Some(fields);
+            }
+            self.expect(", ")?;
+        }
+    }
+
+    /// A `'...'` string.
+    fn string(&mut self) -> Option<String> {
+        self.expect("'")?;
+        self.quoted('\'')
+    }
+
+    /// The rest of a string or identifier opened by `quote`, which is doubled
+    /// inside it.
+    fn quoted(&mut self, quote: char) -> Option<String> {
+        let mut value = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c != quote {
+                value.push(c);
+            } else if self.rest[i + 1..].starts_with(quote) {
+                value.push(quote);
+                chars.next();
+            } else {
+                self.rest = &self.rest[i + 1..];
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let len = self.rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest.len());
+        let number = self.rest[..len].parse().ok()?;
+        self.rest = &self.rest[len..];
+        Some(number)
+    }
+}
+
+fn arrow_time_unit(unit: TimeUnit) -> datatypes::TimeUnit {
+    match unit {
+        TimeUnit::Second => datatypes::TimeUnit::Second,
+        TimeUnit::Millisecond => datatypes::TimeUnit::Millisecond,
+        TimeUnit::Microsecond => datatypes::TimeUnit::Microsecond,
+        TimeUnit::Nanosecond => datatypes::TimeUnit::Nanosecond,
+    }
+}
+
+fn time_unit(unit: &datatypes::TimeUnit) -> TimeUnit {
+    match unit {
+        datatypes::TimeUnit::Second => TimeUnit::Second,
+        datatypes::TimeUnit::Millisecond => TimeUnit::Millisecond,
+        datatypes::TimeUnit::Microsecond => TimeUnit::Microsecond,
+        datatypes::TimeUnit::Nanosecond => TimeUnit::Nanosecond,
+    }
+}
+
+impl Value {
+    /// Returns the full DuckDB type of the value.
+    ///
+    /// An owned value only knows about itself, so the type is inferred from
+    /// it: the element type of a `LIST`, `ARRAY` or `MAP` is that of its
+    /// first non-`NULL` element, or [`Null`](LogicalType::Null) if there is
+    /// none, an `ENUM`'s dictionary holds only the value itself, and a
+    /// `UNION` only its active member. Use
+    /// [`ValueRef::logical_type`](super::ValueRef::logical_type) on a row's
+    /// values for the column's exact type.
+    ///
+    /// Returns `None` if the value has no DuckDB type, which is an `ARRAY`
+    /// with more elements than fit in its size.
+    pub fn logical_type(&self) -> Option<LogicalType> {
+        /// The type of the first non-`NULL` value.
+        fn element<'a>(mut values: impl Iterator<Item = &'a Value>) -> Option<Box<LogicalType>> {
+            let value = values.find(|v| !matches!(v, Value::Null)).unwrap_or(&Value::Null);
+            Some(Box::new(value.logical_type()?))
+        }
+
+        let ty = match self {
+            Value::Null => LogicalType::Null,
+            Value::Boolean(_) => LogicalType::Boolean,
+            Value::TinyInt(_) => LogicalType::TinyInt,
+            Value::SmallInt(_) => LogicalType::SmallInt,
+            Value::Int(_) => LogicalType::Int,
+            Value::BigInt(_) => LogicalType::BigInt,
+            Value::HugeInt(_) => LogicalType::HugeInt,
+            Value::UTinyInt(_) => LogicalType::UTinyInt,
+            Value::USmallInt(_) => LogicalType::USmallInt,
+            Value::UInt(_) => LogicalType::UInt,
+            Value::UBigInt(_) => LogicalType::UBigInt,
+            Value::UHugeInt(_) => LogicalType::UHugeInt,
+            Value::Float(_) => LogicalType::Float,
+            Value::Double(_) => LogicalType::Double,
+            Value::Decimal { width, scale, .. } => LogicalType::Decimal {
+                width: *width,
+                scale: *scale,
+            },
+            Value::Timestamp(unit, _) => LogicalType::Timestamp(*unit),
+            Value::TimestampTz(unit, _) => LogicalType::TimestampTz(*unit),
+            Value::Text(_) => LogicalType::Text,
+            Value::Blob(_) => LogicalType::Blob,
+            Value::Date32(_) => LogicalType::Date32,
+            Value::Time64(..) => LogicalType::Time64,
+            Value::Interval { .. } => LogicalType::Interval,
+            Value::List(items) => LogicalType::List(element(items.iter())?),
+            Value::Array(items) => LogicalType::Array(element(items.iter())?, items.len().try_into().ok()?),
+            Value::Enum(s) => LogicalType::Enum(vec![s.clone()]),
+            Value::Struct(fields) => LogicalType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, value)| Some((name.clone(), value.logical_type()?)))
+                    .collect::<Option<_>>()?,
+            ),
+            Value::Map(entries) => LogicalType::Map(
+                element(entries.iter().map(|(k, _)| k))?,
+                element(entries.iter().map(|(_, v)| v))?,
+            ),
+            Value::Union { tag, value } => LogicalType::Union(vec![(tag.clone(), value.logical_type()?)]),
+        };
+        Some(ty)
+    }
+}
+
+impl From<&LogicalType> for Type {
+    fn from(value: &LogicalType) -> Self {
+        match value {
+            LogicalType::Null => Type::Null,
+            LogicalType::Boolean => Type::Boolean,
+            LogicalType::TinyInt => Type::TinyInt,
+            LogicalType::SmallInt => Type::SmallInt,
+            LogicalType::Int => Type::Int,
+            LogicalType::BigInt => Type::BigInt,
+            LogicalType::HugeInt => Type::HugeInt,
+            LogicalType::UTinyInt => Type::UTinyInt,
+            LogicalType::USmallInt => Type::USmallInt,
+            LogicalType::UInt => Type::UInt,
+            LogicalType::UBigInt => Type::UBigInt,
+            LogicalType::UHugeInt => Type::UHugeInt,
+            LogicalType::Float => Type::Float,
+            LogicalType::Double => Type::Double,
+            LogicalType::Decimal { .. } => Type::Decimal,
+            LogicalType::Timestamp(_) => Type::Timestamp,
+            LogicalType::TimestampTz(_) => Type::TimestampTz,
+            LogicalType::Text => Type::Text,
+            LogicalType::Blob => Type::Blob,
+            LogicalType::Date32 => Type::Date32,
+            LogicalType::Time64 => Type::Time64,
+            LogicalType::Interval => Type::Interval,
+            LogicalType::List(item) => Type::List(Box::new(item.as_ref().into())),
+            LogicalType::Array(item, size) => Type::Array(Box::new(item.as_ref().into()), *size),
+            LogicalType::Enum(_) => Type::Enum,
+            LogicalType::Struct(fields) => {
+                Type::Struct(fields.iter().map(|(name, ty)| (name.clone(), ty.into())).collect())
+            }
+            LogicalType::Map(key, value) => Type::Map(Box::new(key.as_ref().into()), Box::new(value.as_ref().into())),
+            LogicalType::Union(_) => Type::Union,
+        }
+    }
+}
+
+impl fmt::Display for LogicalType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogicalType::Null => f.write_str("NULL"),
+            LogicalType::Boolean => f.write_str("BOOLEAN"),
+            LogicalType::TinyInt => f.write_str("TINYINT"),
+            LogicalType::SmallInt => f.write_str("SMALLINT"),
+            LogicalType::Int => f.write_str("INTEGER"),
+            LogicalType::BigInt => f.write_str("BIGINT"),
+            LogicalType::HugeInt => f.write_str("HUGEINT"),
+            LogicalType::UTinyInt => f.write_str("UTINYINT"),
+            LogicalType::USmallInt => f.write_str("USMALLINT"),
+            LogicalType::UInt => f.write_str("UINTEGER"),
+            LogicalType::UBigInt => f.write_str("UBIGINT"),
+            LogicalType::UHugeInt => f.write_str("UHUGEINT"),
+            LogicalType::Float => f.write_str("FLOAT"),
+            LogicalType::Double => f.write_str("DOUBLE"),
+            LogicalType::Decimal { width, scale } => write!(f, "DECIMAL({width},{scale})"),
+            LogicalType::Timestamp(TimeUnit::Second) => f.write_str("TIMESTAMP_S"),
+            LogicalType::Timestamp(TimeUnit::Millisecond) => f.write_str("TIMESTAMP_MS"),
+            LogicalType::Timestamp(TimeUnit::Microsecond) => f.write_str("TIMESTAMP"),
+            LogicalType::Timestamp(TimeUnit::Nanosecond) => f.write_str("TIMESTAMP_NS"),
+            LogicalType::TimestampTz(_) => f.write_str("TIMESTAMP WITH TIME ZONE"),
+            LogicalType::Text => f.write_str("VARCHAR"),
+            LogicalType::Blob => f.write_str("BLOB"),
+            LogicalType::Date32 => f.write_str("DATE"),
+            LogicalType::Time64 => f.write_str("TIME"),
+            LogicalType::Interval => f.write_str("INTERVAL"),
+            LogicalType::List(item) => write!(f, "{item}[]"),
+            LogicalType::Array(item, size) => write!(f, "{item}[{size}]"),
+            LogicalType::Enum(entries) => {
+                f.write_str("ENUM(")?;
+                for (i, entry) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "'{}'", entry.replace('\'', "''"))?;
+                }
+                f.write_str(")")
+            }
+            LogicalType::Struct(fields) => write_fields(f, "STRUCT", fields),
+            LogicalType::Map(key, value) => write!(f, "MAP({key}, {value})"),
+            LogicalType::Union(members) => write_fields(f, "UNION", members),
+        }
+    }
+}
+
+/// Writes `name(field TYPE, ...)`, quoting the field names DuckDB would.
+fn write_fields(f: &mut fmt::Formatter<'_>, name: &str, fields: &[(String, LogicalType)]) -> fmt::Result {
+    write!(f, "{name}(")?;
+    for (i, (field, ty)) in fields.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        let plain = field.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+            && field
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if plain {
+            write!(f, "{field} {ty}")?;
+        } else {
+            write!(f, "\"{}\" {ty}", field.replace('"', "\"\""))?;
+        }
+    }
+    f.write_str(")")
+}
+
+#[cfg(test)]
+mod test {
+    use super::LogicalType;
+    use crate::{
+        types::{OrderedMap, TimeUnit, Type, Value},
+        Connection, Result,
+    };
+
+    #[test]
+    fn test_logical_type() -> Result<()> {
+        use LogicalType::*;
+
+        let db = Connection::open_in_memory()?;
+        let int = || Box::new(Int);
+        let cases = [
+            ("NULL", Null),
+            ("true", Boolean),
+            ("1::TINYINT", TinyInt),
+            ("1::HUGEINT", HugeInt),
+            ("1::UBIGINT", UBigInt),
+            ("1.5::FLOAT", Float),
+            ("1.5::DECIMAL(4, 1)", Decimal { width: 4, scale: 1 }),
+            ("1::DECIMAL(18, 3)", Decimal { width: 18, scale: 3 }),
+            ("TIMESTAMP_S '2024-01-01'", Timestamp(TimeUnit::Second)),
+            ("TIMESTAMP_MS '2024-01-01'", Timestamp(TimeUnit::Millisecond)),
+            ("TIMESTAMP '2024-01-01'", Timestamp(TimeUnit::Microsecond)),
+            ("TIMESTAMP_NS '2024-01-01'", Timestamp(TimeUnit::Nanosecond)),
+            ("TIMESTAMPTZ '2024-01-01'", TimestampTz(TimeUnit::Microsecond)),
+            ("DATE '2024-01-01'", Date32),
+            ("TIME '01:02:03'", Time64),
+            ("INTERVAL 1 DAY", Interval),
+            ("'a'", Text),
+            ("'a'::BLOB", Blob),
+            ("'a'::ENUM('a', 'it''s')", Enum(vec!["a".to_owned(), "it's".to_owned()])),
+            ("[1, 2]", List(int())),
+            ("[]::INTEGER[]", List(int())),
+            (
+                "[['a'::ENUM('a', 'b')]]",
+                List(Box::new(List(Box::new(Enum(vec!["a".to_owned(), "b".to_owned()]))))),
+            ),
+            ("array_value(1, 2)", Array(int(), 2)),
+            (
+                "{'a': 1, 'b c': ['x']}",
+                Struct(vec![("a".to_owned(), Int), ("b c".to_owned(), List(Box::new(Text)))]),
+            ),
+            ("MAP {'a': 1}", Map(Box::new(Text), int())),
+            (
+                "union_value(n := 1)::UNION(n INTEGER, s VARCHAR)",
+                Union(vec![("n".to_owned(), Int), ("s".to_owned(), Text)]),
+            ),
+        ];
+        for (expr, expected) in cases {
+            let sql = format!("SELECT {expr}, typeof({expr})");
+            let (ty, name) = db.query_row(&sql, [], |r| {
+                Ok((r.get_ref(0)?.logical_type().unwrap(), r.get::<_, String>(1)?))
+            })?;
+            assert_eq!(ty, expected, "{expr}");
+            assert_eq!(ty.to_string(), name, "{expr}");
+            assert_eq!(LogicalType::parse(&name).as_ref(), Some(&ty), "{expr}");
+        }
+
+        let ty = db.query_row("SELECT {'a': [1]}", [], |r| Ok(r.get_ref(0)?.logical_type().unwrap()))?;
+        assert_eq!(
+            Type::from(&ty),
+            Type::Struct(vec![("a".to_owned(), Type::List(Box::new(Type::Int)))])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_logical_type() {
+        let list = Value::List(vec![Value::Null, Value::Int(1)]);
+        assert_eq!(list.logical_type(), Some(LogicalType::List(Box::new(LogicalType::Int))));
+        assert_eq!(list.data_type(), Type::List(Box::new(Type::Int)));
+        assert_eq!(
+            Value::List(vec![]).logical_type(),
+            Some(LogicalType::List(Box::new(LogicalType::Null)))
+        );
+        assert_eq!(
+            Value::Array(vec![Value::Text("a".to_owned())])
+                .logical_type()
+                .unwrap()
+                .to_string(),
+            "VARCHAR[1]"
+        );
+        let map = Value::Map(OrderedMap::from(vec![(Value::Text("k".to_owned()), Value::BigInt(1))]));
+        assert_eq!(map.logical_type().unwrap().to_string(), "MAP(VARCHAR, BIGINT)");
+        assert_eq!(map.data_type(), Type::Map(Box::new(Type::Text), Box::new(Type::BigInt)));
+        let record = Value::Struct(OrderedMap::from(vec![
+            (
+                "Id".to_owned(),
+                Value::Decimal {
+                    value: 15,
+                    width: 3,
+                    scale: 1,
+                },
+            ),
+            ("at".to_owned(), Value::TimestampTz(TimeUnit::Microsecond, 0)),
+        ]));
+        assert_eq!(
+            record.logical_type().unwrap().to_string(),
+            "STRUCT(\"Id\" DECIMAL(3,1), at TIMESTAMP WITH TIME ZONE)"
+        );
+        assert_eq!(
+            Value::Enum("x".to_owned()).logical_type(),
+            Some(LogicalType::Enum(vec!["x".to_owned()]))
+        );
+        let union = Value::Union {
+            tag: "n".to_owned(),
+            value: Box::new(Value::UHugeInt(1)),
+        };
+        assert_eq!(union.logical_type().unwrap().to_string(), "UNION(n UHUGEINT)");
+    }
+
+    #[test]
+    fn test_parse_logical_type() {
+        use LogicalType::*;
+
+        let text = || Box::new(Text);
+        let cases = [
+            ("UUID", Some(Text)),
+            ("JSON[]", Some(List(text()))),
+            ("BIT", Some(Blob)),
+            ("DECIMAL(38,0)", Some(HugeInt)),
+            ("INTEGER[][2]", Some(Array(Box::new(List(Box::new(Int))), 2))),
+            (
+                "STRUCT(A INTEGER, \"select\" VARCHAR, \"x\"\"y\" ENUM('it''s', 'b'))[]",
+                Some(List(Box::new(Struct(vec![
+                    ("A".to_owned(), Int),
+                    ("select".to_owned(), Text),
+                    ("x\"y".to_owned(), Enum(vec!["it's".to_owned(), "b".to_owned()])),
+                ])))),
+            ),
+            (
+                "MAP(VARCHAR, STRUCT(\"ü\" DOUBLE))",
+                Some(Map(text(), Box::new(Struct(vec![("ü".to_owned(), Double)])))),
+            ),
+            ("TIME WITH TIME ZONE", None),
+            ("STRUCT(t TIME WITH TIME ZONE)", None),
+            ("VARCHAR COLLATE NOCASE", None),
+            ("ENUM('a'", None),
+            ("mood", None),
+            ("", None),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(LogicalType::parse(s), expected, "{s}");
+        }
+    }
+}
@@ -4,10 +4,17 @@
 //! the [`ToSql`] and [`FromSql`] traits are provided for the basic types that
 //! DuckDB provides methods for:
 //!
-//! * Strings (`String` and `&str`)
+//! * Strings (`String` and `&str`, and `Cow<str>` borrowing from the row)
 //! * Blobs (`Vec<u8>` and `&[u8]`)
+//! * Lists (`Vec<T>` and `&[T]`, binding elements that implement [`ListElement`]),
+//!   and arrays as `[T; N]`
+//! * Bit strings ([`BitString`])
+//! * Times with a time zone offset ([`TimeTz`])
 //! * Numbers
 //!
+//! Field-less Rust enums can be mapped onto the entries of an `ENUM` column
+//! with the [`enum_mapping!`](crate::enum_mapping) macro.
+//!
 //! The number situation is a little complicated due to the fact that all
 //! numbers in DuckDB are stored as `INTEGER` (`i64`) or `REAL` (`f64`).
 //!
@@ -16,24 +23,47 @@
 //! the value.
 //!
 //! * `INTEGER` to integer: returns an
-//!   [`Error::IntegralValueOutOfRange`](crate::Error::IntegralValueOutOfRange)
-//!   error if the value does not fit in the Rust type.
+//!   [`Error::ColumnConversionFailure`](crate::Error::ColumnConversionFailure)
+//!   error with a [`FromSqlError::OutOfRange`] source if the value does not
+//!   fit in the Rust type, including negative values read as unsigned ones.
+//!   `DECIMAL` values are read the same way,
+//!   after truncating any fraction, and an owned [`Value`] converts through
+//!   `TryFrom` with the matching [`FromSqlError::OutOfRange`].
 //! * `REAL` to integer: always returns an
-//!   [`Error::InvalidColumnType`](crate::Error::InvalidColumnType) error.
+//!   [`Error::ColumnConversionFailure`](crate::Error::ColumnConversionFailure)
+//!   error with a [`FromSqlError::InvalidType`] source.
 //! * `INTEGER` to float: casts using `as` operator. Never fails.
-//! * `REAL` to float: casts using `as` operator. Never fails.
+//! * `REAL` to float: casts using `as` operator. Only fails for a finite
+//!   `DOUBLE` outside the range of `f32`.
+//!
+//! Floats keep their exact bits as parameters, through the
+//! [`Appender`](crate::Appender), in results and through Arrow record batches:
+//! `NaN` payloads and signs, infinities, -0.0 and subnormals all round-trip.
+//! Inside `LIST`, `ARRAY` and `MAP` parameters, and lists appended with the
+//! `Appender`, floats travel as text, which keeps everything but a `NaN`'s
+//! payload.
+//!
+//! `DECIMAL` columns read as [`Value::Decimal`], keeping their unscaled
+//! value, width and scale, and bind and append back with the same type.
+//! Arrow can't tell a `DECIMAL(38, 0)` from a `HUGEINT`, so those read as
+//! [`Value::HugeInt`]. [`rust_decimal::Decimal`] reads any decimal whose
+//! value fits its 96 bit mantissa.
+//!
+//! Unsigned integers bind as DuckDB's unsigned types: `u8`, `u16` and `u32` as
+//! `UTINYINT`, `USMALLINT` and `UINTEGER`, and `u64` and `usize` as `UBIGINT`,
+//! so the full `u64` range round-trips and compares against `UBIGINT` columns
+//! without going through `i64`.
 //!
-//! [`ToSql`] always succeeds except when storing a `u64` or `usize` value that
-//! cannot fit in an `INTEGER` (`i64`). Also note that DuckDB ignores column
-//! types, so if you store an `i64` in a column with type `REAL` it will be
-//! stored as an `INTEGER`, not a `REAL`.
+//! If the `chrono` or `time` feature is enabled, implementations are provided
+//! for their date, time and timestamp types (for `time`: `Date`, `Time`,
+//! `PrimitiveDateTime` and `OffsetDateTime`). These read DuckDB's `DATE`,
+//! `TIME`, `TIMESTAMP` and `TIMESTAMPTZ` columns directly, and are bound as
+//! ISO 8601 strings with microsecond precision. If you want different storage
+//! for datetimes, you can use a newtype.
 //!
-//! If the `time` feature is enabled, implementations are
-//! provided for `time::OffsetDateTime` that use the RFC 3339 date/time format,
-//! `"%Y-%m-%dT%H:%M:%S.%fZ"`, to store time values as strings.  These values
-//! can be parsed by SQLite's builtin
-//! [datetime](https://www.sqlite.org/lang_datefunc.html) functions.  If you
-//! want different storage for datetimes, you can use a newtype.
+//! Without either feature, `std::time::SystemTime` reads and binds as a
+//! `TIMESTAMP` and `std::time::Duration` as an `INTERVAL`, both truncated to
+//! microseconds.
 #![cfg_attr(
     feature = "time",
     doc = r##"
@@ -41,22 +71,24 @@ For example, to store datetimes as `i64`s counting the number of seconds since
 the Unix epoch:
 
 ```
-use duckdb::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use duckdb::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
 use duckdb::Result;
 
 pub struct DateTimeSql(pub time::OffsetDateTime);
 
 impl FromSql for DateTimeSql {
     fn column_result(value: ValueRef) -> FromSqlResult<Self> {
-        i64::column_result(value).map(|as_i64| {
-            DateTimeSql(time::OffsetDateTime::from_unix_timestamp(as_i64))
+        i64::column_result(value).and_then(|as_i64| {
+            time::OffsetDateTime::from_unix_timestamp(as_i64)
+                .map(DateTimeSql)
+                .map_err(|err| FromSqlError::Other(Box::new(err)))
         })
     }
 }
 
 impl ToSql for DateTimeSql {
     fn to_sql(&self) -> Result<ToSqlOutput> {
-        Ok(self.0.timestamp().into())
+        Ok(self.0.unix_timestamp().into())
     }
 }
 ```
@@ -67,32 +99,67 @@ impl ToSql for DateTimeSql {
 //! implements [`ToSql`] or [`FromSql`] for the cases where you want to know if
 //! a value was NULL (which gets translated to `None`).
 
+#[cfg(feature = "geo")]
+pub use self::geo::Wkb;
+#[cfg(feature = "serde")]
+pub use self::serde::{
+    from_value, to_value, Deserialized, ListSerializer, Serialized, StructSerializer, ValueDeserializer,
+    ValueSerializer,
+};
 pub use self::{
-    from_sql::{FromSql, FromSqlError, FromSqlResult},
+    bit_string::{BitString, ParseBitStringError},
+    from_sql::{FromSql, FromSqlError, FromSqlRef, FromSqlResult},
+    inet::{InetAddr, ParseInetAddrError},
+    logical_type::LogicalType,
     ordered_map::OrderedMap,
-    to_sql::{ToSql, ToSqlOutput},
+    time_tz::{ParseTimeTzError, TimeTz},
+    to_sql::{ListElement, ToSql, ToSqlOutput},
     value::Value,
     value_ref::{EnumType, ListType, TimeUnit, ValueRef},
 };
 
+pub(crate) use self::{
+    to_sql::{list_literal, owned_items},
+    value::{check_decimal, decimal_text},
+    value_handle::ValueHandle,
+};
+
 use arrow::datatypes::DataType;
 use std::fmt;
 
+mod bit_string;
 #[cfg(feature = "chrono")]
 mod chrono;
+mod enum_mapping;
 mod from_sql;
+#[cfg(feature = "geo")]
+mod geo;
+mod inet;
+mod logical_type;
+#[cfg(feature = "serde")]
+mod serde;
 #[cfg(feature = "serde_json")]
 mod serde_json;
+#[cfg(feature = "time")]
+mod time;
+mod time_tz;
 mod to_sql;
 #[cfg(feature = "url")]
 mod url;
 mod value;
+mod value_handle;
 mod value_ref;
 
 mod ordered_map;
 
 /// Empty struct that can be used to fill in a query parameter as `NULL`.
 ///
+/// The `NULL` has no type of its own, like `Option::None` and
+/// [`Value::Null`]: DuckDB's C API can't bind a `NULL` of a given type. Where
+/// the type of the parameter matters, such as an argument to an overloaded
+/// function, cast the placeholder to the type in the SQL, as in
+/// `?::INTEGER`.
+///
 /// ## Example
 ///
 /// ```rust,no_run
@@ -106,6 +173,15 @@ mod ordered_map;
 #[derive(Copy, Clone)]
 pub struct Null;
 
+/// Formats a TIMESTAMPTZ as a string with an explicit UTC offset.
+///
+/// The C API can only bind or append a plain TIMESTAMP, which DuckDB then
+/// casts using the session time zone, so instants are passed as strings
+/// instead.
+pub(crate) fn timestamptz_to_string(unit: TimeUnit, t: i64) -> String {
+    Value::TimestampTz(unit, t).to_string()
+}
+
 /// DuckDB data types.
 /// See [Fundamental Datatypes](https://duckdb.org/docs/sql/data_types/overview).
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -132,6 +208,8 @@ pub enum Type {
     UInt,
     /// UBIGINT
     UBigInt,
+    /// UHUGEINT
+    UHugeInt,
     /// FLOAT
     Float,
     /// DOUBLE
@@ -140,6 +218,8 @@ pub enum Type {
     Decimal,
     /// TIMESTAMP
     Timestamp,
+    /// TIMESTAMP WITH TIME ZONE
+    TimestampTz,
     /// Text
     Text,
     /// BLOB
@@ -180,9 +260,10 @@ impl From<&DataType> for Type {
             DataType::UInt32 => Self::UInt,
             DataType::UInt64 => Self::UBigInt,
             // DataType::Float16 => Self::Float16,
-            // DataType::Float32 => Self::Float32,
-            DataType::Float64 => Self::Float,
-            DataType::Timestamp(_, _) => Self::Timestamp,
+            DataType::Float32 => Self::Float,
+            DataType::Float64 => Self::Double,
+            DataType::Timestamp(_, None) => Self::Timestamp,
+            DataType::Timestamp(_, Some(_)) => Self::TimestampTz,
             DataType::Date32 => Self::Date32,
             // DataType::Date64 => Self::Date64,
             // DataType::Time32(_) => Self::Time32,
@@ -205,7 +286,7 @@ impl From<&DataType> for Type {
                     .collect(),
             ),
             DataType::LargeList(inner) => Self::List(Box::new(Type::from(inner.data_type()))),
-            // DataType::Union(_, _) => Self::Union,
+            DataType::Union(..) => Self::Union,
             DataType::Decimal128(..) => Self::Decimal,
             DataType::Decimal256(..) => Self::Decimal,
             DataType::Map(field, ..) => {
@@ -237,10 +318,12 @@ impl fmt::Display for Type {
             Type::USmallInt => f.pad("USmallInt"),
             Type::UInt => f.pad("UInt"),
             Type::UBigInt => f.pad("UBigInt"),
+            Type::UHugeInt => f.pad("UHugeInt"),
             Type::Float => f.pad("Float"),
             Type::Double => f.pad("Double"),
             Type::Decimal => f.pad("Decimal"),
             Type::Timestamp => f.pad("Timestamp"),
+            Type::TimestampTz => f.pad("TimestampTz"),
             Type::Text => f.pad("Text"),
             Type::Blob => f.pad("Blob"),
             Type::Date32 => f.pad("Date32"),
@@ -259,8 +342,8 @@ impl fmt::Display for Type {
 
 #[cfg(test)]
 mod test {
-    use super::Value;
-    use crate::{params, Connection, Error, Result, Statement};
+    use super::{FromSqlError, Null, Value};
+    use crate::{params, Connection, Error, ErrorKind, Result, Statement};
     use std::{
         f64::EPSILON,
         os::raw::{c_double, c_int},
@@ -288,7 +371,7 @@ mod test {
     fn test_empty_blob() -> Result<()> {
         let db = checked_memory_handle()?;
 
-        let empty = vec![];
+        let empty: Vec<u8> = vec![];
         db.execute("INSERT INTO foo(b) VALUES (?)", [&empty])?;
 
         let v: Vec<u8> = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
@@ -369,7 +452,13 @@ mod test {
     #[ignore = "duckdb doesn't support this"]
     fn test_mismatched_types() -> Result<()> {
         fn is_invalid_column_type(err: Error) -> bool {
-            matches!(err, Error::InvalidColumnType(..))
+            matches!(
+                err,
+                Error::ColumnConversionFailure {
+                    source: FromSqlError::InvalidType,
+                    ..
+                }
+            )
         }
 
         let db = checked_memory_handle()?;
@@ -531,9 +620,9 @@ mod test {
         test_conversion!(db_etc, 400u16, u8, expect_from_sql_error);
         test_conversion!(db_etc, -1i8, u8, expect_from_sql_error);
         test_conversion!(db_etc, i64::MIN, u64, expect_from_sql_error);
-        test_conversion!(db_etc, u64::MAX, i64, expect_to_sql_error);
-        test_conversion!(db_etc, u64::MAX, u64, expect_to_sql_error);
-        test_conversion!(db_etc, i64::MAX as u64 + 1, u64, expect_to_sql_error);
+        test_conversion!(db_etc, u64::MAX, i64, expect_from_sql_error);
+        test_conversion!(db_etc, u64::MAX, u64, expect u64::MAX);
+        test_conversion!(db_etc, i64::MAX as u64 + 1, u64, expect i64::MAX as u64 + 1);
 
         // FromSql integer to float, always works.
         test_conversion!(db_etc, i64::MIN, f32, expect i64::MIN as f32);
@@ -546,4 +635,70 @@ mod test {
         test_conversion!(db_etc, 0f64, i64, expect_from_sql_error);
         Ok(())
     }
+
+    #[test]
+    fn test_typed_null() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let untyped: String = db.query_row("SELECT typeof(?)", [Null], |r| r.get(0))?;
+        assert!(untyped.contains("NULL"), "{untyped}");
+        let typed: String = db.query_row("SELECT typeof(?::INTEGER[])", [Null], |r| r.get(0))?;
+        assert_eq!(typed, "INTEGER[]");
+        let len: Option<i64> = db.query_row("SELECT len(?::INTEGER[])", [Null], |r| r.get(0))?;
+        assert_eq!(len, None);
+
+        db.execute_batch("CREATE TABLE not_null (x INTEGER NOT NULL)")?;
+        let err = db
+            .execute("INSERT INTO not_null VALUES (?::INTEGER)", [Null])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Constraint, "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_value() -> Result<()> {
+        use super::{OrderedMap, ValueRef};
+
+        let db = Connection::open_in_memory()?;
+        let sql = "SELECT [{'a': [1, NULL], 'b': MAP {'k': [{'x': 'y'}]}}, NULL] AS v, [[1, 2]::INTEGER[2]] AS w";
+        let text = |s: &str| Value::Text(s.to_owned());
+        let expected = Value::List(vec![
+            Value::Struct(OrderedMap::from(vec![
+                ("a".to_owned(), Value::List(vec![Value::Int(1), Value::Null])),
+                (
+                    "b".to_owned(),
+                    Value::Map(OrderedMap::from(vec![(
+                        text("k"),
+                        Value::List(vec![Value::Struct(OrderedMap::from(vec![("x".to_owned(), text("y"))]))]),
+                    )])),
+                ),
+            ])),
+            Value::Null,
+        ]);
+        let (v, w): (Value, Value) = db.query_row(sql, [], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        assert_eq!(v, expected);
+        assert_eq!(w, Value::List(vec![Value::Array(vec![Value::Int(1), Value::Int(2)])]));
+
+        // The same, walked by reference.
+        let mut stmt = db.prepare(sql)?;
+        let mut rows = stmt.query([])?;
+        let row = rows.next()?.unwrap();
+        let v = row.get_ref(0)?;
+        let items = v.as_list()?;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1], ValueRef::Null);
+        let fields = items[0].as_struct()?;
+        assert_eq!(fields.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(fields[0].1.as_list()?, [ValueRef::Int(1), ValueRef::Null]);
+        let entries = fields[1].1.as_map()?;
+        assert_eq!(entries[0].0.as_str()?, "k");
+        let inner = entries[0].1.as_list()?[0].as_struct()?;
+        assert_eq!(inner[0].0, "x");
+        assert_eq!(inner[0].1.as_str()?, "y");
+        assert_eq!(v.to_owned(), expected);
+
+        let w = row.get_ref(1)?.as_list()?;
+        assert_eq!(w[0].as_list()?, [ValueRef::Int(1), ValueRef::Int(2)]);
+        assert_eq!(w[0].as_struct(), Err(FromSqlError::InvalidType));
+        Ok(())
+    }
 }
@@ -8,6 +8,15 @@ impl<K: PartialEq, V> From<Vec<(K, V)>> for OrderedMap<K, V> {
     }
 }
 
+impl<K, V> IntoIterator for OrderedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
 impl<K: std::cmp::PartialEq, V> OrderedMap<K, V> {
     /// Returns the value corresponding to the key.
     pub fn get(&self, key: &K) -> Option<&V> {
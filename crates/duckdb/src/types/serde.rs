@@ -0,0 +1,819 @@
+//! Deserialize [`Value`]s, such as `STRUCT` columns, into types implementing
+//! `serde::Deserialize`, and serialize types implementing `serde::Serialize`
+//! into `STRUCT` parameters. Whole rows deserialize the same way through
+//! [`Row::deserialize`](crate::Row::deserialize).
+//!
+//! ```rust
+//! # use duckdb::{types::Deserialized, Connection, Result};
+//! #[derive(serde::Deserialize)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! fn get_point(conn: &Connection) -> Result<Point> {
+//!     conn.query_row("SELECT {'x': 1, 'y': 2}", [], |row| {
+//!         row.get::<_, Deserialized<Point>>(0).map(|p| p.0)
+//!     })
+//! }
+//! ```
+//!
+//! ```rust
+//! # use duckdb::{types::Serialized, Connection, Result};
+//! #[derive(serde::Serialize)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! fn count_points(conn: &Connection, point: Point) -> Result<i64> {
+//!     conn.query_row("SELECT count(*) FROM points WHERE p = ?", [Serialized(point)], |row| row.get(0))
+//! }
+//! ```
+
+use serde::{
+    de::{
+        self,
+        value::{Error, MapDeserializer, SeqDeserializer},
+        DeserializeOwned, IntoDeserializer, Visitor,
+    },
+    ser::{self, Serialize},
+};
+
+use super::{
+    value::decimal_text, FromSql, FromSqlError, FromSqlResult, ListElement, OrderedMap, ToSql, ToSqlOutput, Value,
+    ValueRef,
+};
+
+/// Deserialize a [`Value`] into `T`.
+///
+/// Struct fields are matched by name. Fields missing from the value are an
+/// error naming the field, unknown fields are ignored unless `T` opts into
+/// `#[serde(deny_unknown_fields)]`.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(ValueDeserializer(value))
+}
+
+/// Wrapper reading a column through [`from_value`], since `FromSql` can't be
+/// implemented for every `T: Deserialize` directly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deserialized<T>(pub T);
+
+impl<T: DeserializeOwned> FromSql for Deserialized<T> {
+    #[inline]
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        from_value(value.to_owned())
+            .map(Deserialized)
+            .map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+/// Serialize `T` into a [`Value`].
+///
+/// Structs, and maps with string keys, become `STRUCT`s; other map keys are an
+/// error. Sequences and tuples become `LIST`s, and `None` and `()` become
+/// `NULL`. Enum variants are written as `serde_json` writes them: unit variants
+/// as their name, others as a single-field `STRUCT` keyed by it.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+    value.serialize(ValueSerializer)
+}
+
+/// Wrapper binding a parameter through [`to_value`], since `ToSql` can't be
+/// implemented for every `T: Serialize` directly.
+///
+/// The C API can't create `NULL` fields or list elements, so serializing a
+/// `None` anywhere but at the top level fails to bind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Serialized<T>(pub T);
+
+impl<T: Serialize> ToSql for Serialized<T> {
+    #[inline]
+    fn to_sql(&self) -> crate::Result<ToSqlOutput<'_>> {
+        to_value(&self.0)
+            .map(ToSqlOutput::Owned)
+            .map_err(|err| crate::Error::ToSqlConversionFailure(Box::new(err)))
+    }
+}
+
+impl<T: Serialize> ListElement for Serialized<T> {}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = ValueDeserializer;
+
+    #[inline]
+    fn into_deserializer(self) -> Self::Deserializer {
+        ValueDeserializer(self)
+    }
+}
+
+/// A `serde::Deserializer` over an owned [`Value`].
+#[derive(Debug)]
+pub struct ValueDeserializer(Value);
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Boolean(b) => visitor.visit_bool(b),
+            Value::TinyInt(i) => visitor.visit_i8(i),
+            Value::SmallInt(i) => visitor.visit_i16(i),
+            Value::Int(i) => visitor.visit_i32(i),
+            Value::BigInt(i) => visitor.visit_i64(i),
+            Value::HugeInt(i) => visitor.visit_i128(i),
+            Value::UTinyInt(i) => visitor.visit_u8(i),
+            Value::USmallInt(i) => visitor.visit_u16(i),
+            Value::UInt(i) => visitor.visit_u32(i),
+            Value::UBigInt(i) => visitor.visit_u64(i),
+            Value::UHugeInt(i) => visitor.visit_u128(i),
+            Value::Float(f) => visitor.visit_f32(f),
+            Value::Double(f) => visitor.visit_f64(f),
+            Value::Decimal { value, scale, .. } => visitor.visit_string(decimal_text(value, scale)),
+            Value::Timestamp(unit, t) | Value::TimestampTz(unit, t) => visitor.visit_i64(unit.to_micros(t)),
+            Value::Date32(d) => visitor.visit_i32(d),
+            Value::Time64(unit, t) => visitor.visit_i64(unit.to_micros(t)),
+            Value::Interval { .. } => Err(de::Error::custom("cannot deserialize an INTERVAL")),
+            Value::Text(s) | Value::Enum(s) => visitor.visit_string(s),
+            Value::Blob(b) => visitor.visit_byte_buf(b),
+            Value::List(items) | Value::Array(items) => visitor.visit_seq(SeqDeserializer::new(items.into_iter())),
+            Value::Struct(fields) => visitor.visit_map(MapDeserializer::new(fields.into_iter())),
+            Value::Map(entries) => visitor.visit_map(MapDeserializer::new(entries.into_iter())),
+            Value::Union { value, .. } => ValueDeserializer(*value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Text(s) | Value::Enum(s) => s.into_deserializer().deserialize_enum(name, variants, visitor),
+            Value::Union { value, .. } => ValueDeserializer(*value).deserialize_enum(name, variants, visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// A `serde::Serializer` producing a [`Value`].
+#[derive(Debug)]
+pub struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = StructSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = StructSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::TinyInt(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::SmallInt(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::Int(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::BigInt(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        Ok(Value::HugeInt(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::UTinyInt(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::USmallInt(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::UInt(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::UBigInt(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        Ok(Value::UHugeInt(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::Double(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Text(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Text(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Blob(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Value, Error> {
+        Ok(Value::Text(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        Ok(tagged(Some(variant), value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer, Error> {
+        Ok(ListSerializer {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ListSerializer, Error> {
+        Ok(ListSerializer {
+            items: Vec::with_capacity(len),
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<StructSerializer, Error> {
+        Ok(StructSerializer {
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<StructSerializer, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer, Error> {
+        Ok(StructSerializer {
+            fields: Vec::with_capacity(len),
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+/// Wraps the value of an enum variant in a `STRUCT` keyed by its name.
+fn tagged(variant: Option<&'static str>, value: Value) -> Value {
+    match variant {
+        Some(variant) => Value::Struct(OrderedMap::from(vec![(variant.to_owned(), value)])),
+        None => value,
+    }
+}
+
+/// Collects the items of a sequence or tuple into a `LIST`.
+#[derive(Debug)]
+pub struct ListSerializer {
+    items: Vec<Value>,
+    variant: Option<&'static str>,
+}
+
+impl ListSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        Ok(tagged(self.variant, Value::List(self.items)))
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for ListSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+/// Collects the fields of a struct, or the entries of a map, into a `STRUCT`.
+#[derive(Debug)]
+pub struct StructSerializer {
+    fields: Vec<(String, Value)>,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl StructSerializer {
+    fn finish(self) -> Result<Value, Error> {
+        Ok(tagged(self.variant, Value::Struct(OrderedMap::from(self.fields))))
+    }
+}
+
+impl ser::SerializeMap for StructSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        match key.serialize(ValueSerializer)? {
+            Value::Text(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            key => Err(ser::Error::custom(format!("STRUCT keys must be strings, not {key}"))),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| <Error as ser::Error>::custom("serialize_value called before serialize_key"))?;
+        self.fields.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.fields.push((key.to_owned(), value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for StructSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{from_value, to_value, Deserialized, Serialized};
+    use crate::{types::Value, Connection, Error, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Inner {
+        c: Vec<Option<i32>>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Foo {
+        a: i64,
+        b: String,
+        inner: Inner,
+        maybe: Option<f64>,
+    }
+
+    #[test]
+    fn test_struct() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let foo: Deserialized<Foo> = db.query_row(
+            "SELECT {'a': 1, 'b': 'x', 'inner': {'c': [1, NULL]}, 'maybe': NULL, 'extra': true}",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(
+            Foo {
+                a: 1,
+                b: "x".to_owned(),
+                inner: Inner { c: vec![Some(1), None] },
+                maybe: None,
+            },
+            foo.0
+        );
+
+        let value: Value = db.query_row("SELECT {'c': []::INTEGER[]}", [], |r| r.get(0))?;
+        assert!(matches!(value, Value::Struct(..)));
+        assert_eq!(Inner { c: vec![] }, from_value::<Inner>(value).unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_missing_field() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .query_row("SELECT {'a': 1, 'b': 'x'}", [], |r| r.get::<_, Deserialized<Foo>>(0))
+            .unwrap_err();
+        match err {
+            Error::ColumnConversionFailure { index: 0, source, .. } => {
+                assert_eq!("missing field `inner`", source.to_string())
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+        Ok(())
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Person {
+        #[serde(rename = "full_name")]
+        name: String,
+        age: Option<i32>,
+        tags: Vec<String>,
+        inner: Inner,
+    }
+
+    #[test]
+    fn test_row_deserialize() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            r#"CREATE TABLE people (id INTEGER, full_name VARCHAR, age INTEGER, tags VARCHAR[], "inner" STRUCT(c INTEGER[]));
+             INSERT INTO people VALUES
+                 (1, 'ann', 42, ['a', 'b'], {'c': [1, NULL]}),
+                 (2, 'bob', NULL, [], {'c': []});"#,
+        )?;
+
+        let mut stmt = db.prepare("SELECT * FROM people ORDER BY id")?;
+        let people = stmt.query_map_into::<Person, _>([])?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            vec![
+                Person {
+                    name: "ann".to_owned(),
+                    age: Some(42),
+                    tags: vec!["a".to_owned(), "b".to_owned()],
+                    inner: Inner { c: vec![Some(1), None] },
+                },
+                Person {
+                    name: "bob".to_owned(),
+                    age: None,
+                    tags: vec![],
+                    inner: Inner { c: vec![] },
+                },
+            ],
+            people
+        );
+
+        // Columns are matched by name, not position.
+        let inner: Inner = db.query_row("SELECT 'x' AS b, [3] AS c", [], |r| r.deserialize())?;
+        assert_eq!(Inner { c: vec![Some(3)] }, inner);
+        Ok(())
+    }
+
+    #[test]
+    fn test_row_deserialize_errors() -> Result<()> {
+        #[derive(Debug, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Strict {
+            #[allow(dead_code)]
+            c: Vec<i32>,
+        }
+
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .query_row("SELECT 'ann' AS full_name, 42 AS age", [], |r| {
+                r.deserialize::<Person>()
+            })
+            .unwrap_err();
+        match err {
+            Error::FromSqlConversionFailure(_, _, err) => assert_eq!("missing field `tags`", err.to_string()),
+            err => panic!("unexpected error {err:?}"),
+        }
+
+        assert!(db
+            .query_row("SELECT [1] AS c", [], |r| r.deserialize::<Strict>())
+            .is_ok());
+        let err = db
+            .query_row("SELECT [1] AS c, 2 AS d", [], |r| r.deserialize::<Strict>())
+            .unwrap_err();
+        assert!(err.to_string().starts_with("unknown field `d`"), "{err}");
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_to_params() -> Result<()> {
+        use crate::to_params;
+        use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+        #[derive(Debug, PartialEq, Serialize)]
+        struct Event {
+            #[serde(rename = "title")]
+            name: String,
+            count: i64,
+            score: f64,
+            note: Option<String>,
+            day: NaiveDate,
+            at: NaiveDateTime,
+            logged: DateTime<Utc>,
+        }
+
+        let db = Connection::open_in_memory()?;
+        // Instants don't depend on the session's time zone.
+        db.execute_batch(
+            "SET TimeZone = 'America/New_York';
+             CREATE TABLE events (title VARCHAR, count BIGINT, score DOUBLE, note VARCHAR, day DATE, at TIMESTAMP, logged TIMESTAMPTZ)",
+        )?;
+        let at = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_micro_opt(12, 30, 0, 250)
+            .unwrap();
+        let events = [
+            Event {
+                name: "it's".to_owned(),
+                count: -3,
+                score: 0.5,
+                note: None,
+                day: at.date(),
+                at,
+                logged: at.and_utc(),
+            },
+            Event {
+                name: "launch".to_owned(),
+                count: i64::MAX,
+                score: -1e300,
+                note: Some("second".to_owned()),
+                day: NaiveDate::from_ymd_opt(1969, 12, 31).unwrap(),
+                at: NaiveDateTime::UNIX_EPOCH,
+                logged: DateTime::UNIX_EPOCH,
+            },
+        ];
+        // Parameters may come in any order.
+        let mut stmt =
+            db.prepare("INSERT INTO events (logged, at, day, note, score, count, title) VALUES ($logged, $at, $day, $note, $score, $count, $title)")?;
+        for event in &events {
+            assert_eq!(1, stmt.execute(to_params(event)?)?);
+        }
+
+        let mut stmt = db.prepare("SELECT * FROM events ORDER BY count")?;
+        let read = stmt
+            .query_map([], |r| {
+                Ok(Event {
+                    name: r.get(0)?,
+                    count: r.get(1)?,
+                    score: r.get(2)?,
+                    note: r.get(3)?,
+                    day: r.get(4)?,
+                    at: r.get(5)?,
+                    logged: r.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(events.as_slice(), read.as_slice());
+        let matches: bool = db.query_row(
+            "SELECT day = DATE '2024-02-29' AND at = TIMESTAMP '2024-02-29 12:30:00.00025'
+                AND logged = TIMESTAMPTZ '2024-02-29 12:30:00.00025+00' FROM events WHERE count = -3",
+            [],
+            |r| r.get(0),
+        )?;
+        assert!(matches);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_params_errors() -> Result<()> {
+        use crate::to_params;
+
+        let db = Connection::open_in_memory()?;
+        let params = HashMap::from([("a", 1), ("extra", 2)]);
+        let v: i32 = db.query_row("SELECT $a", to_params(&params)?, |r| r.get(0))?;
+        assert_eq!(1, v);
+
+        let err = db
+            .query_row("SELECT $a", to_params(&params)?.strict(), |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert_eq!(Error::InvalidParameterName("extra".to_owned()), err);
+        let err = db
+            .query_row("SELECT $a + $b", to_params(&params)?, |r| r.get::<_, i32>(0))
+            .unwrap_err();
+        assert_eq!("no field for parameter $b", err.to_string());
+        assert!(matches!(to_params(&[1, 2]), Err(Error::ToSqlConversionFailure(..))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum_and_tuple() -> Result<()> {
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Mood {
+            #[serde(rename = "happy")]
+            Happy,
+            #[serde(rename = "sad")]
+            Sad,
+        }
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TYPE mood AS ENUM ('sad', 'happy')")?;
+        let moods: Deserialized<(Mood, Mood)> =
+            db.query_row("SELECT ['happy'::mood, 'sad'::mood]", [], |r| r.get(0))?;
+        assert_eq!((Mood::Happy, Mood::Sad), moods.0);
+        Ok(())
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Line {
+        name: &'static str,
+        points: Vec<Point>,
+        origin: Point,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Point {
+        x: i32,
+        y: f64,
+    }
+
+    #[test]
+    fn test_serialized_struct_param() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TABLE lines (line STRUCT(name VARCHAR, points STRUCT(x INTEGER, y DOUBLE)[], origin STRUCT(x INTEGER, y DOUBLE)));
+             INSERT INTO lines VALUES ({'name': 'a', 'points': [{'x': 1, 'y': 2.5}], 'origin': {'x': 0, 'y': 0}}),
+                                      ({'name': 'b', 'points': [], 'origin': {'x': 0, 'y': 0}});",
+        )?;
+        let line = Line {
+            name: "a",
+            points: vec![Point { x: 1, y: 2.5 }],
+            origin: Point { x: 0, y: 0.0 },
+        };
+        let name: String = db.query_row("SELECT line.name FROM lines WHERE line = ?", [Serialized(&line)], |r| {
+            r.get(0)
+        })?;
+        assert_eq!(name, "a");
+
+        let point: Value = db.query_row("SELECT ?", [Serialized(Point { x: 3, y: 4.0 })], |r| r.get(0))?;
+        assert_eq!("{'x': 3, 'y': 4.0}", point.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialized_map_param() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let map = BTreeMap::from([("a", vec![1, 2]), ("b", vec![])]);
+        let (ty, value): (String, Value) = db.query_row("SELECT typeof(?1), ?1", [Serialized(&map)], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })?;
+        assert_eq!(ty, "STRUCT(a INTEGER[], b INTEGER[])");
+        assert_eq!("{'a': [1, 2], 'b': []}", value.to_string());
+
+        let err = to_value(&HashMap::from([(1, 2)])).unwrap_err();
+        assert_eq!("STRUCT keys must be strings, not 1", err.to_string());
+        let err = db
+            .query_row("SELECT ?", [Serialized(HashMap::from([(1, 2)]))], |r| {
+                r.get::<_, Value>(0)
+            })
+            .unwrap_err();
+        assert!(matches!(err, Error::ToSqlConversionFailure(_)), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialized_enum() {
+        #[derive(Serialize)]
+        enum Shape {
+            Empty,
+            Circle(f64),
+            Rect { w: i32, h: i32 },
+        }
+
+        assert_eq!(Value::Text("Empty".to_owned()), to_value(&Shape::Empty).unwrap());
+        assert_eq!("{'Circle': 1.5}", to_value(&Shape::Circle(1.5)).unwrap().to_string());
+        assert_eq!(
+            "{'Rect': {'w': 1, 'h': 2}}",
+            to_value(&Shape::Rect { w: 1, h: 2 }).unwrap().to_string()
+        );
+    }
+}
@@ -3,19 +3,24 @@
 use serde_json::Value;
 
 use crate::{
-    types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef},
-    Result,
+    types::{FromSql, FromSqlError, FromSqlResult, ListElement, ToSql, ToSqlOutput, ValueRef},
+    Error, Result,
 };
 
-/// Serialize JSON `Value` to text.
+/// Serialize JSON `Value` to text, which DuckDB casts to `JSON` as needed.
 impl ToSql for Value {
     #[inline]
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        Ok(ToSqlOutput::from(serde_json::to_string(self).unwrap()))
+        serde_json::to_string(self)
+            .map(ToSqlOutput::from)
+            .map_err(|err| Error::ToSqlConversionFailure(Box::new(err)))
     }
 }
 
-/// Deserialize text/blob to JSON `Value`.
+impl ListElement for Value {}
+
+/// Deserialize `JSON`/text/blob to JSON `Value`. Text that isn't valid JSON
+/// fails with the parse error attached.
 impl FromSql for Value {
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
@@ -30,7 +35,11 @@ impl FromSql for Value {
 
 #[cfg(test)]
 mod test {
-    use crate::{types::ToSql, Connection, Result};
+    use crate::{
+        types::{FromSqlError, ToSql},
+        Connection, Error, Result,
+    };
+    use serde_json::json;
 
     fn checked_memory_handle() -> Result<Connection> {
         let db = Connection::open_in_memory()?;
@@ -55,4 +64,72 @@ mod test {
         assert_eq!(data, b);
         Ok(())
     }
+
+    #[test]
+    fn test_invalid_json() -> Result<()> {
+        let db = checked_memory_handle()?;
+        db.execute("INSERT INTO foo (t) VALUES (?)", ["{not json"])?;
+
+        let err = db
+            .query_row("SELECT t FROM foo", [], |r| r.get::<_, serde_json::Value>(0))
+            .unwrap_err();
+        match err {
+            Error::ColumnConversionFailure {
+                index: 0,
+                source: FromSqlError::Other(err),
+                ..
+            } => assert!(err.is::<serde_json::Error>()),
+            err => panic!("unexpected error {err:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_json_value() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let data = json!({"a": [1, 2, {"b": null}], "c": "d"});
+        {
+            let mut app = db.appender("foo")?;
+            app.append_row([&data as &dyn ToSql, &"{}".as_bytes()])?;
+        }
+        let t: serde_json::Value = db.query_row("SELECT t FROM foo", [], |r| r.get(0))?;
+        assert_eq!(data, t);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_extract() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(r#"CREATE TABLE j (j JSON); INSERT INTO j VALUES ('{"a": {"b": [1, true, "x"]}}');"#)?;
+
+        let whole: serde_json::Value = db.query_row("SELECT j FROM j", [], |r| r.get(0))?;
+        assert_eq!(json!({"a": {"b": [1, true, "x"]}}), whole);
+        let nested: serde_json::Value = db.query_row("SELECT json_extract(j, '$.a.b') FROM j", [], |r| r.get(0))?;
+        assert_eq!(json!([1, true, "x"]), nested);
+        let scalar: serde_json::Value = db.query_row("SELECT json_extract(j, '$.a.b[2]') FROM j", [], |r| r.get(0))?;
+        assert_eq!(json!("x"), scalar);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json_contains_parameter() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            r#"CREATE TABLE j (id INTEGER, j JSON);
+            INSERT INTO j VALUES (1, '{"user": {"name": "a", "tags": ["x", "y"]}}'), (2, '{"user": {"name": "b"}}');"#,
+        )?;
+
+        let needle = json!({"user": {"tags": ["y"]}});
+        let id: i32 = db.query_row("SELECT id FROM j WHERE json_contains(j, ?::JSON)", [&needle], |r| {
+            r.get(0)
+        })?;
+        assert_eq!(1, id);
+
+        db.execute("INSERT INTO j VALUES (3, ?)", [&needle])?;
+        let stored: serde_json::Value = db.query_row("SELECT j FROM j WHERE id = 3", [], |r| r.get(0))?;
+        assert_eq!(needle, stored);
+        Ok(())
+    }
 }
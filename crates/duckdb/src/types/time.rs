@@ -0,0 +1,330 @@
+//! Convert DuckDB dates, times and timestamps to and from `time` types.
+
+use time::{
+    format_description::{well_known::Rfc3339, FormatItem},
+    macros::format_description,
+    Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset,
+};
+
+use crate::{
+    types::{FromSql, FromSqlError, FromSqlResult, ListElement, TimeUnit, ToSql, ToSqlOutput, Type, ValueRef},
+    Result,
+};
+
+const DATE_FORMAT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
+const TIME_FORMAT: &[FormatItem<'_>] = format_description!("[hour]:[minute]:[second].[subsecond digits:6]");
+const PRIMITIVE_DATE_TIME_FORMAT: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:6]");
+const OFFSET_DATE_TIME_FORMAT: &[FormatItem<'_>] = format_description!(
+    "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:6][offset_hour sign:mandatory]:[offset_minute]"
+);
+
+const TIME_PARSE_FORMAT: &[FormatItem<'_>] =
+    format_description!("[hour]:[minute][optional [:[second][optional [.[subsecond]]]]]");
+const PRIMITIVE_DATE_TIME_PARSE_FORMAT: &[FormatItem<'_>] = format_description!(
+    "[year]-[month]-[day][first [ ][T]][hour]:[minute][optional [:[second][optional [.[subsecond]]]]]"
+);
+const OFFSET_DATE_TIME_PARSE_FORMAT: &[FormatItem<'_>] = format_description!(
+    "[year]-[month]-[day][first [ ][T]][hour]:[minute][optional [:[second][optional [.[subsecond]]]]][offset_hour sign:mandatory][optional [:[offset_minute]]]"
+);
+
+/// Julian day number of 1970-01-01, the DuckDB `DATE` epoch.
+const UNIX_EPOCH_JULIAN_DAY: i32 = 2_440_588;
+
+fn to_text(formatted: std::result::Result<String, time::error::Format>) -> Result<ToSqlOutput<'static>> {
+    formatted
+        .map(ToSqlOutput::from)
+        .map_err(|err| crate::Error::ToSqlConversionFailure(Box::new(err)))
+}
+
+fn from_text(value: &[u8]) -> FromSqlResult<&str> {
+    std::str::from_utf8(value).map_err(FromSqlError::InvalidUtf8)
+}
+
+fn to_nanos(unit: TimeUnit, value: i64) -> i128 {
+    let value = i128::from(value);
+    match unit {
+        TimeUnit::Second => value * 1_000_000_000,
+        TimeUnit::Millisecond => value * 1_000_000,
+        TimeUnit::Microsecond => value * 1_000,
+        TimeUnit::Nanosecond => value,
+    }
+}
+
+fn from_timestamp(unit: TimeUnit, value: i64) -> FromSqlResult<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp_nanos(to_nanos(unit, value)).map_err(|err| FromSqlError::Other(Box::new(err)))
+}
+
+/// ISO 8601 calendar date without timezone => "YYYY-MM-DD"
+impl ToSql for Date {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        to_text(self.format(DATE_FORMAT))
+    }
+}
+
+impl ListElement for Date {}
+
+/// DATE, TIMESTAMP or "YYYY-MM-DD" => ISO 8601 calendar date without timezone.
+impl FromSql for Date {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Date32
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Date32(days) => {
+                Date::from_julian_day(UNIX_EPOCH_JULIAN_DAY + days).map_err(|err| FromSqlError::Other(Box::new(err)))
+            }
+            ValueRef::Text(s) => {
+                let s = from_text(s)?;
+                match Date::parse(s, DATE_FORMAT) {
+                    Ok(date) => Ok(date),
+                    Err(_) => PrimitiveDateTime::parse(s, PRIMITIVE_DATE_TIME_PARSE_FORMAT)
+                        .map(PrimitiveDateTime::date)
+                        .map_err(|err| FromSqlError::Other(Box::new(err))),
+                }
+            }
+            _ => PrimitiveDateTime::column_result(value).map(PrimitiveDateTime::date),
+        }
+    }
+}
+
+/// ISO 8601 time without timezone => "HH:MM:SS.SSSSSS"
+impl ToSql for Time {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        to_text(self.format(TIME_FORMAT))
+    }
+}
+
+impl ListElement for Time {}
+
+/// TIME or "HH:MM"/"HH:MM:SS"/"HH:MM:SS.SSS" => ISO 8601 time without
+/// timezone.
+impl FromSql for Time {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Time64
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Time64(unit, t) => {
+                let nanos = to_nanos(unit, t);
+                let (secs, nanos) = (nanos.div_euclid(1_000_000_000), nanos.rem_euclid(1_000_000_000));
+                let hour = u8::try_from(secs / 3600).map_err(|err| FromSqlError::Other(Box::new(err)))?;
+                Time::from_hms_nano(hour, (secs / 60 % 60) as u8, (secs % 60) as u8, nanos as u32)
+                    .map_err(|err| FromSqlError::Other(Box::new(err)))
+            }
+            ValueRef::Text(s) => {
+                Time::parse(from_text(s)?, TIME_PARSE_FORMAT).map_err(|err| FromSqlError::Other(Box::new(err)))
+            }
+            _ => PrimitiveDateTime::column_result(value).map(PrimitiveDateTime::time),
+        }
+    }
+}
+
+/// ISO 8601 combined date and time without timezone =>
+/// "YYYY-MM-DD HH:MM:SS.SSSSSS"
+impl ToSql for PrimitiveDateTime {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        to_text(self.format(PRIMITIVE_DATE_TIME_FORMAT))
+    }
+}
+
+impl ListElement for PrimitiveDateTime {}
+
+/// TIMESTAMP, DATE or "YYYY-MM-DD HH:MM:SS"/"YYYY-MM-DD HH:MM:SS.SSS" =>
+/// ISO 8601 combined date and time without timezone.
+/// ("YYYY-MM-DDTHH:MM:SS"/"YYYY-MM-DDTHH:MM:SS.SSS" also supported)
+impl FromSql for PrimitiveDateTime {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Timestamp
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Timestamp(unit, t) | ValueRef::TimestampTz(unit, t) => {
+                from_timestamp(unit, t).map(|dt| PrimitiveDateTime::new(dt.date(), dt.time()))
+            }
+            ValueRef::Date32(_) => Date::column_result(value).map(Date::midnight),
+            ValueRef::Text(s) => {
+                let s = from_text(s)?;
+                match PrimitiveDateTime::parse(s, PRIMITIVE_DATE_TIME_PARSE_FORMAT) {
+                    Ok(dt) => Ok(dt),
+                    Err(_) => Date::parse(s, DATE_FORMAT)
+                        .map(Date::midnight)
+                        .map_err(|err| FromSqlError::Other(Box::new(err))),
+                }
+            }
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// Date and time with time zone => UTC timestamp
+/// ("YYYY-MM-DD HH:MM:SS.SSSSSS+00:00").
+impl ToSql for OffsetDateTime {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        to_text(self.to_offset(UtcOffset::UTC).format(OFFSET_DATE_TIME_FORMAT))
+    }
+}
+
+impl ListElement for OffsetDateTime {}
+
+/// TIMESTAMPTZ or RFC3339 ("YYYY-MM-DD HH:MM:SS.SSS[+-]HH:MM") into
+/// `OffsetDateTime`. TIMESTAMPTZ values are always returned in UTC, strings
+/// keep their offset.
+///
+/// A plain TIMESTAMP doesn't identify an instant, so reading one is an error
+/// unless [`Connection::set_lenient_timestamps`](crate::Connection::set_lenient_timestamps)
+/// was enabled.
+impl FromSql for OffsetDateTime {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TimestampTz
+    }
+
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::TimestampTz(unit, t) => from_timestamp(unit, t),
+            ValueRef::Text(s) => {
+                let s = from_text(s)?;
+                OffsetDateTime::parse(s, OFFSET_DATE_TIME_PARSE_FORMAT)
+                    .or_else(|_| OffsetDateTime::parse(s, &Rfc3339))
+                    .or_else(|_| PrimitiveDateTime::column_result(value).map(PrimitiveDateTime::assume_utc))
+            }
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{params, Connection, Result};
+    use time::{macros::datetime, Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    fn checked_memory_handle() -> Result<Connection> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE foo (d DATE, t TEXT, b TIMESTAMP, tt TIME, tz TIMESTAMPTZ)")?;
+        Ok(db)
+    }
+
+    #[test]
+    fn test_date() -> Result<()> {
+        let db = checked_memory_handle()?;
+        for date in [
+            Date::from_calendar_date(2016, Month::February, 23).unwrap(),
+            Date::from_calendar_date(1969, Month::December, 31).unwrap(),
+            Date::from_calendar_date(1066, Month::October, 14).unwrap(),
+        ] {
+            db.execute("INSERT INTO foo (d) VALUES (?)", [date])?;
+            let s: String = db.query_row("SELECT d::VARCHAR FROM foo", [], |r| r.get(0))?;
+            assert_eq!(date.to_string(), s);
+            let v: Date = db.query_row("SELECT d FROM foo", [], |r| r.get(0))?;
+            assert_eq!(date, v);
+            let v: Date = db.query_row("SELECT d::VARCHAR FROM foo", [], |r| r.get(0))?;
+            assert_eq!(date, v);
+            db.execute_batch("DELETE FROM foo")?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_time() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let time = Time::from_hms_micro(23, 56, 4, 12_345).unwrap();
+        db.execute("INSERT INTO foo (tt) VALUES (?)", [time])?;
+
+        let s: String = db.query_row("SELECT tt::VARCHAR FROM foo", [], |r| r.get(0))?;
+        assert_eq!("23:56:04.012345", s);
+        let v: Time = db.query_row("SELECT tt FROM foo", [], |r| r.get(0))?;
+        assert_eq!(time, v);
+        let v: Time = db.query_row("SELECT '23:56'", [], |r| r.get(0))?;
+        assert_eq!(Time::from_hms(23, 56, 0).unwrap(), v);
+        Ok(())
+    }
+
+    #[test]
+    fn test_primitive_date_time() -> Result<()> {
+        let db = checked_memory_handle()?;
+        for dt in [
+            datetime!(2016-02-23 23:56:04.000_789),
+            datetime!(1969-12-31 23:59:59.999_999),
+            datetime!(1900-01-01 00:00:00),
+        ] {
+            db.execute("INSERT INTO foo (b) VALUES (?)", [dt])?;
+            let v: PrimitiveDateTime = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+            assert_eq!(dt, v);
+            let v: PrimitiveDateTime = db.query_row("SELECT b::VARCHAR FROM foo", [], |r| r.get(0))?;
+            assert_eq!(dt, v);
+            db.execute_batch("DELETE FROM foo")?;
+        }
+
+        let v: PrimitiveDateTime = db.query_row("SELECT '2016-02-23T23:56:04'", [], |r| r.get(0))?;
+        assert_eq!(datetime!(2016-02-23 23:56:04), v);
+        let v: PrimitiveDateTime = db.query_row("SELECT DATE '2016-02-23'", [], |r| r.get(0))?;
+        assert_eq!(datetime!(2016-02-23 00:00:00), v);
+        // Precision beyond microseconds is truncated.
+        let v: PrimitiveDateTime = db.query_row(
+            "SELECT ?::TIMESTAMP",
+            [datetime!(2016-02-23 23:56:04.123_456_789)],
+            |r| r.get(0),
+        )?;
+        assert_eq!(datetime!(2016-02-23 23:56:04.123_456), v);
+        Ok(())
+    }
+
+    #[test]
+    fn test_offset_date_time() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let dt = datetime!(1969-07-20 20:17:40.000_001 UTC);
+        db.execute("INSERT INTO foo (b, tz) VALUES (?, ?)", [dt, dt])?;
+
+        let v: OffsetDateTime = db.query_row("SELECT tz FROM foo", [], |r| r.get(0))?;
+        assert_eq!(dt, v);
+        assert_eq!(UtcOffset::UTC, v.offset());
+
+        assert!(db
+            .query_row("SELECT b FROM foo", [], |r| r.get::<_, OffsetDateTime>(0))
+            .is_err());
+        db.set_lenient_timestamps(true);
+        let v: OffsetDateTime = db.query_row("SELECT b FROM foo", [], |r| r.get(0))?;
+        assert_eq!(dt, v);
+
+        let v: OffsetDateTime = db.query_row("SELECT '1969-07-20 15:17:40.000001-05:00'", [], |r| r.get(0))?;
+        assert_eq!(dt, v);
+        assert_eq!(UtcOffset::from_hms(-5, 0, 0).unwrap(), v.offset());
+        let v: OffsetDateTime = db.query_row("SELECT '1969-07-20T20:17:40.000001Z'", [], |r| r.get(0))?;
+        assert_eq!(dt, v);
+        Ok(())
+    }
+
+    #[test]
+    fn test_appender() -> Result<()> {
+        let db = checked_memory_handle()?;
+        let date = Date::from_calendar_date(1955, Month::November, 5).unwrap();
+        let time = Time::from_hms_micro(6, 15, 0, 999_999).unwrap();
+        let dt = PrimitiveDateTime::new(date, time);
+        let odt = dt.assume_utc();
+        {
+            let mut app = db.appender("foo")?;
+            app.append_row(params![date, "", dt, time, odt])?;
+        }
+        let row = db.query_row("SELECT d, b, tt, tz FROM foo", [], |r| {
+            Ok((
+                r.get::<_, Date>(0)?,
+                r.get::<_, PrimitiveDateTime>(1)?,
+                r.get::<_, Time>(2)?,
+                r.get::<_, OffsetDateTime>(3)?,
+            ))
+        })?;
+        assert_eq!((date, dt, time, odt), row);
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use std::{fmt, str::FromStr};
+
+use super::{FromSql, FromSqlError, FromSqlResult, ListElement, ToSql, ToSqlOutput, Type, ValueRef};
+use crate::Result;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 24 * 3600 * MICROS_PER_SECOND;
+
+/// A DuckDB `TIMETZ` (`TIME WITH TIME ZONE`) value, a time of day together
+/// with the UTC offset it was written in.
+///
+/// DuckDB can't export `TIMETZ` columns through Arrow, so to read one select
+/// it as text (`t::VARCHAR`). `TimeTz` is bound as text in DuckDB's
+/// `HH:MM:SS[.ffffff]+HH[:MM[:SS]]` form, which it casts to `TIMETZ`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimeTz {
+    micros: i64,
+    offset_seconds: i32,
+}
+
+impl TimeTz {
+    /// The largest offset DuckDB accepts either side of UTC, 15:59:59.
+    pub const MAX_OFFSET_SECONDS: i32 = 16 * 3600 - 1;
+
+    /// Creates a `TimeTz` from microseconds since midnight, up to and
+    /// including `24:00:00`, and an offset in seconds east of UTC.
+    ///
+    /// Returns `None` if either is out of range.
+    pub fn new(micros: i64, offset_seconds: i32) -> Option<TimeTz> {
+        if (0..=MICROS_PER_DAY).contains(&micros) && offset_seconds.abs() <= Self::MAX_OFFSET_SECONDS {
+            Some(TimeTz { micros, offset_seconds })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the local time in microseconds since midnight.
+    #[inline]
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    /// Returns the offset in seconds east of UTC.
+    #[inline]
+    pub fn offset_seconds(&self) -> i32 {
+        self.offset_seconds
+    }
+}
+
+impl fmt::Display for TimeTz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.micros / MICROS_PER_SECOND;
+        write!(f, "{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)?;
+        let fraction = self.micros % MICROS_PER_SECOND;
+        if fraction != 0 {
+            write!(f, ".{}", format!("{fraction:06}").trim_end_matches('0'))?;
+        }
+        let sign = if self.offset_seconds < 0 { '-' } else { '+' };
+        let offset = self.offset_seconds.unsigned_abs();
+        write!(f, "{sign}{:02}", offset / 3600)?;
+        match (offset / 60 % 60, offset % 60) {
+            (0, 0) => Ok(()),
+            (minutes, 0) => write!(f, ":{minutes:02}"),
+            (minutes, seconds) => write!(f, ":{minutes:02}:{seconds:02}"),
+        }
+    }
+}
+
+/// Error returned when parsing a [`TimeTz`] from a malformed or out of range
+/// string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseTimeTzError(String);
+
+impl fmt::Display for ParseTimeTzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid TIMETZ {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseTimeTzError {}
+
+impl FromStr for TimeTz {
+    type Err = ParseTimeTzError;
+
+    /// Parses DuckDB's text form, `HH:MM:SS[.ffffff]` followed by an offset of
+    /// `+HH[:MM[:SS]]` or `-HH[:MM[:SS]]`. Digits past microseconds are
+    /// truncated.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseTimeTzError(s.to_owned());
+        let split = s.rfind(['+', '-']).ok_or_else(err)?;
+        let (time, offset) = s.split_at(split);
+
+        let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+        let seconds = match parse_fields(time).ok_or_else(err)?[..] {
+            [h, m, s] if m < 60 && s < 60 => (h * 60 + m) * 60 + s,
+            _ => return Err(err()),
+        };
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(err());
+        }
+        let fraction = format!("{:0<6}", &fraction[..fraction.len().min(6)]);
+        let micros = i64::from(seconds) * MICROS_PER_SECOND + fraction.parse::<i64>().map_err(|_| err())?;
+
+        let (sign, offset) = offset.split_at(1);
+        let offset_seconds = match parse_fields(offset).ok_or_else(err)?[..] {
+            [h] => h * 3600,
+            [h, m] if m < 60 => h * 3600 + m * 60,
+            [h, m, s] if m < 60 && s < 60 => h * 3600 + m * 60 + s,
+            _ => return Err(err()),
+        } as i32;
+        let offset_seconds = if sign == "-" { -offset_seconds } else { offset_seconds };
+
+        TimeTz::new(micros, offset_seconds).ok_or_else(err)
+    }
+}
+
+/// Parses `:`-separated two digit fields.
+fn parse_fields(s: &str) -> Option<Vec<u32>> {
+    s.split(':')
+        .map(|field| match field.as_bytes() {
+            [a, b] if a.is_ascii_digit() && b.is_ascii_digit() => Some(u32::from((a - b'0') * 10 + (b - b'0'))),
+            _ => None,
+        })
+        .collect()
+}
+
+impl FromSql for TimeTz {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Text
+    }
+
+    #[inline]
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+impl ToSql for TimeTz {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl ListElement for TimeTz {}
+
+#[cfg(test)]
+mod test {
+    use super::TimeTz;
+    use crate::{Connection, Error, Result};
+
+    #[test]
+    fn test_time_tz() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let t: TimeTz = db.query_row("SELECT TIMETZ '12:30:00+05:30'::VARCHAR", [], |r| r.get(0))?;
+        assert_eq!((45_000_000_000, 19_800), (t.micros(), t.offset_seconds()));
+        assert_eq!("12:30:00+05:30", t.to_string());
+
+        for s in [
+            "00:00:00-15:59",
+            "23:59:59.999999+15:59",
+            "12:00:00.5+15:59:59",
+            "24:00:00-15:59:59",
+            "01:02:03.25-01:30",
+            "12:00:00+00",
+        ] {
+            let t: TimeTz = db.query_row("SELECT ?::TIMETZ::VARCHAR", [s], |r| r.get(0))?;
+            assert_eq!(s, t.to_string());
+            assert_eq!(s.parse::<TimeTz>().unwrap(), t);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_tz_param() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (id INTEGER, t TIMETZ)")?;
+        let max = TimeTz::new(0, TimeTz::MAX_OFFSET_SECONDS).unwrap();
+        let min = TimeTz::new(86_399_999_999, -TimeTz::MAX_OFFSET_SECONDS).unwrap();
+        db.execute("INSERT INTO t VALUES (1, ?)", [max])?;
+        {
+            let mut app = db.appender("t")?;
+            app.append_row(crate::params![2, min])?;
+        }
+        let mut stmt = db.prepare("SELECT t::VARCHAR FROM t ORDER BY id")?;
+        let rows = stmt
+            .query_map([], |r| r.get::<_, TimeTz>(0))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(vec![max, min], rows);
+        assert_eq!("00:00:00+15:59:59", max.to_string());
+        assert_eq!("23:59:59.999999-15:59:59", min.to_string());
+
+        let same: bool = db.query_row(
+            "SELECT ?::TIMETZ = TIMETZ '12:30:00+05:30'",
+            ["12:30:00+05:30".parse::<TimeTz>().unwrap()],
+            |r| r.get(0),
+        )?;
+        assert!(same);
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_tz_invalid() -> Result<()> {
+        assert_eq!(None, TimeTz::new(0, TimeTz::MAX_OFFSET_SECONDS + 1));
+        assert_eq!(None, TimeTz::new(86_400_000_001, 0));
+        for s in [
+            "12:00:00",
+            "12:00+01",
+            "12:60:00+01",
+            "12:00:00+16",
+            "12:00:00+01:60",
+            "1:00:00+01",
+            "12:00:00.x+01",
+        ] {
+            assert!(s.parse::<TimeTz>().is_err(), "{s}");
+        }
+
+        let db = Connection::open_in_memory()?;
+        let err = db
+            .query_row("SELECT TIMETZ '12:30:00+05:30'", [], |r| r.get::<_, TimeTz>(0))
+            .unwrap_err();
+        assert!(matches!(err, Error::DuckDBFailure(..)), "{err:?}");
+        Ok(())
+    }
+}
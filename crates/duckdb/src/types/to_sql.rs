@@ -1,6 +1,13 @@
-use super::{Null, TimeUnit, Value, ValueRef};
-use crate::Result;
-use std::borrow::Cow;
+use super::{
+    value::{decimal_text, float_text},
+    Null, TimeUnit, Value, ValueRef,
+};
+use crate::{Error, Result};
+use rust_decimal::Decimal;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
 
 /// `ToSqlOutput` represents the possible output types for implementers of the
 /// [`ToSql`] trait.
@@ -40,6 +47,7 @@ macro_rules! from_value(
     )
 );
 from_value!(String);
+from_value!(char);
 from_value!(Null);
 from_value!(bool);
 from_value!(i8);
@@ -52,10 +60,12 @@ from_value!(u8);
 from_value!(u16);
 from_value!(u32);
 from_value!(u64);
+from_value!(u128);
 from_value!(usize);
 from_value!(f32);
 from_value!(f64);
 from_value!(Vec<u8>);
+from_value!(Decimal);
 
 #[cfg(feature = "uuid")]
 from_value!(uuid::Uuid);
@@ -63,10 +73,10 @@ from_value!(uuid::Uuid);
 impl ToSql for ToSqlOutput<'_> {
     #[inline]
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        Ok(match *self {
-            ToSqlOutput::Borrowed(v) => ToSqlOutput::Borrowed(v),
-            ToSqlOutput::Owned(ref v) => ToSqlOutput::Borrowed(ValueRef::from(v)),
-        })
+        match *self {
+            ToSqlOutput::Borrowed(v) => Ok(ToSqlOutput::Borrowed(v)),
+            ToSqlOutput::Owned(ref v) => v.to_sql(),
+        }
     }
 }
 
@@ -130,6 +140,7 @@ macro_rules! to_sql_self(
 
 to_sql_self!(Null);
 to_sql_self!(bool);
+to_sql_self!(char);
 to_sql_self!(i8);
 to_sql_self!(i16);
 to_sql_self!(i32);
@@ -142,7 +153,9 @@ to_sql_self!(u32);
 to_sql_self!(f32);
 to_sql_self!(f64);
 to_sql_self!(u64);
+to_sql_self!(u128);
 to_sql_self!(usize);
+to_sql_self!(Decimal);
 
 #[cfg(feature = "uuid")]
 to_sql_self!(uuid::Uuid);
@@ -188,8 +201,201 @@ impl ToSql for [u8] {
 impl ToSql for Value {
     #[inline]
     fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-        Ok(ToSqlOutput::from(self))
+        match self {
+            Value::Map(entries) => map_literal(entries.iter().map(|(k, v)| (ValueRef::from(k), v.to_sql()))),
+            Value::List(_) | Value::Array(_) | Value::Struct(_) => Ok(ToSqlOutput::Owned(self.clone())),
+            _ => Ok(ToSqlOutput::from(self)),
+        }
+    }
+}
+
+/// A marker for types that can be bound as the elements of a `LIST`, through
+/// the [`ToSql`] impls for `Vec<T>` and `[T]`.
+///
+/// `u8` is deliberately not a `ListElement`, so that `Vec<u8>` and `&[u8]`
+/// keep binding as a `BLOB`. Implement it for your own [`ToSql`] types to
+/// bind vectors of them.
+pub trait ListElement: ToSql {}
+
+macro_rules! list_element(
+    ($($t:ty),+ $(,)?) => ($(impl ListElement for $t {})+)
+);
+
+list_element!(Null, bool, i8, i16, i32, i64, i128, isize, u16, u32, u64, u128, usize, f32, f64);
+list_element!(
+    Decimal,
+    char,
+    String,
+    str,
+    Value,
+    Vec<u8>,
+    std::time::Duration,
+    std::time::SystemTime
+);
+
+#[cfg(feature = "uuid")]
+list_element!(uuid::Uuid);
+
+impl<T: ListElement + ?Sized> ListElement for &'_ T {}
+impl<T: ListElement + ?Sized> ListElement for Box<T> {}
+impl<T: ListElement + ?Sized> ListElement for std::rc::Rc<T> {}
+impl<T: ListElement + ?Sized> ListElement for std::sync::Arc<T> {}
+impl<T: ListElement + ToOwned + ?Sized> ListElement for Cow<'_, T> {}
+impl<T: ListElement> ListElement for Option<T> {}
+impl<T: ListElement> ListElement for Vec<T> {}
+impl<T: ListElement, const N: usize> ListElement for [T; N] {}
+
+/// Vectors and slices are bound as a DuckDB `LIST` of the element type, so
+/// `vec![1, 2, 3]` binds as `INTEGER[]`. Nested vectors bind as nested
+/// lists. An empty vector binds as an empty `INTEGER[]`, since the element
+/// type can't be told from the values; cast it, as in `?::VARCHAR[]`, where
+/// the query needs another type. Like arrays, the C API can't create `NULL`
+/// elements, so `None` elements fail to bind.
+impl<T: ListElement> ToSql for Vec<T> {
+    #[inline]
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        self.as_slice().to_sql()
+    }
+}
+
+/// See the `Vec<T>` impl.
+impl<T: ListElement> ToSql for [T] {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Owned(Value::List(owned_items(self)?)))
+    }
+}
+
+/// Arrays are bound as a DuckDB `ARRAY` of the element type, so `[f32; 3]`
+/// binds as `FLOAT[3]`. The C API can't create `NULL` elements, so `None`
+/// elements fail to bind; they can still be appended.
+impl<T: ToSql, const N: usize> ToSql for [T; N] {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Owned(Value::Array(owned_items(self)?)))
+    }
+}
+
+pub(crate) fn owned_items<T: ToSql>(items: &[T]) -> Result<Vec<Value>> {
+    items
+        .iter()
+        .map(|item| {
+            Ok(match item.to_sql()? {
+                ToSqlOutput::Borrowed(v) => v.to_owned(),
+                ToSqlOutput::Owned(v) => v,
+            })
+        })
+        .collect()
+}
+
+/// Maps are bound as text in DuckDB's `{key=value, ...}` form, which it casts
+/// to a `MAP` column or `?::MAP(K, V)`. The C API has no way to construct a
+/// `MAP` value directly.
+impl<K: AsRef<str>, V: ToSql, S> ToSql for HashMap<K, V, S> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        map_literal(self.iter().map(|(k, v)| (ValueRef::from(k.as_ref()), v.to_sql())))
+    }
+}
+
+/// See the [`HashMap`] impl.
+impl<K: AsRef<str>, V: ToSql> ToSql for BTreeMap<K, V> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        map_literal(self.iter().map(|(k, v)| (ValueRef::from(k.as_ref()), v.to_sql())))
+    }
+}
+
+fn map_literal<'a>(entries: impl Iterator<Item = (ValueRef<'a>, Result<ToSqlOutput<'a>>)>) -> Result<ToSqlOutput<'a>> {
+    let mut literal = String::from("{");
+    for (i, (key, value)) in entries.enumerate() {
+        if i > 0 {
+            literal.push_str(", ");
+        }
+        match key {
+            ValueRef::Text(_) => push_literal_element(&mut literal, key, "MAP")?,
+            _ => return Err(Error::ToSqlConversionFailure("MAP keys must be strings".into())),
+        }
+        literal.push('=');
+        match value? {
+            ToSqlOutput::Borrowed(v) => push_literal_element(&mut literal, v, "MAP")?,
+            ToSqlOutput::Owned(ref v) => push_literal_element(&mut literal, scalar_ref(v, "MAP")?, "MAP")?,
+        }
+    }
+    literal.push('}');
+    Ok(ToSqlOutput::from(literal))
+}
+
+/// Formats a `LIST` or `ARRAY` as text in DuckDB's `[item, ...]` form, for
+/// appending. Items may be lists or arrays themselves.
+pub(crate) fn list_literal(items: &[Value], container: &str) -> Result<String> {
+    let mut literal = String::new();
+    push_list_literal(&mut literal, items, container)?;
+    Ok(literal)
+}
+
+fn push_list_literal(literal: &mut String, items: &[Value], container: &str) -> Result<()> {
+    literal.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            literal.push_str(", ");
+        }
+        match item {
+            Value::List(items) | Value::Array(items) => push_list_literal(literal, items, container)?,
+            _ => push_literal_element(literal, scalar_ref(item, container)?, container)?,
+        }
+    }
+    literal.push(']');
+    Ok(())
+}
+
+/// `ValueRef::from`, failing instead of panicking on nested values.
+fn scalar_ref<'a>(value: &'a Value, container: &str) -> Result<ValueRef<'a>> {
+    match value {
+        Value::List(_) | Value::Array(_) | Value::Struct(_) | Value::Map(_) | Value::Union { .. } | Value::Enum(_) => {
+            Err(Error::ToSqlConversionFailure(
+                format!("only scalar values can be bound inside a {container}").into(),
+            ))
+        }
+        _ => Ok(value.into()),
+    }
+}
+
+fn push_literal_element(literal: &mut String, value: ValueRef<'_>, container: &str) -> Result<()> {
+    let unsupported = |reason: String| Err(Error::ToSqlConversionFailure(reason.into()));
+    match value {
+        ValueRef::Null => literal.push_str("NULL"),
+        ValueRef::Boolean(b) => literal.push_str(if b { "true" } else { "false" }),
+        ValueRef::TinyInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::SmallInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::Int(i) => literal.push_str(&i.to_string()),
+        ValueRef::BigInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::HugeInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::UTinyInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::USmallInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::UInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::UBigInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::UHugeInt(i) => literal.push_str(&i.to_string()),
+        ValueRef::Float(f) => literal.push_str(&float_text(f)),
+        ValueRef::Double(f) => literal.push_str(&float_text(f)),
+        ValueRef::Decimal { value, scale, .. } => literal.push_str(&decimal_text(value, scale)),
+        ValueRef::Text(_) => {
+            let s = value
+                .as_str()
+                .map_err(|err| Error::ToSqlConversionFailure(err.into()))?;
+            // DuckDB reads an unescaped string up to the matching quote, and
+            // treats NULL as NULL even when quoted.
+            if s.eq_ignore_ascii_case("null") {
+                return unsupported(format!("the string \"NULL\" can't be bound inside a {container}"));
+            }
+            let quote = match (s.contains('"'), s.contains('\'')) {
+                (false, _) => '"',
+                (true, false) => '\'',
+                (true, true) => return unsupported(format!("{container} strings can't contain both kinds of quote")),
+            };
+            literal.push(quote);
+            literal.push_str(s);
+            literal.push(quote);
+        }
+        _ => return unsupported(format!("only scalar values can be bound inside a {container}")),
     }
+    Ok(())
 }
 
 impl<T: ToSql> ToSql for Option<T> {
@@ -202,12 +408,35 @@ impl<T: ToSql> ToSql for Option<T> {
     }
 }
 
+/// `Duration` => `INTERVAL` without month or day components, like chrono's
+/// `Duration`, truncated to the microsecond precision of DuckDB intervals.
 impl ToSql for std::time::Duration {
     fn to_sql(&self) -> crate::Result<ToSqlOutput<'_>> {
-        Ok(ToSqlOutput::Owned(Value::Timestamp(
-            TimeUnit::Microsecond,
-            self.as_micros() as i64,
-        )))
+        let nanos = i64::try_from(self.as_micros() * 1000)
+            .map_err(|_| Error::ToSqlConversionFailure(format!("{self:?} does not fit in an INTERVAL").into()))?;
+        Ok(ToSqlOutput::Owned(Value::Interval {
+            months: 0,
+            days: 0,
+            nanos,
+        }))
+    }
+}
+
+/// `SystemTime` => `TIMESTAMP`, rounded down to the microsecond, so times
+/// before the Unix epoch are bound as negative microseconds.
+impl ToSql for std::time::SystemTime {
+    fn to_sql(&self) -> crate::Result<ToSqlOutput<'_>> {
+        let micros = match self.duration_since(std::time::UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_micros()).ok(),
+            Err(before) => {
+                let before = before.duration();
+                let partial = before.subsec_nanos() % 1000 != 0;
+                i64::try_from(before.as_micros() + u128::from(partial)).ok().map(|m| -m)
+            }
+        };
+        let micros = micros
+            .ok_or_else(|| Error::ToSqlConversionFailure(format!("{self:?} does not fit in a TIMESTAMP").into()))?;
+        Ok(ToSqlOutput::Owned(Value::Timestamp(TimeUnit::Microsecond, micros)))
     }
 }
 
@@ -226,6 +455,67 @@ mod test {
         is_to_sql::<u8>();
         is_to_sql::<u16>();
         is_to_sql::<u32>();
+        is_to_sql::<u64>();
+        is_to_sql::<usize>();
+    }
+
+    #[test]
+    fn test_ubigint_boundaries() -> crate::Result<()> {
+        use crate::{params, types::FromSqlError, Connection, Error};
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch("CREATE TABLE t (u UBIGINT)")?;
+        let boundaries = [i64::MAX as u64, i64::MAX as u64 + 1, u64::MAX];
+        for u in boundaries {
+            db.execute("INSERT INTO t VALUES (?)", [u])?;
+        }
+        {
+            let mut app = db.appender("t")?;
+            for u in boundaries {
+                app.append_row(params![u as usize])?;
+            }
+        }
+
+        for u in boundaries {
+            let n: i64 = db.query_row("SELECT count(*) FROM t WHERE u = ?", [u], |r| r.get(0))?;
+            assert_eq!(n, 2, "{u}");
+            let n: i64 = db.query_row("SELECT count(*) FROM t WHERE u = ?", [u as usize], |r| r.get(0))?;
+            assert_eq!(n, 2, "{u}");
+            let back: u64 = db.query_row("SELECT ?", [u], |r| r.get(0))?;
+            assert_eq!(back, u);
+        }
+
+        let mut stmt = db.prepare("SELECT u FROM t ORDER BY u")?;
+        let read: Vec<u64> = stmt.query_map([], |r| r.get(0))?.collect::<crate::Result<_>>()?;
+        assert_eq!(
+            read,
+            [
+                boundaries[0],
+                boundaries[0],
+                boundaries[1],
+                boundaries[1],
+                u64::MAX,
+                u64::MAX
+            ]
+        );
+
+        let min: i64 = db.query_row("SELECT min(u) FROM t", [], |r| r.get(0))?;
+        assert_eq!(min, i64::MAX);
+        let err = db
+            .query_row("SELECT max(u) FROM t", [], |r| r.get::<_, i64>(0))
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::ColumnConversionFailure {
+                    index: 0,
+                    source: FromSqlError::OutOfRange { ref value, target: "i64" },
+                    ..
+                } if *value == u64::MAX.to_string()
+            ),
+            "{err:?}"
+        );
+        Ok(())
     }
 
     #[test]
@@ -368,4 +658,124 @@ mod test {
         assert_eq!(found_label, "target");
         Ok(())
     }
+
+    #[test]
+    fn test_list_params() -> crate::Result<()> {
+        use crate::{params, Connection};
+
+        let db = Connection::open_in_memory()?;
+        let contains = |list: &dyn ToSql| db.query_row("SELECT list_contains(?, 2)", [list], |r| r.get::<_, bool>(0));
+        assert!(contains(&vec![1, 2, 3])?);
+        assert!(!contains(&vec![1, 3])?);
+        assert!(contains(&&[1i64, 2][..])?);
+        assert!(contains(&super::Value::List(vec![2.into()]))?);
+
+        db.execute_batch("CREATE TABLE ids AS SELECT range AS id FROM range(10)")?;
+        let mut stmt = db.prepare("SELECT id FROM ids WHERE id IN (SELECT unnest(?)) ORDER BY id")?;
+        let ids = stmt
+            .query_map(params![vec![7u64, 3, 42]], |r| r.get::<_, i64>(0))?
+            .collect::<crate::Result<Vec<_>>>()?;
+        assert_eq!(ids, [3, 7]);
+
+        let strings = db.query_row("SELECT ?", [vec!["it's", "a \"test\""]], |r| r.get::<_, Vec<String>>(0))?;
+        assert_eq!(strings, ["it's", "a \"test\""]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_list_params() -> crate::Result<()> {
+        use crate::Connection;
+
+        let db = Connection::open_in_memory()?;
+        let nested = vec![vec![1, 2], vec![], vec![3]];
+        let (ty, back) = db.query_row("SELECT typeof(?1), ?1", [&nested], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, Vec<Vec<i32>>>(1)?))
+        })?;
+        assert_eq!(ty, "INTEGER[][]");
+        assert_eq!(back, nested);
+
+        let dates = vec![super::Value::Date32(19723), super::Value::Date32(-1)];
+        let ty: String = db.query_row("SELECT typeof(?)", [&dates], |r| r.get(0))?;
+        assert_eq!(ty, "DATE[]");
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_list_param() -> crate::Result<()> {
+        use crate::Connection;
+
+        let db = Connection::open_in_memory()?;
+        let empty: Vec<i32> = vec![];
+        let (is_null, len) = db.query_row("SELECT ?1 IS NULL, len(?1)", [&empty], |r| {
+            Ok((r.get::<_, bool>(0)?, r.get::<_, i64>(1)?))
+        })?;
+        assert!(!is_null);
+        assert_eq!(len, 0);
+        assert!(!db.query_row("SELECT list_contains(?, 2)", [&empty], |r| r.get::<_, bool>(0))?);
+
+        let strings: Vec<String> = db.query_row("SELECT ?::VARCHAR[]", [&empty], |r| r.get(0))?;
+        assert!(strings.is_empty());
+
+        db.execute_batch("CREATE TABLE lists (l INTEGER[])")?;
+        db.execute("INSERT INTO lists VALUES (?)", [&empty])?;
+        db.execute("INSERT INTO lists VALUES (?)", [vec![1, 2]])?;
+        let mut app = db.appender("lists")?;
+        app.append_row([&empty])?;
+        app.append_row([vec![4, 5]])?;
+        app.flush()?;
+        let lists = db
+            .prepare("SELECT l FROM lists")?
+            .query_map([], |r| r.get::<_, Vec<i32>>(0))?
+            .collect::<crate::Result<Vec<_>>>()?;
+        assert_eq!(lists, [vec![], vec![1, 2], vec![], vec![4, 5]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_null_element() {
+        use crate::Connection;
+
+        let db = Connection::open_in_memory().unwrap();
+        let err = db
+            .query_row("SELECT ?", [vec![Some(1), None]], |r| r.get::<_, Vec<i32>>(0))
+            .unwrap_err();
+        assert_eq!("NULL elements can't be bound inside a LIST", err.to_string());
+    }
+
+    #[test]
+    fn test_struct_param() -> crate::Result<()> {
+        use crate::{
+            types::{OrderedMap, Value},
+            Connection,
+        };
+
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(
+            "CREATE TABLE items (id INTEGER, s STRUCT(a INTEGER, b STRUCT(c VARCHAR, d DATE[])));
+             INSERT INTO items VALUES (1, {'a': 1, 'b': {'c': 'x', 'd': ['2024-01-01']}}),
+                                      (2, {'a': 2, 'b': {'c': 'y', 'd': []}});",
+        )?;
+        let inner = Value::Struct(OrderedMap::from(vec![
+            ("c".to_owned(), Value::Text("x".to_owned())),
+            ("d".to_owned(), Value::List(vec![Value::Date32(19723)])),
+        ]));
+        let outer = Value::Struct(OrderedMap::from(vec![
+            ("a".to_owned(), Value::Int(1)),
+            ("b".to_owned(), inner),
+        ]));
+        let id: i32 = db.query_row("SELECT id FROM items WHERE s = ?", [&outer], |r| r.get(0))?;
+        assert_eq!(id, 1);
+        let back: Value = db.query_row("SELECT ?", [&outer], |r| r.get(0))?;
+        assert_eq!(back, outer);
+
+        let empty = Value::Struct(OrderedMap::from(vec![]));
+        let err = db
+            .query_row("SELECT ?", [&empty], |r| r.get::<_, Value>(0))
+            .unwrap_err();
+        assert_eq!("STRUCT parameters need at least one field", err.to_string());
+        let null = Value::Struct(OrderedMap::from(vec![("a".to_owned(), Value::Null)]));
+        let err = db.query_row("SELECT ?", [&null], |r| r.get::<_, Value>(0)).unwrap_err();
+        assert_eq!("NULL fields can't be bound inside a STRUCT", err.to_string());
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 //! [`ToSql`] and [`FromSql`] implementation for [`url::Url`].
 use crate::{
-    types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef},
+    types::{FromSql, FromSqlError, FromSqlResult, ListElement, ToSql, ToSqlOutput, Type, ValueRef},
     Result,
 };
 use url::Url;
@@ -13,15 +13,19 @@ impl ToSql for Url {
     }
 }
 
+impl ListElement for Url {}
+
 /// Deserialize text to `Url`.
 impl FromSql for Url {
+    #[inline]
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::Text
+    }
+
     #[inline]
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
         match value {
-            ValueRef::Text(s) => {
-                let s = std::str::from_utf8(s).map_err(|e| FromSqlError::Other(Box::new(e)))?;
-                Url::parse(s).map_err(|e| FromSqlError::Other(Box::new(e)))
-            }
+            ValueRef::Text(_) => Url::parse(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e))),
             _ => Err(FromSqlError::InvalidType),
         }
     }
@@ -29,7 +33,7 @@ impl FromSql for Url {
 
 #[cfg(test)]
 mod test {
-    use crate::{params, Connection, Error, Result};
+    use crate::{params, types::FromSqlError, Connection, Error, Result};
     use url::{ParseError, Url};
 
     fn checked_memory_handle() -> Result<Connection> {
@@ -69,7 +73,10 @@ mod test {
         // Make sure the conversion error comes through correctly.
         let err = get_url(db, 3).unwrap_err();
         match err {
-            Error::FromSqlConversionFailure(_, _, e) => {
+            Error::ColumnConversionFailure {
+                source: FromSqlError::Other(e),
+                ..
+            } => {
                 assert_eq!(*e.downcast::<ParseError>().unwrap(), ParseError::RelativeUrlWithoutBase,);
             }
             e => {
@@ -1,12 +1,18 @@
 use super::{Null, OrderedMap, TimeUnit, Type};
+use crate::{Error, Result};
 use rust_decimal::prelude::*;
+use std::fmt;
 
 /// Owning [dynamic type value](http://sqlite.org/datatype3.html). Value's type is typically
 /// dictated by DuckDB (not by the caller).
 ///
 /// See [`ValueRef`](crate::types::ValueRef) for a non-owning dynamic type
 /// value.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Values compare equal when they hold the same data, except that decimals
+/// compare by their numeric value alone: `1.50` as a `DECIMAL(4, 2)` equals
+/// `1.5` as a `DECIMAL(2, 1)`.
+#[derive(Clone, Debug)]
 pub enum Value {
     /// The value is a `NULL` value.
     Null,
@@ -30,14 +36,26 @@ pub enum Value {
     UInt(u32),
     /// The value is a unsigned big integer.
     UBigInt(u64),
+    /// The value is a unsigned huge integer.
+    UHugeInt(u128),
     /// The value is a f32.
     Float(f32),
     /// The value is a f64.
     Double(f64),
-    /// The value is a Decimal.
-    Decimal(Decimal),
+    /// The value is a decimal, `value` divided by 10 to the power of `scale`,
+    /// as a `DECIMAL(width, scale)`.
+    Decimal {
+        /// unscaled value
+        value: i128,
+        /// total number of digits
+        width: u8,
+        /// number of digits after the decimal point
+        scale: u8,
+    },
     /// The value is a timestamp.
     Timestamp(TimeUnit, i64),
+    /// The value is a timestamp with time zone, relative to the UTC epoch.
+    TimestampTz(TimeUnit, i64),
     /// The value is a text string.
     Text(String),
     /// The value is a blob of data
@@ -66,7 +84,12 @@ pub enum Value {
     /// The value is a map
     Map(OrderedMap<Value, Value>),
     /// The value is a union
-    Union(Box<Value>),
+    Union {
+        /// The name of the active member
+        tag: String,
+        /// The value of the active member
+        value: Box<Value>,
+    },
 }
 
 impl From<Null> for Value {
@@ -168,6 +191,13 @@ impl From<i128> for Value {
     }
 }
 
+impl From<u128> for Value {
+    #[inline]
+    fn from(i: u128) -> Value {
+        Value::UHugeInt(i)
+    }
+}
+
 impl From<f32> for Value {
     #[inline]
     fn from(f: f32) -> Value {
@@ -182,6 +212,26 @@ impl From<f64> for Value {
     }
 }
 
+impl From<Decimal> for Value {
+    #[inline]
+    fn from(d: Decimal) -> Value {
+        let value = d.mantissa();
+        let scale = d.scale() as u8;
+        Value::Decimal {
+            value,
+            width: decimal_width(value, scale),
+            scale,
+        }
+    }
+}
+
+impl From<char> for Value {
+    #[inline]
+    fn from(c: char) -> Value {
+        Value::Text(c.to_string())
+    }
+}
+
 impl From<String> for Value {
     #[inline]
     fn from(s: String) -> Value {
@@ -225,17 +275,579 @@ impl Value {
             Value::USmallInt(_) => Type::USmallInt,
             Value::UInt(_) => Type::UInt,
             Value::UBigInt(_) => Type::UBigInt,
+            Value::UHugeInt(_) => Type::UHugeInt,
             Value::Float(_) => Type::Float,
             Value::Double(_) => Type::Double,
-            Value::Decimal(_) => Type::Decimal,
+            Value::Decimal { .. } => Type::Decimal,
             Value::Timestamp(_, _) => Type::Timestamp,
+            Value::TimestampTz(_, _) => Type::TimestampTz,
             Value::Text(_) => Type::Text,
             Value::Blob(_) => Type::Blob,
             Value::Date32(_) => Type::Date32,
             Value::Time64(..) => Type::Time64,
             Value::Interval { .. } => Type::Interval,
-            Value::Union(..) | Value::Struct(..) | Value::List(..) | Value::Array(..) | Value::Map(..) => todo!(),
+            Value::Union { .. } => Type::Union,
+            Value::Struct(..) | Value::List(..) | Value::Array(..) | Value::Map(..) => {
+                self.logical_type().map_or(Type::Any, |ty| Type::from(&ty))
+            }
             Value::Enum(..) => Type::Enum,
         }
     }
 }
+
+impl Value {
+    /// Returns a DuckDB literal for the value, such that `SELECT <literal>`
+    /// gives back an equal value: `NULL`, `'it''s'`,
+    /// `TIMESTAMP '2024-01-01 00:00:00'`, `[1, 2]::INTEGER[]` and so on.
+    ///
+    /// Numbers carry a cast unless DuckDB would infer the same type, blobs use
+    /// the `\xHH` escapes of DuckDB's `BLOB` casts for anything but printable
+    /// ASCII, and nested values are written recursively. `Enum` entries are written as text.
+    /// DuckDB parses intervals and `TIMESTAMP_NS` literals to microsecond
+    /// precision, so any nanoseconds are lost.
+    pub fn to_sql_literal(&self) -> String {
+        Literal(self).to_string()
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::TinyInt(a), Value::TinyInt(b)) => a == b,
+            (Value::SmallInt(a), Value::SmallInt(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::HugeInt(a), Value::HugeInt(b)) => a == b,
+            (Value::UTinyInt(a), Value::UTinyInt(b)) => a == b,
+            (Value::USmallInt(a), Value::USmallInt(b)) => a == b,
+            (Value::UInt(a), Value::UInt(b)) => a == b,
+            (Value::UBigInt(a), Value::UBigInt(b)) => a == b,
+            (Value::UHugeInt(a), Value::UHugeInt(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            (Value::Double(a), Value::Double(b)) => a == b,
+            (
+                Value::Decimal {
+                    value: a,
+                    scale: a_scale,
+                    ..
+                },
+                Value::Decimal {
+                    value: b,
+                    scale: b_scale,
+                    ..
+                },
+            ) => decimal_eq((*a, *a_scale), (*b, *b_scale)),
+            (Value::Timestamp(a_unit, a), Value::Timestamp(b_unit, b))
+            | (Value::TimestampTz(a_unit, a), Value::TimestampTz(b_unit, b))
+            | (Value::Time64(a_unit, a), Value::Time64(b_unit, b)) => a_unit == b_unit && a == b,
+            (Value::Text(a), Value::Text(b)) | (Value::Enum(a), Value::Enum(b)) => a == b,
+            (Value::Blob(a), Value::Blob(b)) => a == b,
+            (Value::Date32(a), Value::Date32(b)) => a == b,
+            (
+                Value::Interval { months, days, nanos },
+                Value::Interval {
+                    months: b_months,
+                    days: b_days,
+                    nanos: b_nanos,
+                },
+            ) => (months, days, nanos) == (b_months, b_days, b_nanos),
+            (Value::List(a), Value::List(b)) | (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Struct(a), Value::Struct(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            (
+                Value::Union { tag, value },
+                Value::Union {
+                    tag: b_tag,
+                    value: b_value,
+                },
+            ) => tag == b_tag && value == b_value,
+            _ => false,
+        }
+    }
+}
+
+/// Human-readable output matching DuckDB's own casts to `VARCHAR`: text is
+/// written as is, without quotes, and times in ISO 8601 form.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("NULL"),
+            Value::Boolean(b) => write!(f, "{b}"),
+            Value::TinyInt(i) => write!(f, "{i}"),
+            Value::SmallInt(i) => write!(f, "{i}"),
+            Value::Int(i) => write!(f, "{i}"),
+            Value::BigInt(i) => write!(f, "{i}"),
+            Value::HugeInt(i) => write!(f, "{i}"),
+            Value::UTinyInt(i) => write!(f, "{i}"),
+            Value::USmallInt(i) => write!(f, "{i}"),
+            Value::UInt(i) => write!(f, "{i}"),
+            Value::UBigInt(i) => write!(f, "{i}"),
+            Value::UHugeInt(i) => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x:?}"),
+            Value::Double(x) => write!(f, "{x:?}"),
+            Value::Decimal { value, scale, .. } => f.write_str(&decimal_text(*value, *scale)),
+            Value::Timestamp(unit, t) => write_timestamp(f, *unit, *t),
+            Value::TimestampTz(unit, t) => {
+                write_timestamp(f, *unit, *t)?;
+                if is_infinite_timestamp(*t) {
+                    Ok(())
+                } else {
+                    f.write_str("+00")
+                }
+            }
+            Value::Text(s) | Value::Enum(s) => f.write_str(s),
+            Value::Blob(b) => write_blob(f, b),
+            Value::Date32(d) => write_date(f, (*d).into()),
+            Value::Time64(unit, t) => write_time(f, i128::from(*t) * nanos_per(*unit)),
+            Value::Interval { months, days, nanos } => write_interval(f, *months, *days, *nanos / 1000),
+            Value::List(items) | Value::Array(items) => write_items(f, "[", items.iter().map(|v| (None, v)), "]"),
+            Value::Struct(fields) => {
+                let fields = fields.iter().map(|(k, v)| (Some(format!("'{k}': ")), v));
+                write_items(f, "{", fields, "}")
+            }
+            Value::Map(entries) => {
+                let entries = entries.iter().map(|(k, v)| (Some(format!("{k}=")), v));
+                write_items(f, "{", entries, "}")
+            }
+            Value::Union { value, .. } => value.fmt(f),
+        }
+    }
+}
+
+/// Formats a [`Value`] as a literal, see [`Value::to_sql_literal`].
+struct Literal<'a>(&'a Value);
+
+impl fmt::Display for Literal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.0;
+        match value {
+            // DuckDB parses the magnitude first, so `-2147483648` would be a
+            // `BIGINT`.
+            Value::Int(i32::MIN) => write_cast(f, i32::MIN, "INTEGER"),
+            Value::Null | Value::Boolean(_) | Value::Int(_) => write!(f, "{value}"),
+            Value::TinyInt(i) => write_cast(f, i, "TINYINT"),
+            Value::SmallInt(i) => write_cast(f, i, "SMALLINT"),
+            Value::BigInt(i) => write_cast(f, i, "BIGINT"),
+            Value::HugeInt(i) => write_cast(f, i, "HUGEINT"),
+            Value::UTinyInt(i) => write_cast(f, i, "UTINYINT"),
+            Value::USmallInt(i) => write_cast(f, i, "USMALLINT"),
+            Value::UInt(i) => write_cast(f, i, "UINTEGER"),
+            Value::UBigInt(i) => write_cast(f, i, "UBIGINT"),
+            // Beyond `HUGEINT` DuckDB parses numbers as `DOUBLE`.
+            Value::UHugeInt(i) => write!(f, "'{i}'::UHUGEINT"),
+            // Numeric literals are parsed as decimals, which have no -0.0 and
+            // round twice on the way to `FLOAT`.
+            Value::Float(x) => write!(f, "'{}'::FLOAT", float_text(*x)),
+            Value::Double(x) => write!(f, "'{}'::DOUBLE", float_text(*x)),
+            Value::Decimal { value, width, scale } => {
+                write_cast(f, decimal_text(*value, *scale), &format!("DECIMAL({width}, {scale})"))
+            }
+            Value::Timestamp(unit, _) => {
+                let ty = match unit {
+                    TimeUnit::Second => "TIMESTAMP_S",
+                    TimeUnit::Millisecond => "TIMESTAMP_MS",
+                    TimeUnit::Microsecond => "TIMESTAMP",
+                    TimeUnit::Nanosecond => "TIMESTAMP_NS",
+                };
+                write!(f, "{ty} '{value}'")
+            }
+            Value::TimestampTz(..) => write!(f, "TIMESTAMPTZ '{value}'"),
+            Value::Text(s) | Value::Enum(s) => f.write_str(&quote_str(s)),
+            Value::Blob(_) => write!(f, "'{value}'::BLOB"),
+            Value::Date32(_) => write!(f, "DATE '{value}'"),
+            Value::Time64(..) => write!(f, "TIME '{value}'"),
+            Value::Interval { months, days, nanos } => {
+                write!(
+                    f,
+                    "INTERVAL '{months} months {days} days {} microseconds'",
+                    nanos / 1000
+                )
+            }
+            Value::List(items) => write_items(f, "[", items.iter().map(|v| (None, Literal(v))), "]"),
+            // `array_value` infers the element type, which an `ARRAY` cast
+            // would need spelled out.
+            Value::Array(items) => write_items(f, "array_value(", items.iter().map(|v| (None, Literal(v))), ")"),
+            Value::Struct(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(k, v)| (Some(format!("{}: ", quote_str(k))), Literal(v)));
+                write_items(f, "{", fields, "}")
+            }
+            Value::Map(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(k, v)| (Some(format!("{}: ", Literal(k))), Literal(v)));
+                write_items(f, "MAP {", entries, "}")
+            }
+            Value::Union { tag, value } => {
+                write!(f, "union_value(\"{}\" := {})", tag.replace('"', "\"\""), Literal(value))
+            }
+        }
+    }
+}
+
+/// Writes `value::ty`, parenthesizing negative values since DuckDB would
+/// otherwise cast before negating.
+fn write_cast(f: &mut fmt::Formatter<'_>, value: impl fmt::Display, ty: &str) -> fmt::Result {
+    let value = value.to_string();
+    if value.starts_with('-') {
+        write!(f, "({value})::{ty}")
+    } else {
+        write!(f, "{value}::{ty}")
+    }
+}
+
+/// Formats a float as text DuckDB parses back to the same value, down to the
+/// sign of -0.0 and of `NaN`, which Rust's own formatting drops. `NaN` payloads
+/// can't be written.
+pub(crate) fn float_text<F: Into<f64> + fmt::Debug + Copy>(x: F) -> String {
+    let wide: f64 = x.into();
+    if wide.is_nan() && wide.is_sign_negative() {
+        "-NaN".to_owned()
+    } else {
+        format!("{x:?}")
+    }
+}
+
+/// Formats `value` scaled down by `scale` digits exactly, keeping trailing
+/// zeros as DuckDB does: `150` at scale 2 is `1.50`.
+pub(crate) fn decimal_text(value: i128, scale: u8) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let digits = value.unsigned_abs().to_string();
+    let scale = usize::from(scale);
+    if scale == 0 {
+        return format!("{sign}{digits}");
+    }
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int, fraction) = digits.split_at(digits.len() - scale);
+    format!("{sign}{int}.{fraction}")
+}
+
+/// The smallest `DECIMAL` width that holds `value` at `scale`.
+pub(crate) fn decimal_width(value: i128, scale: u8) -> u8 {
+    let digits = value.unsigned_abs().checked_ilog10().map_or(1, |d| d + 1) as u8;
+    digits.max(scale).max(1)
+}
+
+/// Checks that `value` at `scale` fits a `DECIMAL(width, scale)`, which
+/// DuckDB allows up to 38 digits wide.
+pub(crate) fn check_decimal(value: i128, width: u8, scale: u8) -> Result<()> {
+    if !(1..=38).contains(&width) || scale > width || decimal_width(value, 0) > width {
+        let text = decimal_text(value, scale);
+        return Err(Error::ToSqlConversionFailure(
+            format!("{text} doesn't fit a DECIMAL({width}, {scale})").into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Compares two decimals by value whatever their scales.
+fn decimal_eq((a, a_scale): (i128, u8), (b, b_scale): (i128, u8)) -> bool {
+    let (a, b, diff) = if a_scale <= b_scale {
+        (a, b, b_scale - a_scale)
+    } else {
+        (b, a, a_scale - b_scale)
+    };
+    match 10i128.checked_pow(diff.into()) {
+        Some(factor) => a.checked_mul(factor) == Some(b),
+        None => a == 0 && b == 0,
+    }
+}
+
+/// Quotes a string literal. DuckDB reads SQL as a C string, so NUL characters
+/// are spliced in with `chr(0)`.
+fn quote_str(s: &str) -> String {
+    let quoted: Vec<String> = s
+        .split('\0')
+        .map(|part| format!("'{}'", part.replace('\'', "''")))
+        .collect();
+    quoted.join(" || chr(0) || ")
+}
+
+fn write_items<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    open: &str,
+    items: impl Iterator<Item = (Option<String>, T)>,
+    close: &str,
+) -> fmt::Result {
+    f.write_str(open)?;
+    for (i, (key, item)) in items.enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}{item}", key.unwrap_or_default())?;
+    }
+    f.write_str(close)
+}
+
+/// Escapes bytes the same way DuckDB casts a `BLOB` to `VARCHAR`.
+fn write_blob(f: &mut fmt::Formatter<'_>, blob: &[u8]) -> fmt::Result {
+    for &b in blob {
+        match b {
+            b'\\' | b'\'' | b'"' => write!(f, "\\x{b:02X}")?,
+            b' '..=b'~' => write!(f, "{}", b as char)?,
+            _ => write!(f, "\\x{b:02X}")?,
+        }
+    }
+    Ok(())
+}
+
+const NANOS_PER_DAY: i128 = 86_400 * 1_000_000_000;
+
+fn nanos_per(unit: TimeUnit) -> i128 {
+    match unit {
+        TimeUnit::Second => 1_000_000_000,
+        TimeUnit::Millisecond => 1_000_000,
+        TimeUnit::Microsecond => 1_000,
+        TimeUnit::Nanosecond => 1,
+    }
+}
+
+/// Whether `t` is one of the sentinels DuckDB stores `infinity` and
+/// `-infinity` timestamps as, whatever their unit.
+pub(crate) fn is_infinite_timestamp(t: i64) -> bool {
+    t == i64::MAX || t == -i64::MAX
+}
+
+fn write_timestamp(f: &mut fmt::Formatter<'_>, unit: TimeUnit, t: i64) -> fmt::Result {
+    if is_infinite_timestamp(t) {
+        return f.write_str(if t > 0 { "infinity" } else { "-infinity" });
+    }
+    let nanos = i128::from(t) * nanos_per(unit);
+    write_date(f, nanos.div_euclid(NANOS_PER_DAY) as i64)?;
+    f.write_str(" ")?;
+    write_time(f, nanos.rem_euclid(NANOS_PER_DAY))
+}
+
+/// Writes days since the Unix epoch as a proleptic Gregorian date, using
+/// DuckDB's `(BC)` suffix for years before 1.
+fn write_date(f: &mut fmt::Formatter<'_>, days: i64) -> fmt::Result {
+    // Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    if year > 0 {
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    } else {
+        write!(f, "{:04}-{month:02}-{day:02} (BC)", 1 - year)
+    }
+}
+
+/// Writes a time of day, or a duration, as `HH:MM:SS` with any fraction of a
+/// second trimmed of trailing zeros.
+fn write_time(f: &mut fmt::Formatter<'_>, nanos: i128) -> fmt::Result {
+    let secs = nanos / 1_000_000_000;
+    write!(f, "{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)?;
+    let fraction = nanos % 1_000_000_000;
+    if fraction != 0 {
+        write!(f, ".{}", format!("{fraction:09}").trim_end_matches('0'))?;
+    }
+    Ok(())
+}
+
+fn write_interval(f: &mut fmt::Formatter<'_>, months: i32, days: i32, micros: i64) -> fmt::Result {
+    let mut sep = "";
+    for (n, unit) in [(months / 12, "year"), (months % 12, "month"), (days, "day")] {
+        if n != 0 {
+            write!(f, "{sep}{n} {unit}{}", if n.abs() == 1 { "" } else { "s" })?;
+            sep = " ";
+        }
+    }
+    if micros != 0 || sep.is_empty() {
+        f.write_str(sep)?;
+        if micros < 0 {
+            f.write_str("-")?;
+        }
+        write_time(f, i128::from(micros).abs() * 1000)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::Value;
+    use crate::{
+        types::{OrderedMap, TimeUnit},
+        Connection, Result,
+    };
+
+    fn values() -> Vec<Value> {
+        let text = |s: &str| Value::Text(s.to_owned());
+        vec![
+            Value::Null,
+            Value::Boolean(true),
+            Value::TinyInt(i8::MIN),
+            Value::SmallInt(i16::MIN),
+            Value::Int(i32::MIN),
+            Value::BigInt(i64::MIN),
+            Value::HugeInt(i128::MIN),
+            Value::UTinyInt(u8::MAX),
+            Value::USmallInt(u16::MAX),
+            Value::UInt(u32::MAX),
+            Value::UBigInt(u64::MAX),
+            Value::UHugeInt(u128::MAX),
+            Value::Float(0.1),
+            Value::Float(-0.0),
+            Value::Float(f32::MIN_POSITIVE / 4.0),
+            Value::Float(f32::NAN),
+            Value::Float(f32::INFINITY),
+            Value::Double(f64::NEG_INFINITY),
+            Value::Double(-1e300),
+            Value::Double(5e-324),
+            Value::Decimal {
+                value: -12_340,
+                width: 5,
+                scale: 3,
+            },
+            Value::Decimal {
+                value: -42,
+                width: 5,
+                scale: 0,
+            },
+            Value::Decimal {
+                value: 150,
+                width: 4,
+                scale: 2,
+            },
+            Value::Decimal {
+                value: -5,
+                width: 18,
+                scale: 9,
+            },
+            Value::Decimal {
+                value: 10i128.pow(38) - 1,
+                width: 38,
+                scale: 9,
+            },
+            Value::Timestamp(TimeUnit::Second, 1_704_067_200),
+            Value::Timestamp(TimeUnit::Millisecond, 1_704_067_200_123),
+            Value::Timestamp(TimeUnit::Microsecond, -1),
+            Value::Timestamp(TimeUnit::Nanosecond, -1_000),
+            Value::Timestamp(TimeUnit::Microsecond, -62_135_596_800_000_001),
+            Value::Timestamp(TimeUnit::Microsecond, i64::MAX),
+            Value::TimestampTz(TimeUnit::Microsecond, 1_704_067_200_500_000),
+            Value::TimestampTz(TimeUnit::Microsecond, -62_135_596_800_000_001),
+            Value::TimestampTz(TimeUnit::Microsecond, -i64::MAX),
+            text("it's \"quoted\" \\ back\\slash'); DROP TABLE t; --"),
+            text("NULL"),
+            text("a\0b\0"),
+            text(""),
+            text("🦆 ünïcode"),
+            Value::Blob(vec![0, 0xFF, b'\'', b'\\', b'"', b'a', b' ', 0x7F]),
+            Value::Blob(vec![]),
+            Value::Date32(-719_163),
+            Value::Date32(19_723),
+            Value::Time64(TimeUnit::Microsecond, 86_400_000_000),
+            Value::Time64(TimeUnit::Microsecond, 1),
+            Value::Interval {
+                months: -14,
+                days: 3,
+                nanos: -5_000,
+            },
+            Value::Interval {
+                months: 0,
+                days: 0,
+                nanos: 0,
+            },
+            Value::List(vec![Value::Int(1), Value::Null, Value::Int(3)]),
+            Value::List(vec![text("it's"), text("a, b]"), text("NULL")]),
+            Value::List(vec![Value::List(vec![Value::BigInt(-1)]), Value::List(vec![])]),
+            Value::Array(vec![Value::Double(1.5), Value::Double(-2.0)]),
+            Value::Struct(OrderedMap::from(vec![
+                ("it's".to_owned(), Value::Int(1)),
+                ("b".to_owned(), Value::List(vec![text("x'}")])),
+            ])),
+            Value::Map(OrderedMap::from(vec![
+                (text("k'"), Value::Int(1)),
+                (text("=,"), Value::Null),
+            ])),
+            Value::Union {
+                tag: "a \"b\"".to_owned(),
+                value: Box::new(Value::Int(1)),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_to_sql_literal_round_trip() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        for value in values() {
+            // Arrow can't export `UHUGEINT`; it is covered through `VARCHAR`
+            // by `test_display_matches_varchar`.
+            if matches!(value, Value::UHugeInt(_)) {
+                continue;
+            }
+            let literal = value.to_sql_literal();
+            let read: Value = db
+                .query_row(&format!("SELECT {literal}"), [], |r| r.get(0))
+                .unwrap_or_else(|err| panic!("{literal}: {err}"));
+            // Compared through `Debug` to tell NaN and -0.0 apart.
+            assert_eq!(format!("{value:?}"), format!("{read:?}"), "{literal}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_sql_literal() {
+        let text = |s: &str| Value::Text(s.to_owned());
+        for (value, literal) in [
+            (Value::Null, "NULL"),
+            (Value::Int(-1), "-1"),
+            (Value::TinyInt(-1), "(-1)::TINYINT"),
+            (Value::Float(0.1), "'0.1'::FLOAT"),
+            (Value::Double(f64::NAN), "'NaN'::DOUBLE"),
+            (Value::Float(-f32::NAN), "'-NaN'::FLOAT"),
+            (
+                Value::Decimal {
+                    value: -5,
+                    width: 18,
+                    scale: 9,
+                },
+                "(-0.000000005)::DECIMAL(18, 9)",
+            ),
+            (text("it's"), "'it''s'"),
+            (text("\0"), "'' || chr(0) || ''"),
+            (Value::Blob(b"\0a'".to_vec()), "'\\x00a\\x27'::BLOB"),
+            (
+                Value::Timestamp(TimeUnit::Microsecond, 1_704_067_200_000_000),
+                "TIMESTAMP '2024-01-01 00:00:00'",
+            ),
+            (Value::Date32(-719_163), "DATE '0001-12-31 (BC)'"),
+            (Value::List(vec![Value::Int(1), text("a")]), "[1, 'a']"),
+            (
+                Value::Struct(OrderedMap::from(vec![("a".to_owned(), Value::Null)])),
+                "{'a': NULL}",
+            ),
+        ] {
+            assert_eq!(literal, value.to_sql_literal());
+        }
+    }
+
+    #[test]
+    fn test_display_matches_varchar() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        for value in values() {
+            // DuckDB spells floats differently, e.g. `1e+300` and `nan`.
+            if matches!(value, Value::Float(_) | Value::Double(_)) {
+                continue;
+            }
+            let literal = value.to_sql_literal();
+            let varchar: Option<String> = db.query_row(&format!("SELECT ({literal})::VARCHAR"), [], |r| r.get(0))?;
+            assert_eq!(
+                varchar.unwrap_or_else(|| "NULL".to_owned()),
+                value.to_string(),
+                "{literal}"
+            );
+        }
+        assert_eq!("-1e300", Value::Double(-1e300).to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+//! Owned DuckDB C API values, for parameters the `duckdb_bind_*` functions
+//! can't express.
+
+use std::{ffi::CString, os::raw::c_char};
+
+use super::{
+    value::{check_decimal, decimal_text, float_text},
+    OrderedMap, TimeUnit, Value,
+};
+use crate::{ffi, Error, Result};
+
+/// A `duckdb_value`, destroyed on drop.
+pub(crate) struct ValueHandle(ffi::duckdb_value);
+
+impl ValueHandle {
+    #[inline]
+    pub(crate) fn ptr(&self) -> ffi::duckdb_value {
+        self.0
+    }
+
+    /// Builds a `LIST` value from its elements, which may be lists or arrays
+    /// themselves.
+    ///
+    /// The C API can only create `BIGINT` and `VARCHAR` scalars, so every
+    /// scalar is created as one of those, in DuckDB's own text form for
+    /// temporal types and blobs, and cast by DuckDB to the element type of
+    /// the first item. The C API has no `NULL` type for the elements of an
+    /// empty list, so they are `INTEGER`s, which DuckDB casts to other numeric
+    /// lists and, on insert, to any list column. There is no way to create a
+    /// `NULL` element.
+    pub(crate) fn list(items: &[Value]) -> Result<ValueHandle> {
+        let child = LogicalTypeHandle::element_of(items, "LIST")?;
+        let values = items
+            .iter()
+            .map(|v| ValueHandle::new(v, "LIST"))
+            .collect::<Result<Vec<_>>>()?;
+        let mut ptrs: Vec<_> = values.iter().map(|v| v.0).collect();
+        let list = unsafe { ffi::duckdb_create_list_value(child.0, ptrs.as_mut_ptr(), ptrs.len() as u64) };
+        ValueHandle::checked(list, || element_error(items, "LIST"))
+    }
+
+    /// Builds an `ARRAY` value from its elements, like [`ValueHandle::list`].
+    /// `ARRAY`s can't be empty.
+    pub(crate) fn array(items: &[Value]) -> Result<ValueHandle> {
+        if items.is_empty() {
+            return Err(Error::ToSqlConversionFailure("ARRAY parameters can't be empty".into()));
+        }
+        let child = LogicalTypeHandle::element_of(items, "ARRAY")?;
+        let values = items
+            .iter()
+            .map(|v| ValueHandle::new(v, "ARRAY"))
+            .collect::<Result<Vec<_>>>()?;
+        let mut ptrs: Vec<_> = values.iter().map(|v| v.0).collect();
+        let array = unsafe { ffi::duckdb_create_array_value(child.0, ptrs.as_mut_ptr(), ptrs.len() as u64) };
+        ValueHandle::checked(array, || element_error(items, "ARRAY"))
+    }
+
+    /// Builds a `STRUCT` value from its fields, like [`ValueHandle::list`].
+    /// DuckDB has no empty structs, and a `NULL` field can't be created either.
+    pub(crate) fn structure(fields: &OrderedMap<String, Value>) -> Result<ValueHandle> {
+        let ty = LogicalTypeHandle::structure(fields)?;
+        let values = fields
+            .values()
+            .map(|v| ValueHandle::new(v, "STRUCT"))
+            .collect::<Result<Vec<_>>>()?;
+        let mut ptrs: Vec<_> = values.iter().map(|v| v.0).collect();
+        let value = unsafe { ffi::duckdb_create_struct_value(ty.0, ptrs.as_mut_ptr()) };
+        ValueHandle::checked(value, || "STRUCT fields can't be converted to their types".to_owned())
+    }
+
+    fn checked(value: ffi::duckdb_value, error: impl FnOnce() -> String) -> Result<ValueHandle> {
+        if value.is_null() {
+            return Err(Error::ToSqlConversionFailure(error().into()));
+        }
+        Ok(ValueHandle(value))
+    }
+
+    fn new(value: &Value, container: &str) -> Result<ValueHandle> {
+        let text = match *value {
+            Value::List(ref items) => return ValueHandle::list(items),
+            Value::Array(ref items) => return ValueHandle::array(items),
+            Value::Struct(ref fields) => return ValueHandle::structure(fields),
+            Value::TinyInt(i) => return Ok(ValueHandle::int64(i.into())),
+            Value::SmallInt(i) => return Ok(ValueHandle::int64(i.into())),
+            Value::Int(i) => return Ok(ValueHandle::int64(i.into())),
+            Value::BigInt(i) => return Ok(ValueHandle::int64(i)),
+            Value::UTinyInt(i) => return Ok(ValueHandle::int64(i.into())),
+            Value::USmallInt(i) => return Ok(ValueHandle::int64(i.into())),
+            Value::UInt(i) => return Ok(ValueHandle::int64(i.into())),
+            Value::Boolean(b) => b.to_string(),
+            Value::HugeInt(i) => i.to_string(),
+            Value::UBigInt(i) => i.to_string(),
+            Value::UHugeInt(i) => i.to_string(),
+            Value::Float(f) => float_text(f),
+            Value::Double(f) => float_text(f),
+            Value::Decimal { value, scale, .. } => decimal_text(value, scale),
+            Value::Text(ref s) => s.clone(),
+            // DuckDB parses its own `VARCHAR` output of these back.
+            Value::Blob(_)
+            | Value::Date32(_)
+            | Value::Time64(..)
+            | Value::Timestamp(..)
+            | Value::TimestampTz(..)
+            | Value::Interval { .. } => value.to_string(),
+            _ => return Err(unsupported(value, container)),
+        };
+        Ok(ValueHandle(unsafe {
+            ffi::duckdb_create_varchar_length(text.as_ptr() as *const c_char, text.len() as u64)
+        }))
+    }
+
+    #[inline]
+    fn int64(i: i64) -> ValueHandle {
+        ValueHandle(unsafe { ffi::duckdb_create_int64(i) })
+    }
+}
+
+impl Drop for ValueHandle {
+    fn drop(&mut self) {
+        unsafe { ffi::duckdb_destroy_value(&mut self.0) };
+    }
+}
+
+fn element_error(items: &[Value], container: &str) -> String {
+    let first = match items.first() {
+        None => "INTEGER".to_owned(),
+        Some(Value::List(_)) => "LIST".to_owned(),
+        Some(Value::Array(_)) => "ARRAY".to_owned(),
+        Some(Value::Struct(_)) => "STRUCT".to_owned(),
+        Some(v) => v.data_type().to_string(),
+    };
+    format!("{container} elements can't be converted to {first}")
+}
+
+fn unsupported(value: &Value, container: &str) -> Error {
+    let kind = match value {
+        Value::Null => "NULL".to_owned(),
+        Value::Map(..) | Value::Union { .. } => "Nested".to_owned(),
+        _ => value.data_type().to_string(),
+    };
+    let reason = match container {
+        "STRUCT" => format!("{kind} fields can't be bound inside a STRUCT"),
+        "ARRAY" => format!("{kind} elements can't be bound inside an ARRAY"),
+        _ => format!("{kind} elements can't be bound inside a {container}"),
+    };
+    Error::ToSqlConversionFailure(reason.into())
+}
+
+/// A `duckdb_logical_type`, destroyed on drop.
+struct LogicalTypeHandle(ffi::duckdb_logical_type);
+
+impl LogicalTypeHandle {
+    /// The element type DuckDB should cast the items of a `LIST` or `ARRAY`
+    /// to, taken from the first.
+    fn element_of(items: &[Value], container: &str) -> Result<LogicalTypeHandle> {
+        match items.first() {
+            Some(first) => LogicalTypeHandle::of(first, container),
+            None => Ok(LogicalTypeHandle::id(ffi::DUCKDB_TYPE_DUCKDB_TYPE_INTEGER)),
+        }
+    }
+
+    /// The type DuckDB should cast a [`ValueHandle`] to.
+    fn of(value: &Value, container: &str) -> Result<LogicalTypeHandle> {
+        let id = match *value {
+            Value::List(ref items) => {
+                let child = LogicalTypeHandle::element_of(items, container)?;
+                return Ok(LogicalTypeHandle(unsafe { ffi::duckdb_create_list_type(child.0) }));
+            }
+            Value::Array(ref items) => {
+                let child = LogicalTypeHandle::element_of(items, container)?;
+                return Ok(LogicalTypeHandle(unsafe {
+                    ffi::duckdb_create_array_type(child.0, items.len() as u64)
+                }));
+            }
+            Value::Struct(ref fields) => return LogicalTypeHandle::structure(fields),
+            Value::Boolean(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_BOOLEAN,
+            Value::TinyInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_TINYINT,
+            Value::SmallInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_SMALLINT,
+            Value::Int(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_INTEGER,
+            Value::BigInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_BIGINT,
+            Value::HugeInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_HUGEINT,
+            Value::UTinyInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_UTINYINT,
+            Value::USmallInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_USMALLINT,
+            Value::UInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_UINTEGER,
+            Value::UBigInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_UBIGINT,
+            Value::UHugeInt(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_UHUGEINT,
+            Value::Float(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_FLOAT,
+            Value::Double(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_DOUBLE,
+            Value::Decimal { value, width, scale } => {
+                check_decimal(value, width, scale)?;
+                return Ok(LogicalTypeHandle(unsafe {
+                    ffi::duckdb_create_decimal_type(width, scale)
+                }));
+            }
+            Value::Text(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_VARCHAR,
+            Value::Blob(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_BLOB,
+            Value::Date32(_) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_DATE,
+            Value::Time64(..) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIME,
+            Value::Timestamp(TimeUnit::Second, _) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_S,
+            Value::Timestamp(TimeUnit::Millisecond, _) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_MS,
+            Value::Timestamp(TimeUnit::Microsecond, _) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP,
+            Value::Timestamp(TimeUnit::Nanosecond, _) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_NS,
+            Value::TimestampTz(..) => ffi::DUCKDB_TYPE_DUCKDB_TYPE_TIMESTAMP_TZ,
+            Value::Interval { .. } => ffi::DUCKDB_TYPE_DUCKDB_TYPE_INTERVAL,
+            _ => return Err(unsupported(value, container)),
+        };
+        Ok(LogicalTypeHandle::id(id))
+    }
+
+    fn structure(fields: &OrderedMap<String, Value>) -> Result<LogicalTypeHandle> {
+        if fields.iter().next().is_none() {
+            return Err(Error::ToSqlConversionFailure(
+                "STRUCT parameters need at least one field".into(),
+            ));
+        }
+        let types = fields
+            .values()
+            .map(|v| LogicalTypeHandle::of(v, "STRUCT"))
+            .collect::<Result<Vec<_>>>()?;
+        let names = fields
+            .keys()
+            .map(|k| CString::new(k.as_str()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| Error::ToSqlConversionFailure(err.into()))?;
+        let mut type_ptrs: Vec<_> = types.iter().map(|t| t.0).collect();
+        let mut name_ptrs: Vec<_> = names.iter().map(|n| n.as_ptr()).collect();
+        Ok(LogicalTypeHandle(unsafe {
+            ffi::duckdb_create_struct_type(type_ptrs.as_mut_ptr(), name_ptrs.as_mut_ptr(), names.len() as u64)
+        }))
+    }
+
+    #[inline]
+    fn id(id: ffi::DUCKDB_TYPE) -> LogicalTypeHandle {
+        LogicalTypeHandle(unsafe { ffi::duckdb_create_logical_type(id) })
+    }
+}
+
+impl Drop for LogicalTypeHandle {
+    fn drop(&mut self) {
+        unsafe { ffi::duckdb_destroy_logical_type(&mut self.0) };
+    }
+}
@@ -1,15 +1,14 @@
-use super::{Type, Value};
+use super::{LogicalType, Type, Value};
 use crate::types::{FromSqlError, FromSqlResult, OrderedMap};
 
 use crate::Row;
-use rust_decimal::prelude::*;
 
 use arrow::{
     array::{
         Array, ArrayRef, DictionaryArray, FixedSizeListArray, LargeListArray, ListArray, MapArray, StructArray,
         UnionArray,
     },
-    datatypes::{UInt16Type, UInt32Type, UInt8Type},
+    datatypes::{DataType, UInt16Type, UInt32Type, UInt8Type},
 };
 
 /// An absolute length of time in seconds, milliseconds, microseconds or nanoseconds.
@@ -38,8 +37,10 @@ impl TimeUnit {
     }
 }
 
-/// A non-owning [static type value](https://duckdb.org/docs/sql/data_types/overview). Typically the
-/// memory backing this value is owned by SQLite.
+/// A non-owning [static type value](https://duckdb.org/docs/sql/data_types/overview). The memory
+/// backing `Text`, `Blob` and nested values is owned by the result chunk the
+/// value was read from, so a `ValueRef` obtained from a [`Row`](crate::Row)
+/// borrows that row and can't outlive it.
 ///
 /// See [`Value`](Value) for an owning dynamic type value.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -66,14 +67,26 @@ pub enum ValueRef<'a> {
     UInt(u32),
     /// The value is a unsigned big integer.
     UBigInt(u64),
+    /// The value is a unsigned huge integer.
+    UHugeInt(u128),
     /// The value is a f32.
     Float(f32),
     /// The value is a f64.
     Double(f64),
-    /// The value is a decimal
-    Decimal(Decimal),
+    /// The value is a decimal, see [`Value::Decimal`]. Unlike values,
+    /// references compare their width and scale too.
+    Decimal {
+        /// unscaled value
+        value: i128,
+        /// total number of digits
+        width: u8,
+        /// number of digits after the decimal point
+        scale: u8,
+    },
     /// The value is a timestamp.
     Timestamp(TimeUnit, i64),
+    /// The value is a timestamp with time zone, relative to the UTC epoch.
+    TimestampTz(TimeUnit, i64),
     /// The value is a text string.
     Text(&'a [u8]),
     /// The value is a blob of data
@@ -125,6 +138,18 @@ pub enum EnumType<'a> {
     UInt32(&'a DictionaryArray<UInt32Type>),
 }
 
+impl<'a> EnumType<'a> {
+    /// Looks up the dictionary entry for the enum at `idx`.
+    fn entry(&self, idx: usize) -> ValueRef<'a> {
+        let (key, values) = match *self {
+            EnumType::UInt8(res) => (res.keys().value(idx) as usize, res.values()),
+            EnumType::UInt16(res) => (res.keys().value(idx) as usize, res.values()),
+            EnumType::UInt32(res) => (res.keys().value(idx) as usize, res.values()),
+        };
+        Row::value_ref_internal(key, 0, values)
+    }
+}
+
 impl ValueRef<'_> {
     /// Returns DuckDB fundamental datatype.
     #[inline]
@@ -141,10 +166,12 @@ impl ValueRef<'_> {
             ValueRef::USmallInt(_) => Type::USmallInt,
             ValueRef::UInt(_) => Type::UInt,
             ValueRef::UBigInt(_) => Type::UBigInt,
+            ValueRef::UHugeInt(_) => Type::UHugeInt,
             ValueRef::Float(_) => Type::Float,
             ValueRef::Double(_) => Type::Double,
-            ValueRef::Decimal(_) => Type::Decimal,
+            ValueRef::Decimal { .. } => Type::Decimal,
             ValueRef::Timestamp(..) => Type::Timestamp,
+            ValueRef::TimestampTz(..) => Type::TimestampTz,
             ValueRef::Text(_) => Type::Text,
             ValueRef::Blob(_) => Type::Blob,
             ValueRef::Date32(_) => Type::Date32,
@@ -162,6 +189,52 @@ impl ValueRef<'_> {
         }
     }
 
+    /// Returns the full DuckDB type of the value, see [`LogicalType`].
+    ///
+    /// Nested values report the type of the column they were read from, so
+    /// an empty `LIST` still knows its element type and an `ENUM` its whole
+    /// dictionary. A `NULL` reports [`LogicalType::Null`] whatever its
+    /// column's type.
+    ///
+    /// Returns `None` if a nested value's Arrow type isn't one DuckDB
+    /// converts values to.
+    pub fn logical_type(&self) -> Option<LogicalType> {
+        let ty = match *self {
+            ValueRef::Null => LogicalType::Null,
+            ValueRef::Boolean(_) => LogicalType::Boolean,
+            ValueRef::TinyInt(_) => LogicalType::TinyInt,
+            ValueRef::SmallInt(_) => LogicalType::SmallInt,
+            ValueRef::Int(_) => LogicalType::Int,
+            ValueRef::BigInt(_) => LogicalType::BigInt,
+            ValueRef::HugeInt(_) => LogicalType::HugeInt,
+            ValueRef::UTinyInt(_) => LogicalType::UTinyInt,
+            ValueRef::USmallInt(_) => LogicalType::USmallInt,
+            ValueRef::UInt(_) => LogicalType::UInt,
+            ValueRef::UBigInt(_) => LogicalType::UBigInt,
+            ValueRef::UHugeInt(_) => LogicalType::UHugeInt,
+            ValueRef::Float(_) => LogicalType::Float,
+            ValueRef::Double(_) => LogicalType::Double,
+            ValueRef::Decimal { width, scale, .. } => LogicalType::Decimal { width, scale },
+            ValueRef::Timestamp(unit, _) => LogicalType::Timestamp(unit),
+            ValueRef::TimestampTz(unit, _) => LogicalType::TimestampTz(unit),
+            ValueRef::Text(_) => LogicalType::Text,
+            ValueRef::Blob(_) => LogicalType::Blob,
+            ValueRef::Date32(_) => LogicalType::Date32,
+            ValueRef::Time64(..) => LogicalType::Time64,
+            ValueRef::Interval { .. } => LogicalType::Interval,
+            ValueRef::List(ListType::Regular(arr), _) => LogicalType::of_array(arr)?,
+            ValueRef::List(ListType::Large(arr), _) => LogicalType::of_array(arr)?,
+            ValueRef::Enum(EnumType::UInt8(arr), _) => LogicalType::of_array(arr)?,
+            ValueRef::Enum(EnumType::UInt16(arr), _) => LogicalType::of_array(arr)?,
+            ValueRef::Enum(EnumType::UInt32(arr), _) => LogicalType::of_array(arr)?,
+            ValueRef::Struct(arr, _) => LogicalType::of_array(arr)?,
+            ValueRef::Array(arr, _) => LogicalType::of_array(arr)?,
+            ValueRef::Map(arr, _) => LogicalType::of_array(arr)?,
+            ValueRef::Union(arr, _) => LogicalType::of_array(arr.as_ref())?,
+        };
+        Some(ty)
+    }
+
     /// Returns an owned version of this ValueRef
     pub fn to_owned(&self) -> Value {
         (*self).into()
@@ -169,12 +242,27 @@ impl ValueRef<'_> {
 }
 
 impl<'a> ValueRef<'a> {
-    /// If `self` is case `Text`, returns the string value. Otherwise, returns
-    /// [`Err(Error::InvalidColumnType)`](crate::Error::InvalidColumnType).
+    /// If `self` is case `Text` or `Enum`, returns the string value without
+    /// copying it. Returns [`Err(FromSqlError::InvalidUtf8)`](FromSqlError::InvalidUtf8)
+    /// if the text is not valid UTF-8, or
+    /// [`Err(Error::InvalidColumnType)`](crate::Error::InvalidColumnType) for
+    /// other cases.
     #[inline]
     pub fn as_str(&self) -> FromSqlResult<&'a str> {
         match *self {
-            ValueRef::Text(t) => std::str::from_utf8(t).map_err(|e| FromSqlError::Other(Box::new(e))),
+            ValueRef::Text(t) => std::str::from_utf8(t).map_err(FromSqlError::InvalidUtf8),
+            ValueRef::Enum(items, idx) => items.entry(idx).as_str(),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+
+    /// If `self` is case `Text` or `Blob`, returns the raw bytes without copying
+    /// them or checking that text is valid UTF-8. Otherwise, returns
+    /// [`Err(Error::InvalidColumnType)`](crate::Error::InvalidColumnType).
+    #[inline]
+    pub fn as_bytes(&self) -> FromSqlResult<&'a [u8]> {
+        match *self {
+            ValueRef::Text(b) | ValueRef::Blob(b) => Ok(b),
             _ => Err(FromSqlError::InvalidType),
         }
     }
@@ -189,6 +277,76 @@ impl<'a> ValueRef<'a> {
             _ => Err(FromSqlError::InvalidType),
         }
     }
+
+    /// If `self` is case `List` or `Array`, returns its elements, which
+    /// borrow from the result like `self` does, so nested values can be
+    /// walked without copying them. Otherwise, returns
+    /// [`Err(Error::InvalidColumnType)`](crate::Error::InvalidColumnType).
+    pub fn as_list(&self) -> FromSqlResult<Vec<ValueRef<'a>>> {
+        let (values, range) = match *self {
+            ValueRef::List(ListType::Regular(items), idx) => {
+                let offsets = items.value_offsets();
+                (items.values(), offsets[idx] as usize..offsets[idx + 1] as usize)
+            }
+            ValueRef::List(ListType::Large(items), idx) => {
+                let offsets = items.value_offsets();
+                (items.values(), offsets[idx] as usize..offsets[idx + 1] as usize)
+            }
+            ValueRef::Array(items, idx) => {
+                let len = items.value_length() as usize;
+                (items.values(), idx * len..(idx + 1) * len)
+            }
+            _ => return Err(FromSqlError::InvalidType),
+        };
+        Ok(range.map(|row| Row::value_ref_internal(row, 0, values)).collect())
+    }
+
+    /// If `self` is case `Struct`, returns the name and value of each of its
+    /// fields without copying them. Otherwise, returns
+    /// [`Err(Error::InvalidColumnType)`](crate::Error::InvalidColumnType).
+    pub fn as_struct(&self) -> FromSqlResult<Vec<(&'a str, ValueRef<'a>)>> {
+        match *self {
+            ValueRef::Struct(items, idx) => Ok(items
+                .fields()
+                .iter()
+                .zip(items.columns())
+                .map(|(field, column)| (field.name().as_str(), Row::value_ref_internal(idx, 0, column)))
+                .collect()),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+
+    /// If `self` is case `Map`, returns its keys and values without copying
+    /// them. Otherwise, returns
+    /// [`Err(Error::InvalidColumnType)`](crate::Error::InvalidColumnType).
+    pub fn as_map(&self) -> FromSqlResult<Vec<(ValueRef<'a>, ValueRef<'a>)>> {
+        match *self {
+            ValueRef::Map(arr, idx) => {
+                let (keys, values) = (arr.keys(), arr.values());
+                let offsets = arr.value_offsets();
+                Ok((offsets[idx] as usize..offsets[idx + 1] as usize)
+                    .map(|row| {
+                        (
+                            Row::value_ref_internal(row, 0, keys),
+                            Row::value_ref_internal(row, 0, values),
+                        )
+                    })
+                    .collect())
+            }
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+
+    /// If `self` is case `Union`, returns the name and value of the active
+    /// member. Otherwise, returns
+    /// [`Err(Error::InvalidColumnType)`](crate::Error::InvalidColumnType).
+    #[inline]
+    pub fn as_union(&self) -> FromSqlResult<(&'a str, ValueRef<'a>)> {
+        match *self {
+            ValueRef::Union(column, idx) => Ok(union_member(column, idx)),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
 }
 
 impl From<ValueRef<'_>> for Value {
@@ -206,10 +364,12 @@ impl From<ValueRef<'_>> for Value {
             ValueRef::USmallInt(i) => Value::USmallInt(i),
             ValueRef::UInt(i) => Value::UInt(i),
             ValueRef::UBigInt(i) => Value::UBigInt(i),
+            ValueRef::UHugeInt(i) => Value::UHugeInt(i),
             ValueRef::Float(i) => Value::Float(i),
             ValueRef::Double(i) => Value::Double(i),
-            ValueRef::Decimal(i) => Value::Decimal(i),
+            ValueRef::Decimal { value, width, scale } => Value::Decimal { value, width, scale },
             ValueRef::Timestamp(tu, t) => Value::Timestamp(tu, t),
+            ValueRef::TimestampTz(tu, t) => Value::TimestampTz(tu, t),
             ValueRef::Text(s) => {
                 let s = std::str::from_utf8(s).expect("invalid UTF-8");
                 Value::Text(s.to_string())
@@ -218,100 +378,59 @@ impl From<ValueRef<'_>> for Value {
             ValueRef::Date32(d) => Value::Date32(d),
             ValueRef::Time64(t, d) => Value::Time64(t, d),
             ValueRef::Interval { months, days, nanos } => Value::Interval { months, days, nanos },
-            ValueRef::List(items, idx) => match items {
-                ListType::Regular(items) => {
-                    let offsets = items.offsets();
-                    from_list(
-                        offsets[idx].try_into().unwrap(),
-                        offsets[idx + 1].try_into().unwrap(),
-                        idx,
-                        items.values(),
-                    )
-                }
-                ListType::Large(items) => {
-                    let offsets = items.offsets();
-                    from_list(
-                        offsets[idx].try_into().unwrap(),
-                        offsets[idx + 1].try_into().unwrap(),
-                        idx,
-                        items.values(),
-                    )
-                }
-            },
+            ValueRef::List(..) => Value::List(to_owned_all(borrowed.as_list().unwrap())),
             ValueRef::Enum(items, idx) => {
-                let value = Row::value_ref_internal(
-                    idx,
-                    0,
-                    match items {
-                        EnumType::UInt8(res) => res.values(),
-                        EnumType::UInt16(res) => res.values(),
-                        EnumType::UInt32(res) => res.values(),
-                    },
-                )
-                .to_owned();
-
-                if let Value::Text(s) = value {
+                if let Value::Text(s) = items.entry(idx).to_owned() {
                     Value::Enum(s)
                 } else {
                     panic!("Enum value is not a string")
                 }
             }
-            ValueRef::Struct(items, idx) => {
-                let value: Vec<(String, Value)> = items
-                    .columns()
-                    .iter()
-                    .zip(items.fields().iter().map(|f| f.name().to_owned()))
-                    .map(|(column, name)| -> (String, Value) {
-                        (name, Row::value_ref_internal(idx, 0, column).to_owned())
-                    })
-                    .collect();
-                Value::Struct(OrderedMap::from(value))
-            }
-            ValueRef::Map(arr, idx) => {
-                let keys = arr.keys();
-                let values = arr.values();
-                let offsets = arr.offsets();
-                let range = offsets[idx]..offsets[idx + 1];
-                Value::Map(OrderedMap::from(
-                    range
-                        .map(|row| {
-                            let row = row.try_into().unwrap();
-                            let key = Row::value_ref_internal(row, idx, keys).to_owned();
-                            let value = Row::value_ref_internal(row, idx, values).to_owned();
-                            (key, value)
-                        })
-                        .collect::<Vec<_>>(),
-                ))
-            }
-            ValueRef::Array(items, idx) => {
-                let value_length = usize::try_from(items.value_length()).unwrap();
-                let range = (idx * value_length)..((idx + 1) * value_length);
-                Value::Array(
-                    range
-                        .map(|row| Row::value_ref_internal(row, idx, items.values()).to_owned())
-                        .collect(),
-                )
-            }
+            ValueRef::Struct(..) => Value::Struct(OrderedMap::from(
+                borrowed
+                    .as_struct()
+                    .unwrap()
+                    .into_iter()
+                    .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                    .collect::<Vec<_>>(),
+            )),
+            ValueRef::Map(..) => Value::Map(OrderedMap::from(
+                borrowed
+                    .as_map()
+                    .unwrap()
+                    .into_iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                    .collect::<Vec<_>>(),
+            )),
+            ValueRef::Array(..) => Value::Array(to_owned_all(borrowed.as_list().unwrap())),
             ValueRef::Union(column, idx) => {
-                let column = column.as_any().downcast_ref::<UnionArray>().unwrap();
-                let type_id = column.type_id(idx);
-                let value_offset = column.value_offset(idx);
-
-                let tag = Row::value_ref_internal(idx, value_offset, column.child(type_id));
-                Value::Union(Box::new(tag.to_owned()))
+                let (tag, value) = union_member(column, idx);
+                Value::Union {
+                    tag: tag.to_owned(),
+                    value: Box::new(value.to_owned()),
+                }
             }
         }
     }
 }
 
-fn from_list(start: usize, end: usize, idx: usize, values: &ArrayRef) -> Value {
-    Value::List(
-        (start..end)
-            .map(|row| Row::value_ref_internal(row, idx, values).to_owned())
-            .collect(),
+fn union_member(column: &ArrayRef, idx: usize) -> (&str, ValueRef<'_>) {
+    let column = column.as_any().downcast_ref::<UnionArray>().unwrap();
+    let type_id = column.type_id(idx);
+    let tag = match column.data_type() {
+        DataType::Union(fields, _) => fields.iter().find(|(id, _)| *id == type_id).unwrap().1.name(),
+        _ => unreachable!(),
+    };
+    (
+        tag,
+        Row::value_ref_internal(column.value_offset(idx), 0, column.child(type_id)),
     )
 }
 
+fn to_owned_all(values: Vec<ValueRef<'_>>) -> Vec<Value> {
+    values.iter().map(ValueRef::to_owned).collect()
+}
+
 impl<'a> From<&'a str> for ValueRef<'a> {
     #[inline]
     fn from(s: &str) -> ValueRef<'_> {
@@ -341,17 +460,19 @@ impl<'a> From<&'a Value> for ValueRef<'a> {
             Value::USmallInt(i) => ValueRef::USmallInt(i),
             Value::UInt(i) => ValueRef::UInt(i),
             Value::UBigInt(i) => ValueRef::UBigInt(i),
+            Value::UHugeInt(i) => ValueRef::UHugeInt(i),
             Value::Float(i) => ValueRef::Float(i),
             Value::Double(i) => ValueRef::Double(i),
-            Value::Decimal(i) => ValueRef::Decimal(i),
+            Value::Decimal { value, width, scale } => ValueRef::Decimal { value, width, scale },
             Value::Timestamp(tu, t) => ValueRef::Timestamp(tu, t),
+            Value::TimestampTz(tu, t) => ValueRef::TimestampTz(tu, t),
             Value::Text(ref s) => ValueRef::Text(s.as_bytes()),
             Value::Blob(ref b) => ValueRef::Blob(b),
             Value::Date32(d) => ValueRef::Date32(d),
             Value::Time64(t, d) => ValueRef::Time64(t, d),
             Value::Interval { months, days, nanos } => ValueRef::Interval { months, days, nanos },
             Value::Enum(..) => todo!(),
-            Value::List(..) | Value::Struct(..) | Value::Map(..) | Value::Array(..) | Value::Union(..) => {
+            Value::List(..) | Value::Struct(..) | Value::Map(..) | Value::Array(..) | Value::Union { .. } => {
                 unimplemented!()
             }
         }
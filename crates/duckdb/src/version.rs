@@ -0,0 +1,151 @@
+//! The version of the linked DuckDB library, and of the files it stores
+
+use std::{ffi::CStr, fs::File, io::Read, path::Path};
+
+use crate::{ffi, Connection, DatabaseName, Error, Result};
+
+/// The version of the linked DuckDB library, such as `v0.10.2`.
+///
+/// Unlike [`Connection::version`], this needs no connection.
+pub fn version() -> &'static str {
+    // The string is static within the library.
+    let version = unsafe { CStr::from_ptr(ffi::duckdb_library_version()) };
+    version.to_str().expect("DuckDB versions are ASCII")
+}
+
+/// The version of the linked DuckDB library as `(major, minor, patch)`,
+/// such as `(0, 10, 2)`, to compare against, or `None` if the library
+/// doesn't report a `vMAJOR.MINOR.PATCH` version, as builds that only know
+/// their commit do.
+///
+/// A development build, such as `v0.10.3-dev42`, reports the release it
+/// leads up to.
+pub fn version_number() -> Option<(u64, u64, u64)> {
+    parse_version(version())
+}
+
+/// Parses `vMAJOR.MINOR.PATCH`, ignoring anything after the patch number.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.strip_prefix('v')?.splitn(3, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?;
+    let digits = patch.find(|c: char| !c.is_ascii_digit()).unwrap_or(patch.len());
+    Some((major, minor, patch[..digits].parse().ok()?))
+}
+
+/// Where the storage version is in a database file: after the checksum of
+/// the main header and its magic bytes.
+const MAGIC_OFFSET: usize = 8;
+const MAGIC: &[u8; 4] = b"DUCK";
+
+impl Connection {
+    /// The platform DuckDB was built for, such as `linux_amd64` or
+    /// `osx_arm64`, as reported by `PRAGMA platform`. Extensions are built
+    /// per platform.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the underlying DuckDB call fails.
+    pub fn platform(&self) -> Result<String> {
+        self.query_row("PRAGMA platform", [], |row| row.get(0))
+    }
+
+    /// The user agent DuckDB sends when it installs extensions, as reported
+    /// by `PRAGMA user_agent`: the DuckDB version and platform, `duckdb-rs`,
+    /// and any [`Config::custom_user_agent`](crate::Config::custom_user_agent).
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if the underlying DuckDB call fails.
+    pub fn user_agent(&self) -> Result<String> {
+        self.query_row("PRAGMA user_agent", [], |row| row.get(0))
+    }
+
+    /// The storage format version of the file of the database `db`, read
+    /// from its header, or `None` if `db` is in memory or isn't stored by
+    /// DuckDB itself, such as a SQLite file attached through an extension.
+    /// `DatabaseName::Main` is the connection's default database.
+    ///
+    /// DuckDB can read files of its own storage version, and of some
+    /// versions before it.
+    ///
+    /// # Failure
+    ///
+    /// Will return `Err` if `db` isn't attached, if its file can't be read
+    /// or has no DuckDB header, or if the underlying DuckDB call fails.
+    pub fn storage_version(&self, db: DatabaseName<'_>) -> Result<Option<u64>> {
+        let (path, ty): (Option<String>, String) = self.query_row(
+            "SELECT path, type FROM duckdb_databases() WHERE database_name = ?",
+            [self.catalog_name(db)?],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        match path {
+            Some(path) if !path.is_empty() && ty == "duckdb" => read_storage_version(Path::new(&path)).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+fn read_storage_version(path: &Path) -> Result<u64> {
+    let failure = |msg: String| Error::DuckDBFailure(ffi::Error::new(ffi::DuckDBError), Some(msg));
+    let mut header = [0; MAGIC_OFFSET + MAGIC.len() + 8];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|err| failure(format!("Failed to read {}: {err}", path.display())))?;
+    let (magic, version) = header[MAGIC_OFFSET..].split_at(MAGIC.len());
+    if magic != MAGIC {
+        return Err(failure(format!("{} is not a DuckDB database", path.display())));
+    }
+    Ok(u64::from_le_bytes(version.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_version, version, version_number};
+    use crate::{Connection, DatabaseName, Result};
+
+    #[test]
+    fn test_version() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        assert_eq!(version(), db.version()?);
+        let (major, minor, patch) = version_number().unwrap();
+        assert_eq!(format!("v{major}.{minor}.{patch}"), db.version()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v0.10.2"), Some((0, 10, 2)));
+        assert_eq!(parse_version("v1.1.0"), Some((1, 1, 0)));
+        assert_eq!(parse_version("v0.10.3-dev42"), Some((0, 10, 3)));
+        assert_eq!(parse_version("0.10.2"), None);
+        assert_eq!(parse_version("v0.10"), None);
+        assert_eq!(parse_version("6536a77232"), None);
+    }
+
+    #[test]
+    fn test_platform() -> Result<()> {
+        let db = Connection::open_in_memory()?;
+        let platform = db.platform()?;
+        assert!(platform.contains('_'), "{platform}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_storage_version() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Connection::open(dir.path().join("stored.db"))?;
+        db.execute_batch("CREATE TABLE t AS SELECT 1 AS x; CHECKPOINT")?;
+        let stored = db.storage_version(DatabaseName::Main)?;
+        assert!(matches!(stored, Some(v) if v > 0), "{stored:?}");
+
+        db.execute_batch("ATTACH ':memory:' AS mem")?;
+        assert_eq!(db.storage_version(DatabaseName::Attached("mem"))?, None);
+        assert!(db.storage_version(DatabaseName::Attached("missing")).is_err());
+
+        let in_memory = Connection::open_in_memory()?;
+        assert_eq!(in_memory.storage_version(DatabaseName::Main)?, None);
+        Ok(())
+    }
+}
@@ -612,12 +612,12 @@ mod test {
     use arrow::{
         array::{
             Array, ArrayRef, AsArray, BinaryArray, Date32Array, Date64Array, Decimal128Array, Decimal256Array,
-            FixedSizeListArray, GenericListArray, Int32Array, ListArray, OffsetSizeTrait, PrimitiveArray, StringArray,
-            StructArray, Time32SecondArray, Time64MicrosecondArray, TimestampMicrosecondArray,
-            TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
+            FixedSizeListArray, Float32Array, Float64Array, GenericListArray, Int32Array, ListArray, OffsetSizeTrait,
+            PrimitiveArray, StringArray, StructArray, Time32SecondArray, Time64MicrosecondArray,
+            TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
         },
         buffer::{OffsetBuffer, ScalarBuffer},
-        datatypes::{i256, ArrowPrimitiveType, DataType, Field, Fields, Schema},
+        datatypes::{i256, ArrowPrimitiveType, DataType, Field, Fields, Float32Type, Float64Type, Schema},
         record_batch::RecordBatch,
     };
     use std::{error::Error, sync::Arc};
@@ -642,6 +642,41 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_vtab_arrow_special_floats() -> Result<(), Box<dyn Error>> {
+        let db = Connection::open_in_memory()?;
+        db.register_table_function::<ArrowVTab>("arrow")?;
+
+        // NaNs with a payload and a sign, infinities, -0.0 and subnormals.
+        let doubles = [
+            0x7ff8_0000_0000_0001,
+            0xfff8_0000_0000_0000,
+            0x7ff0_0000_0000_0000,
+            0x8000_0000_0000_0000,
+            1,
+        ]
+        .map(f64::from_bits);
+        let floats = [0x7fc0_0001, 0xffc0_0000, 0xff80_0000, 0x8000_0000, 1].map(f32::from_bits);
+        let schema = Schema::new(vec![
+            Field::new("d", DataType::Float64, false),
+            Field::new("f", DataType::Float32, false),
+        ]);
+        let rb = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(doubles.to_vec())),
+                Arc::new(Float32Array::from(floats.to_vec())),
+            ],
+        )?;
+        let mut stmt = db.prepare("SELECT d, f FROM arrow(?, ?)")?;
+        let rb = stmt.query_arrow(arrow_recordbatch_to_query_params(rb))?.next().unwrap();
+        let d = rb.column(0).as_primitive::<Float64Type>().values();
+        let f = rb.column(1).as_primitive::<Float32Type>().values();
+        assert_eq!(doubles.map(f64::to_bits), [0, 1, 2, 3, 4].map(|i| d[i].to_bits()));
+        assert_eq!(floats.map(f32::to_bits), [0, 1, 2, 3, 4].map(|i| f[i].to_bits()));
+        Ok(())
+    }
+
     #[test]
     fn test_vtab_arrow_rust_array() -> Result<(), Box<dyn Error>> {
         let db = Connection::open_in_memory()?;
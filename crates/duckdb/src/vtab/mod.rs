@@ -313,6 +313,18 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_table_function_in_clone() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        let clone = conn.try_clone()?;
+        clone.register_table_function::<HelloVTab>("hello")?;
+        drop(clone);
+
+        let val = conn.query_row("select * from hello('clone')", [], |row| <(String,)>::try_from(row))?;
+        assert_eq!(val, ("Hello clone".to_string(),));
+        Ok(())
+    }
+
     #[test]
     fn test_named_table_function() -> Result<(), Box<dyn Error>> {
         let conn = Connection::open_in_memory()?;
@@ -327,7 +339,7 @@ mod test {
     }
 
     #[cfg(feature = "vtab-loadable")]
-    use duckdb_loadable_macros::duckdb_entrypoint;
+    use crate::duckdb_entrypoint;
 
     // this function is never called, but is still type checked
     // Exposes a extern C function named "libhello_ext_init" in the compiled dynamic library,
@@ -338,4 +350,28 @@ mod test {
         conn.register_table_function::<HelloVTab>("hello")?;
         Ok(())
     }
+
+    #[cfg(feature = "vtab-loadable")]
+    #[duckdb_entrypoint]
+    fn libpanicking_ext_init(_conn: Connection) -> Result<(), Box<dyn Error>> {
+        panic!("can't load");
+    }
+
+    #[cfg(feature = "vtab-loadable")]
+    #[test]
+    fn test_entrypoint() -> Result<(), Box<dyn Error>> {
+        let mut db: ffi::duckdb_database = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(ffi::duckdb_open(std::ptr::null(), &mut db), ffi::DuckDBSuccess);
+            // A panic doesn't unwind into DuckDB.
+            libpanicking_ext_init(db.cast());
+            libhello_ext_init(db.cast());
+        }
+        let conn = unsafe { Connection::open_from_raw(db)? };
+        let val: String = conn.query_row("select * from hello('duckdb')", [], |row| row.get(0))?;
+        assert_eq!(val, "Hello duckdb");
+        drop(conn);
+        unsafe { ffi::duckdb_close(&mut db) };
+        Ok(())
+    }
 }
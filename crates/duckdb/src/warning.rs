@@ -0,0 +1,379 @@
+//! Warnings about statements that succeeded, such as CSV rows that were
+//! skipped because they couldn't be read
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{batch, Connection, Result};
+
+/// How many warnings a connection keeps for
+/// [`take_warnings`](Connection::take_warnings), dropping the oldest.
+const KEPT_WARNINGS: usize = 100;
+
+/// A warning about a statement that succeeded, as given to the
+/// [warning handler](Connection::set_warning_handler) or returned by
+/// [`Connection::take_warnings`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Warning {
+    /// What the warning is about.
+    pub category: WarningCategory,
+    /// DuckDB's description of the problem.
+    pub message: String,
+    /// The line of the file the warning is about, counting from 1.
+    pub line: Option<u64>,
+    /// The column the warning is about.
+    pub column: Option<String>,
+}
+
+/// The kind of a [`Warning`].
+///
+/// DuckDB has no way to report warnings to clients. The warnings this crate
+/// can see are the rows `read_csv` and `COPY ... FROM` skip when
+/// `store_rejects`, `rejects_table` or `rejects_scan` is set. After a
+/// statement setting one of them succeeds, the connection runs one more
+/// query to read the new rows of the rejects table. That query isn't
+/// reported to [`trace`](Connection::trace) or
+/// [`profile`](Connection::profile) callbacks, and if it fails, no warnings
+/// are reported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningCategory {
+    /// A CSV value couldn't be cast to the type of its column, such as a
+    /// number that overflows it.
+    CsvCast,
+    /// A CSV row has fewer columns than the file.
+    CsvMissingColumns,
+    /// A CSV row has more columns than the file.
+    CsvTooManyColumns,
+    /// A CSV value has an unterminated or misplaced quote.
+    CsvUnquotedValue,
+    /// A CSV line is longer than `max_line_size`.
+    CsvLineTooLong,
+    /// A CSV row isn't valid UTF-8.
+    CsvInvalidUnicode,
+    /// Any other warning.
+    Other,
+}
+
+impl WarningCategory {
+    /// The category of a row in a CSV rejects table, from its `error_type`.
+    fn from_csv_error_type(error_type: &str) -> WarningCategory {
+        match error_type {
+            "CAST" => WarningCategory::CsvCast,
+            "MISSING COLUMNS" => WarningCategory::CsvMissingColumns,
+            "TOO MANY COLUMNS" => WarningCategory::CsvTooManyColumns,
+            "UNQUOTED VALUE" => WarningCategory::CsvUnquotedValue,
+            "LINE SIZE OVER MAXIMUM" => WarningCategory::CsvLineTooLong,
+            "INVALID UNICODE" => WarningCategory::CsvInvalidUnicode,
+            _ => WarningCategory::Other,
+        }
+    }
+}
+
+/// The warnings of a connection, and where they go.
+#[derive(Default)]
+pub(crate) struct Warnings {
+    handler: Option<Box<dyn Fn(Warning) + Send>>,
+    kept: VecDeque<Warning>,
+    // The last scan reported of each CSV rejects table.
+    last_scans: HashMap<String, u64>,
+}
+
+impl Connection {
+    /// Register a callback function that is called with each warning about
+    /// a statement this connection ran, once the statement succeeded. It
+    /// replaces the default, which keeps the last 100 warnings for
+    /// [`take_warnings`](Connection::take_warnings).
+    ///
+    /// See [`WarningCategory`] for the warnings this crate can report.
+    /// Clones made with [`try_clone`](Connection::try_clone) keep their
+    /// warnings instead.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use duckdb::{Connection, Result};
+    /// fn load(conn: &Connection) -> Result<()> {
+    ///     conn.set_warning_handler(|warning| eprintln!("skipped a row: {}", warning.message));
+    ///     conn.execute_batch("CREATE TABLE t AS FROM read_csv('data.csv', store_rejects = true)")
+    /// }
+    /// ```
+    pub fn set_warning_handler<F>(&self, handler: F)
+    where
+        F: Fn(Warning) + Send + 'static,
+    {
+        self.warnings.borrow_mut().handler = Some(Box::new(handler));
+    }
+
+    /// Remove the callback registered with
+    /// [`set_warning_handler`](Connection::set_warning_handler), going back
+    /// to keeping warnings for [`take_warnings`](Connection::take_warnings).
+    pub fn clear_warning_handler(&self) {
+        self.warnings.borrow_mut().handler = None;
+    }
+
+    /// Return the warnings kept since the last call, oldest first. Only the
+    /// last 100 are kept, and none while a
+    /// [warning handler](Connection::set_warning_handler) is registered.
+    pub fn take_warnings(&self) -> Vec<Warning> {
+        self.warnings.borrow_mut().kept.drain(..).collect()
+    }
+
+    /// Reports the warnings about `sql`, which just succeeded. Warnings that
+    /// can't be read are dropped, as the statement itself succeeded.
+    pub(crate) fn collect_warnings(&self, sql: &str) {
+        let Some(table) = csv_rejects_table(sql) else {
+            return;
+        };
+        let last_scan = self.warnings.borrow().last_scans.get(&table).copied();
+        let Ok(rejects) = self.csv_rejects(&table, last_scan) else {
+            return;
+        };
+        for (scan, warning) in rejects {
+            let mut warnings = self.warnings.borrow_mut();
+            let last_scan = warnings.last_scans.entry(table.clone()).or_default();
+            *last_scan = scan.max(*last_scan);
+            match warnings.handler.take() {
+                Some(handler) => {
+                    // The handler may register another one.
+                    drop(warnings);
+                    handler(warning);
+                    self.warnings.borrow_mut().handler.get_or_insert(handler);
+                }
+                None => {
+                    if warnings.kept.len() == KEPT_WARNINGS {
+                        warnings.kept.pop_front();
+                    }
+                    warnings.kept.push_back(warning);
+                }
+            }
+        }
+    }
+
+    /// The rows of the CSV rejects table `table` after scan `last_scan`,
+    /// with the scan that rejected each. The query runs on the raw
+    /// statement, so that it's neither traced nor profiled.
+    fn csv_rejects(&self, table: &str, last_scan: Option<u64>) -> Result<Vec<(u64, Warning)>> {
+        let sql = format!(
+            "SELECT scan_id, line, column_name, error_type::VARCHAR, error_message FROM temp.main.\"{}\"
+             WHERE scan_id > ? ORDER BY scan_id, file_id, line",
+            table.replace('"', "\"\"")
+        );
+        let mut stmt = self.prepare(&sql)?;
+        stmt.raw_bind_parameter(1, last_scan.map_or(-1, |scan| scan as i64))?;
+        stmt.stmt.execute()?;
+        let rows = stmt.raw_query().mapped(|row| {
+            let error_type: String = row.get(3)?;
+            let warning = Warning {
+                category: WarningCategory::from_csv_error_type(&error_type),
+                message: row.get(4)?,
+                line: row.get(1)?,
+                column: row.get(2)?,
+            };
+            Ok((row.get(0)?, warning))
+        });
+        rows.collect()
+    }
+}
+
+/// The name of the CSV rejects table `sql` stores rejected rows in, or
+/// `None` if it stores none: the value of its `rejects_table` option, or
+/// DuckDB's default if it only sets `store_rejects` or `rejects_scan`.
+///
+/// Only the arguments of `read_csv` and the options of `COPY` are looked at,
+/// so columns of the same names don't count.
+fn csv_rejects_table(sql: &str) -> Option<String> {
+    // Spares statements that can't set any of the options tokenizing them.
+    if !sql.as_bytes().windows(7).any(|w| w.eq_ignore_ascii_case(b"rejects")) {
+        return None;
+    }
+    let tokens = batch::tokens(sql);
+    let copy = tokens.first().is_some_and(|t| t.eq_ignore_ascii_case("copy"));
+    // Whether `COPY` named its file yet, so that a `(` starts its options.
+    let mut copy_file = false;
+    // Whether each open `(` starts a list of CSV options.
+    let mut lists = Vec::new();
+    let mut stores = false;
+    let mut table = None;
+    for (i, &token) in tokens.iter().enumerate() {
+        match token {
+            "(" => {
+                let read_csv = i > 0
+                    && tokens[i - 1]
+                        .get(..8)
+                        .is_some_and(|f| f.eq_ignore_ascii_case("read_csv"));
+                lists.push(read_csv || (copy_file && lists.is_empty()));
+                continue;
+            }
+            ")" => {
+                lists.pop();
+                continue;
+            }
+            _ => {}
+        }
+        if copy && lists.is_empty() && (token.eq_ignore_ascii_case("from") || token.eq_ignore_ascii_case("to")) {
+            copy_file = true;
+        }
+        // Options start their list or follow a `,`.
+        if lists.last() != Some(&true) || !matches!(tokens[i - 1], "(" | ",") {
+            continue;
+        }
+        // `read_csv` gives its options with `=` or `:=`, `COPY` may leave
+        // the value out.
+        let value = tokens[i + 1..]
+            .iter()
+            .find(|&&t| t != ":" && t != "=")
+            .filter(|&&t| t != "," && t != ")");
+        match token.to_ascii_lowercase().as_str() {
+            "store_rejects" => stores = !value.is_some_and(|v| v.eq_ignore_ascii_case("false")),
+            "rejects_table" => {
+                stores = true;
+                table = value
+                    .and_then(|v| v.strip_prefix('\'')?.strip_suffix('\''))
+                    .map(|name| name.replace("''", "'"));
+            }
+            "rejects_scan" => stores = true,
+            _ => {}
+        }
+    }
+    stores.then(|| table.unwrap_or_else(|| "reject_errors".to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        sync::{Arc, Mutex},
+    };
+
+    use super::{csv_rejects_table, WarningCategory};
+    use crate::{Connection, Result};
+
+    fn overflowing_csv() -> (tempfile::TempDir, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("small.csv");
+        fs::write(&path, "x,y\n1,a\n300,b\n2,c\n-129,d\n").unwrap();
+        (dir, path.to_str().unwrap().to_owned())
+    }
+
+    #[test]
+    fn test_csv_cast_warnings() -> Result<()> {
+        let (_dir, path) = overflowing_csv();
+        let db = Connection::open_in_memory()?;
+        let sql = format!(
+            "CREATE TABLE small AS FROM read_csv('{path}', columns = {{'x': 'TINYINT', 'y': 'VARCHAR'}}, header = true, \
+             store_rejects = true)"
+        );
+        db.execute_batch(&sql)?;
+        let count: i64 = db.query_row("SELECT count(*) FROM small", [], |r| r.get(0))?;
+        assert_eq!(count, 2);
+
+        let warnings = db.take_warnings();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings.iter().all(|w| w.category == WarningCategory::CsvCast));
+        assert!(warnings[0].line.is_some());
+        assert_eq!(warnings[0].column.as_deref(), Some("x"));
+        assert!(warnings[0].message.contains("300"), "{}", warnings[0].message);
+        assert!(db.take_warnings().is_empty());
+
+        // Rows are reported once, and statements without rejects add none.
+        db.execute_batch("SELECT * FROM reject_errors")?;
+        db.execute("INSERT INTO small VALUES (?, 'e')", [3])?;
+        assert!(db.take_warnings().is_empty());
+
+        // Columns named like the options don't look up a rejects table.
+        db.execute_batch("CREATE TABLE opts (rejects_scan INTEGER); INSERT INTO opts VALUES (1)")?;
+        assert_eq!(db.execute("UPDATE opts SET rejects_scan = 3", [])?, 1);
+        assert!(db.take_warnings().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_warning_handler() -> Result<()> {
+        let (_dir, path) = overflowing_csv();
+        let db = Connection::open_in_memory()?;
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let handler_seen = Arc::clone(&seen);
+        db.set_warning_handler(move |warning| handler_seen.lock().unwrap().push(warning));
+
+        let mut stmt = db.prepare(&format!(
+            "SELECT count(*) FROM read_csv('{path}', columns = {{'x': 'TINYINT', 'y': 'VARCHAR'}}, header = true, \
+             rejects_table = 'my_rejects', rejects_scan = 'my_scans')"
+        ))?;
+        let count: i64 = stmt.query_row([], |r| r.get(0))?;
+        assert_eq!(count, 2);
+        let count: i64 = stmt.query_row([], |r| r.get(0))?;
+        assert_eq!(count, 2);
+        let categories: Vec<_> = seen.lock().unwrap().iter().map(|w| w.category).collect();
+        assert_eq!(categories, [WarningCategory::CsvCast; 4]);
+        // The handler took them.
+        assert!(db.take_warnings().is_empty());
+
+        db.clear_warning_handler();
+        stmt.query_row([], |r| r.get::<_, i64>(0))?;
+        assert_eq!(db.take_warnings().len(), 2);
+        assert_eq!(seen.lock().unwrap().len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_query_untraced() -> Result<()> {
+        static TRACED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        fn tracer(sql: &str) {
+            TRACED.lock().unwrap().push(sql.to_owned());
+        }
+
+        let (_dir, path) = overflowing_csv();
+        let db = Connection::open_in_memory()?;
+        db.trace(Some(tracer));
+        let sql = format!("CREATE TABLE small AS FROM read_csv('{path}', header = true, store_rejects = true)");
+        db.execute_batch(&sql)?;
+        db.trace(None);
+        assert_eq!(*TRACED.lock().unwrap(), [sql]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_rejects_table() {
+        assert_eq!(
+            csv_rejects_table("FROM read_csv('x.csv', store_rejects = true)").as_deref(),
+            Some("reject_errors")
+        );
+        assert_eq!(
+            csv_rejects_table("FROM read_csv('x.csv', rejects_table = 'bad_rows')").as_deref(),
+            Some("bad_rows")
+        );
+        assert_eq!(
+            csv_rejects_table("COPY t FROM 'x.csv' (REJECTS_TABLE 'Bad', STORE_REJECTS)").as_deref(),
+            Some("Bad")
+        );
+        assert_eq!(
+            csv_rejects_table("FROM read_csv('x.csv', rejects_table := 'r')").as_deref(),
+            Some("r")
+        );
+        assert_eq!(
+            csv_rejects_table("FROM read_csv('x.csv', rejects_scan = 's')").as_deref(),
+            Some("reject_errors")
+        );
+
+        // Statements that merely mention rejects store none.
+        assert_eq!(csv_rejects_table("FROM read_csv('x.csv', store_rejects = false)"), None);
+        assert_eq!(csv_rejects_table("CREATE TABLE rejects (store_rejects BOOLEAN)"), None);
+        assert_eq!(csv_rejects_table("SELECT * FROM reject_errors"), None);
+        assert_eq!(
+            csv_rejects_table("SELECT 'store_rejects = true' -- , store_rejects"),
+            None
+        );
+        assert_eq!(csv_rejects_table("UPDATE t SET rejects_scan = 3"), None);
+        assert_eq!(
+            csv_rejects_table("SELECT * FROM read_csv('x.csv') WHERE store_rejects = 1"),
+            None
+        );
+        assert_eq!(csv_rejects_table("SELECT f(store_rejects = 1) FROM t"), None);
+        assert_eq!(csv_rejects_table("COPY t (store_rejects) FROM 'x.csv'"), None);
+        assert_eq!(
+            csv_rejects_table("COPY (SELECT 1 AS store_rejects) TO 'x.csv' (HEADER)"),
+            None
+        );
+    }
+}